@@ -0,0 +1,12 @@
+-- Add indexes for common event and batch queries (SQLite)
+-- Created: 2026-10-16
+
+-- Event listings filter on block_timestamp and order by (block_number, log_index);
+-- balance and history lookups filter on batch_id. The timestamp and batch_id
+-- indexes already exist on most databases and are only re-asserted here.
+CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events(block_timestamp);
+CREATE INDEX IF NOT EXISTS idx_events_block_log ON events(block_number, log_index);
+CREATE INDEX IF NOT EXISTS idx_events_batch ON events(batch_id);
+
+-- Batch listings filter and order on created_at
+CREATE INDEX IF NOT EXISTS idx_batches_created_at ON batches(created_at);
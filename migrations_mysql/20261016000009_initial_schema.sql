@@ -0,0 +1,133 @@
+-- Initial schema for beeport-stamp-stats database (MySQL/MariaDB)
+-- Created: 2026-10-16
+
+-- Matches the SQLite and PostgreSQL schemas as of their migration
+-- 20261016000009, so the MySQL backend starts at the same version.
+-- Indexed and unique text columns are VARCHAR because MySQL cannot index
+-- TEXT without a prefix length; `key` is a reserved word and is quoted.
+
+-- Events table: stores all blockchain events
+CREATE TABLE IF NOT EXISTS events (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    event_type VARCHAR(64) NOT NULL,
+    batch_id VARCHAR(255),
+    block_number BIGINT NOT NULL,
+    block_timestamp BIGINT NOT NULL,
+    transaction_hash VARCHAR(255) NOT NULL,
+    log_index BIGINT NOT NULL,
+    contract_source VARCHAR(64) NOT NULL DEFAULT 'PostageStamp',
+    contract_address VARCHAR(255),
+    block_hash VARCHAR(255),
+    data TEXT NOT NULL,
+    pot_recipient VARCHAR(255),
+    pot_total_amount VARCHAR(255),
+    price VARCHAR(255),
+    copy_index VARCHAR(255),
+    copy_batch_id VARCHAR(255),
+    UNIQUE KEY uq_events_tx_log (transaction_hash, log_index),
+    INDEX idx_events_block (block_number),
+    INDEX idx_events_timestamp (block_timestamp),
+    INDEX idx_events_block_log (block_number, log_index),
+    INDEX idx_events_batch (batch_id),
+    INDEX idx_events_contract (contract_source),
+    INDEX idx_events_contract_address (contract_address),
+    INDEX idx_events_pot_recipient (pot_recipient),
+    INDEX idx_events_price (price)
+);
+
+-- RPC cache table: caches chunk fetch results
+CREATE TABLE IF NOT EXISTS rpc_cache (
+    chunk_hash VARCHAR(255) PRIMARY KEY,
+    contract_address VARCHAR(255) NOT NULL,
+    from_block BIGINT NOT NULL,
+    to_block BIGINT NOT NULL,
+    processed_at BIGINT NOT NULL,
+    event_count BIGINT NOT NULL,
+    INDEX idx_rpc_cache_blocks (contract_address, from_block, to_block)
+);
+
+-- Batches table: stores batch creation information
+CREATE TABLE IF NOT EXISTS batches (
+    batch_id VARCHAR(255) PRIMARY KEY,
+    owner VARCHAR(255) NOT NULL,
+    payer VARCHAR(255),
+    contract_source VARCHAR(64) NOT NULL DEFAULT 'PostageStamp',
+    depth BIGINT NOT NULL,
+    bucket_depth BIGINT NOT NULL,
+    immutable BIGINT NOT NULL,
+    normalised_balance VARCHAR(255) NOT NULL,
+    created_at BIGINT NOT NULL,
+    block_number BIGINT NOT NULL DEFAULT 0,
+    INDEX idx_batches_block (block_number),
+    INDEX idx_batches_payer (payer),
+    INDEX idx_batches_contract_source (contract_source),
+    INDEX idx_batches_created_at (created_at)
+);
+
+-- Batch balances table: caches balance lookups
+CREATE TABLE IF NOT EXISTS batch_balances (
+    batch_id VARCHAR(255) PRIMARY KEY,
+    remaining_balance VARCHAR(255) NOT NULL,
+    fetched_at BIGINT NOT NULL,
+    fetched_block BIGINT NOT NULL,
+    INDEX idx_batch_balances_fetched (fetched_at)
+);
+
+-- Cache metadata table: stores last cached price, follow cursor and snapshots
+CREATE TABLE IF NOT EXISTS cache_metadata (
+    `key` VARCHAR(255) PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+-- Storage incentives events: PriceOracle, StakeRegistry and Redistribution events
+CREATE TABLE IF NOT EXISTS storage_incentives_events (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    block_timestamp BIGINT NOT NULL,
+    transaction_hash VARCHAR(255) NOT NULL,
+    log_index BIGINT NOT NULL,
+    contract_source VARCHAR(64) NOT NULL,
+    contract_address VARCHAR(255),
+    event_type VARCHAR(64) NOT NULL,
+    round_number BIGINT,
+    phase VARCHAR(16),
+    owner_address VARCHAR(255),
+    overlay VARCHAR(255),
+    price TEXT,
+    committed_stake TEXT,
+    potential_stake TEXT,
+    height BIGINT,
+    slash_amount TEXT,
+    freeze_time TEXT,
+    withdraw_amount TEXT,
+    stake TEXT,
+    stake_density TEXT,
+    reserve_commitment TEXT,
+    depth BIGINT,
+    anchor TEXT,
+    truth_hash TEXT,
+    truth_depth BIGINT,
+    winner_overlay TEXT,
+    winner_owner TEXT,
+    winner_depth BIGINT,
+    winner_stake TEXT,
+    winner_stake_density TEXT,
+    winner_hash TEXT,
+    commit_count BIGINT,
+    reveal_count BIGINT,
+    chunk_count BIGINT,
+    redundancy_count BIGINT,
+    chunk_index_in_rc BIGINT,
+    chunk_address TEXT,
+    UNIQUE KEY uq_si_tx_log (transaction_hash, log_index),
+    INDEX idx_si_contract_source (contract_source),
+    INDEX idx_si_event_type (event_type),
+    INDEX idx_si_block_number (block_number),
+    INDEX idx_si_round_number (round_number),
+    INDEX idx_si_phase (phase),
+    INDEX idx_si_overlay (overlay),
+    INDEX idx_si_owner_address (owner_address),
+    INDEX idx_si_block_timestamp (block_timestamp),
+    INDEX idx_si_contract_address (contract_address)
+);
@@ -0,0 +1,6 @@
+-- Store rpc_cache contract addresses lowercase (MySQL/MariaDB)
+-- Created: 2026-10-16
+
+-- Cached range lookups compare against a lowercased address so they can use
+-- idx_rpc_cache_blocks instead of applying LOWER() to every row.
+UPDATE rpc_cache SET contract_address = LOWER(contract_address);
@@ -0,0 +1,13 @@
+-- Add fetch cursor columns to sync_state (MySQL/MariaDB)
+-- Created: 2026-10-16
+
+-- Chunk grid of the last fetch over each contract and the highest chunk it
+-- completed, so an interrupted fetch resumes after that chunk. NULL until a
+-- fetch has completed a chunk. Cursors previously kept in cache_metadata are
+-- dropped; the chunk cache still skips the ranges they covered.
+ALTER TABLE sync_state
+    ADD COLUMN from_block BIGINT NULL,
+    ADD COLUMN chunk_size BIGINT NULL,
+    ADD COLUMN last_completed_to_block BIGINT NULL;
+
+DELETE FROM cache_metadata WHERE `key` LIKE 'fetch_cursor:%';
@@ -0,0 +1,6 @@
+-- Add block_hash column to events table (PostgreSQL)
+-- Created: 2026-10-16
+
+-- Hash of the block each event was included in, used by follow mode to
+-- detect chain reorganizations. Existing rows have NULL and are not checked.
+ALTER TABLE events ADD COLUMN IF NOT EXISTS block_hash TEXT;
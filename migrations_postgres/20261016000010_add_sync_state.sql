@@ -0,0 +1,10 @@
+-- Track the highest fully scanned block per contract (PostgreSQL)
+-- Created: 2026-10-16
+
+-- Incremental fetches resume from here rather than from the last stored event,
+-- so block ranges without any events are not scanned again on every run.
+CREATE TABLE IF NOT EXISTS sync_state (
+    contract_address TEXT PRIMARY KEY,
+    last_scanned_block BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
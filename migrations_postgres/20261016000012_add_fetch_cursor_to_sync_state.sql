@@ -0,0 +1,12 @@
+-- Add fetch cursor columns to sync_state (PostgreSQL)
+-- Created: 2026-10-16
+
+-- Chunk grid of the last fetch over each contract and the highest chunk it
+-- completed, so an interrupted fetch resumes after that chunk. NULL until a
+-- fetch has completed a chunk. Cursors previously kept in cache_metadata are
+-- dropped; the chunk cache still skips the ranges they covered.
+ALTER TABLE sync_state ADD COLUMN IF NOT EXISTS from_block BIGINT;
+ALTER TABLE sync_state ADD COLUMN IF NOT EXISTS chunk_size BIGINT;
+ALTER TABLE sync_state ADD COLUMN IF NOT EXISTS last_completed_to_block BIGINT;
+
+DELETE FROM cache_metadata WHERE key LIKE 'fetch_cursor:%';
@@ -1,10 +1,10 @@
-use crate::events::StampEvent;
+use crate::events::{BatchInfo, EventData, StampEvent};
 use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Statistics for a time period
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PeriodStats {
     pub period_key: String,
     pub period_label: String,
@@ -13,13 +13,167 @@ pub struct PeriodStats {
     pub batch_depth_increase_count: usize,
     pub total_events: usize,
     pub unique_batches: usize,
+    /// Chunks bought by `BatchCreated` events (`2^depth` per batch)
+    #[serde(default)]
+    pub chunks_created: u128,
+    /// Chunks added by `BatchDepthIncrease` events over the batch's previous depth
+    #[serde(default)]
+    pub chunks_from_depth_increase: u128,
+    /// Events emitted by the PostageStamp contract, of any type
+    #[serde(default)]
+    pub postage_stamp_events: usize,
+    /// Events emitted by the StampsRegistry contract, of any type
+    #[serde(default)]
+    pub stamps_registry_events: usize,
+}
+
+impl PeriodStats {
+    /// Net chunks added in this period by new batches and depth increases
+    pub fn net_chunks_added(&self) -> u128 {
+        self.chunks_created + self.chunks_from_depth_increase
+    }
+}
+
+/// Overall and per-period statistics written by `summary --snapshot`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryStats {
+    pub total_events: usize,
+    pub total_batches: usize,
+    pub periods: Vec<PeriodStats>,
+}
+
+impl SummaryStats {
+    /// Build summary statistics from filtered events and batches
+    pub fn new(events: &[StampEvent], batches: &[BatchInfo], group_by: &crate::cli::GroupBy) -> Self {
+        Self {
+            total_events: events.len(),
+            total_batches: batches.len(),
+            periods: aggregate_events(events, group_by),
+        }
+    }
+
+    /// Compare against an earlier snapshot
+    ///
+    /// Periods present in only one snapshot are compared against zero.
+    pub fn diff(&self, previous: &SummaryStats) -> SummaryDiff {
+        let empty = |p: &PeriodStats| PeriodStats {
+            batch_created_count: 0,
+            batch_topup_count: 0,
+            batch_depth_increase_count: 0,
+            total_events: 0,
+            unique_batches: 0,
+            chunks_created: 0,
+            chunks_from_depth_increase: 0,
+            postage_stamp_events: 0,
+            stamps_registry_events: 0,
+            ..p.clone()
+        };
+
+        let mut keys: BTreeMap<&str, (PeriodStats, PeriodStats)> = BTreeMap::new();
+        for period in &previous.periods {
+            keys.insert(&period.period_key, (period.clone(), empty(period)));
+        }
+        for period in &self.periods {
+            keys.entry(&period.period_key)
+                .and_modify(|(_, current)| *current = period.clone())
+                .or_insert_with(|| (empty(period), period.clone()));
+        }
+
+        SummaryDiff {
+            total_events: delta(self.total_events, previous.total_events),
+            total_batches: delta(self.total_batches, previous.total_batches),
+            periods: keys
+                .into_values()
+                .map(|(before, after)| PeriodDelta {
+                    period_key: after.period_key,
+                    period_label: after.period_label,
+                    batch_created_count: delta(after.batch_created_count, before.batch_created_count),
+                    batch_topup_count: delta(after.batch_topup_count, before.batch_topup_count),
+                    batch_depth_increase_count: delta(
+                        after.batch_depth_increase_count,
+                        before.batch_depth_increase_count,
+                    ),
+                    total_events: delta(after.total_events, before.total_events),
+                    unique_batches: delta(after.unique_batches, before.unique_batches),
+                    chunks_created: chunk_delta(after.chunks_created, before.chunks_created),
+                    chunks_from_depth_increase: chunk_delta(
+                        after.chunks_from_depth_increase,
+                        before.chunks_from_depth_increase,
+                    ),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Change in summary statistics between two snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryDiff {
+    pub total_events: i64,
+    pub total_batches: i64,
+    pub periods: Vec<PeriodDelta>,
+}
+
+/// Change in one period's statistics between two snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeriodDelta {
+    pub period_key: String,
+    pub period_label: String,
+    pub batch_created_count: i64,
+    pub batch_topup_count: i64,
+    pub batch_depth_increase_count: i64,
+    pub total_events: i64,
+    pub unique_batches: i64,
+    pub chunks_created: i128,
+    pub chunks_from_depth_increase: i128,
+}
+
+impl PeriodDelta {
+    /// Change in net chunks added by new batches and depth increases
+    pub fn net_chunks_added(&self) -> i128 {
+        self.chunks_created + self.chunks_from_depth_increase
+    }
+
+    /// Whether any metric changed in this period
+    pub fn is_changed(&self) -> bool {
+        self.batch_created_count != 0
+            || self.batch_topup_count != 0
+            || self.batch_depth_increase_count != 0
+            || self.total_events != 0
+            || self.unique_batches != 0
+            || self.chunks_created != 0
+            || self.chunks_from_depth_increase != 0
+    }
+}
+
+/// Signed difference between two counts
+fn delta(current: usize, previous: usize) -> i64 {
+    current as i64 - previous as i64
+}
+
+/// Signed difference between two chunk counts
+fn chunk_delta(current: u128, previous: u128) -> i128 {
+    current as i128 - previous as i128
 }
 
 /// Aggregate events by time period
+///
+/// Events are bucketed by their UTC calendar date, so an event at 23:59:59 UTC
+/// and one at 00:00:00 UTC the next day always land in different periods,
+/// regardless of the local timezone.
+///
+/// Storage growth is tracked by replaying events in chain order: a depth
+/// increase adds the chunks between the batch's previous and new depth. Depth
+/// increases of batches created before the first event cannot be attributed
+/// and count as zero growth.
 pub fn aggregate_events(events: &[StampEvent], group_by: &crate::cli::GroupBy) -> Vec<PeriodStats> {
     let mut periods: HashMap<String, PeriodStatsBuilder> = HashMap::new();
+    let mut depths: HashMap<&str, u8> = HashMap::new();
 
-    for event in events {
+    let mut ordered: Vec<&StampEvent> = events.iter().collect();
+    ordered.sort_by_key(|e| (e.block_number, e.log_index));
+
+    for event in ordered {
         let period_key = get_period_key(&event.block_timestamp, group_by);
         let period_label = get_period_label(&event.block_timestamp, group_by);
 
@@ -28,6 +182,22 @@ pub fn aggregate_events(events: &[StampEvent], group_by: &crate::cli::GroupBy) -
             .or_insert_with(|| PeriodStatsBuilder::new(period_key, period_label));
 
         stats.add_event(event);
+
+        if let Some(batch_id) = event.batch_id.as_deref() {
+            match &event.data {
+                EventData::BatchCreated { depth, .. } => {
+                    stats.chunks_created += 1u128 << depth;
+                    depths.insert(batch_id, *depth);
+                }
+                EventData::BatchDepthIncrease { new_depth, .. } => {
+                    if let Some(previous) = depths.insert(batch_id, *new_depth) {
+                        stats.chunks_from_depth_increase +=
+                            (1u128 << new_depth).saturating_sub(1u128 << previous);
+                    }
+                }
+                _ => {}
+            }
+        }
     }
 
     let mut stats: Vec<_> = periods.into_values().map(|s| s.build()).collect();
@@ -36,27 +206,71 @@ pub fn aggregate_events(events: &[StampEvent], group_by: &crate::cli::GroupBy) -
     stats
 }
 
+/// Collapse events that both contracts emitted for one batch operation
+///
+/// StampsRegistry calls into PostageStamp, so a batch created through the
+/// registry is reported once by each contract. Events from different contracts
+/// sharing `(batch_id, event_type, block_number)` are merged into the
+/// StampsRegistry copy, which carries the `payer`. Events without a batch ID
+/// and repeats from the same contract are kept; the order is preserved.
+pub fn dedupe_events(events: Vec<StampEvent>) -> Vec<StampEvent> {
+    let mut kept: Vec<StampEvent> = Vec::with_capacity(events.len());
+    let mut first_seen: HashMap<(String, String, u64), usize> = HashMap::new();
+
+    for event in events {
+        let Some(batch_id) = event.batch_id.clone() else {
+            kept.push(event);
+            continue;
+        };
+        let key = (batch_id, event.event_type.to_string(), event.block_number);
+
+        match first_seen.get(&key) {
+            Some(&index) if kept[index].contract_source != event.contract_source => {
+                if event.contract_source == "StampsRegistry" {
+                    kept[index] = event;
+                }
+            }
+            _ => {
+                first_seen.entry(key).or_insert(kept.len());
+                kept.push(event);
+            }
+        }
+    }
+
+    kept
+}
+
 /// Get period key for grouping
+///
+/// Weeks are ISO weeks (Monday start), keyed by ISO week-numbering year so the
+/// days around New Year sort with the week they belong to. Quarters are calendar
+/// quarters (`2024-Q2`); every key sorts chronologically as a string.
 fn get_period_key(timestamp: &DateTime<Utc>, group_by: &crate::cli::GroupBy) -> String {
+    let date = timestamp.date_naive();
     match group_by {
-        crate::cli::GroupBy::Day => timestamp.format("%Y-%m-%d").to_string(),
+        crate::cli::GroupBy::Day => date.format("%Y-%m-%d").to_string(),
         crate::cli::GroupBy::Week => {
-            let iso_week = timestamp.iso_week();
+            let iso_week = date.iso_week();
             format!("{}-W{:02}", iso_week.year(), iso_week.week())
         }
-        crate::cli::GroupBy::Month => timestamp.format("%Y-%m").to_string(),
+        crate::cli::GroupBy::Month => date.format("%Y-%m").to_string(),
+        crate::cli::GroupBy::Quarter => format!("{}-Q{}", date.year(), date.month0() / 3 + 1),
+        crate::cli::GroupBy::Year => date.year().to_string(),
     }
 }
 
 /// Get human-readable period label
 fn get_period_label(timestamp: &DateTime<Utc>, group_by: &crate::cli::GroupBy) -> String {
+    let date = timestamp.date_naive();
     match group_by {
-        crate::cli::GroupBy::Day => timestamp.format("%b %d, %Y").to_string(),
+        crate::cli::GroupBy::Day => date.format("%b %d, %Y").to_string(),
         crate::cli::GroupBy::Week => {
-            let iso_week = timestamp.iso_week();
+            let iso_week = date.iso_week();
             format!("Week {} of {}", iso_week.week(), iso_week.year())
         }
-        crate::cli::GroupBy::Month => timestamp.format("%B %Y").to_string(),
+        crate::cli::GroupBy::Month => date.format("%B %Y").to_string(),
+        crate::cli::GroupBy::Quarter => format!("Q{} {}", date.month0() / 3 + 1, date.year()),
+        crate::cli::GroupBy::Year => date.year().to_string(),
     }
 }
 
@@ -68,6 +282,10 @@ struct PeriodStatsBuilder {
     batch_topup_count: usize,
     batch_depth_increase_count: usize,
     batch_ids: std::collections::HashSet<String>,
+    chunks_created: u128,
+    chunks_from_depth_increase: u128,
+    postage_stamp_events: usize,
+    stamps_registry_events: usize,
 }
 
 impl PeriodStatsBuilder {
@@ -79,6 +297,10 @@ impl PeriodStatsBuilder {
             batch_topup_count: 0,
             batch_depth_increase_count: 0,
             batch_ids: std::collections::HashSet::new(),
+            chunks_created: 0,
+            chunks_from_depth_increase: 0,
+            postage_stamp_events: 0,
+            stamps_registry_events: 0,
         }
     }
 
@@ -94,6 +316,12 @@ impl PeriodStatsBuilder {
             EventType::CopyBatchFailed => {} // CopyBatchFailed events don't affect batch stats
         }
 
+        match event.contract_source.as_str() {
+            "PostageStamp" => self.postage_stamp_events += 1,
+            "StampsRegistry" => self.stamps_registry_events += 1,
+            _ => {}
+        }
+
         if let Some(batch_id) = &event.batch_id {
             self.batch_ids.insert(batch_id.clone());
         }
@@ -110,6 +338,10 @@ impl PeriodStatsBuilder {
                 + self.batch_topup_count
                 + self.batch_depth_increase_count,
             unique_batches: self.batch_ids.len(),
+            chunks_created: self.chunks_created,
+            chunks_from_depth_increase: self.chunks_from_depth_increase,
+            postage_stamp_events: self.postage_stamp_events,
+            stamps_registry_events: self.stamps_registry_events,
         }
     }
 }
@@ -117,7 +349,7 @@ impl PeriodStatsBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::events::{EventData, EventType};
+    use crate::events::EventType;
     use chrono::TimeZone;
 
     #[test]
@@ -153,6 +385,7 @@ mod tests {
                 log_index: 0,
                 contract_source: "PostageStamp".to_string(),
                 contract_address: None,
+                block_hash: None,
                 data: EventData::BatchCreated {
                     total_amount: "1000000000000000000".to_string(),
                     normalised_balance: "500000000000000000".to_string(),
@@ -172,6 +405,7 @@ mod tests {
                 log_index: 0,
                 contract_source: "PostageStamp".to_string(),
                 contract_address: None,
+                block_hash: None,
                 data: EventData::BatchTopUp {
                     topup_amount: "100000000000000000".to_string(),
                     normalised_balance: "600000000000000000".to_string(),
@@ -188,4 +422,341 @@ mod tests {
         assert_eq!(stats[0].total_events, 2);
         assert_eq!(stats[0].unique_batches, 1);
     }
+
+    #[test]
+    fn test_dedupe_events_prefers_stamps_registry() {
+        let created = |contract_source: &str, log_index: u64, payer: Option<&str>| StampEvent {
+            event_type: EventType::BatchCreated,
+            batch_id: Some("0x1234".to_string()),
+            block_number: 1000,
+            block_timestamp: Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap(),
+            transaction_hash: "0xabcd1".to_string(),
+            log_index,
+            contract_source: contract_source.to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchCreated {
+                total_amount: "1000".to_string(),
+                normalised_balance: "500".to_string(),
+                owner: "0x5678".to_string(),
+                depth: 20,
+                bucket_depth: 16,
+                immutable_flag: false,
+                payer: payer.map(str::to_string),
+            },
+        };
+        let topup = StampEvent {
+            event_type: EventType::BatchTopUp,
+            block_number: 1001,
+            log_index: 0,
+            data: EventData::BatchTopUp {
+                topup_amount: "100".to_string(),
+                normalised_balance: "600".to_string(),
+                payer: None,
+            },
+            ..created("PostageStamp", 0, None)
+        };
+
+        // The registry's log follows the PostageStamp log it triggered
+        let events = vec![
+            created("PostageStamp", 0, None),
+            created("StampsRegistry", 1, Some("0x9999")),
+            topup.clone(),
+        ];
+        let deduped = dedupe_events(events);
+
+        assert_eq!(deduped.len(), 2);
+        assert_eq!(deduped[0].contract_source, "StampsRegistry");
+        assert!(matches!(
+            &deduped[0].data,
+            EventData::BatchCreated { payer: Some(payer), .. } if payer == "0x9999"
+        ));
+        assert_eq!(deduped[1].block_number, topup.block_number);
+        assert_eq!(aggregate_events(&deduped, &crate::cli::GroupBy::Day)[0].batch_created_count, 1);
+
+        // Two events from the same contract are distinct operations
+        let same_contract = vec![created("PostageStamp", 0, None), created("PostageStamp", 1, None)];
+        assert_eq!(dedupe_events(same_contract).len(), 2);
+    }
+
+    #[test]
+    fn test_period_key_day_boundary() {
+        let before = Utc.with_ymd_and_hms(2025, 3, 15, 23, 59, 59).unwrap();
+        let after = Utc.with_ymd_and_hms(2025, 3, 16, 0, 0, 0).unwrap();
+        assert_eq!(get_period_key(&before, &crate::cli::GroupBy::Day), "2025-03-15");
+        assert_eq!(get_period_key(&after, &crate::cli::GroupBy::Day), "2025-03-16");
+
+        // 01:00 at UTC+2 is still the previous UTC day
+        let offset = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        let local = offset.with_ymd_and_hms(2025, 3, 16, 1, 0, 0).unwrap().with_timezone(&Utc);
+        assert_eq!(get_period_key(&local, &crate::cli::GroupBy::Day), "2025-03-15");
+    }
+
+    #[test]
+    fn test_period_key_week_boundaries() {
+        let week = |y, m, d, h, min, sec| {
+            get_period_key(
+                &Utc.with_ymd_and_hms(y, m, d, h, min, sec).unwrap(),
+                &crate::cli::GroupBy::Week,
+            )
+        };
+
+        // Sunday 23:59:59 -> Monday 00:00:00
+        assert_eq!(week(2025, 3, 16, 23, 59, 59), "2025-W11");
+        assert_eq!(week(2025, 3, 17, 0, 0, 0), "2025-W12");
+
+        // 2020 has 53 ISO weeks; early January 2021 still belongs to week 53
+        assert_eq!(week(2020, 12, 31, 12, 0, 0), "2020-W53");
+        assert_eq!(week(2021, 1, 3, 23, 59, 59), "2020-W53");
+        assert_eq!(week(2021, 1, 4, 0, 0, 0), "2021-W01");
+
+        // Late December can belong to week 1 of the next ISO year
+        assert_eq!(week(2024, 12, 29, 23, 59, 59), "2024-W52");
+        assert_eq!(week(2024, 12, 30, 0, 0, 0), "2025-W01");
+    }
+
+    #[test]
+    fn test_aggregate_events_across_year_boundary() {
+        let event = |tx: &str, timestamp| StampEvent {
+            event_type: EventType::BatchTopUp,
+            batch_id: Some("0x1234".to_string()),
+            block_number: 1000,
+            block_timestamp: timestamp,
+            transaction_hash: tx.to_string(),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchTopUp {
+                topup_amount: "100".to_string(),
+                normalised_balance: "200".to_string(),
+                payer: None,
+            },
+        };
+        let events = vec![
+            event("0x03", Utc.with_ymd_and_hms(2021, 1, 4, 0, 0, 0).unwrap()),
+            event("0x01", Utc.with_ymd_and_hms(2020, 12, 31, 23, 59, 59).unwrap()),
+            event("0x02", Utc.with_ymd_and_hms(2021, 1, 3, 23, 59, 59).unwrap()),
+        ];
+
+        let stats = aggregate_events(&events, &crate::cli::GroupBy::Week);
+
+        let buckets: Vec<(&str, usize)> = stats
+            .iter()
+            .map(|s| (s.period_key.as_str(), s.total_events))
+            .collect();
+        assert_eq!(buckets, [("2020-W53", 2), ("2021-W01", 1)]);
+        assert_eq!(stats[0].period_label, "Week 53 of 2020");
+    }
+
+    #[test]
+    fn test_aggregate_events_per_contract_counts() {
+        let event = |block_number: u64, day: u32, contract_source: &str| StampEvent {
+            event_type: EventType::BatchTopUp,
+            batch_id: Some(format!("0x{block_number:x}")),
+            block_number,
+            block_timestamp: Utc.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap(),
+            transaction_hash: format!("0x{block_number:x}"),
+            log_index: 0,
+            contract_source: contract_source.to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchTopUp {
+                topup_amount: "100".to_string(),
+                normalised_balance: "200".to_string(),
+                payer: None,
+            },
+        };
+        // ISO weeks 11 (10-16 March) and 12 (17-23 March)
+        let events = vec![
+            event(1, 10, "PostageStamp"),
+            event(2, 11, "PostageStamp"),
+            event(3, 12, "StampsRegistry"),
+            event(4, 17, "PostageStamp"),
+            event(5, 18, "StampsRegistry"),
+            event(6, 19, "StampsRegistry"),
+            event(7, 20, "StampsRegistry"),
+        ];
+
+        let stats = aggregate_events(&events, &crate::cli::GroupBy::Week);
+
+        let counts: Vec<(&str, usize, usize)> = stats
+            .iter()
+            .map(|s| (s.period_key.as_str(), s.postage_stamp_events, s.stamps_registry_events))
+            .collect();
+        assert_eq!(counts, [("2025-W11", 2, 1), ("2025-W12", 1, 3)]);
+
+        // Exported with the other period fields
+        let json = serde_json::to_value(&stats[1]).unwrap();
+        assert_eq!(json["stamps_registry_events"], 3);
+    }
+
+    #[test]
+    fn test_aggregate_events_by_quarter_and_year() {
+        let event = |block_number: u64, timestamp| StampEvent {
+            event_type: EventType::BatchTopUp,
+            batch_id: Some("0x1234".to_string()),
+            block_number,
+            block_timestamp: timestamp,
+            transaction_hash: format!("0x{block_number:x}"),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchTopUp {
+                topup_amount: "100".to_string(),
+                normalised_balance: "200".to_string(),
+                payer: None,
+            },
+        };
+        let events = vec![
+            event(1, Utc.with_ymd_and_hms(2023, 11, 20, 0, 0, 0).unwrap()),
+            event(2, Utc.with_ymd_and_hms(2023, 12, 31, 23, 59, 59).unwrap()),
+            event(3, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            event(4, Utc.with_ymd_and_hms(2024, 3, 31, 23, 59, 59).unwrap()),
+            event(5, Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap()),
+            event(6, Utc.with_ymd_and_hms(2024, 9, 30, 12, 0, 0).unwrap()),
+        ];
+        let buckets = |group_by| -> Vec<(String, String, usize)> {
+            aggregate_events(&events, &group_by)
+                .into_iter()
+                .map(|s| (s.period_key, s.period_label, s.total_events))
+                .collect()
+        };
+
+        assert_eq!(
+            buckets(crate::cli::GroupBy::Quarter),
+            [
+                ("2023-Q4".to_string(), "Q4 2023".to_string(), 2),
+                ("2024-Q1".to_string(), "Q1 2024".to_string(), 2),
+                ("2024-Q2".to_string(), "Q2 2024".to_string(), 1),
+                ("2024-Q3".to_string(), "Q3 2024".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            buckets(crate::cli::GroupBy::Year),
+            [("2023".to_string(), "2023".to_string(), 2), ("2024".to_string(), "2024".to_string(), 4)]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_events_storage_growth() {
+        let event = |block_number: u64, day: u32, data: EventData| StampEvent {
+            event_type: match data {
+                EventData::BatchCreated { .. } => EventType::BatchCreated,
+                _ => EventType::BatchDepthIncrease,
+            },
+            batch_id: Some("0x1234".to_string()),
+            block_number,
+            block_timestamp: Utc.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap(),
+            transaction_hash: format!("0x{block_number:x}"),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data,
+        };
+        let created = EventData::BatchCreated {
+            total_amount: "1000".to_string(),
+            normalised_balance: "500".to_string(),
+            owner: "0x5678".to_string(),
+            depth: 20,
+            bucket_depth: 16,
+            immutable_flag: false,
+            payer: None,
+        };
+        let increased = EventData::BatchDepthIncrease {
+            new_depth: 22,
+            normalised_balance: "250".to_string(),
+            payer: None,
+        };
+
+        // Passed out of order; the depth increase must see depth 20 as the previous depth
+        let events = vec![event(2000, 16, increased), event(1000, 15, created)];
+        let stats = aggregate_events(&events, &crate::cli::GroupBy::Day);
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].chunks_created, 1 << 20);
+        assert_eq!(stats[0].chunks_from_depth_increase, 0);
+        assert_eq!(stats[1].chunks_created, 0);
+        assert_eq!(stats[1].chunks_from_depth_increase, (1 << 22) - (1 << 20));
+
+        // Net growth over both periods equals the final batch size
+        let total: u128 = stats.iter().map(PeriodStats::net_chunks_added).sum();
+        assert_eq!(total, 1 << 22);
+    }
+
+    fn period(key: &str, created: usize, topups: usize, unique: usize) -> PeriodStats {
+        PeriodStats {
+            period_key: key.to_string(),
+            period_label: key.to_string(),
+            batch_created_count: created,
+            batch_topup_count: topups,
+            batch_depth_increase_count: 0,
+            total_events: created + topups,
+            unique_batches: unique,
+            chunks_created: 0,
+            chunks_from_depth_increase: 0,
+            postage_stamp_events: 0,
+            stamps_registry_events: 0,
+        }
+    }
+
+    #[test]
+    fn test_summary_diff_between_snapshots() {
+        let previous = SummaryStats {
+            total_events: 8,
+            total_batches: 5,
+            periods: vec![
+                PeriodStats {
+                    chunks_created: 1 << 18,
+                    ..period("2025-W10", 3, 2, 4)
+                },
+                PeriodStats {
+                    chunks_created: 1 << 21,
+                    ..period("2025-W11", 2, 1, 2)
+                },
+            ],
+        };
+        let current = SummaryStats {
+            total_events: 12,
+            total_batches: 6,
+            periods: vec![
+                PeriodStats {
+                    chunks_created: 1 << 20,
+                    chunks_from_depth_increase: 1 << 17,
+                    ..period("2025-W11", 3, 3, 3)
+                },
+                period("2025-W12", 1, 0, 1),
+            ],
+        };
+
+        // Snapshots survive a JSON roundtrip unchanged
+        let json = serde_json::to_string(&previous).unwrap();
+        assert_eq!(serde_json::from_str::<SummaryStats>(&json).unwrap(), previous);
+
+        let diff = current.diff(&previous);
+        assert_eq!(diff.total_events, 4);
+        assert_eq!(diff.total_batches, 1);
+
+        let keys: Vec<&str> = diff.periods.iter().map(|p| p.period_key.as_str()).collect();
+        assert_eq!(keys, ["2025-W10", "2025-W11", "2025-W12"]);
+
+        // Period dropped out of the window
+        assert_eq!(diff.periods[0].batch_created_count, -3);
+        assert_eq!(diff.periods[0].total_events, -5);
+        assert_eq!(diff.periods[0].chunks_created, -(1 << 18));
+        // Period present in both
+        assert_eq!(diff.periods[1].batch_created_count, 1);
+        assert_eq!(diff.periods[1].batch_topup_count, 2);
+        assert_eq!(diff.periods[1].unique_batches, 1);
+        assert_eq!(diff.periods[1].chunks_created, -(1 << 20));
+        assert_eq!(diff.periods[1].chunks_from_depth_increase, 1 << 17);
+        assert_eq!(diff.periods[1].net_chunks_added(), -(1 << 20) + (1 << 17));
+        // New period
+        assert_eq!(diff.periods[2].total_events, 1);
+
+        assert!(diff.periods.iter().all(PeriodDelta::is_changed));
+        assert!(current.diff(&current).periods.iter().all(|p| !p.is_changed()));
+    }
 }
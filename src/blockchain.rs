@@ -1,36 +1,217 @@
-use crate::cache::Cache;
+use crate::cache::{Cache, FetchCursor};
 use crate::config::BlockchainConfig;
 use crate::contracts::{
-    abi::PostageStamp, Contract, ContractRegistry, StorageIncentivesContract,
+    abi::PostageStamp, parser::to_hex, Contract, ContractRegistry, StorageIncentivesContract,
     StorageIncentivesContractRegistry,
 };
-use crate::error::{Result, StampError};
+use crate::error::{classify_rpc_error, Result, StampError};
 use crate::events::{BatchInfo, EventData, EventType, StampEvent, StorageIncentivesEvent};
+use crate::metrics::Metrics;
 use crate::retry::RetryConfig;
+use crate::types::BatchId;
 use alloy::primitives::Address;
 use alloy::providers::{Provider, ProviderBuilder, RootProvider};
 use alloy::rpc::types::{Block, BlockTransactionsKind, Filter, Log};
 use alloy::transports::http::{Client, Http};
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Selector of the `BatchDoesNotExist()` revert returned by `remainingBalance`
+const BATCH_NOT_FOUND_SELECTOR: [u8; 4] = [0x4e, 0xe9, 0xbc, 0x0f];
+
+/// Whether a contract call reverted with `BatchDoesNotExist()`
+///
+/// Only the revert data of a JSON-RPC error response is inspected, so other
+/// reverts and transport failures are never mistaken for a missing batch.
+fn is_batch_not_found(error: &alloy::contract::Error) -> bool {
+    match error {
+        alloy::contract::Error::TransportError(e) => e
+            .as_error_resp()
+            .and_then(|payload| payload.as_revert_data())
+            .is_some_and(|data| data.starts_with(&BATCH_NOT_FOUND_SELECTOR)),
+        _ => false,
+    }
+}
+
+/// Clap value parser for `--rpc-header "Name: Value"`
+pub fn parse_rpc_header(input: &str) -> std::result::Result<(String, String), String> {
+    // The input is not echoed back, as it may hold an API key
+    let (name, value) = input
+        .split_once(':')
+        .ok_or_else(|| "expected 'Name: Value'".to_string())?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("header name cannot be empty".to_string());
+    }
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
+/// Cached and uncached chunks in one contract's fetch range, for `--dry-run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkPlan {
+    pub contract: String,
+    pub from_block: u64,
+    pub to_block: u64,
+    pub cached_chunks: u64,
+    pub uncached_chunks: u64,
+}
+
+/// Machine-readable summary of a fetch run, for `--report-json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FetchReport {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub events_fetched: usize,
+    /// `BatchCreated` events among the fetched events
+    pub batches_fetched: usize,
+    pub chunks_cached: u64,
+    pub chunks_fetched: u64,
+    pub duration_ms: u64,
+    pub per_contract: BTreeMap<String, ContractFetchReport>,
+}
+
+/// Per-contract counts of a [`FetchReport`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ContractFetchReport {
+    pub events_fetched: usize,
+    pub chunks_cached: u64,
+    pub chunks_fetched: u64,
+}
+
+impl FetchReport {
+    fn new(from_block: u64, to_block: u64) -> Self {
+        Self {
+            from_block,
+            to_block,
+            ..Self::default()
+        }
+    }
+
+    /// Count one chunk of `contract`, either skipped as cached or fetched with `events` events
+    fn record_chunk(&mut self, contract: &str, cached: bool, events: usize) {
+        let entry = self.per_contract.entry(contract.to_string()).or_default();
+        if cached {
+            self.chunks_cached += 1;
+            entry.chunks_cached += 1;
+        } else {
+            self.chunks_fetched += 1;
+            entry.chunks_fetched += 1;
+        }
+        self.events_fetched += events;
+        entry.events_fetched += events;
+    }
+
+    /// Fold the report of a later run over the same range into this one
+    pub fn merge(&mut self, other: FetchReport) {
+        self.from_block = self.from_block.min(other.from_block);
+        self.to_block = self.to_block.max(other.to_block);
+        self.events_fetched += other.events_fetched;
+        self.batches_fetched += other.batches_fetched;
+        self.chunks_cached += other.chunks_cached;
+        self.chunks_fetched += other.chunks_fetched;
+        self.duration_ms += other.duration_ms;
+        for (contract, counts) in other.per_contract {
+            let entry = self.per_contract.entry(contract).or_default();
+            entry.events_fetched += counts.events_fetched;
+            entry.chunks_cached += counts.chunks_cached;
+            entry.chunks_fetched += counts.chunks_fetched;
+        }
+    }
+}
+
+/// Default staleness window for [`BlockchainClient::get_current_block_cached`]
+pub const DEFAULT_BLOCK_CACHE_TTL: Duration = Duration::from_secs(12);
 
 #[derive(Clone)]
 pub struct BlockchainClient {
     provider: RootProvider<Http<Client>>,
+    /// Latest block number and when it was fetched, shared between clones
+    current_block: Arc<Mutex<Option<(u64, Instant)>>>,
+    block_cache_ttl: Duration,
+    /// Counters for RPC requests and fetched events, shared between clones
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl BlockchainClient {
     /// Create a new blockchain client
+    #[cfg(test)]
     pub async fn new(rpc_url: &str) -> Result<Self> {
-        let provider = ProviderBuilder::new().on_http(
-            rpc_url
-                .parse()
-                .map_err(|e| StampError::Rpc(format!("Invalid RPC URL: {e}")))?,
-        );
+        Self::new_with_headers(rpc_url, Vec::new()).await
+    }
+
+    /// Create a blockchain client that sends `headers` with every RPC request
+    ///
+    /// Used for providers that take an API key in a header, such as
+    /// `Authorization: Bearer ...`. Header values are never logged.
+    pub async fn new_with_headers(rpc_url: &str, headers: Vec<(String, String)>) -> Result<Self> {
+        use alloy::transports::http::reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 
-        Ok(Self { provider })
+        let url: alloy::transports::http::reqwest::Url = rpc_url
+            .parse()
+            .map_err(|e| StampError::Rpc(format!("Invalid RPC URL: {e}")))?;
+
+        let provider = if headers.is_empty() {
+            ProviderBuilder::new().on_http(url)
+        } else {
+            let mut header_map = HeaderMap::new();
+            for (name, value) in &headers {
+                let header_name = HeaderName::from_str(name)
+                    .map_err(|e| StampError::Config(format!("Invalid RPC header name '{name}': {e}")))?;
+                let mut header_value = HeaderValue::from_str(value)
+                    .map_err(|_| StampError::Config(format!("Invalid value for RPC header '{name}'")))?;
+                header_value.set_sensitive(true);
+                header_map.insert(header_name, header_value);
+            }
+            let names: Vec<&str> = header_map.keys().map(|name| name.as_str()).collect();
+            tracing::debug!("Sending RPC headers: {} (values redacted)", names.join(", "));
+
+            let http_client = Client::builder()
+                .default_headers(header_map)
+                .build()
+                .map_err(|e| StampError::Config(format!("Failed to build RPC HTTP client: {e}")))?;
+            let is_local = alloy::transports::utils::guess_local_url(&url);
+            let rpc_client = alloy::rpc::client::ClientBuilder::default()
+                .transport(Http::with_client(http_client, url), is_local);
+            ProviderBuilder::new().on_client(rpc_client)
+        };
+
+        Ok(Self {
+            provider,
+            current_block: Arc::new(Mutex::new(None)),
+            block_cache_ttl: DEFAULT_BLOCK_CACHE_TTL,
+            metrics: None,
+        })
+    }
+
+    /// Set how long a fetched block number is reused by `get_current_block_cached`
+    pub fn with_block_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.block_cache_ttl = ttl;
+        self
+    }
+
+    /// Record RPC requests and fetched events in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Count one RPC request in the attached metrics, if any
+    fn record_rpc_request(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_rpc_request();
+        }
+    }
+
+    /// Count one fetched event in the attached metrics, if any
+    fn record_event(&self, event_type: &str, contract: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_event(event_type, contract);
+        }
     }
 
     /// Fetch all batch-related events from all configured contracts
@@ -39,6 +220,9 @@ impl BlockchainClient {
     /// to store events incrementally to avoid data loss on interruption.
     ///
     /// If `refresh` is true, cached chunks will be reprocessed (useful after adding new event types).
+    /// Only contracts named in `contracts` are fetched, or all of them when it is empty.
+    /// The events are returned with a [`FetchReport`] counting them and the chunks visited.
+    /// Each contract's sync checkpoint advances as its chunks complete.
     #[allow(clippy::too_many_arguments)]
     pub async fn fetch_batch_events<F, Fut>(
         &self,
@@ -49,16 +233,20 @@ impl BlockchainClient {
         blockchain_config: &BlockchainConfig,
         retry_config: &RetryConfig,
         refresh: bool,
+        contracts: &[String],
         on_chunk_complete: F,
-    ) -> Result<Vec<StampEvent>>
+    ) -> Result<(Vec<StampEvent>, FetchReport)>
     where
         F: Fn(Vec<StampEvent>) -> Fut + Copy,
         Fut: std::future::Future<Output = Result<()>>,
     {
+        let started = Instant::now();
+        let to_block = self.resolve_to_block(to_block).await?;
+        let mut report = FetchReport::new(from_block, to_block);
         let mut all_events = Vec::new();
 
-        // Fetch events from each contract
-        for contract in registry.all() {
+        // Fetch events from each selected contract
+        for contract in registry.all().iter().filter(|c| is_selected(contracts, c.name())) {
             let events = self
                 .fetch_contract_events(
                     contract.as_ref(),
@@ -68,20 +256,101 @@ impl BlockchainClient {
                     blockchain_config,
                     retry_config,
                     refresh,
+                    &mut report,
                     on_chunk_complete,
                 )
                 .await?;
             all_events.extend(events);
         }
 
-        // Sort by block number and log index
+        // Sort by block number and log index, with the transaction hash as a
+        // tiebreaker so the order matches `Cache::get_events`
         all_events.sort_by(|a, b| {
             a.block_number
                 .cmp(&b.block_number)
                 .then(a.log_index.cmp(&b.log_index))
+                .then_with(|| a.transaction_hash.cmp(&b.transaction_hash))
         });
 
-        Ok(all_events)
+        report.batches_fetched = all_events
+            .iter()
+            .filter(|event| matches!(event.event_type, EventType::BatchCreated))
+            .count();
+        report.duration_ms = started.elapsed().as_millis() as u64;
+
+        Ok((all_events, report))
+    }
+
+    /// Where a chunked fetch starting at `from_block` picks up, given the contract's stored cursor
+    ///
+    /// Returns the first block to fetch and the number of chunks already done.
+    /// The cursor is only trusted when it was written by a fetch over the same
+    /// chunk grid; otherwise the fetch starts at `from_block` and relies on the
+    /// chunk cache alone.
+    fn resume_point(cursor: Option<FetchCursor>, from_block: u64, chunk_size: u64) -> (u64, u64) {
+        match cursor {
+            Some(cursor)
+                if cursor.from_block == from_block
+                    && cursor.chunk_size == chunk_size
+                    && cursor.last_completed_to_block >= from_block =>
+            {
+                let resume_from = cursor.last_completed_to_block + 1;
+                (resume_from, (resume_from - from_block).div_ceil(chunk_size))
+            }
+            _ => (from_block, 0),
+        }
+    }
+
+    /// Pick up a chunked fetch of one contract where the last run left off
+    ///
+    /// Returns the first block to fetch, the number of chunks already done and
+    /// the cursor to advance as chunks complete. Chunks skipped by the resume
+    /// are counted as cached in `report`; `refresh` ignores the stored cursor.
+    #[allow(clippy::too_many_arguments)]
+    async fn resume_fetch(
+        cache: &Cache,
+        name: &str,
+        address: &str,
+        from_block: u64,
+        chunk_size: u64,
+        total_chunks: u64,
+        refresh: bool,
+        report: &mut FetchReport,
+    ) -> Result<(u64, u64, FetchCursor)> {
+        let cursor = if refresh {
+            None
+        } else {
+            cache.get_fetch_cursor(address).await?
+        };
+        let (current_from, chunk_num) = Self::resume_point(cursor, from_block, chunk_size);
+        let chunk_num_resumed = chunk_num.min(total_chunks);
+        if chunk_num_resumed > 0 {
+            tracing::info!(
+                "  {} - Resuming after chunk {}/{} (block {})",
+                name,
+                chunk_num_resumed,
+                total_chunks,
+                current_from - 1
+            );
+            for _ in 0..chunk_num_resumed {
+                report.record_chunk(name, true, 0);
+            }
+        }
+        let cursor = FetchCursor {
+            from_block,
+            chunk_size,
+            last_completed_to_block: current_from.saturating_sub(1),
+        };
+        Ok((current_from, chunk_num, cursor))
+    }
+
+    /// Mark the chunk ending at `to_block` as fully stored
+    ///
+    /// Advances the contract's sync checkpoint and its fetch cursor together.
+    async fn complete_chunk(cache: &Cache, address: &str, cursor: &mut FetchCursor, to_block: u64) -> Result<()> {
+        cache.set_sync_checkpoint(address, to_block).await?;
+        cursor.last_completed_to_block = to_block;
+        cache.set_fetch_cursor(address, cursor).await
     }
 
     /// Generate a cache key for a chunk request
@@ -94,12 +363,109 @@ impl BlockchainClient {
         format!("{result:x}")
     }
 
+    /// Plan a `fetch_batch_events` run without fetching anything
+    ///
+    /// Walks the same chunks but only checks which are already cached: no logs
+    /// are requested and nothing is written to the cache. The latest block is
+    /// looked up when `to_block` is `u64::MAX`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn plan_batch_events(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        cache: &Cache,
+        registry: &ContractRegistry,
+        blockchain_config: &BlockchainConfig,
+        refresh: bool,
+        contracts: &[String],
+    ) -> Result<Vec<ChunkPlan>> {
+        let to_block = self.resolve_to_block(to_block).await?;
+
+        let mut plans = Vec::new();
+        for contract in registry.all().iter().filter(|c| is_selected(contracts, c.name())) {
+            if let Some((from, to)) = contract.fetch_window(from_block, to_block) {
+                let plan = Self::plan_chunks(cache, contract.name(), contract.address(), from, to, blockchain_config, refresh);
+                plans.push(plan.await?);
+            }
+        }
+        Ok(plans)
+    }
+
+    /// Plan a `fetch_storage_incentives_events` run without fetching anything
+    ///
+    /// See [`Self::plan_batch_events`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn plan_storage_incentives_events(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        cache: &Cache,
+        registry: &StorageIncentivesContractRegistry,
+        blockchain_config: &BlockchainConfig,
+        refresh: bool,
+        contracts: &[String],
+    ) -> Result<Vec<ChunkPlan>> {
+        let to_block = self.resolve_to_block(to_block).await?;
+
+        let mut plans = Vec::new();
+        for contract in registry.all().iter().filter(|c| is_selected(contracts, c.name())) {
+            let from = std::cmp::max(from_block, contract.deployment_block());
+            if from <= to_block {
+                let plan = Self::plan_chunks(cache, contract.name(), contract.address(), from, to_block, blockchain_config, refresh);
+                plans.push(plan.await?);
+            }
+        }
+        Ok(plans)
+    }
+
+    /// Replace the `u64::MAX` "latest" placeholder with the current block
+    async fn resolve_to_block(&self, to_block: u64) -> Result<u64> {
+        if to_block == u64::MAX {
+            self.get_current_block().await
+        } else {
+            Ok(to_block)
+        }
+    }
+
+    /// Count the cached and uncached chunks of one contract's range
+    async fn plan_chunks(
+        cache: &Cache,
+        contract_name: &str,
+        contract_address: &str,
+        from_block: u64,
+        to_block: u64,
+        blockchain_config: &BlockchainConfig,
+        refresh: bool,
+    ) -> Result<ChunkPlan> {
+        let mut plan = ChunkPlan {
+            contract: contract_name.to_string(),
+            from_block,
+            to_block,
+            cached_chunks: 0,
+            uncached_chunks: 0,
+        };
+
+        let mut current_from = from_block;
+        while current_from <= to_block {
+            let current_to = std::cmp::min(current_from + blockchain_config.chunk_size - 1, to_block);
+            let chunk_hash = Self::generate_chunk_hash(contract_address, current_from, current_to);
+            if !refresh && cache.is_chunk_cached(&chunk_hash).await? {
+                plan.cached_chunks += 1;
+            } else {
+                plan.uncached_chunks += 1;
+            }
+            current_from = current_to + 1;
+        }
+        Ok(plan)
+    }
+
     /// Fetch events from a specific contract
     ///
     /// The `on_chunk_complete` callback is called after each chunk is fetched with the events
     /// from that chunk, allowing for incremental storage.
     ///
     /// If `refresh` is true, cached chunks will be reprocessed (useful after adding new event types).
+    /// `to_block` must already be resolved; every chunk is counted in `report`.
     #[allow(clippy::too_many_arguments)]
     async fn fetch_contract_events<F, Fut>(
         &self,
@@ -110,6 +476,7 @@ impl BlockchainClient {
         blockchain_config: &BlockchainConfig,
         retry_config: &RetryConfig,
         refresh: bool,
+        report: &mut FetchReport,
         on_chunk_complete: F,
     ) -> Result<Vec<StampEvent>>
     where
@@ -121,31 +488,22 @@ impl BlockchainClient {
 
         let mut events = Vec::new();
         let mut block_cache: HashMap<u64, Block> = HashMap::new();
+        let mut seen_events: HashSet<(String, u64)> = HashSet::new();
 
-        // Determine the actual to_block
-        let to_block = if to_block == u64::MAX {
-            tracing::debug!("RPC: get_block_number()");
-            self.provider
-                .get_block_number()
-                .await
-                .map_err(|e| StampError::Rpc(format!("Failed to get latest block: {e}")))?
-        } else {
-            to_block
-        };
-
-        // Adjust from_block to not start before contract deployment
+        // Clamp the range to the blocks this contract version was live for
         let deployment_block = contract.deployment_block();
-        let adjusted_from_block = std::cmp::max(from_block, deployment_block);
-
-        // Skip if the requested range is entirely before deployment
-        if adjusted_from_block > to_block {
+        let Some((adjusted_from_block, to_block)) = contract.fetch_window(from_block, to_block) else {
             tracing::info!(
-                "Skipping {} - contract deployed at block {} (after requested range)",
+                "Skipping {} at {} - live from block {} to {} (outside requested range)",
                 contract.name(),
-                deployment_block
+                contract.address(),
+                deployment_block,
+                contract
+                    .end_block()
+                    .map_or_else(|| "now".to_string(), |end| end.saturating_sub(1).to_string())
             );
             return Ok(events);
-        }
+        };
 
         tracing::info!(
             "Fetching {} events from block {} to {} (contract deployed at {})",
@@ -157,11 +515,22 @@ impl BlockchainClient {
 
         // Fetch events in chunks to avoid RPC limits
         let chunk_size = blockchain_config.chunk_size;
-        let mut current_from = adjusted_from_block;
 
         let total_blocks = to_block - adjusted_from_block + 1;
         let total_chunks = total_blocks.div_ceil(chunk_size);
-        let mut chunk_num = 0;
+
+        // Resume an interrupted fetch over the same range after its last completed chunk
+        let (mut current_from, mut chunk_num, mut cursor) = Self::resume_fetch(
+            cache,
+            contract.name(),
+            contract.address(),
+            adjusted_from_block,
+            chunk_size,
+            total_chunks,
+            refresh,
+            report,
+        )
+        .await?;
 
         while current_from <= to_block {
             let current_to = std::cmp::min(current_from + chunk_size - 1, to_block);
@@ -181,6 +550,8 @@ impl BlockchainClient {
                     current_from,
                     current_to
                 );
+                report.record_chunk(contract.name(), true, 0);
+                Self::complete_chunk(cache, contract.address(), &mut cursor, current_to).await?;
                 current_from = current_to + 1;
                 continue;
             }
@@ -209,9 +580,11 @@ impl BlockchainClient {
             );
             let provider = &self.provider;
             let logs = retry_config
-                .execute(|| async { provider.get_logs(&filter).await })
-                .await
-                .map_err(StampError::Rpc)?;
+                .execute(|| async {
+                    self.record_rpc_request();
+                    provider.get_logs(&filter).await
+                })
+                .await?;
 
             if !logs.is_empty() {
                 tracing::info!(
@@ -222,7 +595,6 @@ impl BlockchainClient {
             }
 
             // Parse each log
-            let chunk_event_count = events.len();
             let mut chunk_events = Vec::new();
             for log in logs {
                 if let Some(event) = self
@@ -235,11 +607,27 @@ impl BlockchainClient {
                     )
                     .await?
                 {
-                    chunk_events.push(event.clone());
-                    events.push(event);
+                    chunk_events.push(event);
                 }
             }
-            let parsed_events = events.len() - chunk_event_count;
+
+            // Drop logs already returned by an earlier chunk in this run
+            let duplicates = dedupe_run_events(&mut seen_events, &mut chunk_events);
+            if duplicates > 0 {
+                tracing::warn!(
+                    "    {} - {} duplicate log(s) in blocks {} to {} already seen in this run (overlapping provider response?)",
+                    contract.name(),
+                    duplicates,
+                    current_from,
+                    current_to
+                );
+            }
+            for event in &chunk_events {
+                self.record_event(&event.event_type.to_string(), &event.contract_source);
+            }
+            events.extend(chunk_events.iter().cloned());
+            let parsed_events = chunk_events.len();
+            report.record_chunk(contract.name(), false, parsed_events);
 
             // Cache this chunk
             cache
@@ -257,6 +645,9 @@ impl BlockchainClient {
                 on_chunk_complete(chunk_events).await?;
             }
 
+            // Only now is the chunk fully stored, even if it had no events
+            Self::complete_chunk(cache, contract.address(), &mut cursor, current_to).await?;
+
             current_from = current_to + 1;
         }
 
@@ -312,6 +703,7 @@ impl BlockchainClient {
             let provider = &self.provider;
             let fetched_block = retry_config
                 .execute(|| async {
+                    self.record_rpc_request();
                     let block = provider
                         .get_block_by_number(block_number.into(), BlockTransactionsKind::Hashes)
                         .await
@@ -328,8 +720,7 @@ impl BlockchainClient {
                         })?;
                     Ok::<Block, std::io::Error>(block)
                 })
-                .await
-                .map_err(StampError::Rpc)?;
+                .await?;
 
             let timestamp = fetched_block.header.timestamp;
 
@@ -347,6 +738,7 @@ impl BlockchainClient {
     ///
     /// Similar to fetch_batch_events but for PriceOracle, StakeRegistry, and Redistribution contracts.
     /// The `on_chunk_complete` callback is called after each chunk is fetched.
+    /// The events are returned with a [`FetchReport`] counting them and the chunks visited.
     #[allow(clippy::too_many_arguments)]
     pub async fn fetch_storage_incentives_events<F, Fut>(
         &self,
@@ -357,16 +749,20 @@ impl BlockchainClient {
         blockchain_config: &BlockchainConfig,
         retry_config: &RetryConfig,
         refresh: bool,
+        contracts: &[String],
         on_chunk_complete: F,
-    ) -> Result<Vec<StorageIncentivesEvent>>
+    ) -> Result<(Vec<StorageIncentivesEvent>, FetchReport)>
     where
         F: Fn(Vec<StorageIncentivesEvent>) -> Fut + Copy,
         Fut: std::future::Future<Output = Result<()>>,
     {
+        let started = Instant::now();
+        let to_block = self.resolve_to_block(to_block).await?;
+        let mut report = FetchReport::new(from_block, to_block);
         let mut all_events = Vec::new();
 
-        // Fetch events from each storage incentives contract
-        for contract in registry.all() {
+        // Fetch events from each selected storage incentives contract
+        for contract in registry.all().iter().filter(|c| is_selected(contracts, c.name())) {
             let events = self
                 .fetch_storage_incentives_contract_events(
                     contract.as_ref(),
@@ -376,6 +772,7 @@ impl BlockchainClient {
                     blockchain_config,
                     retry_config,
                     refresh,
+                    &mut report,
                     on_chunk_complete,
                 )
                 .await?;
@@ -389,12 +786,15 @@ impl BlockchainClient {
                 .then(a.log_index.cmp(&b.log_index))
         });
 
-        Ok(all_events)
+        report.duration_ms = started.elapsed().as_millis() as u64;
+
+        Ok((all_events, report))
     }
 
     /// Fetch events from a specific storage incentives contract
     ///
     /// If `refresh` is true, cached chunks will be reprocessed (useful after adding new event types).
+    /// `to_block` must already be resolved; every chunk is counted in `report`.
     #[allow(clippy::too_many_arguments)]
     async fn fetch_storage_incentives_contract_events<F, Fut>(
         &self,
@@ -405,6 +805,7 @@ impl BlockchainClient {
         blockchain_config: &BlockchainConfig,
         retry_config: &RetryConfig,
         refresh: bool,
+        report: &mut FetchReport,
         on_chunk_complete: F,
     ) -> Result<Vec<StorageIncentivesEvent>>
     where
@@ -416,17 +817,7 @@ impl BlockchainClient {
 
         let mut events = Vec::new();
         let mut block_cache: HashMap<u64, Block> = HashMap::new();
-
-        // Determine the actual to_block
-        let to_block = if to_block == u64::MAX {
-            tracing::debug!("RPC: get_block_number()");
-            self.provider
-                .get_block_number()
-                .await
-                .map_err(|e| StampError::Rpc(format!("Failed to get latest block: {e}")))?
-        } else {
-            to_block
-        };
+        let mut seen_events: HashSet<(String, u64)> = HashSet::new();
 
         // Adjust from_block to not start before contract deployment
         let deployment_block = contract.deployment_block();
@@ -452,11 +843,22 @@ impl BlockchainClient {
 
         // Fetch events in chunks to avoid RPC limits
         let chunk_size = blockchain_config.chunk_size;
-        let mut current_from = adjusted_from_block;
 
         let total_blocks = to_block - adjusted_from_block + 1;
         let total_chunks = total_blocks.div_ceil(chunk_size);
-        let mut chunk_num = 0;
+
+        // Resume an interrupted fetch over the same range after its last completed chunk
+        let (mut current_from, mut chunk_num, mut cursor) = Self::resume_fetch(
+            cache,
+            contract.name(),
+            contract.address(),
+            adjusted_from_block,
+            chunk_size,
+            total_chunks,
+            refresh,
+            report,
+        )
+        .await?;
 
         while current_from <= to_block {
             let current_to = std::cmp::min(current_from + chunk_size - 1, to_block);
@@ -476,6 +878,8 @@ impl BlockchainClient {
                     current_from,
                     current_to
                 );
+                report.record_chunk(contract.name(), true, 0);
+                Self::complete_chunk(cache, contract.address(), &mut cursor, current_to).await?;
                 current_from = current_to + 1;
                 continue;
             }
@@ -504,9 +908,11 @@ impl BlockchainClient {
             );
             let provider = &self.provider;
             let logs = retry_config
-                .execute(|| async { provider.get_logs(&filter).await })
-                .await
-                .map_err(StampError::Rpc)?;
+                .execute(|| async {
+                    self.record_rpc_request();
+                    provider.get_logs(&filter).await
+                })
+                .await?;
 
             if !logs.is_empty() {
                 tracing::info!(
@@ -517,7 +923,6 @@ impl BlockchainClient {
             }
 
             // Parse each log
-            let chunk_event_count = events.len();
             let mut chunk_events = Vec::new();
             for log in logs {
                 if let Some(event) = self
@@ -530,11 +935,27 @@ impl BlockchainClient {
                     )
                     .await?
                 {
-                    chunk_events.push(event.clone());
-                    events.push(event);
+                    chunk_events.push(event);
                 }
             }
-            let parsed_events = events.len() - chunk_event_count;
+
+            // Drop logs already returned by an earlier chunk in this run
+            let duplicates = dedupe_run_events(&mut seen_events, &mut chunk_events);
+            if duplicates > 0 {
+                tracing::warn!(
+                    "    {} - {} duplicate log(s) in blocks {} to {} already seen in this run (overlapping provider response?)",
+                    contract.name(),
+                    duplicates,
+                    current_from,
+                    current_to
+                );
+            }
+            for event in &chunk_events {
+                self.record_event(&event.event_type, &event.contract_source);
+            }
+            events.extend(chunk_events.iter().cloned());
+            let parsed_events = chunk_events.len();
+            report.record_chunk(contract.name(), false, parsed_events);
 
             // Cache this chunk
             cache
@@ -552,6 +973,9 @@ impl BlockchainClient {
                 on_chunk_complete(chunk_events).await?;
             }
 
+            // Only now is the chunk fully stored, even if it had no events
+            Self::complete_chunk(cache, contract.address(), &mut cursor, current_to).await?;
+
             current_from = current_to + 1;
         }
 
@@ -607,6 +1031,7 @@ impl BlockchainClient {
             let provider = &self.provider;
             let fetched_block = retry_config
                 .execute(|| async {
+                    self.record_rpc_request();
                     let block = provider
                         .get_block_by_number(block_number.into(), BlockTransactionsKind::Hashes)
                         .await
@@ -623,8 +1048,7 @@ impl BlockchainClient {
                         })?;
                     Ok::<Block, std::io::Error>(block)
                 })
-                .await
-                .map_err(StampError::Rpc)?;
+                .await?;
 
             let timestamp = fetched_block.header.timestamp;
 
@@ -656,11 +1080,12 @@ impl BlockchainClient {
         let postage_stamp_contract = PostageStamp::new(contract_address, &self.provider);
 
         tracing::debug!("RPC: lastPrice()");
+        self.record_rpc_request();
         let price = postage_stamp_contract
             .lastPrice()
             .call()
             .await
-            .map_err(|e| StampError::Rpc(format!("Failed to get current price: {e}")))?;
+            .map_err(|e| classify_rpc_error(format!("Failed to get current price: {e}")))?;
 
         Ok(price._0 as u128)
     }
@@ -668,10 +1093,82 @@ impl BlockchainClient {
     /// Get current block number
     pub async fn get_current_block(&self) -> Result<u64> {
         tracing::debug!("RPC: get_block_number()");
+        self.record_rpc_request();
         self.provider
             .get_block_number()
             .await
-            .map_err(|e| StampError::Rpc(format!("Failed to get current block: {e}")))
+            .map_err(|e| classify_rpc_error(format!("Failed to get current block: {e}")))
+    }
+
+    /// Get the chain ID reported by the RPC endpoint
+    pub async fn get_chain_id(&self) -> Result<u64> {
+        tracing::debug!("RPC: get_chain_id()");
+        self.record_rpc_request();
+        self.provider
+            .get_chain_id()
+            .await
+            .map_err(|e| classify_rpc_error(format!("Failed to get chain ID: {e}")))
+    }
+
+    /// Fail with [`StampError::ChainMismatch`] unless the endpoint reports `expected_chain_id`
+    ///
+    /// Pointing at the wrong chain would otherwise just find no events at the
+    /// configured contract addresses.
+    pub async fn verify_chain(&self, expected_chain_id: u64) -> Result<()> {
+        let actual = self.get_chain_id().await?;
+        if actual != expected_chain_id {
+            return Err(StampError::ChainMismatch { expected: expected_chain_id, actual });
+        }
+        Ok(())
+    }
+
+    /// Get the deployed bytecode at an address
+    ///
+    /// Returns an empty vector when nothing is deployed there.
+    pub async fn get_code(&self, address: &str) -> Result<Vec<u8>> {
+        let address = Address::from_str(address)
+            .map_err(|e| StampError::Contract(format!("Invalid contract address: {e}")))?;
+
+        tracing::debug!("RPC: get_code_at({})", address);
+        self.record_rpc_request();
+        let code = self
+            .provider
+            .get_code_at(address)
+            .await
+            .map_err(|e| classify_rpc_error(format!("Failed to get code at {address}: {e}")))?;
+        Ok(code.to_vec())
+    }
+
+    /// Get current block number, reusing a recent result
+    ///
+    /// Returns the last fetched block number while it is younger than the
+    /// block cache TTL, so commands run in one process share a single RPC call.
+    /// Use `get_current_block` where the latest head is required.
+    pub async fn get_current_block_cached(&self) -> Result<u64> {
+        let cached = *self.current_block.lock().expect("block cache lock poisoned");
+        if let Some((block, fetched_at)) = cached
+            && fetched_at.elapsed() < self.block_cache_ttl
+        {
+            tracing::debug!("Using cached current block {}", block);
+            return Ok(block);
+        }
+
+        let block = self.get_current_block().await?;
+        *self.current_block.lock().expect("block cache lock poisoned") = Some((block, Instant::now()));
+        Ok(block)
+    }
+
+    /// Get the canonical hash of a block, or `None` if the node does not know the block
+    pub async fn get_block_hash(&self, block_number: u64) -> Result<Option<String>> {
+        tracing::debug!("RPC: get_block_by_number(block={})", block_number);
+        self.record_rpc_request();
+        let block = self
+            .provider
+            .get_block_by_number(block_number.into(), BlockTransactionsKind::Hashes)
+            .await
+            .map_err(|e| classify_rpc_error(format!("Failed to get block {block_number}: {e}")))?;
+
+        Ok(block.map(|b| to_hex(b.header.hash)))
     }
 
     /// Get remaining balance for a batch from the blockchain with retry logic
@@ -683,7 +1180,7 @@ impl BlockchainClient {
         registry: &ContractRegistry,
         retry_config: &RetryConfig,
     ) -> Result<String> {
-        use alloy::primitives::{Address, FixedBytes};
+        use alloy::primitives::Address;
 
         let contract = registry
             .find_balance_query_contract()
@@ -697,59 +1194,713 @@ impl BlockchainClient {
             .map_err(|e| StampError::Contract(format!("Invalid contract address: {e}")))?;
 
         // Parse batch ID as bytes32
-        let batch_id_bytes = FixedBytes::<32>::from_str(batch_id.trim_start_matches("0x"))
-            .map_err(|e| StampError::Parse(format!("Invalid batch ID: {e}")))?;
+        let batch_id_bytes = BatchId::new(batch_id)?.to_bytes();
 
         let postage_stamp_contract = PostageStamp::new(contract_address, &self.provider);
 
-        // Use retry policy for rate limit handling
+        // Use retry policy for rate limit handling; a missing batch is an answer, not a failure
         tracing::debug!("RPC: remainingBalance(batch_id={})", batch_id);
         retry_config
             .execute(|| async {
-                postage_stamp_contract
-                    .remainingBalance(batch_id_bytes)
-                    .call()
-                    .await
-                    .map(|balance| balance._0.to_string())
+                self.record_rpc_request();
+                match postage_stamp_contract.remainingBalance(batch_id_bytes).call().await {
+                    Ok(balance) => Ok(Ok(balance._0.to_string())),
+                    Err(e) if is_batch_not_found(&e) => Ok(Err(format!("Operation failed: {e}"))),
+                    Err(e) => Err(e),
+                }
+            })
+            .await?
+            .map_err(|message| StampError::BatchNotFound {
+                batch_id: batch_id.to_string(),
+                message,
             })
-            .await
-            .map_err(StampError::Rpc)
     }
 
     /// Fetch batch information for BatchCreated events
     pub async fn fetch_batch_info(&self, events: &[StampEvent]) -> Result<Vec<BatchInfo>> {
-        let mut batches = Vec::new();
-
-        for event in events {
-            if matches!(event.event_type, EventType::BatchCreated)
-                && let EventData::BatchCreated {
-                    owner,
-                    depth,
-                    bucket_depth,
-                    immutable_flag,
-                    normalised_balance,
-                    payer,
-                    ..
-                } = &event.data
-            {
-                batches.push(BatchInfo {
-                    batch_id: event.batch_id.clone().unwrap_or_default(),
-                    owner: owner.clone(),
-                    payer: payer.clone(),
-                    contract_source: event.contract_source.clone(),
-                    depth: *depth,
-                    bucket_depth: *bucket_depth,
-                    immutable: *immutable_flag,
-                    normalised_balance: normalised_balance.clone(),
-                    created_at: event.block_timestamp,
-                    block_number: event.block_number,
-                });
-            }
+        Ok(batch_info_from_events(events))
+    }
+}
+
+/// Build batch records from the BatchCreated events in `events`
+///
+/// Everything a batch record holds is carried by its creation event, so this
+/// needs no RPC access; `import` uses it to rebuild batches from exported events.
+/// Events whose batch ID is malformed are skipped with a warning.
+pub fn batch_info_from_events(events: &[StampEvent]) -> Vec<BatchInfo> {
+    let mut batches = Vec::new();
+
+    for event in events {
+        if matches!(event.event_type, EventType::BatchCreated)
+            && let EventData::BatchCreated {
+                owner,
+                depth,
+                bucket_depth,
+                immutable_flag,
+                normalised_balance,
+                payer,
+                ..
+            } = &event.data
+        {
+            let Some(batch_id) = &event.batch_id else {
+                continue;
+            };
+            let batch_id = match BatchId::new(batch_id) {
+                Ok(batch_id) => batch_id,
+                Err(e) => {
+                    tracing::warn!("Skipping batch record at block {}: {}", event.block_number, e);
+                    continue;
+                }
+            };
+            batches.push(BatchInfo {
+                batch_id,
+                owner: owner.clone(),
+                payer: payer.clone(),
+                contract_source: event.contract_source.clone(),
+                depth: *depth,
+                bucket_depth: *bucket_depth,
+                immutable: *immutable_flag,
+                normalised_balance: normalised_balance.clone(),
+                created_at: event.block_timestamp,
+                block_number: event.block_number,
+            });
         }
+    }
+
+    batches
+}
 
-        Ok(batches)
+/// Number of most recent event blocks re-checked for reorgs on each poll
+pub const REORG_CHECK_DEPTH: usize = 64;
+
+/// Detect a chain reorganization affecting cached events and roll the cache back
+///
+/// Compares the stored hashes of the most recent event blocks, newest first, with
+/// `canonical_hash` until one matches. If any differ, everything at or above the
+/// first divergent block is removed via `Cache::rollback_to_block` and that block
+/// is returned so the caller can re-fetch from it. A block the node does not know
+/// yet (`None`, e.g. a lagging backend) ends the scan without counting as divergent.
+pub async fn detect_reorg<F, Fut>(cache: &Cache, canonical_hash: F) -> Result<Option<u64>>
+where
+    F: Fn(u64) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<String>>>,
+{
+    let mut first_divergent = None;
+
+    for (block_number, stored_hash) in cache.get_recent_block_hashes(REORG_CHECK_DEPTH).await? {
+        let Some(hash) = canonical_hash(block_number).await? else {
+            break;
+        };
+        if hash.eq_ignore_ascii_case(&stored_hash) {
+            break;
+        }
+        first_divergent = Some(block_number);
     }
+
+    if let Some(block_number) = first_divergent {
+        let removed = cache.rollback_to_block(block_number).await?;
+        tracing::warn!(
+            "Chain reorg detected at block {}: rolled back {} cached events",
+            block_number,
+            removed
+        );
+    }
+
+    Ok(first_divergent)
 }
 
 // Note: Integration tests with actual RPC would go in tests/ directory
 // to avoid making network calls during unit tests
+
+/// Whether a `--contract` value names the contract called `name`
+///
+/// Case and dashes are ignored, so `stamps-registry` matches `StampsRegistry`.
+pub fn contract_name_matches(selected: &str, name: &str) -> bool {
+    selected.replace('-', "").eq_ignore_ascii_case(name)
+}
+
+/// Whether a `--contract` selection includes the contract called `name`
+///
+/// An empty selection includes every contract.
+pub fn is_selected(contracts: &[String], name: &str) -> bool {
+    contracts.is_empty() || contracts.iter().any(|selected| contract_name_matches(selected, name))
+}
+
+/// An event parsed from a single log
+trait LogEvent {
+    /// `(transaction_hash, log_index)`, which uniquely identifies the source log
+    fn log_key(&self) -> (String, u64);
+}
+
+impl LogEvent for StampEvent {
+    fn log_key(&self) -> (String, u64) {
+        (self.transaction_hash.clone(), self.log_index)
+    }
+}
+
+impl LogEvent for StorageIncentivesEvent {
+    fn log_key(&self) -> (String, u64) {
+        (self.transaction_hash.clone(), self.log_index)
+    }
+}
+
+/// Remove events already seen earlier in the same fetch run
+///
+/// Events are keyed on their source log. Newly seen keys are recorded in
+/// `seen`. Returns the number of duplicates removed.
+fn dedupe_run_events<E: LogEvent>(seen: &mut HashSet<(String, u64)>, events: &mut Vec<E>) -> usize {
+    let before = events.len();
+    events.retain(|event| seen.insert(event.log_key()));
+    before - events.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedupe_overlapping_chunks() {
+        let mut seen = HashSet::new();
+
+        // First chunk covers blocks 100-110
+        let mut chunk1 = vec![
+            StampEvent::test_topup("0xaa", 0, 100),
+            StampEvent::test_topup("0xaa", 1, 100),
+            StampEvent::test_topup("0xbb", 0, 110),
+        ];
+        assert_eq!(dedupe_run_events(&mut seen, &mut chunk1), 0);
+        assert_eq!(chunk1.len(), 3);
+
+        // Second chunk overlaps at block 110 and repeats its log
+        let mut chunk2 = vec![StampEvent::test_topup("0xbb", 0, 110), StampEvent::test_topup("0xcc", 0, 115)];
+        assert_eq!(dedupe_run_events(&mut seen, &mut chunk2), 1);
+        assert_eq!(chunk2.len(), 1);
+        assert_eq!(chunk2[0].transaction_hash, "0xcc");
+    }
+
+    #[test]
+    fn test_dedupe_within_single_chunk() {
+        let mut seen = HashSet::new();
+        let mut chunk = vec![
+            StampEvent::test_topup("0xaa", 0, 100),
+            StampEvent::test_topup("0xaa", 0, 100),
+            StampEvent::test_topup("0xaa", 1, 100),
+        ];
+        assert_eq!(dedupe_run_events(&mut seen, &mut chunk), 1);
+        assert_eq!(chunk.len(), 2);
+    }
+
+    #[test]
+    fn test_dedupe_storage_incentives_events() {
+        let event = |tx: &str, log_index: u64| StorageIncentivesEvent {
+            transaction_hash: tx.to_string(),
+            log_index,
+            ..Default::default()
+        };
+        let mut seen = HashSet::new();
+
+        let mut chunk1 = vec![event("0xaa", 0), event("0xaa", 1)];
+        assert_eq!(dedupe_run_events(&mut seen, &mut chunk1), 0);
+
+        // An overlapping response repeats a PriceOracle/Redistribution log
+        let mut chunk2 = vec![event("0xaa", 1), event("0xbb", 0)];
+        assert_eq!(dedupe_run_events(&mut seen, &mut chunk2), 1);
+        assert_eq!(chunk2.len(), 1);
+        assert_eq!(chunk2[0].transaction_hash, "0xbb");
+    }
+
+    fn hashed_event(tx: &str, block_number: u64, block_hash: &str) -> StampEvent {
+        StampEvent {
+            block_hash: Some(block_hash.to_string()),
+            ..StampEvent::test_topup(tx, 0, block_number)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_reorg_rolls_back_and_refetches() {
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        cache
+            .store_events(&[
+                hashed_event("0x01", 100, "0xa100"),
+                hashed_event("0x02", 101, "0xa101"),
+                hashed_event("0x03", 102, "0xa102"),
+            ])
+            .await
+            .unwrap();
+        cache.cache_chunk("chunk", "0xcontract", 100, 102, 3).await.unwrap();
+
+        // Blocks 101 and 102 were orphaned; 100 is still canonical
+        let chain: HashMap<u64, &str> =
+            HashMap::from([(100, "0xA100"), (101, "0xb101"), (102, "0xb102")]);
+        let canonical_hash = |n: u64| {
+            let hash = chain.get(&n).map(|h| h.to_string());
+            async move { Ok(hash) }
+        };
+
+        assert_eq!(detect_reorg(&cache, canonical_hash).await.unwrap(), Some(101));
+        assert_eq!(cache.get_last_block().await.unwrap(), Some(100));
+        assert!(!cache.is_chunk_cached("chunk").await.unwrap());
+
+        // Re-fetching the canonical block makes the cache consistent again
+        cache.store_events(&[hashed_event("0x04", 101, "0xb101")]).await.unwrap();
+        assert_eq!(detect_reorg(&cache, canonical_hash).await.unwrap(), None);
+        assert_eq!(cache.count_events().await.unwrap(), 2);
+
+        // A node that does not know the newest block yet is not a reorg
+        let lagging = |n: u64| {
+            let hash = (n < 101).then(|| "0xa100".to_string());
+            async move { Ok(hash) }
+        };
+        assert_eq!(detect_reorg(&cache, lagging).await.unwrap(), None);
+        assert_eq!(cache.count_events().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_plan_batch_events_counts_cached_chunks_without_fetching() {
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+        // No mocks: any RPC request would fail the plan
+        let server = mockito::Server::new_async().await;
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+
+        let registry = ContractRegistry::from_config(&crate::config::AppConfig::default()).unwrap();
+        let contract = &registry.all()[0];
+        let from = contract.deployment_block();
+        let config = BlockchainConfig { chunk_size: 100, block_time_seconds: 5.0, chain_id: None };
+
+        // The first of three chunks was fetched by an earlier run
+        let hash = BlockchainClient::generate_chunk_hash(contract.address(), from, from + 99);
+        cache.cache_chunk(&hash, contract.address(), from, from + 99, 0).await.unwrap();
+
+        let plan_for = |plans: Vec<ChunkPlan>| plans.into_iter().find(|plan| plan.contract == contract.name()).unwrap();
+        let plans = client.plan_batch_events(from, from + 249, &cache, &registry, &config, false, &[]).await.unwrap();
+        assert_eq!(
+            plan_for(plans),
+            ChunkPlan {
+                contract: contract.name().to_string(),
+                from_block: from,
+                to_block: from + 249,
+                cached_chunks: 1,
+                uncached_chunks: 2,
+            }
+        );
+
+        let plans = client.plan_batch_events(from, from + 249, &cache, &registry, &config, true, &[]).await.unwrap();
+        assert_eq!(plan_for(plans).uncached_chunks, 3);
+
+        // Planning writes nothing
+        assert_eq!(cache.get_cached_ranges(contract.address()).await.unwrap(), vec![(from, from + 99)]);
+        assert_eq!(cache.count_events().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_report_matches_fetched_events() {
+        use alloy::primitives::{B256, U256};
+        use alloy::sol_types::SolEvent;
+
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+        let registry = ContractRegistry::from_config(&crate::config::AppConfig::default()).unwrap();
+        let contract = &registry.all()[0];
+        let address = Address::from_str(contract.address()).unwrap();
+        let from = contract.deployment_block();
+        let block = from + 150;
+
+        let log = |data: alloy::primitives::LogData, tx: u8, log_index: u64| Log {
+            inner: alloy::primitives::Log { address, data },
+            block_number: Some(block),
+            transaction_hash: Some(B256::repeat_byte(tx)),
+            log_index: Some(log_index),
+            ..Default::default()
+        };
+        let created = PostageStamp::BatchCreated {
+            batchId: B256::repeat_byte(0x01),
+            totalAmount: U256::from(1000u64),
+            normalisedBalance: U256::from(500u64),
+            owner: Address::repeat_byte(0xab),
+            depth: 20,
+            bucketDepth: 16,
+            immutableFlag: false,
+        };
+        let topup = PostageStamp::BatchTopUp {
+            batchId: B256::repeat_byte(0x01),
+            topupAmount: U256::from(10u64),
+            normalisedBalance: U256::from(510u64),
+        };
+        let logs = vec![log(created.encode_log_data(), 0xa1, 0), log(topup.encode_log_data(), 0xa2, 1)];
+
+        let mut rpc_block: Block = Block::default();
+        rpc_block.header.inner.number = block;
+        rpc_block.header.inner.timestamp = 1_700_000_000;
+
+        let rpc_mock = |server: &mut mockito::Server, method: &str, result: serde_json::Value| {
+            server
+                .mock("POST", "/")
+                .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "method": method })))
+                .with_header("content-type", "application/json")
+                .with_body(serde_json::json!({ "jsonrpc": "2.0", "id": 0, "result": result }).to_string())
+        };
+        let mut server = mockito::Server::new_async().await;
+        let logs_mock = rpc_mock(&mut server, "eth_getLogs", serde_json::to_value(&logs).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+        let _block_mock = rpc_mock(&mut server, "eth_getBlockByNumber", serde_json::to_value(&rpc_block).unwrap())
+            .create_async()
+            .await;
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+
+        // The first of the two chunks was fetched by an earlier run
+        let hash = BlockchainClient::generate_chunk_hash(contract.address(), from, from + 99);
+        cache.cache_chunk(&hash, contract.address(), from, from + 99, 0).await.unwrap();
+
+        let config = BlockchainConfig { chunk_size: 100, block_time_seconds: 5.0, chain_id: None };
+        let retry = RetryConfig { initial_delay_ms: 1, ..RetryConfig::default() };
+        let (events, mut report) = client
+            .fetch_batch_events(from, from + 199, &cache, &registry, &config, &retry, false, &[], |_events: Vec<StampEvent>| async {
+                Ok(())
+            })
+            .await
+            .unwrap();
+        logs_mock.assert_async().await;
+
+        assert_eq!(events.len(), 2);
+        report.duration_ms = 0;
+        assert_eq!(
+            report,
+            FetchReport {
+                from_block: from,
+                to_block: from + 199,
+                events_fetched: events.len(),
+                batches_fetched: 1,
+                chunks_cached: 1,
+                chunks_fetched: 1,
+                duration_ms: 0,
+                per_contract: BTreeMap::from([(
+                    contract.name().to_string(),
+                    ContractFetchReport { events_fetched: 2, chunks_cached: 1, chunks_fetched: 1 },
+                )]),
+            }
+        );
+
+        // Storage incentives runs fold into the same report
+        let mut merged = report.clone();
+        merged.merge(FetchReport {
+            events_fetched: 3,
+            chunks_fetched: 1,
+            per_contract: BTreeMap::from([("PriceOracle".to_string(), ContractFetchReport { events_fetched: 3, chunks_cached: 0, chunks_fetched: 1 })]),
+            ..FetchReport::new(from, from + 199)
+        });
+        assert_eq!(merged.events_fetched, 5);
+        assert_eq!(merged.chunks_fetched, 2);
+        assert_eq!(merged.per_contract.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_batch_events_only_selected_contracts() {
+        use alloy::primitives::{B256, U256};
+        use alloy::sol_types::SolEvent;
+
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+        let registry = ContractRegistry::from_config(&crate::config::AppConfig::default()).unwrap();
+        let names: Vec<&str> = registry.all().iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["PostageStamp", "StampsRegistry"]);
+
+        // A range where both contracts are live
+        let postage_stamp = &registry.all()[0];
+        let from = registry.all().iter().map(|c| c.deployment_block()).max().unwrap();
+        let block = from + 50;
+
+        let topup = PostageStamp::BatchTopUp {
+            batchId: B256::repeat_byte(0x01),
+            topupAmount: U256::from(10u64),
+            normalisedBalance: U256::from(510u64),
+        };
+        let log = Log {
+            inner: alloy::primitives::Log {
+                address: Address::from_str(postage_stamp.address()).unwrap(),
+                data: topup.encode_log_data(),
+            },
+            block_number: Some(block),
+            transaction_hash: Some(B256::repeat_byte(0xa1)),
+            log_index: Some(0),
+            ..Default::default()
+        };
+        let mut rpc_block: Block = Block::default();
+        rpc_block.header.inner.number = block;
+        rpc_block.header.inner.timestamp = 1_700_000_000;
+
+        let mut server = mockito::Server::new_async().await;
+        // A single chunk is requested: StampsRegistry is never queried
+        let logs_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "method": "eth_getLogs" })))
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "jsonrpc": "2.0", "id": 0, "result": [log] }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let _block_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "method": "eth_getBlockByNumber" })))
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "jsonrpc": "2.0", "id": 0, "result": rpc_block }).to_string())
+            .create_async()
+            .await;
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+
+        let config = BlockchainConfig { chunk_size: 100, block_time_seconds: 5.0, chain_id: None };
+        let selected = ["postagestamp".to_string()];
+        let (events, report) = client
+            .fetch_batch_events(from, from + 99, &cache, &registry, &config, &RetryConfig::default(), false, &selected, |_events: Vec<StampEvent>| async {
+                Ok(())
+            })
+            .await
+            .unwrap();
+        logs_mock.assert_async().await;
+
+        assert_eq!(events.len(), 1);
+        assert!(events.iter().all(|event| event.contract_source == "PostageStamp"));
+        assert_eq!(report.per_contract.keys().collect::<Vec<_>>(), ["PostageStamp"]);
+    }
+
+    #[tokio::test]
+    async fn test_sync_checkpoint_advances_past_empty_ranges() {
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+        let registry = ContractRegistry::from_config(&crate::config::AppConfig::default()).unwrap();
+        let contract = &registry.all()[0];
+        let from = contract.deployment_block();
+
+        // Every chunk of the range is empty
+        let mut server = mockito::Server::new_async().await;
+        let logs_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "method": "eth_getLogs" })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":[]}"#)
+            .expect(3)
+            .create_async()
+            .await;
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+
+        let config = BlockchainConfig { chunk_size: 100, block_time_seconds: 5.0, chain_id: None };
+        let (events, _) = client
+            .fetch_batch_events(from, from + 299, &cache, &registry, &config, &RetryConfig::default(), false, &[], |_events: Vec<StampEvent>| async {
+                Ok(())
+            })
+            .await
+            .unwrap();
+        logs_mock.assert_async().await;
+
+        // No events mark the progress, but the checkpoint covers the whole range
+        assert!(events.is_empty());
+        assert_eq!(cache.get_last_block().await.unwrap(), None);
+        assert_eq!(cache.get_sync_checkpoint(contract.address()).await.unwrap(), Some(from + 299));
+    }
+
+    #[test]
+    fn test_resume_point_requires_same_chunk_grid() {
+        let cursor = |from_block, chunk_size, last| {
+            Some(FetchCursor { from_block, chunk_size, last_completed_to_block: last })
+        };
+
+        assert_eq!(BlockchainClient::resume_point(None, 1000, 100), (1000, 0));
+        // Two chunks done: resume at the third
+        assert_eq!(BlockchainClient::resume_point(cursor(1000, 100, 1199), 1000, 100), (1200, 2));
+        // A different start or chunk size is a different grid
+        assert_eq!(BlockchainClient::resume_point(cursor(900, 100, 1199), 1000, 100), (1000, 0));
+        assert_eq!(BlockchainClient::resume_point(cursor(1000, 50, 1199), 1000, 100), (1000, 0));
+    }
+
+    #[tokio::test]
+    async fn test_verify_chain_rejects_wrong_chain_id() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "method": "eth_chainId" })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1"}"#)
+            .create_async()
+            .await;
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+
+        let err = client.verify_chain(100).await.unwrap_err();
+        assert!(matches!(err, StampError::ChainMismatch { expected: 100, actual: 1 }));
+        assert!(err.to_string().contains("chain 1, expected chain 100"));
+        client.verify_chain(1).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_resumes_after_last_completed_chunk() {
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+        let registry = ContractRegistry::from_config(&crate::config::AppConfig::default()).unwrap();
+        let contract = &registry.all()[0];
+        let from = contract.deployment_block();
+        let config = BlockchainConfig { chunk_size: 100, block_time_seconds: 5.0, chain_id: None };
+        let selected = ["postagestamp".to_string()];
+        let chunks = |numbers: &[u64]| {
+            let starts: Vec<String> = numbers.iter().map(|n| format!("{:#x}", from + (n - 1) * 100)).collect();
+            mockito::Matcher::Regex(format!(r#""fromBlock":"({})""#, starts.join("|")))
+        };
+
+        // First run dies at chunk 3 of 5
+        let mut server = mockito::Server::new_async().await;
+        let done = server
+            .mock("POST", "/")
+            .match_body(chunks(&[1, 2]))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":[]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(chunks(&[3]))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"error":{"code":-32000,"message":"killed"}}"#)
+            .create_async()
+            .await;
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+        let result = client
+            .fetch_batch_events(from, from + 499, &cache, &registry, &config, &RetryConfig::default(), false, &selected, |_events: Vec<StampEvent>| async {
+                Ok(())
+            })
+            .await;
+        assert!(result.is_err());
+        done.assert_async().await;
+
+        let cursor = cache.get_fetch_cursor(contract.address()).await.unwrap();
+        assert_eq!(cursor.map(|c| c.last_completed_to_block), Some(from + 199));
+        assert_eq!(BlockchainClient::resume_point(cursor, from, 100), (from + 200, 2));
+
+        // The restart never asks for chunks 1 and 2 again and starts at chunk 3
+        let mut server = mockito::Server::new_async().await;
+        let skipped = server
+            .mock("POST", "/")
+            .match_body(chunks(&[1, 2]))
+            .expect(0)
+            .create_async()
+            .await;
+        let remaining = server
+            .mock("POST", "/")
+            .match_body(chunks(&[3, 4, 5]))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":[]}"#)
+            .expect(3)
+            .create_async()
+            .await;
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+        let (_, report) = client
+            .fetch_batch_events(from, from + 499, &cache, &registry, &config, &RetryConfig::default(), false, &selected, |_events: Vec<StampEvent>| async {
+                Ok(())
+            })
+            .await
+            .unwrap();
+        skipped.assert_async().await;
+        remaining.assert_async().await;
+        assert_eq!((report.chunks_cached, report.chunks_fetched), (2, 3));
+        assert_eq!(cache.get_sync_checkpoint(contract.address()).await.unwrap(), Some(from + 499));
+    }
+
+    fn block_number_mock(server: &mut mockito::Server, block: u64) -> mockito::Mock {
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({ "method": "eth_blockNumber" }),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":"0x{block:x}"}}"#))
+    }
+
+    #[tokio::test]
+    async fn test_current_block_cached_within_ttl() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = block_number_mock(&mut server, 1000).expect(2).create_async().await;
+
+        let client = BlockchainClient::new(&server.url())
+            .await
+            .unwrap()
+            .with_block_cache_ttl(Duration::from_millis(200));
+
+        // Clones share the cache, so only the first call reaches the node
+        assert_eq!(client.get_current_block_cached().await.unwrap(), 1000);
+        assert_eq!(client.clone().get_current_block_cached().await.unwrap(), 1000);
+        assert_eq!(client.get_current_block_cached().await.unwrap(), 1000);
+
+        // Once the window passes the block number is fetched again
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(client.get_current_block_cached().await.unwrap(), 1000);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_current_block_uncached_always_fetches() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = block_number_mock(&mut server, 42).expect(2).create_async().await;
+
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+        assert_eq!(client.get_current_block_cached().await.unwrap(), 42);
+        assert_eq!(client.get_current_block().await.unwrap(), 42);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_rpc_headers_sent_with_requests() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = block_number_mock(&mut server, 7)
+            .match_header("authorization", "Bearer secret")
+            .match_header("x-api-key", "abc")
+            .create_async()
+            .await;
+
+        let headers = vec![
+            parse_rpc_header("Authorization: Bearer secret").unwrap(),
+            parse_rpc_header("X-Api-Key:abc").unwrap(),
+        ];
+        let client = BlockchainClient::new_with_headers(&server.url(), headers).await.unwrap();
+        assert_eq!(client.get_current_block().await.unwrap(), 7);
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_parse_rpc_header() {
+        assert_eq!(
+            parse_rpc_header("Authorization: Bearer a:b").unwrap(),
+            ("Authorization".to_string(), "Bearer a:b".to_string())
+        );
+        assert!(parse_rpc_header("Bearer secret").is_err());
+        assert!(parse_rpc_header(": value").is_err());
+    }
+
+    fn revert_error(data: &str) -> alloy::contract::Error {
+        let payload = serde_json::from_value(serde_json::json!({
+            "code": 3,
+            "message": "execution reverted",
+            "data": data,
+        }))
+        .unwrap();
+        alloy::contract::Error::TransportError(alloy::transports::RpcError::ErrorResp(payload))
+    }
+
+    #[test]
+    fn test_batch_not_found_revert_decoded() {
+        assert!(is_batch_not_found(&revert_error("0x4ee9bc0f")));
+
+        // Other reverts and non-revert failures are not a missing batch
+        assert!(!is_batch_not_found(&revert_error("0x08c379a0")));
+        assert!(!is_batch_not_found(&alloy::contract::Error::TransportError(
+            alloy::transports::TransportErrorKind::backend_gone()
+        )));
+        assert!(!is_batch_not_found(&alloy::contract::Error::UnknownFunction(
+            "remainingBalance".to_string()
+        )));
+    }
+}
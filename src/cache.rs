@@ -1,24 +1,293 @@
-use crate::error::Result;
-use crate::events::{BatchInfo, EventData, EventType, StampEvent, StorageIncentivesEvent};
+use crate::error::{QueryLimit, Result, StampError};
+use crate::events::{BatchInfo, EventData, EventType, RoundWinner, StampEvent, StorageIncentivesEvent};
+use crate::price::{NetworkStorage, PricePoint};
+use crate::types::{BatchId, ContractAddress};
 use chrono::{DateTime, Duration, Utc};
+use futures::TryStreamExt;
 use sqlx::Row;
+use std::collections::HashMap;
 use std::path::Path;
 
+/// Maximum bound parameters in one SQLite statement
+const SQLITE_MAX_PARAMS: usize = 999;
+
+/// Maximum bound parameters in one PostgreSQL statement
+const POSTGRES_MAX_PARAMS: usize = 65535;
+
+/// Maximum bound parameters in one MySQL statement
+const MYSQL_MAX_PARAMS: usize = 65535;
+
+/// Column values of one `events` row, ready to bind
+struct EventRow<'a> {
+    event_type: String,
+    batch_id: Option<&'a str>,
+    block_number: i64,
+    block_timestamp: i64,
+    transaction_hash: &'a str,
+    log_index: i64,
+    contract_source: &'a str,
+    contract_address: Option<&'a str>,
+    block_hash: Option<&'a str>,
+    data: String,
+    pot_recipient: Option<&'a str>,
+    pot_total_amount: Option<&'a str>,
+    price: Option<&'a str>,
+    copy_index: Option<&'a str>,
+    copy_batch_id: Option<&'a str>,
+}
+
+impl<'a> EventRow<'a> {
+    /// Number of bound parameters per row
+    const COLUMNS: usize = 15;
+
+    const COLUMN_LIST: &'static str = "event_type, batch_id, block_number, block_timestamp, transaction_hash, log_index, contract_source, contract_address, block_hash, data, pot_recipient, pot_total_amount, price, copy_index, copy_batch_id";
+
+    fn new(event: &'a StampEvent) -> Result<Self> {
+        // Extract event-specific data
+        let (pot_recipient, pot_total_amount, price, copy_index, copy_batch_id) = match &event.data {
+            EventData::PotWithdrawn { recipient, total_amount } => {
+                (Some(recipient.as_str()), Some(total_amount.as_str()), None, None, None)
+            }
+            EventData::PriceUpdate { price } => {
+                (None, None, Some(price.as_str()), None, None)
+            }
+            EventData::CopyBatchFailed { index, batch_id } => {
+                (None, None, None, Some(index.as_str()), Some(batch_id.as_str()))
+            }
+            _ => (None, None, None, None, None),
+        };
+
+        Ok(Self {
+            event_type: event.event_type.to_string(),
+            batch_id: event.batch_id.as_deref(),
+            block_number: event.block_number as i64,
+            block_timestamp: event.block_timestamp.timestamp(),
+            transaction_hash: &event.transaction_hash,
+            log_index: event.log_index as i64,
+            contract_source: &event.contract_source,
+            contract_address: event.contract_address.as_ref().map(|addr| addr.as_str()),
+            block_hash: event.block_hash.as_deref(),
+            data: serde_json::to_string(&event.data)?,
+            pot_recipient,
+            pot_total_amount,
+            price,
+            copy_index,
+            copy_batch_id,
+        })
+    }
+}
+
 #[derive(Clone)]
 enum DatabasePool {
     Sqlite(sqlx::SqlitePool),
     Postgres(sqlx::PgPool),
+    MySql(sqlx::MySqlPool),
+}
+
+/// How long a SQLite connection waits for a lock before failing with "database is locked"
+pub const DEFAULT_SQLITE_BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(5000);
+
+/// Connection pool limits and SQLite pragmas for the primary and read databases
+///
+/// Unset pool fields keep the sqlx defaults (10 connections at most, none kept
+/// idle, 30 second acquire timeout). SQLite databases use WAL journaling with
+/// `synchronous = NORMAL` unless `sqlite_wal` is `Some(false)`, so readers such
+/// as `serve` are not blocked while `follow` writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolSettings {
+    pub max_connections: Option<u32>,
+    pub min_connections: Option<u32>,
+    pub acquire_timeout: Option<std::time::Duration>,
+    pub sqlite_wal: Option<bool>,
+    /// Default: [`DEFAULT_SQLITE_BUSY_TIMEOUT`]
+    pub sqlite_busy_timeout: Option<std::time::Duration>,
+}
+
+impl PoolSettings {
+    /// Pool options for either backend with these limits applied
+    fn options<DB: sqlx::Database>(&self) -> sqlx::pool::PoolOptions<DB> {
+        let mut options = sqlx::pool::PoolOptions::new();
+        if let Some(max) = self.max_connections {
+            options = options.max_connections(max);
+        }
+        if let Some(min) = self.min_connections {
+            options = options.min_connections(min);
+        }
+        if let Some(timeout) = self.acquire_timeout {
+            options = options.acquire_timeout(timeout);
+        }
+        options
+    }
+
+    /// Apply the SQLite pragmas to `options`; set on every new connection
+    fn sqlite_options(&self, options: sqlx::sqlite::SqliteConnectOptions) -> sqlx::sqlite::SqliteConnectOptions {
+        use sqlx::sqlite::{SqliteJournalMode, SqliteSynchronous};
+
+        let options = options.busy_timeout(self.sqlite_busy_timeout.unwrap_or(DEFAULT_SQLITE_BUSY_TIMEOUT));
+        if self.sqlite_wal.unwrap_or(true) {
+            options
+                .journal_mode(SqliteJournalMode::Wal)
+                .synchronous(SqliteSynchronous::Normal)
+        } else {
+            options
+        }
+    }
+}
+
+/// Progress of a chunked fetch over one contract, stored in `sync_state` after every chunk
+///
+/// A restarted fetch over the same chunk grid (same start block and chunk
+/// size) resumes after `last_completed_to_block` instead of re-checking every
+/// cached chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchCursor {
+    /// First block of the fetch that wrote the cursor
+    pub from_block: u64,
+    pub chunk_size: u64,
+    /// Last block of the highest chunk completed so far
+    pub last_completed_to_block: u64,
+}
+
+/// Event filters evaluated by the database in paged event queries
+///
+/// `event_type` and `contract_source` match their columns exactly;
+/// `batch_id_contains` is a substring of `batch_id`, in
+/// [`crate::filter::normalize_hex_fragment`] form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventColumnFilter {
+    pub event_type: Option<String>,
+    pub batch_id_contains: Option<String>,
+    pub contract_source: Option<String>,
+}
+
+impl EventColumnFilter {
+    /// Whether no filter is set, so every event matches
+    pub fn is_empty(&self) -> bool {
+        self.event_type.is_none() && self.batch_id_contains.is_none() && self.contract_source.is_none()
+    }
+
+    /// Extra `WHERE` conditions and their parameters, in bind order
+    ///
+    /// `placeholder` renders the n-th (1-based) parameter of the whole query;
+    /// `first` is the number of the first parameter added here.
+    fn conditions(&self, placeholder: impl Fn(usize) -> String, first: usize) -> (String, Vec<String>) {
+        let mut sql = String::new();
+        let mut params = Vec::new();
+        let columns = [
+            ("event_type = ", self.event_type.clone()),
+            ("batch_id LIKE ", self.batch_id_contains.as_ref().map(|f| format!("%{f}%"))),
+            ("contract_source = ", self.contract_source.clone()),
+        ];
+        for (condition, value) in columns {
+            if let Some(value) = value {
+                sql.push_str(&format!(" AND {condition}{}", placeholder(first + params.len())));
+                params.push(value);
+            }
+        }
+        (sql, params)
+    }
+}
+
+/// Result of [`Cache::healthcheck`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheHealth {
+    /// Migrations recorded as successfully applied in the database
+    pub applied_migrations: usize,
+    /// Migrations embedded in this binary
+    pub expected_migrations: usize,
+}
+
+impl CacheHealth {
+    /// Whether every embedded migration has been applied
+    pub fn migrations_current(&self) -> bool {
+        self.applied_migrations >= self.expected_migrations
+    }
 }
 
 #[derive(Clone)]
 pub struct Cache {
     pool: DatabasePool,
+    /// Pool limits, reused when connecting the read database
+    pool_settings: PoolSettings,
+    /// Optional replica serving read-only reporting queries
+    read_pool: Option<DatabasePool>,
+    /// Refuse to load more rows than this in a single query (unlimited when `None`)
+    max_query_rows: Option<u64>,
+    /// Log reporting queries with their parameters and query plans at debug level
+    explain_sql: bool,
 }
 
 impl Cache {
     /// Create a new cache instance and initialize the database
-    /// Supports both SQLite (file path or sqlite://) and PostgreSQL (postgres://)
+    /// Supports SQLite (file path or sqlite://), PostgreSQL (postgres://) and
+    /// MySQL/MariaDB (mysql:// or mariadb://)
+    #[cfg(test)]
     pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self> {
+        Self::with_pool_settings(db_path, PoolSettings::default()).await
+    }
+
+    /// Create a new cache instance whose connection pools honor `pool_settings`
+    pub async fn with_pool_settings<P: AsRef<Path>>(db_path: P, pool_settings: PoolSettings) -> Result<Self> {
+        let cache = Self {
+            pool: Self::connect(db_path, &pool_settings).await?,
+            pool_settings,
+            read_pool: None,
+            max_query_rows: None,
+            explain_sql: false,
+        };
+        cache.run_migrations().await?;
+
+        Ok(cache)
+    }
+
+    /// Route read-only reporting queries to a separate database, such as a Postgres replica
+    ///
+    /// Event, batch and price history reads (summary, export, analytics and the
+    /// query server) use the read database; writes and the bookkeeping reads that
+    /// fetch, sync and follow depend on stay on the primary. The read database is
+    /// expected to carry the same schema and is never migrated. `None` keeps all
+    /// queries on the primary.
+    pub async fn with_read_url(mut self, read_url: Option<&str>) -> Result<Self> {
+        self.read_pool = match read_url {
+            Some(url) => Some(Self::connect_read_only(url, &self.pool_settings).await?),
+            None => None,
+        };
+        Ok(self)
+    }
+
+    /// Pool used by read-only reporting queries
+    fn reader(&self) -> &DatabasePool {
+        self.read_pool.as_ref().unwrap_or(&self.pool)
+    }
+
+    /// Connect to a read database without creating or migrating it
+    async fn connect_read_only(url: &str, pool_settings: &PoolSettings) -> Result<DatabasePool> {
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            tracing::info!("Connecting to PostgreSQL read database");
+            Ok(DatabasePool::Postgres(pool_settings.options().connect(url).await?))
+        } else if url.starts_with("mysql://") || url.starts_with("mariadb://") {
+            tracing::info!("Connecting to MySQL read database");
+            Ok(DatabasePool::MySql(pool_settings.options().connect(&mysql_url(url)).await?))
+        } else {
+            use sqlx::sqlite::SqliteConnectOptions;
+            use std::str::FromStr;
+
+            tracing::info!("Connecting to SQLite read database");
+            let db_url = if url.starts_with("sqlite:") {
+                url.to_string()
+            } else {
+                format!("sqlite:{url}")
+            };
+            // The journal mode is stored in the file, so only the busy timeout applies here
+            let options = SqliteConnectOptions::from_str(&db_url)?
+                .read_only(true)
+                .busy_timeout(pool_settings.sqlite_busy_timeout.unwrap_or(DEFAULT_SQLITE_BUSY_TIMEOUT));
+            Ok(DatabasePool::Sqlite(pool_settings.options().connect_with(options).await?))
+        }
+    }
+
+    /// Connect to the primary database, creating it if it does not exist
+    async fn connect<P: AsRef<Path>>(db_path: P, pool_settings: &PoolSettings) -> Result<DatabasePool> {
         let path_str = db_path.as_ref().to_string_lossy();
 
         // Detect database type and connect with appropriate driver
@@ -27,7 +296,7 @@ impl Cache {
             tracing::info!("Connecting to PostgreSQL database");
 
             // Try to connect, and create database if it doesn't exist
-            let pg_pool = match sqlx::PgPool::connect(&path_str).await {
+            let pg_pool = match pool_settings.options().connect(&path_str).await {
                 Ok(pool) => pool,
                 Err(e) => {
                     let err_msg = e.to_string();
@@ -60,7 +329,7 @@ impl Cache {
                         }
 
                         // Now connect to the newly created database
-                        sqlx::PgPool::connect(&path_str).await?
+                        pool_settings.options().connect(&path_str).await?
                     } else {
                         return Err(e.into());
                     }
@@ -68,6 +337,17 @@ impl Cache {
             };
 
             DatabasePool::Postgres(pg_pool)
+        } else if path_str.starts_with("mysql://") || path_str.starts_with("mariadb://") {
+            use sqlx::migrate::MigrateDatabase;
+
+            tracing::info!("Connecting to MySQL database");
+            let url = mysql_url(&path_str);
+            if !sqlx::MySql::database_exists(&url).await? {
+                tracing::info!("Database does not exist, creating it...");
+                sqlx::MySql::create_database(&url).await?;
+            }
+
+            DatabasePool::MySql(pool_settings.options().connect(&url).await?)
         } else {
             // SQLite (either with sqlite:// prefix or as file path)
             let db_url = if path_str.starts_with("sqlite://") {
@@ -88,16 +368,163 @@ impl Cache {
             // Use SqliteConnectOptions to auto-create database file
             use sqlx::sqlite::SqliteConnectOptions;
             use std::str::FromStr;
-            let options = SqliteConnectOptions::from_str(&db_url)?
-                .create_if_missing(true);
-            let sqlite_pool = sqlx::SqlitePool::connect_with(options).await?;
+            let options = pool_settings.sqlite_options(
+                SqliteConnectOptions::from_str(&db_url)?.create_if_missing(true),
+            );
+            let sqlite_pool = pool_settings.options().connect_with(options).await?;
             DatabasePool::Sqlite(sqlite_pool)
         };
 
-        let cache = Self { pool };
-        cache.run_migrations().await?;
+        Ok(pool)
+    }
 
-        Ok(cache)
+    /// Limit how many rows a single event or batch query may load into memory
+    ///
+    /// Queries that would exceed the limit fail with `StampError::QueryTooLarge`
+    /// before any rows are fetched.
+    pub fn with_max_query_rows(mut self, max_query_rows: Option<u64>) -> Self {
+        self.max_query_rows = max_query_rows;
+        self
+    }
+
+    /// Log the SQL of reporting queries with bound parameters, plus its query plan
+    ///
+    /// Everything is logged at debug level under `beeport_stamp_stats::cache`.
+    pub fn with_explain_sql(mut self, explain_sql: bool) -> Self {
+        self.explain_sql = explain_sql;
+        self
+    }
+
+    /// Log a reporting query and its plan when `explain_sql` is enabled
+    ///
+    /// A failing `EXPLAIN` is logged and never fails the query itself.
+    async fn explain(&self, sql: &str, params: &[i64]) {
+        if !self.explain_sql {
+            return;
+        }
+
+        let sql = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+        tracing::debug!("SQL: {} -- params: {:?}", sql, params);
+
+        match self.query_plan(&sql, params).await {
+            Ok(lines) => {
+                for line in lines {
+                    tracing::debug!("  plan: {}", line);
+                }
+            }
+            Err(e) => tracing::debug!("  EXPLAIN failed: {}", e),
+        }
+    }
+
+    /// Query plan for a read query, one line per plan step
+    ///
+    /// Uses `EXPLAIN QUERY PLAN` on SQLite and `EXPLAIN` on PostgreSQL and MySQL, run
+    /// against the read database with the same parameters as the query.
+    async fn query_plan(&self, sql: &str, params: &[i64]) -> Result<Vec<String>> {
+        let lines = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let explain = format!("EXPLAIN QUERY PLAN {sql}");
+                let mut query = sqlx::query(&explain);
+                for param in params {
+                    query = query.bind(*param);
+                }
+                query
+                    .fetch_all(pool)
+                    .await?
+                    .iter()
+                    .map(|row| row.get::<String, _>("detail"))
+                    .collect()
+            }
+            DatabasePool::Postgres(pool) => {
+                let explain = format!("EXPLAIN {sql}");
+                let mut query = sqlx::query(&explain);
+                for param in params {
+                    query = query.bind(*param);
+                }
+                query
+                    .fetch_all(pool)
+                    .await?
+                    .iter()
+                    .map(|row| row.get::<String, _>(0))
+                    .collect()
+            }
+            DatabasePool::MySql(pool) => {
+                let explain = format!("EXPLAIN {sql}");
+                let mut query = sqlx::query(&explain);
+                for param in params {
+                    query = query.bind(*param);
+                }
+                let column = |row: &sqlx::mysql::MySqlRow, name: &str| {
+                    row.try_get::<Option<String>, _>(name).ok().flatten().unwrap_or_default()
+                };
+                query
+                    .fetch_all(pool)
+                    .await?
+                    .iter()
+                    .map(|row| {
+                        format!(
+                            "{} type={} key={} {}",
+                            column(row, "table"),
+                            column(row, "type"),
+                            column(row, "key"),
+                            column(row, "Extra")
+                        )
+                    })
+                    .collect()
+            }
+        };
+        Ok(lines)
+    }
+
+    /// Configured row limit for event and batch queries, if any
+    pub fn max_query_rows(&self) -> Option<u64> {
+        self.max_query_rows
+    }
+
+    /// Check a query against `max_query_rows` with a `COUNT` before loading it
+    ///
+    /// `table` and `column` are fixed identifiers; rows with `column` between
+    /// `min` and `max` (inclusive) are counted.
+    async fn check_row_limit(&self, table: &str, column: &str, min: i64, max: i64) -> Result<()> {
+        let Some(limit) = self.max_query_rows else {
+            return Ok(());
+        };
+
+        let count: i64 = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = format!("SELECT COUNT(*) FROM {table} WHERE {column} BETWEEN ? AND ?");
+                self.explain(&sql, &[min, max]).await;
+                sqlx::query_scalar(&sql)
+                    .bind(min)
+                    .bind(max)
+                    .fetch_one(pool)
+                    .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                let sql = format!("SELECT COUNT(*) FROM {table} WHERE {column} BETWEEN $1 AND $2");
+                self.explain(&sql, &[min, max]).await;
+                sqlx::query_scalar(&sql)
+                    .bind(min)
+                    .bind(max)
+                    .fetch_one(pool)
+                    .await?
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = format!("SELECT COUNT(*) FROM {table} WHERE {column} BETWEEN ? AND ?");
+                self.explain(&sql, &[min, max]).await;
+                sqlx::query_scalar(&sql)
+                    .bind(min)
+                    .bind(max)
+                    .fetch_one(pool)
+                    .await?
+            }
+        };
+
+        if count as u64 > limit {
+            return Err(row_limit_error(table, count, limit));
+        }
+
+        Ok(())
     }
 
     /// Run database migrations
@@ -115,66 +542,182 @@ impl Cache {
                     .run(pool)
                     .await?;
             }
+            DatabasePool::MySql(pool) => {
+                tracing::debug!("Running MySQL migrations from ./migrations_mysql");
+                sqlx::migrate!("./migrations_mysql")
+                    .run(pool)
+                    .await?;
+            }
         }
         Ok(())
     }
 
 
+    /// Check that the cache is writable and all migrations are applied
+    ///
+    /// Writes, reads back and deletes a `cache_metadata` row, then counts the
+    /// successfully applied migrations against the ones embedded in the binary.
+    pub async fn healthcheck(&self) -> Result<CacheHealth> {
+        let now = chrono::Utc::now().timestamp();
+        let marker = now.to_string();
+
+        let (read_back, applied, expected) = match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query("INSERT OR REPLACE INTO cache_metadata (key, value, updated_at) VALUES ('healthcheck', ?, ?)")
+                    .bind(&marker)
+                    .bind(now)
+                    .execute(pool)
+                    .await?;
+                let value: Option<String> = sqlx::query_scalar("SELECT value FROM cache_metadata WHERE key = 'healthcheck'")
+                    .fetch_optional(pool)
+                    .await?;
+                sqlx::query("DELETE FROM cache_metadata WHERE key = 'healthcheck'")
+                    .execute(pool)
+                    .await?;
+                let applied: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success")
+                    .fetch_one(pool)
+                    .await?;
+                (value, applied, sqlx::migrate!("./migrations").iter().count())
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata (key, value, updated_at) VALUES ('healthcheck', $1, $2)
+                    ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_at = EXCLUDED.updated_at
+                    "#,
+                )
+                .bind(&marker)
+                .bind(now)
+                .execute(pool)
+                .await?;
+                let value: Option<String> = sqlx::query_scalar("SELECT value FROM cache_metadata WHERE key = 'healthcheck'")
+                    .fetch_optional(pool)
+                    .await?;
+                sqlx::query("DELETE FROM cache_metadata WHERE key = 'healthcheck'")
+                    .execute(pool)
+                    .await?;
+                let applied: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success")
+                    .fetch_one(pool)
+                    .await?;
+                (value, applied, sqlx::migrate!("./migrations_postgres").iter().count())
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata (`key`, value, updated_at) VALUES ('healthcheck', ?, ?)
+                    ON DUPLICATE KEY UPDATE value = VALUES(value), updated_at = VALUES(updated_at)
+                    "#,
+                )
+                .bind(&marker)
+                .bind(now)
+                .execute(pool)
+                .await?;
+                let value: Option<String> = sqlx::query_scalar("SELECT value FROM cache_metadata WHERE `key` = 'healthcheck'")
+                    .fetch_optional(pool)
+                    .await?;
+                sqlx::query("DELETE FROM cache_metadata WHERE `key` = 'healthcheck'")
+                    .execute(pool)
+                    .await?;
+                let applied: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success")
+                    .fetch_one(pool)
+                    .await?;
+                (value, applied, sqlx::migrate!("./migrations_mysql").iter().count())
+            }
+        };
+
+        if read_back.as_deref() != Some(marker.as_str()) {
+            return Err(StampError::Database(sqlx::Error::Protocol(
+                "healthcheck row did not read back after writing it".to_string(),
+            )));
+        }
+
+        Ok(CacheHealth {
+            applied_migrations: applied as usize,
+            expected_migrations: expected,
+        })
+    }
+
     /// Store events in the database
+    ///
+    /// Events are written with multi-row `INSERT` statements, split to stay
+    /// under each backend's bound-parameter limit, inside a single transaction:
+    /// if any statement fails, none of the events are stored.
+    /// An event already cached under the same `(transaction_hash, log_index)`
+    /// is replaced; within `events`, the last occurrence wins.
     pub async fn store_events(&self, events: &[StampEvent]) -> Result<()> {
-        for event in events {
-            let event_type = event.event_type.to_string();
-            let data = serde_json::to_string(&event.data)?;
-            let timestamp = event.block_timestamp.timestamp();
-            let contract_address = event.contract_address.as_ref().map(|addr| addr.as_str());
-            let batch_id = event.batch_id.as_deref();
+        // Keep only the last occurrence of each event, as sequential upserts would
+        let mut last_index = HashMap::new();
+        for (i, event) in events.iter().enumerate() {
+            last_index.insert((event.transaction_hash.as_str(), event.log_index), i);
+        }
+        let rows = events
+            .iter()
+            .enumerate()
+            .filter(|(i, event)| last_index[&(event.transaction_hash.as_str(), event.log_index)] == *i)
+            .map(|(_, event)| EventRow::new(event))
+            .collect::<Result<Vec<_>>>()?;
 
-            // Extract event-specific data
-            let (pot_recipient, pot_total_amount, price, copy_index, copy_batch_id) = match &event.data {
-                EventData::PotWithdrawn { recipient, total_amount } => {
-                    (Some(recipient.as_str()), Some(total_amount.as_str()), None, None, None)
-                }
-                EventData::PriceUpdate { price } => {
-                    (None, None, Some(price.as_str()), None, None)
-                }
-                EventData::CopyBatchFailed { index, batch_id } => {
-                    (None, None, None, Some(index.as_str()), Some(batch_id.as_str()))
-                }
-                _ => (None, None, None, None, None),
-            };
+        if rows.is_empty() {
+            return Ok(());
+        }
 
-            match &self.pool {
-                DatabasePool::Sqlite(pool) => {
-                    sqlx::query(
-                        r#"
-                        INSERT OR REPLACE INTO events
-                        (event_type, batch_id, block_number, block_timestamp, transaction_hash, log_index, contract_source, contract_address, data, pot_recipient, pot_total_amount, price, copy_index, copy_batch_id)
-                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                        "#,
-                    )
-                    .bind(&event_type)
-                    .bind(batch_id)
-                    .bind(event.block_number as i64)
-                    .bind(timestamp)
-                    .bind(&event.transaction_hash)
-                    .bind(event.log_index as i64)
-                    .bind(&event.contract_source)
-                    .bind(contract_address)
-                    .bind(&data)
-                    .bind(pot_recipient)
-                    .bind(pot_total_amount)
-                    .bind(price)
-                    .bind(copy_index)
-                    .bind(copy_batch_id)
-                    .execute(pool)
-                    .await?;
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let mut tx = pool.begin().await?;
+                for chunk in rows.chunks(SQLITE_MAX_PARAMS / EventRow::COLUMNS) {
+                    let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new(format!(
+                        "INSERT OR REPLACE INTO events ({}) ",
+                        EventRow::COLUMN_LIST
+                    ));
+                    query.push_values(chunk, |mut values, row| {
+                        values
+                            .push_bind(&row.event_type)
+                            .push_bind(row.batch_id)
+                            .push_bind(row.block_number)
+                            .push_bind(row.block_timestamp)
+                            .push_bind(row.transaction_hash)
+                            .push_bind(row.log_index)
+                            .push_bind(row.contract_source)
+                            .push_bind(row.contract_address)
+                            .push_bind(row.block_hash)
+                            .push_bind(&row.data)
+                            .push_bind(row.pot_recipient)
+                            .push_bind(row.pot_total_amount)
+                            .push_bind(row.price)
+                            .push_bind(row.copy_index)
+                            .push_bind(row.copy_batch_id);
+                    });
+                    query.build().execute(&mut *tx).await?;
                 }
-                DatabasePool::Postgres(pool) => {
-                    sqlx::query(
+                tx.commit().await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                for chunk in rows.chunks(POSTGRES_MAX_PARAMS / EventRow::COLUMNS) {
+                    let mut query = sqlx::QueryBuilder::<sqlx::Postgres>::new(format!(
+                        "INSERT INTO events ({}) ",
+                        EventRow::COLUMN_LIST
+                    ));
+                    query.push_values(chunk, |mut values, row| {
+                        values
+                            .push_bind(&row.event_type)
+                            .push_bind(row.batch_id)
+                            .push_bind(row.block_number)
+                            .push_bind(row.block_timestamp)
+                            .push_bind(row.transaction_hash)
+                            .push_bind(row.log_index)
+                            .push_bind(row.contract_source)
+                            .push_bind(row.contract_address)
+                            .push_bind(row.block_hash)
+                            .push_bind(&row.data)
+                            .push_bind(row.pot_recipient)
+                            .push_bind(row.pot_total_amount)
+                            .push_bind(row.price)
+                            .push_bind(row.copy_index)
+                            .push_bind(row.copy_batch_id);
+                    });
+                    query.push(
                         r#"
-                        INSERT INTO events
-                        (event_type, batch_id, block_number, block_timestamp, transaction_hash, log_index, contract_source, contract_address, data, pot_recipient, pot_total_amount, price, copy_index, copy_batch_id)
-                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
                         ON CONFLICT (transaction_hash, log_index) DO UPDATE SET
                             event_type = EXCLUDED.event_type,
                             batch_id = EXCLUDED.batch_id,
@@ -182,6 +725,7 @@ impl Cache {
                             block_timestamp = EXCLUDED.block_timestamp,
                             contract_source = EXCLUDED.contract_source,
                             contract_address = EXCLUDED.contract_address,
+                            block_hash = EXCLUDED.block_hash,
                             data = EXCLUDED.data,
                             pot_recipient = EXCLUDED.pot_recipient,
                             pot_total_amount = EXCLUDED.pot_total_amount,
@@ -189,24 +733,57 @@ impl Cache {
                             copy_index = EXCLUDED.copy_index,
                             copy_batch_id = EXCLUDED.copy_batch_id
                         "#,
-                    )
-                    .bind(&event_type)
-                    .bind(batch_id)
-                    .bind(event.block_number as i64)
-                    .bind(timestamp)
-                    .bind(&event.transaction_hash)
-                    .bind(event.log_index as i64)
-                    .bind(&event.contract_source)
-                    .bind(contract_address)
-                    .bind(&data)
-                    .bind(pot_recipient)
-                    .bind(pot_total_amount)
-                    .bind(price)
-                    .bind(copy_index)
-                    .bind(copy_batch_id)
-                    .execute(pool)
-                    .await?;
+                    );
+                    query.build().execute(&mut *tx).await?;
+                }
+                tx.commit().await?;
+            }
+            DatabasePool::MySql(pool) => {
+                let mut tx = pool.begin().await?;
+                for chunk in rows.chunks(MYSQL_MAX_PARAMS / EventRow::COLUMNS) {
+                    let mut query = sqlx::QueryBuilder::<sqlx::MySql>::new(format!(
+                        "INSERT INTO events ({}) ",
+                        EventRow::COLUMN_LIST
+                    ));
+                    query.push_values(chunk, |mut values, row| {
+                        values
+                            .push_bind(&row.event_type)
+                            .push_bind(row.batch_id)
+                            .push_bind(row.block_number)
+                            .push_bind(row.block_timestamp)
+                            .push_bind(row.transaction_hash)
+                            .push_bind(row.log_index)
+                            .push_bind(row.contract_source)
+                            .push_bind(row.contract_address)
+                            .push_bind(row.block_hash)
+                            .push_bind(&row.data)
+                            .push_bind(row.pot_recipient)
+                            .push_bind(row.pot_total_amount)
+                            .push_bind(row.price)
+                            .push_bind(row.copy_index)
+                            .push_bind(row.copy_batch_id);
+                    });
+                    query.push(
+                        r#"
+                        ON DUPLICATE KEY UPDATE
+                            event_type = VALUES(event_type),
+                            batch_id = VALUES(batch_id),
+                            block_number = VALUES(block_number),
+                            block_timestamp = VALUES(block_timestamp),
+                            contract_source = VALUES(contract_source),
+                            contract_address = VALUES(contract_address),
+                            block_hash = VALUES(block_hash),
+                            data = VALUES(data),
+                            pot_recipient = VALUES(pot_recipient),
+                            pot_total_amount = VALUES(pot_total_amount),
+                            price = VALUES(price),
+                            copy_index = VALUES(copy_index),
+                            copy_batch_id = VALUES(copy_batch_id)
+                        "#,
+                    );
+                    query.build().execute(&mut *tx).await?;
                 }
+                tx.commit().await?;
             }
         }
 
@@ -367,42 +944,137 @@ impl Cache {
                     .execute(pool)
                     .await?;
                 }
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Store batch information in the database
-    pub async fn store_batches(&self, batches: &[BatchInfo]) -> Result<()> {
-        for batch in batches {
-            let created_at = batch.created_at.timestamp();
-            let immutable = if batch.immutable { 1 } else { 0 };
-
-            // Use database-specific UPSERT syntax
-            match &self.pool {
-                DatabasePool::Sqlite(pool) => {
+                DatabasePool::MySql(pool) => {
                     sqlx::query(
                         r#"
-                        INSERT OR REPLACE INTO batches
-                        (batch_id, owner, payer, contract_source, depth, bucket_depth, immutable, normalised_balance, created_at, block_number)
-                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                        "#
+                        INSERT INTO storage_incentives_events
+                        (block_number, block_timestamp, transaction_hash, log_index, contract_source, contract_address, event_type,
+                         round_number, phase, owner_address, overlay,
+                         price, committed_stake, potential_stake, height, slash_amount, freeze_time, withdraw_amount,
+                         stake, stake_density, reserve_commitment, depth,
+                         anchor, truth_hash, truth_depth,
+                         winner_overlay, winner_owner, winner_depth, winner_stake, winner_stake_density, winner_hash,
+                         commit_count, reveal_count, chunk_count, redundancy_count,
+                         chunk_index_in_rc, chunk_address)
+                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                        ON DUPLICATE KEY UPDATE
+                            block_number = VALUES(block_number),
+                            block_timestamp = VALUES(block_timestamp),
+                            contract_source = VALUES(contract_source),
+                            contract_address = VALUES(contract_address),
+                            event_type = VALUES(event_type),
+                            round_number = VALUES(round_number),
+                            phase = VALUES(phase),
+                            owner_address = VALUES(owner_address),
+                            overlay = VALUES(overlay),
+                            price = VALUES(price),
+                            committed_stake = VALUES(committed_stake),
+                            potential_stake = VALUES(potential_stake),
+                            height = VALUES(height),
+                            slash_amount = VALUES(slash_amount),
+                            freeze_time = VALUES(freeze_time),
+                            withdraw_amount = VALUES(withdraw_amount),
+                            stake = VALUES(stake),
+                            stake_density = VALUES(stake_density),
+                            reserve_commitment = VALUES(reserve_commitment),
+                            depth = VALUES(depth),
+                            anchor = VALUES(anchor),
+                            truth_hash = VALUES(truth_hash),
+                            truth_depth = VALUES(truth_depth),
+                            winner_overlay = VALUES(winner_overlay),
+                            winner_owner = VALUES(winner_owner),
+                            winner_depth = VALUES(winner_depth),
+                            winner_stake = VALUES(winner_stake),
+                            winner_stake_density = VALUES(winner_stake_density),
+                            winner_hash = VALUES(winner_hash),
+                            commit_count = VALUES(commit_count),
+                            reveal_count = VALUES(reveal_count),
+                            chunk_count = VALUES(chunk_count),
+                            redundancy_count = VALUES(redundancy_count),
+                            chunk_index_in_rc = VALUES(chunk_index_in_rc),
+                            chunk_address = VALUES(chunk_address)
+                        "#,
                     )
-                    .bind(&batch.batch_id)
-                    .bind(&batch.owner)
-                    .bind(&batch.payer)
-                    .bind(&batch.contract_source)
-                    .bind(batch.depth as i64)
-                    .bind(batch.bucket_depth as i64)
-                    .bind(immutable)
-                    .bind(&batch.normalised_balance)
-                    .bind(created_at)
-                    .bind(batch.block_number as i64)
-                    .execute(pool)
-                    .await?;
-                }
-                DatabasePool::Postgres(pool) => {
+                    .bind(event.block_number as i64)
+                    .bind(timestamp)
+                    .bind(&event.transaction_hash)
+                    .bind(event.log_index as i64)
+                    .bind(&event.contract_source)
+                    .bind(contract_address)
+                    .bind(&event.event_type)
+                    .bind(event.round_number.map(|v| v as i64))
+                    .bind(&event.phase)
+                    .bind(&event.owner_address)
+                    .bind(&event.overlay)
+                    .bind(&event.price)
+                    .bind(&event.committed_stake)
+                    .bind(&event.potential_stake)
+                    .bind(event.height.map(|v| v as i64))
+                    .bind(&event.slash_amount)
+                    .bind(&event.freeze_time)
+                    .bind(&event.withdraw_amount)
+                    .bind(&event.stake)
+                    .bind(&event.stake_density)
+                    .bind(&event.reserve_commitment)
+                    .bind(event.depth.map(|v| v as i64))
+                    .bind(&event.anchor)
+                    .bind(&event.truth_hash)
+                    .bind(event.truth_depth.map(|v| v as i64))
+                    .bind(&event.winner_overlay)
+                    .bind(&event.winner_owner)
+                    .bind(event.winner_depth.map(|v| v as i64))
+                    .bind(&event.winner_stake)
+                    .bind(&event.winner_stake_density)
+                    .bind(&event.winner_hash)
+                    .bind(event.commit_count.map(|v| v as i64))
+                    .bind(event.reveal_count.map(|v| v as i64))
+                    .bind(event.chunk_count.map(|v| v as i64))
+                    .bind(event.redundancy_count.map(|v| v as i64))
+                    .bind(event.chunk_index_in_rc.map(|v| v as i64))
+                    .bind(&event.chunk_address)
+                    .execute(pool)
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Store batch information in the database
+    ///
+    /// All batches are written in one transaction; if any insert fails, none are stored.
+    pub async fn store_batches(&self, batches: &[BatchInfo]) -> Result<()> {
+        // Use database-specific UPSERT syntax; all batches commit together or not at all
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let mut tx = pool.begin().await?;
+                for batch in batches {
+                    sqlx::query(
+                        r#"
+                        INSERT OR REPLACE INTO batches
+                        (batch_id, owner, payer, contract_source, depth, bucket_depth, immutable, normalised_balance, created_at, block_number)
+                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                        "#
+                    )
+                    .bind(batch.batch_id.as_str())
+                    .bind(&batch.owner)
+                    .bind(&batch.payer)
+                    .bind(&batch.contract_source)
+                    .bind(batch.depth as i64)
+                    .bind(batch.bucket_depth as i64)
+                    .bind(if batch.immutable { 1 } else { 0 })
+                    .bind(&batch.normalised_balance)
+                    .bind(batch.created_at.timestamp())
+                    .bind(batch.block_number as i64)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                tx.commit().await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                for batch in batches {
                     sqlx::query(
                         r#"
                         INSERT INTO batches
@@ -420,19 +1092,55 @@ impl Cache {
                             block_number = EXCLUDED.block_number
                         "#
                     )
-                    .bind(&batch.batch_id)
+                    .bind(batch.batch_id.as_str())
                     .bind(&batch.owner)
                     .bind(&batch.payer)
                     .bind(&batch.contract_source)
                     .bind(batch.depth as i64)
                     .bind(batch.bucket_depth as i64)
-                    .bind(immutable)
+                    .bind(if batch.immutable { 1 } else { 0 })
                     .bind(&batch.normalised_balance)
-                    .bind(created_at)
+                    .bind(batch.created_at.timestamp())
                     .bind(batch.block_number as i64)
-                    .execute(pool)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                tx.commit().await?;
+            }
+            DatabasePool::MySql(pool) => {
+                let mut tx = pool.begin().await?;
+                for batch in batches {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO batches
+                        (batch_id, owner, payer, contract_source, depth, bucket_depth, immutable, normalised_balance, created_at, block_number)
+                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                        ON DUPLICATE KEY UPDATE
+                            owner = VALUES(owner),
+                            payer = VALUES(payer),
+                            contract_source = VALUES(contract_source),
+                            depth = VALUES(depth),
+                            bucket_depth = VALUES(bucket_depth),
+                            immutable = VALUES(immutable),
+                            normalised_balance = VALUES(normalised_balance),
+                            created_at = VALUES(created_at),
+                            block_number = VALUES(block_number)
+                        "#
+                    )
+                    .bind(batch.batch_id.as_str())
+                    .bind(&batch.owner)
+                    .bind(&batch.payer)
+                    .bind(&batch.contract_source)
+                    .bind(batch.depth as i64)
+                    .bind(batch.bucket_depth as i64)
+                    .bind(if batch.immutable { 1 } else { 0 })
+                    .bind(&batch.normalised_balance)
+                    .bind(batch.created_at.timestamp())
+                    .bind(batch.block_number as i64)
+                    .execute(&mut *tx)
                     .await?;
                 }
+                tx.commit().await?;
             }
         }
 
@@ -454,107 +1162,98 @@ impl Cache {
                     .await?;
                 row.get("max_block")
             }
+            DatabasePool::MySql(pool) => {
+                let row = sqlx::query("SELECT MAX(block_number) as max_block FROM events")
+                    .fetch_one(pool)
+                    .await?;
+                row.get("max_block")
+            }
         };
         Ok(max_block.map(|b| b as u64))
     }
 
     /// Retrieve events from the last N months
+    ///
+    /// Events are ordered by `block_number, log_index, transaction_hash`; the
+    /// transaction hash tiebreaker keeps the order stable across backends when
+    /// different contracts emit logs with the same position.
     pub async fn get_events(&self, months: u32) -> Result<Vec<StampEvent>> {
-        let cutoff = if months == 0 {
-            0
-        } else {
-            let cutoff_date = Utc::now() - Duration::days((months * 30) as i64);
-            cutoff_date.timestamp()
-        };
+        self.get_events_between(months_cutoff(months), i64::MAX).await
+    }
 
-        let events = match &self.pool {
+    /// Retrieve events with `start_ts <= block_timestamp <= end_ts`
+    ///
+    /// Both bounds are inclusive Unix timestamps. Events are ordered as in `get_events`.
+    pub async fn get_events_between(&self, start_ts: i64, end_ts: i64) -> Result<Vec<StampEvent>> {
+        self.check_row_limit("events", "block_timestamp", start_ts, end_ts).await?;
+
+        let events = match self.reader() {
             DatabasePool::Sqlite(pool) => {
-                let rows = sqlx::query(
-                    r#"
+                let sql = r#"
                     SELECT event_type, batch_id, block_number, block_timestamp,
-                           transaction_hash, log_index, contract_source, data
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
                     FROM events
-                    WHERE block_timestamp >= ?
-                    ORDER BY block_number ASC, log_index ASC
-                    "#,
-                )
-                .bind(cutoff)
-                .fetch_all(pool)
-                .await?;
+                    WHERE block_timestamp >= ? AND block_timestamp <= ?
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                let rows = sqlx::query(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?;
 
                 let mut events = Vec::new();
                 for row in rows {
-                    let event_type_str: String = row.get("event_type");
-                    let event_type = match event_type_str.as_str() {
-                        "BatchCreated" => EventType::BatchCreated,
-                        "BatchTopUp" => EventType::BatchTopUp,
-                        "BatchDepthIncrease" => EventType::BatchDepthIncrease,
-                        _ => continue,
-                    };
-
-                    let data_str: String = row.get("data");
-                    let data: EventData = serde_json::from_str(&data_str)?;
-
-                    let timestamp: i64 = row.get("block_timestamp");
-                    let block_timestamp =
-                        DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
-
-                    events.push(StampEvent {
-                        event_type,
-                        batch_id: row.get("batch_id"),
-                        block_number: row.get::<i64, _>("block_number") as u64,
-                        block_timestamp,
-                        transaction_hash: row.get("transaction_hash"),
-                        log_index: row.get::<i64, _>("log_index") as u64,
-                        contract_source: row.get("contract_source"),
-                        contract_address: None, // Will be populated from database after migration
-                        data,
-                    });
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
                 }
                 events
             }
             DatabasePool::Postgres(pool) => {
-                let rows = sqlx::query(
-                    r#"
+                let sql = r#"
                     SELECT event_type, batch_id, block_number, block_timestamp,
-                           transaction_hash, log_index, contract_source, data
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
                     FROM events
-                    WHERE block_timestamp >= $1
-                    ORDER BY block_number ASC, log_index ASC
-                    "#,
-                )
-                .bind(cutoff)
-                .fetch_all(pool)
-                .await?;
+                    WHERE block_timestamp >= $1 AND block_timestamp <= $2
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                let rows = sqlx::query(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?;
 
                 let mut events = Vec::new();
                 for row in rows {
-                    let event_type_str: String = row.get("event_type");
-                    let event_type = match event_type_str.as_str() {
-                        "BatchCreated" => EventType::BatchCreated,
-                        "BatchTopUp" => EventType::BatchTopUp,
-                        "BatchDepthIncrease" => EventType::BatchDepthIncrease,
-                        _ => continue,
-                    };
-
-                    let data_str: String = row.get("data");
-                    let data: EventData = serde_json::from_str(&data_str)?;
-
-                    let timestamp: i64 = row.get("block_timestamp");
-                    let block_timestamp =
-                        DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
-
-                    events.push(StampEvent {
-                        event_type,
-                        batch_id: row.get("batch_id"),
-                        block_number: row.get::<i64, _>("block_number") as u64,
-                        block_timestamp,
-                        transaction_hash: row.get("transaction_hash"),
-                        log_index: row.get::<i64, _>("log_index") as u64,
-                        contract_source: row.get("contract_source"),
-                        contract_address: None, // Will be populated from database after migration
-                        data,
-                    });
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
+                }
+                events
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE block_timestamp >= ? AND block_timestamp <= ?
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                let rows = sqlx::query(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?;
+
+                let mut events = Vec::new();
+                for row in rows {
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
                 }
                 events
             }
@@ -563,203 +1262,1531 @@ impl Cache {
         Ok(events)
     }
 
-    /// Retrieve batches from the last N months
-    pub async fn get_batches(&self, months: u32) -> Result<Vec<BatchInfo>> {
-        let cutoff = if months == 0 {
-            0
-        } else {
-            let cutoff_date = Utc::now() - Duration::days((months * 30) as i64);
-            cutoff_date.timestamp()
-        };
+    /// Retrieve the `limit` most recent events, newest first
+    ///
+    /// Only `limit` rows are read, so this stays cheap on large caches. Reverse
+    /// the result for chronological order.
+    pub async fn get_recent_events(&self, limit: usize) -> Result<Vec<StampEvent>> {
+        let limit = limit.min(i64::MAX as usize) as i64;
 
-        let batches = match &self.pool {
+        let events = match self.reader() {
             DatabasePool::Sqlite(pool) => {
-                let rows = sqlx::query(
-                    r#"
-                    SELECT batch_id, owner, payer, contract_source, depth, bucket_depth, immutable,
-                           normalised_balance, created_at, block_number
-                    FROM batches
-                    WHERE created_at >= ?
-                    ORDER BY created_at ASC
-                    "#,
-                )
-                .bind(cutoff)
-                .fetch_all(pool)
-                .await?;
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    ORDER BY block_number DESC, log_index DESC, transaction_hash DESC
+                    LIMIT ?
+                    "#;
+                self.explain(sql, &[limit]).await;
+                let rows = sqlx::query(sql)
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await?;
 
-                let mut batches = Vec::new();
+                let mut events = Vec::new();
                 for row in rows {
-                    let immutable: i64 = row.get("immutable");
-                    let created_at: i64 = row.get("created_at");
-                    let block_number: i64 = row.get("block_number");
-
-                    batches.push(BatchInfo {
-                        batch_id: row.get("batch_id"),
-                        owner: row.get("owner"),
-                        payer: row.get("payer"),
-                        contract_source: row.get("contract_source"),
-                        depth: row.get::<i64, _>("depth") as u8,
-                        bucket_depth: row.get::<i64, _>("bucket_depth") as u8,
-                        immutable: immutable != 0,
-                        normalised_balance: row.get("normalised_balance"),
-                        created_at: DateTime::from_timestamp(created_at, 0).unwrap_or_else(Utc::now),
-                        block_number: block_number as u64,
-                    });
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
                 }
-                batches
+                events
             }
             DatabasePool::Postgres(pool) => {
-                let rows = sqlx::query(
-                    r#"
-                    SELECT batch_id, owner, payer, contract_source, depth, bucket_depth, immutable,
-                           normalised_balance, created_at, block_number
-                    FROM batches
-                    WHERE created_at >= $1
-                    ORDER BY created_at ASC
-                    "#,
-                )
-                .bind(cutoff)
-                .fetch_all(pool)
-                .await?;
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    ORDER BY block_number DESC, log_index DESC, transaction_hash DESC
+                    LIMIT $1
+                    "#;
+                self.explain(sql, &[limit]).await;
+                let rows = sqlx::query(sql)
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await?;
 
-                let mut batches = Vec::new();
+                let mut events = Vec::new();
                 for row in rows {
-                    let immutable: i64 = row.get("immutable");
-                    let created_at: i64 = row.get("created_at");
-                    let block_number: i64 = row.get("block_number");
-
-                    batches.push(BatchInfo {
-                        batch_id: row.get("batch_id"),
-                        owner: row.get("owner"),
-                        payer: row.get("payer"),
-                        contract_source: row.get("contract_source"),
-                        depth: row.get::<i64, _>("depth") as u8,
-                        bucket_depth: row.get::<i64, _>("bucket_depth") as u8,
-                        immutable: immutable != 0,
-                        normalised_balance: row.get("normalised_balance"),
-                        created_at: DateTime::from_timestamp(created_at, 0).unwrap_or_else(Utc::now),
-                        block_number: block_number as u64,
-                    });
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
+                }
+                events
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    ORDER BY block_number DESC, log_index DESC, transaction_hash DESC
+                    LIMIT ?
+                    "#;
+                self.explain(sql, &[limit]).await;
+                let rows = sqlx::query(sql)
+                    .bind(limit)
+                    .fetch_all(pool)
+                    .await?;
+
+                let mut events = Vec::new();
+                for row in rows {
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
                 }
-                batches
+                events
             }
         };
 
-        Ok(batches)
+        Ok(events)
     }
 
-    /// Get total number of events in the database
-    #[allow(dead_code)]
-    pub async fn count_events(&self) -> Result<i64> {
-        let count: i64 = match &self.pool {
+    /// Retrieve one page of the events from the last N months that match `filter`
+    ///
+    /// Events are ordered as in `get_events`, so consecutive pages neither
+    /// overlap nor skip rows while the cache is unchanged. The filter runs in the
+    /// database and only `limit` rows are read; use `count_events_filtered` for
+    /// the total. A `limit` above `max_query_rows`, such as `usize::MAX` for an
+    /// offset-only request, is refused only if more than `max_query_rows`
+    /// matching events follow `offset`.
+    pub async fn get_events_paged(
+        &self,
+        months: u32,
+        filter: &EventColumnFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<StampEvent>> {
+        let cutoff = months_cutoff(months);
+        if let Some(max) = self.max_query_rows.filter(|&max| limit as u64 > max) {
+            let remaining = self.count_events_filtered(months, filter).await?.saturating_sub(offset as i64);
+            if remaining as u64 > max {
+                return Err(row_limit_error("events", remaining, max));
+            }
+        }
+        let limit = limit.min(i64::MAX as usize) as i64;
+        let offset = offset.min(i64::MAX as usize) as i64;
+
+        let select = r#"
+            SELECT event_type, batch_id, block_number, block_timestamp,
+                   transaction_hash, log_index, contract_source, contract_address, block_hash, data
+            FROM events
+            "#;
+        let order = "ORDER BY block_number ASC, log_index ASC, transaction_hash ASC";
+
+        let rows = match self.reader() {
             DatabasePool::Sqlite(pool) => {
-                let row = sqlx::query("SELECT COUNT(*) as count FROM events")
-                    .fetch_one(pool)
-                    .await?;
-                row.get("count")
+                let (conditions, params) = filter.conditions(|_| "?".to_string(), 2);
+                let sql = format!("{select} WHERE block_timestamp >= ?{conditions} {order} LIMIT ? OFFSET ?");
+                if params.is_empty() {
+                    self.explain(&sql, &[cutoff, limit, offset]).await;
+                }
+                let mut query = sqlx::query(&sql).bind(cutoff);
+                for param in params {
+                    query = query.bind(param);
+                }
+                let rows = query.bind(limit).bind(offset).fetch_all(pool).await?;
+                rows.iter().map(parse_event_row).collect::<Result<Vec<_>>>()?
             }
             DatabasePool::Postgres(pool) => {
-                let row = sqlx::query("SELECT COUNT(*) as count FROM events")
-                    .fetch_one(pool)
-                    .await?;
-                row.get("count")
+                let (conditions, params) = filter.conditions(|n| format!("${n}"), 2);
+                let n = params.len() + 2;
+                let sql = format!(
+                    "{select} WHERE block_timestamp >= $1{conditions} {order} LIMIT ${n} OFFSET ${}",
+                    n + 1
+                );
+                if params.is_empty() {
+                    self.explain(&sql, &[cutoff, limit, offset]).await;
+                }
+                let mut query = sqlx::query(&sql).bind(cutoff);
+                for param in params {
+                    query = query.bind(param);
+                }
+                let rows = query.bind(limit).bind(offset).fetch_all(pool).await?;
+                rows.iter().map(parse_event_row).collect::<Result<Vec<_>>>()?
+            }
+            DatabasePool::MySql(pool) => {
+                let (conditions, params) = filter.conditions(|_| "?".to_string(), 2);
+                let sql = format!("{select} WHERE block_timestamp >= ?{conditions} {order} LIMIT ? OFFSET ?");
+                if params.is_empty() {
+                    self.explain(&sql, &[cutoff, limit, offset]).await;
+                }
+                let mut query = sqlx::query(&sql).bind(cutoff);
+                for param in params {
+                    query = query.bind(param);
+                }
+                let rows = query.bind(limit).bind(offset).fetch_all(pool).await?;
+                rows.iter().map(parse_event_row).collect::<Result<Vec<_>>>()?
             }
         };
-        Ok(count)
+
+        Ok(rows.into_iter().flatten().collect())
     }
 
-    /// Get total number of batches in the database
-    #[allow(dead_code)]
-    pub async fn count_batches(&self) -> Result<i64> {
-        let count: i64 = match &self.pool {
+    /// Stream events with `block_timestamp >= since_ts` without loading them all into memory
+    ///
+    /// Rows are read through a database cursor and handed to `on_event` one at a
+    /// time in the same order as `get_events`. Returns the number of events
+    /// delivered to the callback. Use `months_cutoff` for the last N months.
+    pub async fn stream_events<F>(&self, since_ts: i64, mut on_event: F) -> Result<usize>
+    where
+        F: FnMut(StampEvent) -> Result<()>,
+    {
+        let cutoff = since_ts;
+
+        let mut count = 0;
+        match self.reader() {
             DatabasePool::Sqlite(pool) => {
-                let row = sqlx::query("SELECT COUNT(*) as count FROM batches")
-                    .fetch_one(pool)
-                    .await?;
-                row.get("count")
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE block_timestamp >= ?
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[cutoff]).await;
+                let mut rows = sqlx::query(sql)
+                    .bind(cutoff)
+                    .fetch(pool);
+
+                while let Some(row) = rows.try_next().await? {
+                    if let Some(event) = parse_event_row(&row)? {
+                        on_event(event)?;
+                        count += 1;
+                    }
+                }
             }
             DatabasePool::Postgres(pool) => {
-                let row = sqlx::query("SELECT COUNT(*) as count FROM batches")
-                    .fetch_one(pool)
-                    .await?;
-                row.get("count")
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE block_timestamp >= $1
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[cutoff]).await;
+                let mut rows = sqlx::query(sql)
+                    .bind(cutoff)
+                    .fetch(pool);
+
+                while let Some(row) = rows.try_next().await? {
+                    if let Some(event) = parse_event_row(&row)? {
+                        on_event(event)?;
+                        count += 1;
+                    }
+                }
             }
-        };
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE block_timestamp >= ?
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[cutoff]).await;
+                let mut rows = sqlx::query(sql)
+                    .bind(cutoff)
+                    .fetch(pool);
+
+                while let Some(row) = rows.try_next().await? {
+                    if let Some(event) = parse_event_row(&row)? {
+                        on_event(event)?;
+                        count += 1;
+                    }
+                }
+            }
+        }
+
         Ok(count)
     }
 
-    /// Check if an RPC chunk has been cached
-    pub async fn is_chunk_cached(&self, chunk_hash: &str) -> Result<bool> {
-        let count: i64 = match &self.pool {
+    /// Count events with `block_timestamp >= since_ts`
+    pub async fn count_events_since(&self, since_ts: i64) -> Result<i64> {
+        let cutoff = since_ts;
+
+        let count: i64 = match self.reader() {
             DatabasePool::Sqlite(pool) => {
-                let row = sqlx::query("SELECT COUNT(*) as count FROM rpc_cache WHERE chunk_hash = ?")
-                    .bind(chunk_hash)
+                let sql = "SELECT COUNT(*) as count FROM events WHERE block_timestamp >= ?";
+                self.explain(sql, &[cutoff]).await;
+                let row = sqlx::query(sql)
+                    .bind(cutoff)
                     .fetch_one(pool)
                     .await?;
                 row.get("count")
             }
             DatabasePool::Postgres(pool) => {
-                let row = sqlx::query("SELECT COUNT(*) as count FROM rpc_cache WHERE chunk_hash = $1")
-                    .bind(chunk_hash)
+                let sql = "SELECT COUNT(*) as count FROM events WHERE block_timestamp >= $1";
+                self.explain(sql, &[cutoff]).await;
+                let row = sqlx::query(sql)
+                    .bind(cutoff)
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = "SELECT COUNT(*) as count FROM events WHERE block_timestamp >= ?";
+                self.explain(sql, &[cutoff]).await;
+                let row = sqlx::query(sql)
+                    .bind(cutoff)
                     .fetch_one(pool)
                     .await?;
                 row.get("count")
             }
         };
+        Ok(count)
+    }
+
+    /// Count the events `get_events_paged` pages through for the last N months
+    pub async fn count_events_filtered(&self, months: u32, filter: &EventColumnFilter) -> Result<i64> {
+        let cutoff = months_cutoff(months);
+        if filter.is_empty() {
+            return self.count_events_since(cutoff).await;
+        }
+
+        let count: i64 = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let (conditions, params) = filter.conditions(|_| "?".to_string(), 2);
+                let sql = format!("SELECT COUNT(*) as count FROM events WHERE block_timestamp >= ?{conditions}");
+                let mut query = sqlx::query(&sql).bind(cutoff);
+                for param in params {
+                    query = query.bind(param);
+                }
+                query.fetch_one(pool).await?.get("count")
+            }
+            DatabasePool::Postgres(pool) => {
+                let (conditions, params) = filter.conditions(|n| format!("${n}"), 2);
+                let sql = format!("SELECT COUNT(*) as count FROM events WHERE block_timestamp >= $1{conditions}");
+                let mut query = sqlx::query(&sql).bind(cutoff);
+                for param in params {
+                    query = query.bind(param);
+                }
+                query.fetch_one(pool).await?.get("count")
+            }
+            DatabasePool::MySql(pool) => {
+                let (conditions, params) = filter.conditions(|_| "?".to_string(), 2);
+                let sql = format!("SELECT COUNT(*) as count FROM events WHERE block_timestamp >= ?{conditions}");
+                let mut query = sqlx::query(&sql).bind(cutoff);
+                for param in params {
+                    query = query.bind(param);
+                }
+                query.fetch_one(pool).await?.get("count")
+            }
+        };
+        Ok(count)
+    }
+
+    /// Retrieve batches from the last N months
+    pub async fn get_batches(&self, months: u32) -> Result<Vec<BatchInfo>> {
+        self.get_batches_between(months_cutoff(months), i64::MAX).await
+    }
+
+    /// Retrieve batches created with `start_ts <= created_at <= end_ts`
+    ///
+    /// Both bounds are inclusive Unix timestamps.
+    pub async fn get_batches_between(&self, start_ts: i64, end_ts: i64) -> Result<Vec<BatchInfo>> {
+        self.check_row_limit("batches", "created_at", start_ts, end_ts).await?;
+
+        let batches = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = r#"
+                    SELECT batch_id, owner, payer, contract_source, depth, bucket_depth, immutable,
+                           normalised_balance, created_at, block_number
+                    FROM batches
+                    WHERE created_at >= ? AND created_at <= ?
+                    ORDER BY created_at ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                let rows = sqlx::query(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+            DatabasePool::Postgres(pool) => {
+                let sql = r#"
+                    SELECT batch_id, owner, payer, contract_source, depth, bucket_depth, immutable,
+                           normalised_balance, created_at, block_number
+                    FROM batches
+                    WHERE created_at >= $1 AND created_at <= $2
+                    ORDER BY created_at ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                let rows = sqlx::query(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT batch_id, owner, payer, contract_source, depth, bucket_depth, immutable,
+                           normalised_balance, created_at, block_number
+                    FROM batches
+                    WHERE created_at >= ? AND created_at <= ?
+                    ORDER BY created_at ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                let rows = sqlx::query(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+        };
+
+        Ok(batches)
+    }
+
+    /// Retrieve batches with their depth after any depth increases
+    ///
+    /// Like `get_batches`, but `depth` is the larger of the creation depth and
+    /// the highest `new_depth` of any cached `BatchDepthIncrease` event for the
+    /// batch, so size and TTL reflect how far the batch has grown.
+    pub async fn get_batches_with_current_depth(&self, months: u32) -> Result<Vec<BatchInfo>> {
+        let cutoff = months_cutoff(months);
+        self.check_row_limit("batches", "created_at", cutoff, i64::MAX).await?;
+
+        let batches = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = r#"
+                    SELECT b.batch_id, b.owner, b.payer, b.contract_source,
+                           MAX(b.depth, COALESCE(d.max_depth, 0)) AS depth,
+                           b.bucket_depth, b.immutable, b.normalised_balance, b.created_at, b.block_number
+                    FROM batches b
+                    LEFT JOIN (
+                        SELECT batch_id, MAX(CAST(json_extract(data, '$.new_depth') AS INTEGER)) AS max_depth
+                        FROM events
+                        WHERE event_type = 'BatchDepthIncrease'
+                        GROUP BY batch_id
+                    ) d ON d.batch_id = b.batch_id
+                    WHERE b.created_at >= ?
+                    ORDER BY b.created_at ASC
+                    "#;
+                self.explain(sql, &[cutoff]).await;
+                let rows = sqlx::query(sql)
+                    .bind(cutoff)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+            DatabasePool::Postgres(pool) => {
+                let sql = r#"
+                    SELECT b.batch_id, b.owner, b.payer, b.contract_source,
+                           GREATEST(b.depth, COALESCE(d.max_depth, 0)) AS depth,
+                           b.bucket_depth, b.immutable, b.normalised_balance, b.created_at, b.block_number
+                    FROM batches b
+                    LEFT JOIN (
+                        SELECT batch_id, MAX(CAST(data::json->>'new_depth' AS BIGINT)) AS max_depth
+                        FROM events
+                        WHERE event_type = 'BatchDepthIncrease'
+                        GROUP BY batch_id
+                    ) d ON d.batch_id = b.batch_id
+                    WHERE b.created_at >= $1
+                    ORDER BY b.created_at ASC
+                    "#;
+                self.explain(sql, &[cutoff]).await;
+                let rows = sqlx::query(sql)
+                    .bind(cutoff)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT b.batch_id, b.owner, b.payer, b.contract_source,
+                           GREATEST(b.depth, COALESCE(d.max_depth, 0)) AS depth,
+                           b.bucket_depth, b.immutable, b.normalised_balance, b.created_at, b.block_number
+                    FROM batches b
+                    LEFT JOIN (
+                        SELECT batch_id, MAX(CAST(JSON_UNQUOTE(JSON_EXTRACT(data, '$.new_depth')) AS SIGNED)) AS max_depth
+                        FROM events
+                        WHERE event_type = 'BatchDepthIncrease'
+                        GROUP BY batch_id
+                    ) d ON d.batch_id = b.batch_id
+                    WHERE b.created_at >= ?
+                    ORDER BY b.created_at ASC
+                    "#;
+                self.explain(sql, &[cutoff]).await;
+                let rows = sqlx::query(sql)
+                    .bind(cutoff)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+        };
+
+        Ok(batches)
+    }
+
+    /// Retrieve one batch by its full ID, with its depth after any depth increases
+    ///
+    /// Reads a single row, with `depth` computed as in `get_batches_with_current_depth`.
+    pub async fn get_batch_with_current_depth(&self, batch_id: &str) -> Result<Option<BatchInfo>> {
+        let row = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = r#"
+                    SELECT b.batch_id, b.owner, b.payer, b.contract_source,
+                           MAX(b.depth, COALESCE((
+                               SELECT MAX(CAST(json_extract(data, '$.new_depth') AS INTEGER))
+                               FROM events
+                               WHERE event_type = 'BatchDepthIncrease' AND batch_id = b.batch_id
+                           ), 0)) AS depth,
+                           b.bucket_depth, b.immutable, b.normalised_balance, b.created_at, b.block_number
+                    FROM batches b
+                    WHERE b.batch_id = ?
+                    "#;
+                sqlx::query(sql)
+                    .bind(batch_id)
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| parse_batch_row(&row))
+            }
+            DatabasePool::Postgres(pool) => {
+                let sql = r#"
+                    SELECT b.batch_id, b.owner, b.payer, b.contract_source,
+                           GREATEST(b.depth, COALESCE((
+                               SELECT MAX(CAST(data::json->>'new_depth' AS BIGINT))
+                               FROM events
+                               WHERE event_type = 'BatchDepthIncrease' AND batch_id = b.batch_id
+                           ), 0)) AS depth,
+                           b.bucket_depth, b.immutable, b.normalised_balance, b.created_at, b.block_number
+                    FROM batches b
+                    WHERE b.batch_id = $1
+                    "#;
+                sqlx::query(sql)
+                    .bind(batch_id)
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| parse_batch_row(&row))
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT b.batch_id, b.owner, b.payer, b.contract_source,
+                           GREATEST(b.depth, COALESCE((
+                               SELECT MAX(CAST(JSON_UNQUOTE(JSON_EXTRACT(data, '$.new_depth')) AS SIGNED))
+                               FROM events
+                               WHERE event_type = 'BatchDepthIncrease' AND batch_id = b.batch_id
+                           ), 0)) AS depth,
+                           b.bucket_depth, b.immutable, b.normalised_balance, b.created_at, b.block_number
+                    FROM batches b
+                    WHERE b.batch_id = ?
+                    "#;
+                sqlx::query(sql)
+                    .bind(batch_id)
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| parse_batch_row(&row))
+            }
+        };
+
+        row.transpose()
+    }
+
+    /// Creation `total_amount` of every batch with a cached BatchCreated event
+    ///
+    /// Keyed by lowercase batch ID. Only BatchCreated rows are read, and only
+    /// their amount is extracted from the event data.
+    pub async fn get_creation_amounts(&self) -> Result<HashMap<String, String>> {
+        let rows: Vec<(String, Option<String>)> = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = r#"
+                    SELECT batch_id, json_extract(data, '$.total_amount')
+                    FROM events
+                    WHERE event_type = 'BatchCreated' AND batch_id IS NOT NULL
+                    "#;
+                self.explain(sql, &[]).await;
+                sqlx::query_as(sql).fetch_all(pool).await?
+            }
+            DatabasePool::Postgres(pool) => {
+                let sql = r#"
+                    SELECT batch_id, data::json->>'total_amount'
+                    FROM events
+                    WHERE event_type = 'BatchCreated' AND batch_id IS NOT NULL
+                    "#;
+                self.explain(sql, &[]).await;
+                sqlx::query_as(sql).fetch_all(pool).await?
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT batch_id, JSON_UNQUOTE(JSON_EXTRACT(data, '$.total_amount'))
+                    FROM events
+                    WHERE event_type = 'BatchCreated' AND batch_id IS NOT NULL
+                    "#;
+                self.explain(sql, &[]).await;
+                sqlx::query_as(sql).fetch_all(pool).await?
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(batch_id, total_amount)| Some((batch_id.to_lowercase(), total_amount?)))
+            .collect())
+    }
+
+    /// Retrieve events with `from_block <= block_number <= to_block`
+    ///
+    /// Both bounds are inclusive. Events are ordered as in `get_events`.
+    pub async fn get_events_in_block_range(&self, from_block: u64, to_block: u64) -> Result<Vec<StampEvent>> {
+        let (from, to) = block_range_bounds(from_block, to_block)?;
+        self.check_row_limit("events", "block_number", from, to).await?;
+
+        let events = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE block_number BETWEEN ? AND ?
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                let rows = sqlx::query(sql)
+                    .bind(from)
+                    .bind(to)
+                    .fetch_all(pool)
+                    .await?;
+
+                let mut events = Vec::new();
+                for row in rows {
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
+                }
+                events
+            }
+            DatabasePool::Postgres(pool) => {
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE block_number BETWEEN $1 AND $2
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                let rows = sqlx::query(sql)
+                    .bind(from)
+                    .bind(to)
+                    .fetch_all(pool)
+                    .await?;
+
+                let mut events = Vec::new();
+                for row in rows {
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
+                }
+                events
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE block_number BETWEEN ? AND ?
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                let rows = sqlx::query(sql)
+                    .bind(from)
+                    .bind(to)
+                    .fetch_all(pool)
+                    .await?;
+
+                let mut events = Vec::new();
+                for row in rows {
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
+                }
+                events
+            }
+        };
+
+        Ok(events)
+    }
+
+    /// Retrieve every event of one batch, in chain order
+    ///
+    /// `batch_id` must be the full ID; the lookup uses the `batch_id` index.
+    /// See [`Cache::find_batch_ids`] to resolve a partial ID first.
+    pub async fn get_events_for_batch(&self, batch_id: &str) -> Result<Vec<StampEvent>> {
+        let events = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let rows = sqlx::query(
+                    r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE batch_id = ?
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#,
+                )
+                .bind(batch_id)
+                .fetch_all(pool)
+                .await?;
+
+                let mut events = Vec::new();
+                for row in rows {
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
+                }
+                events
+            }
+            DatabasePool::Postgres(pool) => {
+                let rows = sqlx::query(
+                    r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE batch_id = $1
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#,
+                )
+                .bind(batch_id)
+                .fetch_all(pool)
+                .await?;
+
+                let mut events = Vec::new();
+                for row in rows {
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
+                }
+                events
+            }
+            DatabasePool::MySql(pool) => {
+                let rows = sqlx::query(
+                    r#"
+                    SELECT event_type, batch_id, block_number, block_timestamp,
+                           transaction_hash, log_index, contract_source, contract_address, block_hash, data
+                    FROM events
+                    WHERE batch_id = ?
+                    ORDER BY block_number ASC, log_index ASC, transaction_hash ASC
+                    "#,
+                )
+                .bind(batch_id)
+                .fetch_all(pool)
+                .await?;
+
+                let mut events = Vec::new();
+                for row in rows {
+                    if let Some(event) = parse_event_row(&row)? {
+                        events.push(event);
+                    }
+                }
+                events
+            }
+        };
+
+        Ok(events)
+    }
+
+    /// IDs of the cached batches owned by `owner`
+    ///
+    /// Owners are stored in lowercase, so `owner` is expected in
+    /// `filter::normalize_hex` form.
+    pub async fn get_batch_ids_by_owner(&self, owner: &str) -> Result<Vec<String>> {
+        let ids = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_scalar("SELECT batch_id FROM batches WHERE owner = ?")
+                    .bind(owner)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_scalar("SELECT batch_id FROM batches WHERE owner = $1")
+                    .bind(owner)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query_scalar("SELECT batch_id FROM batches WHERE owner = ?")
+                    .bind(owner)
+                    .fetch_all(pool)
+                    .await?
+            }
+        };
+
+        Ok(ids)
+    }
+
+    /// Full IDs of the cached batches with events whose ID contains `fragment`
+    ///
+    /// Expects `fragment` in `filter::normalize_hex` form, like the batch ID filters.
+    pub async fn find_batch_ids(&self, fragment: &str) -> Result<Vec<String>> {
+        let pattern = format!("%{fragment}%");
+        let ids = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_scalar(
+                    "SELECT DISTINCT batch_id FROM events WHERE batch_id LIKE ? ORDER BY batch_id",
+                )
+                .bind(&pattern)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_scalar(
+                    "SELECT DISTINCT batch_id FROM events WHERE batch_id LIKE $1 ORDER BY batch_id",
+                )
+                .bind(&pattern)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query_scalar(
+                    "SELECT DISTINCT batch_id FROM events WHERE batch_id LIKE ? ORDER BY batch_id",
+                )
+                .bind(&pattern)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+
+        Ok(ids)
+    }
+
+    /// Retrieve batches created with `from_block <= block_number <= to_block`
+    ///
+    /// Both bounds are inclusive. Batches are ordered as in `get_batches`.
+    pub async fn get_batches_in_block_range(&self, from_block: u64, to_block: u64) -> Result<Vec<BatchInfo>> {
+        let (from, to) = block_range_bounds(from_block, to_block)?;
+        self.check_row_limit("batches", "block_number", from, to).await?;
+
+        let batches = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = r#"
+                    SELECT batch_id, owner, payer, contract_source, depth, bucket_depth, immutable,
+                           normalised_balance, created_at, block_number
+                    FROM batches
+                    WHERE block_number BETWEEN ? AND ?
+                    ORDER BY created_at ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                let rows = sqlx::query(sql)
+                    .bind(from)
+                    .bind(to)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+            DatabasePool::Postgres(pool) => {
+                let sql = r#"
+                    SELECT batch_id, owner, payer, contract_source, depth, bucket_depth, immutable,
+                           normalised_balance, created_at, block_number
+                    FROM batches
+                    WHERE block_number BETWEEN $1 AND $2
+                    ORDER BY created_at ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                let rows = sqlx::query(sql)
+                    .bind(from)
+                    .bind(to)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT batch_id, owner, payer, contract_source, depth, bucket_depth, immutable,
+                           normalised_balance, created_at, block_number
+                    FROM batches
+                    WHERE block_number BETWEEN ? AND ?
+                    ORDER BY created_at ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                let rows = sqlx::query(sql)
+                    .bind(from)
+                    .bind(to)
+                    .fetch_all(pool)
+                    .await?;
+
+                rows.iter().map(parse_batch_row).collect::<Result<_>>()?
+            }
+        };
+
+        Ok(batches)
+    }
+
+    /// Get total number of events in the database
+    #[allow(dead_code)]
+    pub async fn count_events(&self) -> Result<i64> {
+        let count: i64 = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM events")
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+            DatabasePool::Postgres(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM events")
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+            DatabasePool::MySql(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM events")
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+        };
+        Ok(count)
+    }
+
+    /// Get total number of batches in the database
+    #[allow(dead_code)]
+    pub async fn count_batches(&self) -> Result<i64> {
+        let count: i64 = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM batches")
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+            DatabasePool::Postgres(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM batches")
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+            DatabasePool::MySql(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM batches")
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+        };
+        Ok(count)
+    }
+
+    /// Check if an RPC chunk has been cached
+    pub async fn is_chunk_cached(&self, chunk_hash: &str) -> Result<bool> {
+        let count: i64 = match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM rpc_cache WHERE chunk_hash = ?")
+                    .bind(chunk_hash)
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+            DatabasePool::Postgres(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM rpc_cache WHERE chunk_hash = $1")
+                    .bind(chunk_hash)
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+            DatabasePool::MySql(pool) => {
+                let row = sqlx::query("SELECT COUNT(*) as count FROM rpc_cache WHERE chunk_hash = ?")
+                    .bind(chunk_hash)
+                    .fetch_one(pool)
+                    .await?;
+                row.get("count")
+            }
+        };
+
+        Ok(count > 0)
+    }
+
+    /// Store RPC chunk metadata in cache
+    pub async fn cache_chunk(
+        &self,
+        chunk_hash: &str,
+        contract_address: &str,
+        from_block: u64,
+        to_block: u64,
+        event_count: usize,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        // Stored lowercase so range lookups can use the index with a plain comparison
+        let contract_address = contract_address.to_lowercase();
+
+        // Use database-specific UPSERT syntax
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT OR REPLACE INTO rpc_cache
+                    (chunk_hash, contract_address, from_block, to_block, processed_at, event_count)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    "#
+                )
+                .bind(chunk_hash)
+                .bind(&contract_address)
+                .bind(from_block as i64)
+                .bind(to_block as i64)
+                .bind(now)
+                .bind(event_count as i64)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO rpc_cache
+                    (chunk_hash, contract_address, from_block, to_block, processed_at, event_count)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (chunk_hash) DO UPDATE SET
+                        contract_address = EXCLUDED.contract_address,
+                        from_block = EXCLUDED.from_block,
+                        to_block = EXCLUDED.to_block,
+                        processed_at = EXCLUDED.processed_at,
+                        event_count = EXCLUDED.event_count
+                    "#
+                )
+                .bind(chunk_hash)
+                .bind(&contract_address)
+                .bind(from_block as i64)
+                .bind(to_block as i64)
+                .bind(now)
+                .bind(event_count as i64)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO rpc_cache
+                    (chunk_hash, contract_address, from_block, to_block, processed_at, event_count)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        contract_address = VALUES(contract_address),
+                        from_block = VALUES(from_block),
+                        to_block = VALUES(to_block),
+                        processed_at = VALUES(processed_at),
+                        event_count = VALUES(event_count)
+                    "#
+                )
+                .bind(chunk_hash)
+                .bind(&contract_address)
+                .bind(from_block as i64)
+                .bind(to_block as i64)
+                .bind(now)
+                .bind(event_count as i64)
+                .execute(pool)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the block ranges cached for a contract, sorted by `from_block`
+    ///
+    /// Each range is an inclusive `(from_block, to_block)` chunk from `rpc_cache`.
+    /// Ranges may overlap when chunk sizes changed between runs. Addresses are
+    /// stored lowercase, so the lookup is case-insensitive.
+    pub async fn get_cached_ranges(&self, contract_address: &str) -> Result<Vec<(u64, u64)>> {
+        let contract_address = contract_address.to_lowercase();
+        let rows: Vec<(i64, i64)> = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_as(
+                    r#"
+                    SELECT from_block, to_block
+                    FROM rpc_cache
+                    WHERE contract_address = ?
+                    ORDER BY from_block ASC, to_block ASC
+                    "#,
+                )
+                .bind(&contract_address)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_as(
+                    r#"
+                    SELECT from_block, to_block
+                    FROM rpc_cache
+                    WHERE contract_address = $1
+                    ORDER BY from_block ASC, to_block ASC
+                    "#,
+                )
+                .bind(&contract_address)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query_as(
+                    r#"
+                    SELECT from_block, to_block
+                    FROM rpc_cache
+                    WHERE contract_address = ?
+                    ORDER BY from_block ASC, to_block ASC
+                    "#,
+                )
+                .bind(&contract_address)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(|(from, to)| (from as u64, to as u64))
+            .collect())
+    }
+
+    /// Get statistics about RPC cache
+    #[allow(dead_code)]
+    pub async fn get_cache_stats(&self) -> Result<(i64, i64)> {
+        let (chunk_count, total_events) = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let row = sqlx::query(
+                    "SELECT COUNT(*) as chunk_count, COALESCE(SUM(event_count), 0) as total_events FROM rpc_cache",
+                )
+                .fetch_one(pool)
+                .await?;
+
+                let chunk_count: i64 = row.get("chunk_count");
+                let total_events: i64 = row.get("total_events");
+                (chunk_count, total_events)
+            }
+            DatabasePool::Postgres(pool) => {
+                let row = sqlx::query(
+                    "SELECT COUNT(*) as chunk_count, COALESCE(SUM(event_count), 0) as total_events FROM rpc_cache",
+                )
+                .fetch_one(pool)
+                .await?;
+
+                let chunk_count: i64 = row.get("chunk_count");
+                let total_events: i64 = row.get("total_events");
+                (chunk_count, total_events)
+            }
+            DatabasePool::MySql(pool) => {
+                let row = sqlx::query(
+                    "SELECT COUNT(*) as chunk_count, CAST(COALESCE(SUM(event_count), 0) AS SIGNED) as total_events FROM rpc_cache",
+                )
+                .fetch_one(pool)
+                .await?;
+
+                let chunk_count: i64 = row.get("chunk_count");
+                let total_events: i64 = row.get("total_events");
+                (chunk_count, total_events)
+            }
+        };
+
+        Ok((chunk_count, total_events))
+    }
+
+    /// Get cached batch balance if available and not too old
+    pub async fn get_cached_balance(&self, batch_id: &str, current_block: u64, validity_blocks: u64) -> Result<Option<String>> {
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let row = sqlx::query(
+                    "SELECT remaining_balance, fetched_block FROM batch_balances WHERE batch_id = ?",
+                )
+                .bind(batch_id)
+                .fetch_optional(pool)
+                .await?;
+
+                if let Some(row) = row {
+                    let fetched_block: i64 = row.get("fetched_block");
+                    // Consider cache valid if fetched within the specified validity period
+                    if current_block.saturating_sub(fetched_block as u64) < validity_blocks {
+                        return Ok(Some(row.get("remaining_balance")));
+                    }
+                }
+
+                Ok(None)
+            }
+            DatabasePool::Postgres(pool) => {
+                let row = sqlx::query(
+                    "SELECT remaining_balance, fetched_block FROM batch_balances WHERE batch_id = $1",
+                )
+                .bind(batch_id)
+                .fetch_optional(pool)
+                .await?;
 
-        Ok(count > 0)
+                if let Some(row) = row {
+                    let fetched_block: i64 = row.get("fetched_block");
+                    // Consider cache valid if fetched within the specified validity period
+                    if current_block.saturating_sub(fetched_block as u64) < validity_blocks {
+                        return Ok(Some(row.get("remaining_balance")));
+                    }
+                }
+
+                Ok(None)
+            }
+            DatabasePool::MySql(pool) => {
+                let row = sqlx::query(
+                    "SELECT remaining_balance, fetched_block FROM batch_balances WHERE batch_id = ?",
+                )
+                .bind(batch_id)
+                .fetch_optional(pool)
+                .await?;
+
+                if let Some(row) = row {
+                    let fetched_block: i64 = row.get("fetched_block");
+                    // Consider cache valid if fetched within the specified validity period
+                    if current_block.saturating_sub(fetched_block as u64) < validity_blocks {
+                        return Ok(Some(row.get("remaining_balance")));
+                    }
+                }
+
+                Ok(None)
+            }
+        }
     }
 
-    /// Store RPC chunk metadata in cache
-    pub async fn cache_chunk(
-        &self,
-        chunk_hash: &str,
-        contract_address: &str,
-        from_block: u64,
-        to_block: u64,
-        event_count: usize,
-    ) -> Result<()> {
+    /// Cache a batch balance
+    pub async fn cache_balance(&self, batch_id: &str, balance: &str, current_block: u64) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        // Use database-specific UPSERT syntax
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT OR REPLACE INTO batch_balances
+                    (batch_id, remaining_balance, fetched_at, fetched_block)
+                    VALUES (?, ?, ?, ?)
+                    "#
+                )
+                .bind(batch_id)
+                .bind(balance)
+                .bind(now)
+                .bind(current_block as i64)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO batch_balances
+                    (batch_id, remaining_balance, fetched_at, fetched_block)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (batch_id) DO UPDATE SET
+                        remaining_balance = EXCLUDED.remaining_balance,
+                        fetched_at = EXCLUDED.fetched_at,
+                        fetched_block = EXCLUDED.fetched_block
+                    "#
+                )
+                .bind(batch_id)
+                .bind(balance)
+                .bind(now)
+                .bind(current_block as i64)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO batch_balances
+                    (batch_id, remaining_balance, fetched_at, fetched_block)
+                    VALUES (?, ?, ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        remaining_balance = VALUES(remaining_balance),
+                        fetched_at = VALUES(fetched_at),
+                        fetched_block = VALUES(fetched_block)
+                    "#
+                )
+                .bind(batch_id)
+                .bind(balance)
+                .bind(now)
+                .bind(current_block as i64)
+                .execute(pool)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the last cached price
+    pub async fn get_cached_price(&self) -> Result<Option<u128>> {
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let row = sqlx::query(
+                    "SELECT value FROM cache_metadata WHERE key = 'last_price'",
+                )
+                .fetch_optional(pool)
+                .await?;
+
+                if let Some(row) = row {
+                    let value: String = row.get("value");
+                    let price = value.parse::<u128>()
+                        .map_err(|_| crate::error::StampError::Parse("Invalid cached price".to_string()))?;
+                    Ok(Some(price))
+                } else {
+                    Ok(None)
+                }
+            }
+            DatabasePool::Postgres(pool) => {
+                let row = sqlx::query(
+                    "SELECT value FROM cache_metadata WHERE key = 'last_price'",
+                )
+                .fetch_optional(pool)
+                .await?;
+
+                if let Some(row) = row {
+                    let value: String = row.get("value");
+                    let price = value.parse::<u128>()
+                        .map_err(|_| crate::error::StampError::Parse("Invalid cached price".to_string()))?;
+                    Ok(Some(price))
+                } else {
+                    Ok(None)
+                }
+            }
+            DatabasePool::MySql(pool) => {
+                let row = sqlx::query(
+                    "SELECT value FROM cache_metadata WHERE `key` = 'last_price'",
+                )
+                .fetch_optional(pool)
+                .await?;
+
+                if let Some(row) = row {
+                    let value: String = row.get("value");
+                    let price = value.parse::<u128>()
+                        .map_err(|_| crate::error::StampError::Parse("Invalid cached price".to_string()))?;
+                    Ok(Some(price))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    /// Cache the current price
+    pub async fn cache_price(&self, price: u128) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        // Use database-specific UPSERT syntax
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT OR REPLACE INTO cache_metadata
+                    (key, value, updated_at)
+                    VALUES ('last_price', ?, ?)
+                    "#
+                )
+                .bind(price.to_string())
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata
+                    (key, value, updated_at)
+                    VALUES ('last_price', $1, $2)
+                    ON CONFLICT (key) DO UPDATE SET
+                        value = EXCLUDED.value,
+                        updated_at = EXCLUDED.updated_at
+                    "#
+                )
+                .bind(price.to_string())
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata
+                    (`key`, value, updated_at)
+                    VALUES ('last_price', ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        value = VALUES(value),
+                        updated_at = VALUES(updated_at)
+                    "#
+                )
+                .bind(price.to_string())
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the last computed network storage snapshot
+    pub async fn get_cached_network_storage(&self) -> Result<Option<NetworkStorage>> {
+        let row = match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query("SELECT value FROM cache_metadata WHERE key = 'network_storage'")
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query("SELECT value FROM cache_metadata WHERE key = 'network_storage'")
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query("SELECT value FROM cache_metadata WHERE `key` = 'network_storage'")
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+        };
+
+        Ok(row.map(|value| serde_json::from_str(&value)).transpose()?)
+    }
+
+    /// Cache a network storage snapshot, replacing the previous one
+    pub async fn cache_network_storage(&self, storage: &NetworkStorage) -> Result<()> {
+        let value = serde_json::to_string(storage)?;
+        let computed_at = storage.computed_at.timestamp();
+
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT OR REPLACE INTO cache_metadata
+                    (key, value, updated_at)
+                    VALUES ('network_storage', ?, ?)
+                    "#
+                )
+                .bind(value)
+                .bind(computed_at)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata
+                    (key, value, updated_at)
+                    VALUES ('network_storage', $1, $2)
+                    ON CONFLICT (key) DO UPDATE SET
+                        value = EXCLUDED.value,
+                        updated_at = EXCLUDED.updated_at
+                    "#
+                )
+                .bind(value)
+                .bind(computed_at)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata
+                    (`key`, value, updated_at)
+                    VALUES ('network_storage', ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        value = VALUES(value),
+                        updated_at = VALUES(updated_at)
+                    "#
+                )
+                .bind(value)
+                .bind(computed_at)
+                .execute(pool)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the cached BZZ price in a fiat currency, if fetched within `max_age_secs`
+    pub async fn get_cached_fiat_rate(&self, currency: &str, max_age_secs: i64) -> Result<Option<f64>> {
+        let key = format!("fiat_rate_{currency}");
+        let cutoff = chrono::Utc::now().timestamp() - max_age_secs;
+
+        let row = match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query("SELECT value FROM cache_metadata WHERE key = ? AND updated_at >= ?")
+                    .bind(&key)
+                    .bind(cutoff)
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query("SELECT value FROM cache_metadata WHERE key = $1 AND updated_at >= $2")
+                    .bind(&key)
+                    .bind(cutoff)
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query("SELECT value FROM cache_metadata WHERE `key` = ? AND updated_at >= ?")
+                    .bind(&key)
+                    .bind(cutoff)
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+        };
+
+        row.map(|value| {
+            value
+                .parse::<f64>()
+                .map_err(|_| crate::error::StampError::Parse("Invalid cached fiat rate".to_string()))
+        })
+        .transpose()
+    }
+
+    /// Cache the BZZ price in a fiat currency, replacing the previous one
+    pub async fn cache_fiat_rate(&self, currency: &str, rate: f64) -> Result<()> {
+        let key = format!("fiat_rate_{currency}");
         let now = chrono::Utc::now().timestamp();
 
-        // Use database-specific UPSERT syntax
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
                 sqlx::query(
                     r#"
-                    INSERT OR REPLACE INTO rpc_cache
-                    (chunk_hash, contract_address, from_block, to_block, processed_at, event_count)
-                    VALUES (?, ?, ?, ?, ?, ?)
+                    INSERT OR REPLACE INTO cache_metadata
+                    (key, value, updated_at)
+                    VALUES (?, ?, ?)
                     "#
                 )
-                .bind(chunk_hash)
-                .bind(contract_address)
-                .bind(from_block as i64)
-                .bind(to_block as i64)
+                .bind(key)
+                .bind(rate.to_string())
                 .bind(now)
-                .bind(event_count as i64)
                 .execute(pool)
                 .await?;
             }
             DatabasePool::Postgres(pool) => {
                 sqlx::query(
                     r#"
-                    INSERT INTO rpc_cache
-                    (chunk_hash, contract_address, from_block, to_block, processed_at, event_count)
-                    VALUES ($1, $2, $3, $4, $5, $6)
-                    ON CONFLICT (chunk_hash) DO UPDATE SET
-                        contract_address = EXCLUDED.contract_address,
-                        from_block = EXCLUDED.from_block,
-                        to_block = EXCLUDED.to_block,
-                        processed_at = EXCLUDED.processed_at,
-                        event_count = EXCLUDED.event_count
+                    INSERT INTO cache_metadata
+                    (key, value, updated_at)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (key) DO UPDATE SET
+                        value = EXCLUDED.value,
+                        updated_at = EXCLUDED.updated_at
                     "#
                 )
-                .bind(chunk_hash)
-                .bind(contract_address)
-                .bind(from_block as i64)
-                .bind(to_block as i64)
+                .bind(key)
+                .bind(rate.to_string())
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata
+                    (`key`, value, updated_at)
+                    VALUES (?, ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        value = VALUES(value),
+                        updated_at = VALUES(updated_at)
+                    "#
+                )
+                .bind(key)
+                .bind(rate.to_string())
                 .bind(now)
-                .bind(event_count as i64)
                 .execute(pool)
                 .await?;
             }
@@ -768,200 +2795,850 @@ impl Cache {
         Ok(())
     }
 
-    /// Get statistics about RPC cache
-    #[allow(dead_code)]
-    pub async fn get_cache_stats(&self) -> Result<(i64, i64)> {
-        let (chunk_count, total_events) = match &self.pool {
+    /// Get the last block scanned by follow mode
+    ///
+    /// Returns `None` if follow mode has never completed a scan against this cache.
+    pub async fn get_follow_cursor(&self) -> Result<Option<u64>> {
+        let row = match &self.pool {
             DatabasePool::Sqlite(pool) => {
-                let row = sqlx::query(
-                    "SELECT COUNT(*) as chunk_count, COALESCE(SUM(event_count), 0) as total_events FROM rpc_cache",
+                sqlx::query("SELECT value FROM cache_metadata WHERE key = 'follow_cursor'")
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query("SELECT value FROM cache_metadata WHERE key = 'follow_cursor'")
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query("SELECT value FROM cache_metadata WHERE `key` = 'follow_cursor'")
+                    .fetch_optional(pool)
+                    .await?
+                    .map(|row| row.get::<String, _>("value"))
+            }
+        };
+
+        row.map(|value| {
+            value
+                .parse::<u64>()
+                .map_err(|_| crate::error::StampError::Parse("Invalid follow cursor".to_string()))
+        })
+        .transpose()
+    }
+
+    /// Persist the last block scanned by follow mode so it can resume after a restart
+    pub async fn set_follow_cursor(&self, block_number: u64) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT OR REPLACE INTO cache_metadata
+                    (key, value, updated_at)
+                    VALUES ('follow_cursor', ?, ?)
+                    "#
                 )
-                .fetch_one(pool)
+                .bind(block_number.to_string())
+                .bind(now)
+                .execute(pool)
                 .await?;
-
-                let chunk_count: i64 = row.get("chunk_count");
-                let total_events: i64 = row.get("total_events");
-                (chunk_count, total_events)
             }
             DatabasePool::Postgres(pool) => {
-                let row = sqlx::query(
-                    "SELECT COUNT(*) as chunk_count, COALESCE(SUM(event_count), 0) as total_events FROM rpc_cache",
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata
+                    (key, value, updated_at)
+                    VALUES ('follow_cursor', $1, $2)
+                    ON CONFLICT (key) DO UPDATE SET
+                        value = EXCLUDED.value,
+                        updated_at = EXCLUDED.updated_at
+                    "#
                 )
-                .fetch_one(pool)
+                .bind(block_number.to_string())
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO cache_metadata
+                    (`key`, value, updated_at)
+                    VALUES ('follow_cursor', ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        value = VALUES(value),
+                        updated_at = VALUES(updated_at)
+                    "#
+                )
+                .bind(block_number.to_string())
+                .bind(now)
+                .execute(pool)
                 .await?;
+            }
+        }
 
-                let chunk_count: i64 = row.get("chunk_count");
-                let total_events: i64 = row.get("total_events");
-                (chunk_count, total_events)
+        Ok(())
+    }
+
+    /// Get the chunk cursor of the last fetch over a contract, if any
+    pub async fn get_fetch_cursor(&self, contract_address: &str) -> Result<Option<FetchCursor>> {
+        let address = contract_address.to_lowercase();
+        let row: Option<(Option<i64>, Option<i64>, Option<i64>)> = match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_as(
+                    "SELECT from_block, chunk_size, last_completed_to_block FROM sync_state WHERE contract_address = ?",
+                )
+                .bind(address)
+                .fetch_optional(pool)
+                .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_as(
+                    "SELECT from_block, chunk_size, last_completed_to_block FROM sync_state WHERE contract_address = $1",
+                )
+                .bind(address)
+                .fetch_optional(pool)
+                .await?
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query_as(
+                    "SELECT from_block, chunk_size, last_completed_to_block FROM sync_state WHERE contract_address = ?",
+                )
+                .bind(address)
+                .fetch_optional(pool)
+                .await?
             }
         };
 
-        Ok((chunk_count, total_events))
+        // The columns stay NULL until a fetch has completed a chunk
+        Ok(match row {
+            Some((Some(from_block), Some(chunk_size), Some(last_completed_to_block))) => Some(FetchCursor {
+                from_block: from_block as u64,
+                chunk_size: chunk_size as u64,
+                last_completed_to_block: last_completed_to_block as u64,
+            }),
+            _ => None,
+        })
     }
 
-    /// Get cached batch balance if available and not too old
-    pub async fn get_cached_balance(&self, batch_id: &str, current_block: u64, validity_blocks: u64) -> Result<Option<String>> {
+    /// Store the chunk cursor of a fetch over a contract, replacing the previous one
+    ///
+    /// The cursor lives on the contract's `sync_state` row. A new row starts its
+    /// sync checkpoint at the cursor's last completed block.
+    pub async fn set_fetch_cursor(&self, contract_address: &str, cursor: &FetchCursor) -> Result<()> {
+        let address = contract_address.to_lowercase();
+        let now = chrono::Utc::now().timestamp();
+
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
-                let row = sqlx::query(
-                    "SELECT remaining_balance, fetched_block FROM batch_balances WHERE batch_id = ?",
+                sqlx::query(
+                    r#"
+                    INSERT INTO sync_state
+                    (contract_address, last_scanned_block, from_block, chunk_size, last_completed_to_block, updated_at)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    ON CONFLICT (contract_address) DO UPDATE SET
+                        from_block = excluded.from_block,
+                        chunk_size = excluded.chunk_size,
+                        last_completed_to_block = excluded.last_completed_to_block,
+                        updated_at = excluded.updated_at
+                    "#
                 )
-                .bind(batch_id)
-                .fetch_optional(pool)
+                .bind(address)
+                .bind(cursor.last_completed_to_block as i64)
+                .bind(cursor.from_block as i64)
+                .bind(cursor.chunk_size as i64)
+                .bind(cursor.last_completed_to_block as i64)
+                .bind(now)
+                .execute(pool)
                 .await?;
-
-                if let Some(row) = row {
-                    let fetched_block: i64 = row.get("fetched_block");
-                    // Consider cache valid if fetched within the specified validity period
-                    if current_block.saturating_sub(fetched_block as u64) < validity_blocks {
-                        return Ok(Some(row.get("remaining_balance")));
-                    }
-                }
-
-                Ok(None)
             }
             DatabasePool::Postgres(pool) => {
-                let row = sqlx::query(
-                    "SELECT remaining_balance, fetched_block FROM batch_balances WHERE batch_id = $1",
+                sqlx::query(
+                    r#"
+                    INSERT INTO sync_state
+                    (contract_address, last_scanned_block, from_block, chunk_size, last_completed_to_block, updated_at)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (contract_address) DO UPDATE SET
+                        from_block = EXCLUDED.from_block,
+                        chunk_size = EXCLUDED.chunk_size,
+                        last_completed_to_block = EXCLUDED.last_completed_to_block,
+                        updated_at = EXCLUDED.updated_at
+                    "#
                 )
-                .bind(batch_id)
-                .fetch_optional(pool)
+                .bind(address)
+                .bind(cursor.last_completed_to_block as i64)
+                .bind(cursor.from_block as i64)
+                .bind(cursor.chunk_size as i64)
+                .bind(cursor.last_completed_to_block as i64)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO sync_state
+                    (contract_address, last_scanned_block, from_block, chunk_size, last_completed_to_block, updated_at)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        from_block = VALUES(from_block),
+                        chunk_size = VALUES(chunk_size),
+                        last_completed_to_block = VALUES(last_completed_to_block),
+                        updated_at = VALUES(updated_at)
+                    "#
+                )
+                .bind(address)
+                .bind(cursor.last_completed_to_block as i64)
+                .bind(cursor.from_block as i64)
+                .bind(cursor.chunk_size as i64)
+                .bind(cursor.last_completed_to_block as i64)
+                .bind(now)
+                .execute(pool)
                 .await?;
+            }
+        }
 
-                if let Some(row) = row {
-                    let fetched_block: i64 = row.get("fetched_block");
-                    // Consider cache valid if fetched within the specified validity period
-                    if current_block.saturating_sub(fetched_block as u64) < validity_blocks {
-                        return Ok(Some(row.get("remaining_balance")));
-                    }
-                }
+        Ok(())
+    }
 
-                Ok(None)
+    /// Get the highest block fully scanned for a contract, if any
+    pub async fn get_sync_checkpoint(&self, contract_address: &str) -> Result<Option<u64>> {
+        let address = contract_address.to_lowercase();
+        let block: Option<i64> = match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_scalar("SELECT last_scanned_block FROM sync_state WHERE contract_address = ?")
+                    .bind(address)
+                    .fetch_optional(pool)
+                    .await?
             }
-        }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_scalar("SELECT last_scanned_block FROM sync_state WHERE contract_address = $1")
+                    .bind(address)
+                    .fetch_optional(pool)
+                    .await?
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query_scalar("SELECT last_scanned_block FROM sync_state WHERE contract_address = ?")
+                    .bind(address)
+                    .fetch_optional(pool)
+                    .await?
+            }
+        };
+
+        Ok(block.map(|b| b as u64))
     }
 
-    /// Cache a batch balance
-    pub async fn cache_balance(&self, batch_id: &str, balance: &str, current_block: u64) -> Result<()> {
+    /// Record that a contract has been scanned up to and including `block_number`
+    ///
+    /// The checkpoint only moves forward; re-scanning an older range leaves it alone.
+    pub async fn set_sync_checkpoint(&self, contract_address: &str, block_number: u64) -> Result<()> {
+        let address = contract_address.to_lowercase();
         let now = chrono::Utc::now().timestamp();
 
-        // Use database-specific UPSERT syntax
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
                 sqlx::query(
                     r#"
-                    INSERT OR REPLACE INTO batch_balances
-                    (batch_id, remaining_balance, fetched_at, fetched_block)
-                    VALUES (?, ?, ?, ?)
+                    INSERT INTO sync_state (contract_address, last_scanned_block, updated_at)
+                    VALUES (?, ?, ?)
+                    ON CONFLICT (contract_address) DO UPDATE SET
+                        last_scanned_block = MAX(last_scanned_block, excluded.last_scanned_block),
+                        updated_at = excluded.updated_at
                     "#
                 )
-                .bind(batch_id)
-                .bind(balance)
+                .bind(address)
+                .bind(block_number as i64)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO sync_state (contract_address, last_scanned_block, updated_at)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (contract_address) DO UPDATE SET
+                        last_scanned_block = GREATEST(sync_state.last_scanned_block, EXCLUDED.last_scanned_block),
+                        updated_at = EXCLUDED.updated_at
+                    "#
+                )
+                .bind(address)
+                .bind(block_number as i64)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO sync_state (contract_address, last_scanned_block, updated_at)
+                    VALUES (?, ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        last_scanned_block = GREATEST(last_scanned_block, VALUES(last_scanned_block)),
+                        updated_at = VALUES(updated_at)
+                    "#
+                )
+                .bind(address)
+                .bind(block_number as i64)
                 .bind(now)
-                .bind(current_block as i64)
                 .execute(pool)
                 .await?;
             }
-            DatabasePool::Postgres(pool) => {
+        }
+
+        Ok(())
+    }
+
+    /// Get stored block hashes for the most recent blocks that have events
+    ///
+    /// Returns up to `limit` `(block_number, block_hash)` pairs, newest first.
+    /// Events stored before block hashes were recorded are skipped.
+    pub async fn get_recent_block_hashes(&self, limit: usize) -> Result<Vec<(u64, String)>> {
+        let rows: Vec<(i64, String)> = match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query_as(
+                    r#"
+                    SELECT DISTINCT block_number, block_hash
+                    FROM events
+                    WHERE block_hash IS NOT NULL
+                    ORDER BY block_number DESC
+                    LIMIT ?
+                    "#,
+                )
+                .bind(limit as i64)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query_as(
+                    r#"
+                    SELECT DISTINCT block_number, block_hash
+                    FROM events
+                    WHERE block_hash IS NOT NULL
+                    ORDER BY block_number DESC
+                    LIMIT $1
+                    "#,
+                )
+                .bind(limit as i64)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::MySql(pool) => {
+                sqlx::query_as(
+                    r#"
+                    SELECT DISTINCT block_number, block_hash
+                    FROM events
+                    WHERE block_hash IS NOT NULL
+                    ORDER BY block_number DESC
+                    LIMIT ?
+                    "#,
+                )
+                .bind(limit as i64)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(|(block, hash)| (block as u64, hash))
+            .collect())
+    }
+
+    /// Remove everything cached at or above `block_number` after a chain reorganization
+    ///
+    /// Deletes events, batches, storage incentives events and RPC chunk entries
+    /// reaching `block_number` and moves sync checkpoints and fetch cursors back
+    /// before it, so the range is re-fetched on the next sync. Older batches
+    /// touched by the removed events lose their cached balance, and their
+    /// current depth drops back to the remaining depth increases.
+    /// Returns the number of events removed.
+    pub async fn rollback_to_block(&self, block_number: u64) -> Result<u64> {
+        let block = i64::try_from(block_number).unwrap_or(i64::MAX);
+        // Sync checkpoints at or past the rollback point fall back to the block before it
+        let checkpoint = block.saturating_sub(1).max(0);
+
+        let removed = match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let mut tx = pool.begin().await?;
+                // Cached balances of batches topped up or grown in the orphaned blocks, or
+                // read from the orphaned chain, no longer match the canonical chain
+                sqlx::query(
+                    r#"
+                    DELETE FROM batch_balances
+                    WHERE fetched_block >= ?
+                       OR batch_id IN (SELECT batch_id FROM events WHERE block_number >= ? AND batch_id IS NOT NULL)
+                    "#,
+                )
+                .bind(block)
+                .bind(block)
+                .execute(&mut *tx)
+                .await?;
+                let removed = sqlx::query("DELETE FROM events WHERE block_number >= ?")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                sqlx::query("DELETE FROM batches WHERE block_number >= ?")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM storage_incentives_events WHERE block_number >= ?")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM rpc_cache WHERE to_block >= ?")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE sync_state SET last_scanned_block = ? WHERE last_scanned_block >= ?")
+                    .bind(checkpoint)
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE sync_state SET last_completed_to_block = ? WHERE last_completed_to_block >= ?")
+                    .bind(checkpoint)
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+                removed
+            }
+            DatabasePool::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                // Cached balances of batches topped up or grown in the orphaned blocks, or
+                // read from the orphaned chain, no longer match the canonical chain
+                sqlx::query(
+                    r#"
+                    DELETE FROM batch_balances
+                    WHERE fetched_block >= $1
+                       OR batch_id IN (SELECT batch_id FROM events WHERE block_number >= $2 AND batch_id IS NOT NULL)
+                    "#,
+                )
+                .bind(block)
+                .bind(block)
+                .execute(&mut *tx)
+                .await?;
+                let removed = sqlx::query("DELETE FROM events WHERE block_number >= $1")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                sqlx::query("DELETE FROM batches WHERE block_number >= $1")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM storage_incentives_events WHERE block_number >= $1")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM rpc_cache WHERE to_block >= $1")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE sync_state SET last_scanned_block = $1 WHERE last_scanned_block >= $2")
+                    .bind(checkpoint)
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE sync_state SET last_completed_to_block = $1 WHERE last_completed_to_block >= $2")
+                    .bind(checkpoint)
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+                removed
+            }
+            DatabasePool::MySql(pool) => {
+                let mut tx = pool.begin().await?;
+                // Cached balances of batches topped up or grown in the orphaned blocks, or
+                // read from the orphaned chain, no longer match the canonical chain
                 sqlx::query(
                     r#"
-                    INSERT INTO batch_balances
-                    (batch_id, remaining_balance, fetched_at, fetched_block)
-                    VALUES ($1, $2, $3, $4)
-                    ON CONFLICT (batch_id) DO UPDATE SET
-                        remaining_balance = EXCLUDED.remaining_balance,
-                        fetched_at = EXCLUDED.fetched_at,
-                        fetched_block = EXCLUDED.fetched_block
-                    "#
+                    DELETE FROM batch_balances
+                    WHERE fetched_block >= ?
+                       OR batch_id IN (SELECT batch_id FROM events WHERE block_number >= ? AND batch_id IS NOT NULL)
+                    "#,
                 )
-                .bind(batch_id)
-                .bind(balance)
-                .bind(now)
-                .bind(current_block as i64)
-                .execute(pool)
+                .bind(block)
+                .bind(block)
+                .execute(&mut *tx)
                 .await?;
+                let removed = sqlx::query("DELETE FROM events WHERE block_number >= ?")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                sqlx::query("DELETE FROM batches WHERE block_number >= ?")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM storage_incentives_events WHERE block_number >= ?")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("DELETE FROM rpc_cache WHERE to_block >= ?")
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE sync_state SET last_scanned_block = ? WHERE last_scanned_block >= ?")
+                    .bind(checkpoint)
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE sync_state SET last_completed_to_block = ? WHERE last_completed_to_block >= ?")
+                    .bind(checkpoint)
+                    .bind(block)
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+                removed
             }
-        }
+        };
 
-        Ok(())
+        Ok(removed)
     }
 
-    /// Get the last cached price
-    pub async fn get_cached_price(&self) -> Result<Option<u128>> {
-        match &self.pool {
+    /// Retrieve the storage price series with `start_ts <= block_timestamp <= end_ts`
+    ///
+    /// Built from cached PriceOracle `PriceUpdate` events, ordered by block.
+    /// Use `(months_cutoff(months), i64::MAX)` for the last N months.
+    pub async fn get_price_history(&self, start_ts: i64, end_ts: i64) -> Result<Vec<PricePoint>> {
+
+        let rows: Vec<(i64, i64, String)> = match self.reader() {
             DatabasePool::Sqlite(pool) => {
-                let row = sqlx::query(
-                    "SELECT value FROM cache_metadata WHERE key = 'last_price'",
-                )
-                .fetch_optional(pool)
-                .await?;
+                let sql = r#"
+                    SELECT block_number, block_timestamp, price
+                    FROM storage_incentives_events
+                    WHERE event_type = 'PriceUpdate' AND price IS NOT NULL AND block_timestamp >= ?
+                      AND block_timestamp <= ?
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                sqlx::query_as(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::Postgres(pool) => {
+                let sql = r#"
+                    SELECT block_number, block_timestamp, price
+                    FROM storage_incentives_events
+                    WHERE event_type = 'PriceUpdate' AND price IS NOT NULL AND block_timestamp >= $1
+                      AND block_timestamp <= $2
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                sqlx::query_as(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT block_number, block_timestamp, price
+                    FROM storage_incentives_events
+                    WHERE event_type = 'PriceUpdate' AND price IS NOT NULL AND block_timestamp >= ?
+                      AND block_timestamp <= ?
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                sqlx::query_as(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?
+            }
+        };
 
-                if let Some(row) = row {
-                    let value: String = row.get("value");
-                    let price = value.parse::<u128>()
-                        .map_err(|_| crate::error::StampError::Parse("Invalid cached price".to_string()))?;
-                    Ok(Some(price))
-                } else {
-                    Ok(None)
-                }
+        rows.into_iter()
+            .map(|(block_number, timestamp, price)| {
+                let price = price.parse::<u128>().map_err(|_| {
+                    crate::error::StampError::Parse(format!("Invalid cached price: {price}"))
+                })?;
+                Ok(PricePoint {
+                    block_number: block_number as u64,
+                    block_timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now),
+                    price,
+                })
+            })
+            .collect()
+    }
+
+    /// Get cached redistribution winners ordered by block
+    ///
+    /// Reads `WinnerSelected` events stored by `fetch` with
+    /// `start_ts <= block_timestamp <= end_ts`. Pass `(0, i64::MAX)` for all time.
+    pub async fn get_winners(&self, start_ts: i64, end_ts: i64) -> Result<Vec<RoundWinner>> {
+
+        type WinnerRow = (i64, i64, Option<i64>, String, String, String);
+        let rows: Vec<WinnerRow> = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = r#"
+                    SELECT block_number, block_timestamp, round_number, winner_overlay, winner_owner, winner_stake
+                    FROM storage_incentives_events
+                    WHERE event_type = 'WinnerSelected'
+                      AND winner_overlay IS NOT NULL AND winner_owner IS NOT NULL AND winner_stake IS NOT NULL
+                      AND block_timestamp >= ? AND block_timestamp <= ?
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                sqlx::query_as(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?
             }
             DatabasePool::Postgres(pool) => {
-                let row = sqlx::query(
-                    "SELECT value FROM cache_metadata WHERE key = 'last_price'",
-                )
-                .fetch_optional(pool)
-                .await?;
+                let sql = r#"
+                    SELECT block_number, block_timestamp, round_number, winner_overlay, winner_owner, winner_stake
+                    FROM storage_incentives_events
+                    WHERE event_type = 'WinnerSelected'
+                      AND winner_overlay IS NOT NULL AND winner_owner IS NOT NULL AND winner_stake IS NOT NULL
+                      AND block_timestamp >= $1 AND block_timestamp <= $2
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                sqlx::query_as(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT block_number, block_timestamp, round_number, winner_overlay, winner_owner, winner_stake
+                    FROM storage_incentives_events
+                    WHERE event_type = 'WinnerSelected'
+                      AND winner_overlay IS NOT NULL AND winner_owner IS NOT NULL AND winner_stake IS NOT NULL
+                      AND block_timestamp >= ? AND block_timestamp <= ?
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[start_ts, end_ts]).await;
+                sqlx::query_as(sql)
+                    .bind(start_ts)
+                    .bind(end_ts)
+                    .fetch_all(pool)
+                    .await?
+            }
+        };
 
-                if let Some(row) = row {
-                    let value: String = row.get("value");
-                    let price = value.parse::<u128>()
-                        .map_err(|_| crate::error::StampError::Parse("Invalid cached price".to_string()))?;
-                    Ok(Some(price))
-                } else {
-                    Ok(None)
-                }
+        rows.into_iter()
+            .map(|(block_number, timestamp, round_number, overlay, owner, stake)| {
+                let stake = stake.parse::<u128>().map_err(|_| {
+                    crate::error::StampError::Parse(format!("Invalid cached winner stake: {stake}"))
+                })?;
+                Ok(RoundWinner {
+                    block_number: block_number as u64,
+                    block_timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now),
+                    round_number: round_number.map(|r| r as u64),
+                    overlay,
+                    owner,
+                    stake,
+                })
+            })
+            .collect()
+    }
+
+    /// Retrieve all cached StakeRegistry events, in chain order
+    ///
+    /// Only the staking fields of each [`StorageIncentivesEvent`] are filled in.
+    pub async fn get_stake_events(&self) -> Result<Vec<StorageIncentivesEvent>> {
+        type StakeRow = (
+            i64,
+            i64,
+            String,
+            i64,
+            String,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        );
+        let rows: Vec<StakeRow> = match self.reader() {
+            DatabasePool::Sqlite(pool) => {
+                let sql = r#"
+                    SELECT block_number, block_timestamp, transaction_hash, log_index, event_type,
+                           owner_address, overlay, committed_stake, potential_stake, height,
+                           slash_amount, freeze_time, withdraw_amount
+                    FROM storage_incentives_events
+                    WHERE contract_source = 'StakeRegistry'
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[]).await;
+                sqlx::query_as(sql).fetch_all(pool).await?
             }
-        }
+            DatabasePool::Postgres(pool) => {
+                let sql = r#"
+                    SELECT block_number, block_timestamp, transaction_hash, log_index, event_type,
+                           owner_address, overlay, committed_stake, potential_stake, height,
+                           slash_amount, freeze_time, withdraw_amount
+                    FROM storage_incentives_events
+                    WHERE contract_source = 'StakeRegistry'
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[]).await;
+                sqlx::query_as(sql).fetch_all(pool).await?
+            }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT block_number, block_timestamp, transaction_hash, log_index, event_type,
+                           owner_address, overlay, committed_stake, potential_stake, height,
+                           slash_amount, freeze_time, withdraw_amount
+                    FROM storage_incentives_events
+                    WHERE contract_source = 'StakeRegistry'
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[]).await;
+                sqlx::query_as(sql).fetch_all(pool).await?
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(
+                    block_number,
+                    timestamp,
+                    transaction_hash,
+                    log_index,
+                    event_type,
+                    owner_address,
+                    overlay,
+                    committed_stake,
+                    potential_stake,
+                    height,
+                    slash_amount,
+                    freeze_time,
+                    withdraw_amount,
+                )| StorageIncentivesEvent {
+                    block_number: block_number as u64,
+                    block_timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now),
+                    transaction_hash,
+                    log_index: log_index as u64,
+                    contract_source: "StakeRegistry".to_string(),
+                    event_type,
+                    owner_address,
+                    overlay,
+                    committed_stake,
+                    potential_stake,
+                    height: height.map(|h| h as u8),
+                    slash_amount,
+                    freeze_time,
+                    withdraw_amount,
+                    ..Default::default()
+                },
+            )
+            .collect())
     }
 
-    /// Cache the current price
-    pub async fn cache_price(&self, price: u128) -> Result<()> {
-        let now = chrono::Utc::now().timestamp();
+    /// Retrieve cached Redistribution events for rounds `from_round..=to_round`, in chain order
+    ///
+    /// Only the round fields of each [`StorageIncentivesEvent`] are filled in:
+    /// the overlay, truth hash, winner and commit/reveal counts.
+    pub async fn get_round_events(&self, from_round: u64, to_round: u64) -> Result<Vec<StorageIncentivesEvent>> {
+        let from = from_round.min(i64::MAX as u64) as i64;
+        let to = to_round.min(i64::MAX as u64) as i64;
 
-        // Use database-specific UPSERT syntax
-        match &self.pool {
+        type RoundRow = (
+            i64,
+            i64,
+            String,
+            i64,
+            String,
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<i64>,
+            Option<i64>,
+        );
+        let rows: Vec<RoundRow> = match self.reader() {
             DatabasePool::Sqlite(pool) => {
-                sqlx::query(
-                    r#"
-                    INSERT OR REPLACE INTO cache_metadata
-                    (key, value, updated_at)
-                    VALUES ('last_price', ?, ?)
-                    "#
-                )
-                .bind(price.to_string())
-                .bind(now)
-                .execute(pool)
-                .await?;
+                let sql = r#"
+                    SELECT block_number, block_timestamp, transaction_hash, log_index, event_type,
+                           round_number, overlay, truth_hash, winner_overlay, winner_owner,
+                           commit_count, reveal_count
+                    FROM storage_incentives_events
+                    WHERE contract_source = 'Redistribution'
+                      AND round_number >= ? AND round_number <= ?
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                sqlx::query_as(sql).bind(from).bind(to).fetch_all(pool).await?
             }
             DatabasePool::Postgres(pool) => {
-                sqlx::query(
-                    r#"
-                    INSERT INTO cache_metadata
-                    (key, value, updated_at)
-                    VALUES ('last_price', $1, $2)
-                    ON CONFLICT (key) DO UPDATE SET
-                        value = EXCLUDED.value,
-                        updated_at = EXCLUDED.updated_at
-                    "#
-                )
-                .bind(price.to_string())
-                .bind(now)
-                .execute(pool)
-                .await?;
+                let sql = r#"
+                    SELECT block_number, block_timestamp, transaction_hash, log_index, event_type,
+                           round_number, overlay, truth_hash, winner_overlay, winner_owner,
+                           commit_count, reveal_count
+                    FROM storage_incentives_events
+                    WHERE contract_source = 'Redistribution'
+                      AND round_number >= $1 AND round_number <= $2
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                sqlx::query_as(sql).bind(from).bind(to).fetch_all(pool).await?
             }
-        }
+            DatabasePool::MySql(pool) => {
+                let sql = r#"
+                    SELECT block_number, block_timestamp, transaction_hash, log_index, event_type,
+                           round_number, overlay, truth_hash, winner_overlay, winner_owner,
+                           commit_count, reveal_count
+                    FROM storage_incentives_events
+                    WHERE contract_source = 'Redistribution'
+                      AND round_number >= ? AND round_number <= ?
+                    ORDER BY block_number ASC, log_index ASC
+                    "#;
+                self.explain(sql, &[from, to]).await;
+                sqlx::query_as(sql).bind(from).bind(to).fetch_all(pool).await?
+            }
+        };
 
-        Ok(())
+        Ok(rows
+            .into_iter()
+            .map(
+                |(
+                    block_number,
+                    timestamp,
+                    transaction_hash,
+                    log_index,
+                    event_type,
+                    round_number,
+                    overlay,
+                    truth_hash,
+                    winner_overlay,
+                    winner_owner,
+                    commit_count,
+                    reveal_count,
+                )| StorageIncentivesEvent {
+                    block_number: block_number as u64,
+                    block_timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now),
+                    transaction_hash,
+                    log_index: log_index as u64,
+                    contract_source: "Redistribution".to_string(),
+                    event_type,
+                    round_number: round_number.map(|r| r as u64),
+                    overlay,
+                    truth_hash,
+                    winner_overlay,
+                    winner_owner,
+                    commit_count: commit_count.map(|c| c as u64),
+                    reveal_count: reveal_count.map(|c| c as u64),
+                    ..Default::default()
+                },
+            )
+            .collect())
     }
 
     /// Get block timestamp from cached event data
@@ -1022,85 +3699,338 @@ impl Cache {
                     return Ok(Some(row.get("block_timestamp")));
                 }
 
+                Ok(None)
+            }
+            DatabasePool::MySql(pool) => {
+                // Try events table first
+                let row = sqlx::query(
+                    "SELECT block_timestamp FROM events WHERE block_number = ? LIMIT 1"
+                )
+                .bind(block_number as i64)
+                .fetch_optional(pool)
+                .await?;
+
+                if let Some(row) = row {
+                    return Ok(Some(row.get("block_timestamp")));
+                }
+
+                // Try storage_incentives_events table
+                let row = sqlx::query(
+                    "SELECT block_timestamp FROM storage_incentives_events WHERE block_number = ? LIMIT 1"
+                )
+                .bind(block_number as i64)
+                .fetch_optional(pool)
+                .await?;
+
+                if let Some(row) = row {
+                    return Ok(Some(row.get("block_timestamp")));
+                }
+
                 Ok(None)
             }
         }
     }
 }
 
+/// Normalize a MySQL or MariaDB URL for the sqlx MySQL driver
+///
+/// MariaDB speaks the MySQL protocol; sqlx only recognizes the `mysql://` scheme.
+fn mysql_url(url: &str) -> String {
+    match url.strip_prefix("mariadb://") {
+        Some(rest) => format!("mysql://{rest}"),
+        None => url.to_string(),
+    }
+}
+
+/// Error for a query that would load `count` rows of `table`, over `max_query_rows`
+fn row_limit_error(table: &str, count: i64, limit: u64) -> StampError {
+    StampError::QueryTooLarge {
+        limit: QueryLimit::MaxQueryRows,
+        message: format!(
+            "query would load {count} {table} rows, more than max_query_rows ({limit}). \
+             Narrow it with --months or --from-block/--to-block, or use 'export', which streams large event sets"
+        ),
+    }
+}
+
+/// Unix timestamp of the `months` cutoff used by the month-based queries
+///
+/// `0` months means all time and yields a cutoff of `0`.
+pub fn months_cutoff(months: u32) -> i64 {
+    if months == 0 {
+        0
+    } else {
+        let cutoff_date = Utc::now() - Duration::days((months * 30) as i64);
+        cutoff_date.timestamp()
+    }
+}
+
+/// Validate an inclusive block range and convert it to database integers
+///
+/// Bounds above `i64::MAX` are clamped so `u64::MAX` can be used as "no upper bound".
+fn block_range_bounds(from_block: u64, to_block: u64) -> Result<(i64, i64)> {
+    if from_block > to_block {
+        return Err(crate::error::StampError::Parse(format!(
+            "Invalid block range: from block {from_block} is greater than to block {to_block}"
+        )));
+    }
+    let clamp = |block: u64| i64::try_from(block).unwrap_or(i64::MAX);
+    Ok((clamp(from_block), clamp(to_block)))
+}
+
+/// Convert a `batches` table row into a `BatchInfo`
+fn parse_batch_row<R>(row: &R) -> Result<BatchInfo>
+where
+    R: Row,
+    &'static str: sqlx::ColumnIndex<R>,
+    for<'r> String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> Option<String>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let immutable: i64 = row.get("immutable");
+    let created_at: i64 = row.get("created_at");
+    let block_number: i64 = row.get("block_number");
+
+    Ok(BatchInfo {
+        batch_id: BatchId::new(row.get::<String, _>("batch_id"))?,
+        owner: row.get("owner"),
+        payer: row.get("payer"),
+        contract_source: row.get("contract_source"),
+        depth: row.get::<i64, _>("depth") as u8,
+        bucket_depth: row.get::<i64, _>("bucket_depth") as u8,
+        immutable: immutable != 0,
+        normalised_balance: row.get("normalised_balance"),
+        created_at: DateTime::from_timestamp(created_at, 0).unwrap_or_else(Utc::now),
+        block_number: block_number as u64,
+    })
+}
+
+/// Convert an `events` table row into a `StampEvent`
+///
+/// Returns `None` for event types that are not batch events.
+fn parse_event_row<R>(row: &R) -> Result<Option<StampEvent>>
+where
+    R: Row,
+    &'static str: sqlx::ColumnIndex<R>,
+    for<'r> String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> Option<String>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    let event_type_str: String = row.get("event_type");
+    let event_type = match event_type_str.as_str() {
+        "BatchCreated" => EventType::BatchCreated,
+        "BatchTopUp" => EventType::BatchTopUp,
+        "BatchDepthIncrease" => EventType::BatchDepthIncrease,
+        "PotWithdrawn" => EventType::PotWithdrawn,
+        "PriceUpdate" => EventType::PriceUpdate,
+        "CopyBatchFailed" => EventType::CopyBatchFailed,
+        _ => return Ok(None),
+    };
+
+    let data_str: String = row.get("data");
+    let data: EventData = serde_json::from_str(&data_str)?;
+
+    let timestamp: i64 = row.get("block_timestamp");
+    let block_timestamp = DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
+
+    Ok(Some(StampEvent {
+        event_type,
+        batch_id: row.get("batch_id"),
+        block_number: row.get::<i64, _>("block_number") as u64,
+        block_timestamp,
+        transaction_hash: row.get("transaction_hash"),
+        log_index: row.get::<i64, _>("log_index") as u64,
+        contract_source: row.get("contract_source"),
+        contract_address: row
+            .get::<Option<String>, _>("contract_address")
+            .and_then(|address| ContractAddress::new(address).ok()),
+        block_hash: row.get("block_hash"),
+        data,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::NamedTempFile;
 
-    async fn create_test_cache() -> (Cache, NamedTempFile) {
-        let temp_file = NamedTempFile::new().unwrap();
-        let cache = Cache::new(temp_file.path()).await.unwrap();
-        (cache, temp_file)
-    }
+    async fn create_test_cache() -> (Cache, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(temp_file.path()).await.unwrap();
+        (cache, temp_file)
+    }
+
+    #[tokio::test]
+    async fn test_cache_creation() {
+        let (cache, _temp_file) = create_test_cache().await;
+        assert_eq!(cache.count_events().await.unwrap(), 0);
+        assert_eq!(cache.count_batches().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_migrations_are_idempotent_and_create_query_indexes() {
+        let (cache, _temp_file) = create_test_cache().await;
+        cache.run_migrations().await.unwrap();
+
+        let DatabasePool::Sqlite(pool) = &cache.pool else {
+            panic!("expected a SQLite cache");
+        };
+        let index_names = |table: &'static str| async move {
+            sqlx::query(&format!("PRAGMA index_list('{table}')"))
+                .fetch_all(pool)
+                .await
+                .unwrap()
+                .iter()
+                .map(|row| row.get::<String, _>("name"))
+                .collect::<Vec<_>>()
+        };
+
+        let events = index_names("events").await;
+        for index in ["idx_events_timestamp", "idx_events_block_log", "idx_events_batch"] {
+            assert!(events.iter().any(|name| name == index), "missing {index} in {events:?}");
+        }
+        assert!(index_names("batches").await.iter().any(|name| name == "idx_batches_created_at"));
+    }
+
+    #[test]
+    fn test_mysql_url_accepts_mariadb_scheme() {
+        assert_eq!(mysql_url("mariadb://u:p@db/stamps"), "mysql://u:p@db/stamps");
+        assert_eq!(mysql_url("mysql://u:p@db/stamps"), "mysql://u:p@db/stamps");
+    }
+
+    #[tokio::test]
+    async fn test_single_connection_pool_serializes_queries() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let settings = PoolSettings {
+            max_connections: Some(1),
+            min_connections: None,
+            acquire_timeout: Some(std::time::Duration::from_secs(5)),
+            ..PoolSettings::default()
+        };
+        let cache = Cache::with_pool_settings(temp_file.path(), settings).await.unwrap();
+
+        // Both queries need the only connection; the second waits for the first
+        let (events, batches) = tokio::join!(cache.count_events(), cache.count_batches());
+        assert_eq!(events.unwrap(), 0);
+        assert_eq!(batches.unwrap(), 0);
+
+        let (stored, events) = tokio::join!(cache.cache_balance("0x01", "1000", 10), cache.count_events());
+        stored.unwrap();
+        assert_eq!(events.unwrap(), 0);
+        assert!(cache.get_cached_balance("0x01", 10, 100).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_store_and_retrieve_events() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let events = vec![StampEvent {
+            event_type: EventType::BatchCreated,
+            batch_id: Some("0x1234".to_string()),
+            block_number: 1000,
+            block_timestamp: Utc::now(),
+            transaction_hash: "0xabcd".to_string(),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchCreated {
+                total_amount: "1000000000000000000".to_string(),
+                normalised_balance: "500000000000000000".to_string(),
+                owner: "0x5678".to_string(),
+                depth: 20,
+                bucket_depth: 16,
+                immutable_flag: false,
+                payer: None,
+            },
+        }];
+
+        cache.store_events(&events).await.unwrap();
+        assert_eq!(cache.count_events().await.unwrap(), 1);
+
+        let retrieved = cache.get_events(0).await.unwrap();
+        assert_eq!(retrieved.len(), 1);
+        assert_eq!(retrieved[0].batch_id, Some("0x1234".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_event_without_batch_id_roundtrip() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let events = vec![StampEvent {
+            event_type: EventType::PotWithdrawn,
+            batch_id: None,
+            block_number: 1000,
+            block_timestamp: Utc::now(),
+            transaction_hash: "0xabcd".to_string(),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::PotWithdrawn {
+                recipient: "0x5678".to_string(),
+                total_amount: "42".to_string(),
+            },
+        }];
+
+        cache.store_events(&events).await.unwrap();
 
-    #[tokio::test]
-    async fn test_cache_creation() {
-        let (cache, _temp_file) = create_test_cache().await;
-        assert_eq!(cache.count_events().await.unwrap(), 0);
-        assert_eq!(cache.count_batches().await.unwrap(), 0);
+        let retrieved = cache.get_events(0).await.unwrap();
+        assert_eq!(retrieved.len(), 1);
+        assert!(matches!(retrieved[0].event_type, EventType::PotWithdrawn));
+        assert_eq!(retrieved[0].batch_id, None);
+        assert!(matches!(
+            &retrieved[0].data,
+            EventData::PotWithdrawn { total_amount, .. } if total_amount == "42"
+        ));
     }
 
     #[tokio::test]
-    async fn test_store_and_retrieve_events() {
+    async fn test_contract_address_roundtrip() {
         let (cache, _temp_file) = create_test_cache().await;
 
+        let address = ContractAddress::new("0x45a1502382541Cd610CC9068e88727426b696293").unwrap();
         let events = vec![StampEvent {
-            event_type: EventType::BatchCreated,
+            event_type: EventType::BatchTopUp,
             batch_id: Some("0x1234".to_string()),
             block_number: 1000,
             block_timestamp: Utc::now(),
             transaction_hash: "0xabcd".to_string(),
             log_index: 0,
             contract_source: "PostageStamp".to_string(),
-            contract_address: None,
-            data: EventData::BatchCreated {
-                total_amount: "1000000000000000000".to_string(),
-                normalised_balance: "500000000000000000".to_string(),
-                owner: "0x5678".to_string(),
-                depth: 20,
-                bucket_depth: 16,
-                immutable_flag: false,
+            contract_address: Some(address.clone()),
+            block_hash: None,
+            data: EventData::BatchTopUp {
+                topup_amount: "100".to_string(),
+                normalised_balance: "200".to_string(),
                 payer: None,
             },
         }];
 
         cache.store_events(&events).await.unwrap();
-        assert_eq!(cache.count_events().await.unwrap(), 1);
 
         let retrieved = cache.get_events(0).await.unwrap();
-        assert_eq!(retrieved.len(), 1);
-        assert_eq!(retrieved[0].batch_id, Some("0x1234".to_string()));
+        assert_eq!(retrieved[0].contract_address, Some(address));
+        let in_range = cache.get_events_in_block_range(1000, 1000).await.unwrap();
+        assert_eq!(in_range[0].contract_address, retrieved[0].contract_address);
     }
 
     #[tokio::test]
     async fn test_store_and_retrieve_batches() {
         let (cache, _temp_file) = create_test_cache().await;
 
-        let batches = vec![BatchInfo {
-            batch_id: "0x1234".to_string(),
-            owner: "0x5678".to_string(),
-            payer: None,
-            contract_source: "PostageStamp".to_string(),
-            depth: 20,
-            bucket_depth: 16,
-            immutable: false,
-            normalised_balance: "500000000000000000".to_string(),
-            created_at: Utc::now(),
-            block_number: 1000,
-        }];
+        let batches = vec![BatchInfo::test_batch(0x1234, 20, "500000000000000000")];
 
         cache.store_batches(&batches).await.unwrap();
         assert_eq!(cache.count_batches().await.unwrap(), 1);
 
         let retrieved = cache.get_batches(0).await.unwrap();
         assert_eq!(retrieved.len(), 1);
-        assert_eq!(retrieved[0].batch_id, "0x1234");
+        assert_eq!(retrieved[0].batch_id, BatchId::test_id(0x1234));
+
+        let owned = cache.get_batch_ids_by_owner("0x5678").await.unwrap();
+        assert_eq!(owned, vec![BatchId::test_id(0x1234).as_str().to_string()]);
+        assert!(cache.get_batch_ids_by_owner("0x9999").await.unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -1119,6 +4049,7 @@ mod tests {
                 log_index: 0,
                 contract_source: "PostageStamp".to_string(),
                 contract_address: None,
+                block_hash: None,
                 data: EventData::BatchCreated {
                     total_amount: "1000000000000000000".to_string(),
                     normalised_balance: "500000000000000000".to_string(),
@@ -1138,6 +4069,7 @@ mod tests {
                 log_index: 0,
                 contract_source: "PostageStamp".to_string(),
                 contract_address: None,
+                block_hash: None,
                 data: EventData::BatchTopUp {
                     topup_amount: "100000000000000000".to_string(),
                     normalised_balance: "600000000000000000".to_string(),
@@ -1149,4 +4081,693 @@ mod tests {
         cache.store_events(&events).await.unwrap();
         assert_eq!(cache.get_last_block().await.unwrap(), Some(2000));
     }
+
+    fn price_update_event(block_number: u64, log_index: u64, price: &str) -> StorageIncentivesEvent {
+        // Optional fields are omitted and deserialize as None
+        serde_json::from_value(serde_json::json!({
+            "block_number": block_number,
+            "block_timestamp": Utc::now(),
+            "transaction_hash": format!("0x{block_number:x}{log_index:x}"),
+            "log_index": log_index,
+            "contract_source": "PriceOracle",
+            "event_type": "PriceUpdate",
+            "price": price,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_follow_cursor_roundtrip() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        assert_eq!(cache.get_follow_cursor().await.unwrap(), None);
+
+        cache.set_follow_cursor(38_000_000).await.unwrap();
+        assert_eq!(cache.get_follow_cursor().await.unwrap(), Some(38_000_000));
+
+        cache.set_follow_cursor(38_000_120).await.unwrap();
+        assert_eq!(cache.get_follow_cursor().await.unwrap(), Some(38_000_120));
+
+        // The cursor is independent of the cached price metadata
+        cache.cache_price(24000).await.unwrap();
+        assert_eq!(cache.get_follow_cursor().await.unwrap(), Some(38_000_120));
+    }
+
+    #[tokio::test]
+    async fn test_sync_checkpoint_only_moves_forward() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        assert_eq!(cache.get_sync_checkpoint("0xAbC").await.unwrap(), None);
+
+        cache.set_sync_checkpoint("0xAbC", 1000).await.unwrap();
+        cache.set_sync_checkpoint("0xabc", 500).await.unwrap();
+        assert_eq!(cache.get_sync_checkpoint("0xABC").await.unwrap(), Some(1000));
+        assert_eq!(cache.get_sync_checkpoint("0xdef").await.unwrap(), None);
+
+        // A reorg rollback moves the checkpoint back before the orphaned blocks
+        cache.rollback_to_block(800).await.unwrap();
+        assert_eq!(cache.get_sync_checkpoint("0xabc").await.unwrap(), Some(799));
+        cache.rollback_to_block(900).await.unwrap();
+        assert_eq!(cache.get_sync_checkpoint("0xabc").await.unwrap(), Some(799));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_cursor_round_trip_and_rollback() {
+        let (cache, _temp_file) = create_test_cache().await;
+        let cursor = FetchCursor { from_block: 1000, chunk_size: 100, last_completed_to_block: 1299 };
+
+        assert_eq!(cache.get_fetch_cursor("0xAbC").await.unwrap(), None);
+        cache.set_fetch_cursor("0xAbC", &cursor).await.unwrap();
+        assert_eq!(cache.get_fetch_cursor("0xabc").await.unwrap(), Some(cursor));
+
+        // The cursor shares the sync_state row without moving its checkpoint
+        cache.set_sync_checkpoint("0xabc", 1500).await.unwrap();
+        cache.set_fetch_cursor("0xabc", &cursor).await.unwrap();
+        assert_eq!(cache.get_sync_checkpoint("0xabc").await.unwrap(), Some(1500));
+
+        // Rolled-back chunks are no longer complete, so the cursor moves back before them
+        cache.rollback_to_block(1200).await.unwrap();
+        assert_eq!(
+            cache.get_fetch_cursor("0xabc").await.unwrap(),
+            Some(FetchCursor { last_completed_to_block: 1199, ..cursor })
+        );
+        cache.rollback_to_block(1250).await.unwrap();
+        assert_eq!(cache.get_fetch_cursor("0xabc").await.unwrap().map(|c| c.last_completed_to_block), Some(1199));
+
+        // A checkpoint written alone has no cursor
+        cache.set_sync_checkpoint("0xdef", 1000).await.unwrap();
+        assert_eq!(cache.get_fetch_cursor("0xdef").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_reverts_batches_touched_by_orphaned_events() {
+        let (cache, _temp_file) = create_test_cache().await;
+        let (grown, untouched) = (BatchId::test_id(0xaaaa), BatchId::test_id(0xbbbb));
+
+        let batch = |batch_id: &BatchId| BatchInfo {
+            batch_id: batch_id.clone(),
+            block_number: 100,
+            ..BatchInfo::test_batch(0, 18, "200")
+        };
+        cache.store_batches(&[batch(&grown), batch(&untouched)]).await.unwrap();
+
+        // A depth increase and top-up of the older batch land in orphaned blocks
+        let orphaned = |block: u64, data: EventData| StampEvent {
+            event_type: match data {
+                EventData::BatchTopUp { .. } => EventType::BatchTopUp,
+                _ => EventType::BatchDepthIncrease,
+            },
+            batch_id: Some(grown.to_string()),
+            block_number: block,
+            block_timestamp: Utc::now(),
+            transaction_hash: format!("0xtx{block}"),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data,
+        };
+        cache
+            .store_events(&[
+                orphaned(
+                    200,
+                    EventData::BatchDepthIncrease {
+                        new_depth: 20,
+                        normalised_balance: "100".to_string(),
+                        payer: None,
+                    },
+                ),
+                orphaned(
+                    201,
+                    EventData::BatchTopUp {
+                        topup_amount: "1000".to_string(),
+                        normalised_balance: "900".to_string(),
+                        payer: None,
+                    },
+                ),
+            ])
+            .await
+            .unwrap();
+        cache.cache_balance(grown.as_str(), "900", 150).await.unwrap();
+        cache.cache_balance(untouched.as_str(), "200", 150).await.unwrap();
+
+        cache.rollback_to_block(200).await.unwrap();
+
+        // Both batches survive; only the grown one loses its depth and cached balance
+        let batches = cache.get_batches_with_current_depth(0).await.unwrap();
+        assert_eq!(batches.len(), 2);
+        assert!(batches.iter().all(|b| b.depth == 18));
+        assert_eq!(cache.get_cached_balance(grown.as_str(), 150, 100).await.unwrap(), None);
+        assert_eq!(
+            cache.get_cached_balance(untouched.as_str(), 150, 100).await.unwrap().as_deref(),
+            Some("200")
+        );
+
+        // Balances read from the orphaned chain are dropped too
+        cache.cache_balance(untouched.as_str(), "150", 250).await.unwrap();
+        cache.rollback_to_block(220).await.unwrap();
+        assert_eq!(cache.get_cached_balance(untouched.as_str(), 250, 100).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_healthcheck() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let health = cache.healthcheck().await.unwrap();
+        assert!(health.expected_migrations > 0);
+        assert_eq!(health.applied_migrations, health.expected_migrations);
+        assert!(health.migrations_current());
+        // The probe row is cleaned up again
+        let DatabasePool::Sqlite(pool) = &cache.pool else {
+            panic!("expected a SQLite cache");
+        };
+        let (leftover,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM cache_metadata WHERE key = 'healthcheck'")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(leftover, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_price_history() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let mut stake_event = price_update_event(1500, 0, "0");
+        stake_event.contract_source = "StakeRegistry".to_string();
+        stake_event.event_type = "StakeUpdated".to_string();
+        stake_event.price = None;
+
+        cache
+            .store_storage_incentives_events(&[
+                price_update_event(2000, 0, "26000"),
+                price_update_event(1000, 0, "24000"),
+                stake_event,
+            ])
+            .await
+            .unwrap();
+
+        let history = cache.get_price_history(0, i64::MAX).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].block_number, 1000);
+        assert_eq!(history[0].price, 24000);
+        assert_eq!(history[1].price, 26000);
+    }
+
+    #[tokio::test]
+    async fn test_get_events_stable_order_within_block() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let event = |tx: &str, contract: &str| StampEvent {
+            event_type: EventType::BatchTopUp,
+            batch_id: Some("0x1234".to_string()),
+            block_number: 5000,
+            block_timestamp: Utc::now(),
+            transaction_hash: tx.to_string(),
+            log_index: 3,
+            contract_source: contract.to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchTopUp {
+                topup_amount: "100".to_string(),
+                normalised_balance: "200".to_string(),
+                payer: None,
+            },
+        };
+
+        // Same block and log index, inserted out of hash order
+        cache
+            .store_events(&[
+                event("0xcc", "StampsRegistry"),
+                event("0xaa", "PostageStamp"),
+                event("0xbb", "StampsRegistry"),
+            ])
+            .await
+            .unwrap();
+
+        let hashes = |events: &[StampEvent]| {
+            events.iter().map(|e| e.transaction_hash.clone()).collect::<Vec<_>>()
+        };
+        let expected = vec!["0xaa", "0xbb", "0xcc"];
+
+        let retrieved = cache.get_events(0).await.unwrap();
+        assert_eq!(hashes(&retrieved), expected);
+
+        let mut streamed = Vec::new();
+        cache
+            .stream_events(0, |e| {
+                streamed.push(e);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(hashes(&streamed), expected);
+    }
+
+    #[tokio::test]
+    async fn test_block_range_queries_inclusive() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let batch = |block: u64| BatchInfo {
+            block_number: block,
+            ..BatchInfo::test_batch(block, 20, "200")
+        };
+
+        cache
+            .store_events(&[topup_event(100), topup_event(200), topup_event(300), topup_event(400)])
+            .await
+            .unwrap();
+        cache
+            .store_batches(&[batch(100), batch(200), batch(300), batch(400)])
+            .await
+            .unwrap();
+
+        // Both bounds are inclusive
+        let events = cache.get_events_in_block_range(200, 300).await.unwrap();
+        let blocks: Vec<u64> = events.iter().map(|e| e.block_number).collect();
+        assert_eq!(blocks, vec![200, 300]);
+
+        let batches = cache.get_batches_in_block_range(200, 300).await.unwrap();
+        let blocks: Vec<u64> = batches.iter().map(|b| b.block_number).collect();
+        assert_eq!(blocks, vec![200, 300]);
+
+        // Single-block range
+        assert_eq!(cache.get_events_in_block_range(400, 400).await.unwrap().len(), 1);
+
+        // Open-ended upper bound
+        assert_eq!(cache.get_events_in_block_range(150, u64::MAX).await.unwrap().len(), 3);
+
+        // Range between cached blocks is empty
+        assert!(cache.get_events_in_block_range(201, 299).await.unwrap().is_empty());
+        assert!(cache.get_batches_in_block_range(201, 299).await.unwrap().is_empty());
+
+        // Inverted range is rejected
+        assert!(cache.get_events_in_block_range(300, 200).await.is_err());
+        assert!(cache.get_batches_in_block_range(300, 200).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_recent_events_returns_highest_blocks() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        cache.store_events(&(1..=100).map(topup_event).collect::<Vec<_>>()).await.unwrap();
+
+        let recent = cache.get_recent_events(10).await.unwrap();
+        let blocks: Vec<u64> = recent.iter().map(|e| e.block_number).collect();
+        assert_eq!(blocks, (91..=100).rev().collect::<Vec<_>>());
+
+        assert_eq!(cache.get_recent_events(500).await.unwrap().len(), 100);
+        assert!(cache.get_recent_events(0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_time_range_queries_inclusive() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        // One event and batch per day in January 2024
+        let day = |n: i64| DateTime::from_timestamp(1_704_067_200 + n * 86_400, 0).unwrap();
+        let topup = |n: i64| StampEvent {
+            block_timestamp: day(n),
+            ..topup_event(n as u64)
+        };
+        let batch = |n: i64| BatchInfo {
+            created_at: day(n),
+            block_number: n as u64,
+            ..BatchInfo::test_batch(n as u64, 20, "200")
+        };
+
+        cache.store_events(&(0..5).map(topup).collect::<Vec<_>>()).await.unwrap();
+        cache.store_batches(&(0..5).map(batch).collect::<Vec<_>>()).await.unwrap();
+
+        // Both bounds are inclusive
+        let events = cache.get_events_between(day(1).timestamp(), day(3).timestamp()).await.unwrap();
+        let blocks: Vec<u64> = events.iter().map(|e| e.block_number).collect();
+        assert_eq!(blocks, vec![1, 2, 3]);
+
+        let batches = cache.get_batches_between(day(1).timestamp(), day(3).timestamp()).await.unwrap();
+        let blocks: Vec<u64> = batches.iter().map(|b| b.block_number).collect();
+        assert_eq!(blocks, vec![1, 2, 3]);
+
+        assert_eq!(cache.get_events_between(day(3).timestamp(), i64::MAX).await.unwrap().len(), 2);
+        assert!(cache.get_events_between(0, day(0).timestamp() - 1).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_max_query_rows_limit() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        cache
+            .store_events(&[topup_event(100), topup_event(200), topup_event(300)])
+            .await
+            .unwrap();
+
+        let cache = cache.with_max_query_rows(Some(2));
+
+        // Loading everything exceeds the limit
+        let err = cache.get_events(0).await.unwrap_err();
+        assert!(matches!(err, StampError::QueryTooLarge { .. }));
+        assert!(err.to_string().contains("max_query_rows (2)"));
+        assert!(cache.get_events_in_block_range(0, u64::MAX).await.is_err());
+
+        // A narrower query fits
+        assert_eq!(cache.get_events_in_block_range(200, 300).await.unwrap().len(), 2);
+
+        // Exactly at the limit is allowed
+        let cache = cache.with_max_query_rows(Some(3));
+        assert_eq!(cache.get_events(0).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_read_url_routes_reporting_reads() {
+
+        // Same database for reads and writes
+        let (cache, temp_file) = create_test_cache().await;
+        let read_url = temp_file.path().to_string_lossy().to_string();
+        let cache = cache.with_read_url(Some(&read_url)).await.unwrap();
+        cache.store_events(&[topup_event(100)]).await.unwrap();
+        assert_eq!(cache.get_events(0).await.unwrap().len(), 1);
+
+        // Distinct read database: reporting reads see it, bookkeeping reads stay on the primary
+        let (replica, replica_file) = create_test_cache().await;
+        replica.store_events(&[topup_event(500), topup_event(600)]).await.unwrap();
+        let replica_url = format!("sqlite:{}", replica_file.path().display());
+
+        let cache = cache.with_read_url(Some(&replica_url)).await.unwrap();
+        assert_eq!(cache.get_events(0).await.unwrap().len(), 2);
+        assert_eq!(cache.count_events().await.unwrap(), 2);
+        assert_eq!(cache.get_last_block().await.unwrap(), Some(100));
+
+        // Writes still go to the primary
+        cache.store_events(&[topup_event(200)]).await.unwrap();
+        assert_eq!(cache.get_last_block().await.unwrap(), Some(200));
+        assert_eq!(cache.get_events(0).await.unwrap().len(), 2);
+
+        // Unsetting the read URL falls back to the primary
+        let cache = cache.with_read_url(None).await.unwrap();
+        assert_eq!(
+            cache.get_events(0).await.unwrap().iter().map(|e| e.block_number).collect::<Vec<_>>(),
+            [100, 200]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explain_sql_query_plan() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let plan = cache
+            .query_plan(
+                "SELECT block_number FROM events WHERE block_number BETWEEN ? AND ?",
+                &[100, 200],
+            )
+            .await
+            .unwrap();
+        assert!(!plan.is_empty());
+        assert!(plan.iter().any(|line| line.contains("events")));
+
+        // Explaining never changes query results, even with the row limit check
+        let cache = cache.with_explain_sql(true).with_max_query_rows(Some(10));
+        assert!(cache.get_events(0).await.unwrap().is_empty());
+        assert!(cache.get_batches_in_block_range(0, 100).await.unwrap().is_empty());
+        assert_eq!(cache.count_events_since(0).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batches_with_current_depth() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let batch = |n: u64| BatchInfo::test_batch(n, 18, "200");
+        let depth_increase = |block: u64, new_depth: u8| StampEvent {
+            event_type: EventType::BatchDepthIncrease,
+            batch_id: Some(BatchId::test_id(0xaaaa).to_string()),
+            block_number: block,
+            block_timestamp: Utc::now(),
+            transaction_hash: format!("0xtx{block}"),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchDepthIncrease {
+                new_depth,
+                normalised_balance: "100".to_string(),
+                payer: None,
+            },
+        };
+
+        cache.store_batches(&[batch(0xaaaa), batch(0xbbbb)]).await.unwrap();
+        cache
+            .store_events(&[depth_increase(2000, 20), depth_increase(3000, 21)])
+            .await
+            .unwrap();
+
+        let batches = cache.get_batches_with_current_depth(0).await.unwrap();
+        let depths: Vec<(BatchId, u8)> = batches.iter().map(|b| (b.batch_id.clone(), b.depth)).collect();
+        assert_eq!(depths.len(), 2);
+        assert!(depths.contains(&(BatchId::test_id(0xaaaa), 21)));
+        // Batches that never grew keep their creation depth
+        assert!(depths.contains(&(BatchId::test_id(0xbbbb), 18)));
+
+        // The plain query still reports the creation depth
+        let created = cache.get_batches(0).await.unwrap();
+        assert!(created.iter().all(|b| b.depth == 18));
+
+        // Single-batch lookups apply the same depth
+        let id = BatchId::test_id(0xaaaa).to_string();
+        let single = cache.get_batch_with_current_depth(&id).await.unwrap().unwrap();
+        assert_eq!(single.depth, 21);
+        let id = BatchId::test_id(0xbbbb).to_string();
+        assert_eq!(cache.get_batch_with_current_depth(&id).await.unwrap().unwrap().depth, 18);
+        let id = BatchId::test_id(0xcccc).to_string();
+        assert!(cache.get_batch_with_current_depth(&id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_creation_amounts_from_batch_created_events() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        let created = StampEvent {
+            event_type: EventType::BatchCreated,
+            batch_id: Some(BatchId::test_id(0xaaaa).to_string()),
+            block_number: 1000,
+            block_timestamp: Utc::now(),
+            transaction_hash: "0xtx1000".to_string(),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchCreated {
+                total_amount: "40000000000000000".to_string(),
+                normalised_balance: "100".to_string(),
+                owner: "0x5678".to_string(),
+                depth: 20,
+                bucket_depth: 16,
+                immutable_flag: false,
+                payer: None,
+            },
+        };
+        let mut events = topup_events(3);
+        events.push(created);
+        cache.store_events(&events).await.unwrap();
+
+        // Top-ups carry no creation amount
+        let amounts = cache.get_creation_amounts().await.unwrap();
+        assert_eq!(amounts.len(), 1);
+        assert_eq!(
+            amounts.get(BatchId::test_id(0xaaaa).as_str()).map(String::as_str),
+            Some("40000000000000000")
+        );
+    }
+
+    /// Top-up in its own transaction at `block`
+    fn topup_event(block: u64) -> StampEvent {
+        StampEvent::test_topup(&format!("0xtx{block}"), 0, block)
+    }
+
+    fn topup_events(count: u64) -> Vec<StampEvent> {
+        (0..count)
+            .map(|i| StampEvent {
+                batch_id: Some(format!("0x{i:064x}")),
+                data: EventData::BatchTopUp {
+                    topup_amount: "1000".to_string(),
+                    normalised_balance: i.to_string(),
+                    payer: None,
+                },
+                ..StampEvent::test_topup(&format!("0x{:064x}", i / 10), i % 10, 1000 + i / 10)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_get_events_paged() {
+        let (cache, _temp_file) = create_test_cache().await;
+        // Store out of order; pages follow block_number, log_index regardless
+        let mut events = topup_events(250);
+        events.reverse();
+        cache.store_events(&events).await.unwrap();
+        let all = EventColumnFilter::default();
+
+        assert_eq!(cache.count_events_filtered(0, &all).await.unwrap(), 250);
+        let page = cache.get_events_paged(0, &all, 100, 100).await.unwrap();
+        assert_eq!(page.len(), 100);
+        for (event, expected) in page.iter().zip(100u64..200) {
+            assert_eq!(event.block_number, 1000 + expected / 10);
+            assert_eq!(event.log_index, expected % 10);
+            assert!(matches!(
+                &event.data,
+                EventData::BatchTopUp { normalised_balance, .. } if *normalised_balance == expected.to_string()
+            ));
+        }
+
+        // The last page is short, and paging past the end is empty
+        assert_eq!(cache.get_events_paged(0, &all, 100, 200).await.unwrap().len(), 50);
+        assert!(cache.get_events_paged(0, &all, 100, 300).await.unwrap().is_empty());
+
+        // Pages within max_query_rows load; an unbounded page is checked
+        // against the rows left after its offset
+        let cache = cache.with_max_query_rows(Some(200));
+        assert_eq!(cache.get_events_paged(0, &all, 200, 0).await.unwrap().len(), 200);
+        let err = cache.get_events_paged(0, &all, usize::MAX, 0).await.unwrap_err();
+        assert!(matches!(err, StampError::QueryTooLarge { .. }));
+        assert_eq!(cache.get_events_paged(0, &all, usize::MAX, 100).await.unwrap().len(), 150);
+
+        // Filters are applied before the page is cut and count toward the limit
+        let filter = EventColumnFilter {
+            event_type: Some("BatchTopUp".to_string()),
+            batch_id_contains: Some("0x00000000000000000000000000000000000000000000000000000000000000".to_string()),
+            contract_source: Some("PostageStamp".to_string()),
+        };
+        assert_eq!(cache.count_events_filtered(0, &filter).await.unwrap(), 250);
+        let filter = EventColumnFilter {
+            batch_id_contains: Some("f".to_string()),
+            ..filter
+        };
+        // 0x..0f, 0x..1f, ..., 0x..ef, 0x..f0 through 0x..f9
+        assert_eq!(cache.count_events_filtered(0, &filter).await.unwrap(), 25);
+        let page = cache.get_events_paged(0, &filter, 10, 20).await.unwrap();
+        let ids: Vec<u64> = page.iter().map(|e| e.block_number * 10 + e.log_index - 10_000).collect();
+        assert_eq!(ids, vec![0xf5, 0xf6, 0xf7, 0xf8, 0xf9]);
+        assert_eq!(cache.get_events_paged(0, &filter, usize::MAX, 0).await.unwrap().len(), 25);
+
+        let other = EventColumnFilter {
+            contract_source: Some("StampsRegistry".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(cache.count_events_filtered(0, &other).await.unwrap(), 0);
+        assert!(cache.get_events_paged(0, &other, 10, 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_reader_and_writer() {
+        let temp_file = NamedTempFile::new().unwrap();
+        // Separate caches stand in for `follow` and `serve` running side by side
+        let writer = Cache::new(temp_file.path()).await.unwrap();
+        let reader = Cache::new(temp_file.path()).await.unwrap();
+
+        let DatabasePool::Sqlite(pool) = &writer.pool else {
+            panic!("expected a SQLite cache");
+        };
+        let (journal_mode,): (String,) = sqlx::query_as("PRAGMA journal_mode").fetch_one(pool).await.unwrap();
+        assert_eq!(journal_mode, "wal");
+
+        let events = topup_events(2000);
+        let write = tokio::spawn(async move {
+            for chunk in events.chunks(100) {
+                writer.store_events(chunk).await?;
+            }
+            Ok::<_, StampError>(())
+        });
+        let read = tokio::spawn(async move {
+            let mut seen = 0;
+            for _ in 0..20 {
+                seen = reader.get_events(0).await?.len();
+            }
+            Ok::<_, StampError>((reader, seen))
+        });
+
+        write.await.unwrap().unwrap();
+        let (reader, seen) = read.await.unwrap().unwrap();
+        assert!(seen <= 2000);
+        assert_eq!(reader.count_events().await.unwrap(), 2000);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_wal_can_be_disabled() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let settings = PoolSettings { sqlite_wal: Some(false), ..PoolSettings::default() };
+        let cache = Cache::with_pool_settings(temp_file.path(), settings).await.unwrap();
+
+        let DatabasePool::Sqlite(pool) = &cache.pool else {
+            panic!("expected a SQLite cache");
+        };
+        let (journal_mode,): (String,) = sqlx::query_as("PRAGMA journal_mode").fetch_one(pool).await.unwrap();
+        assert_eq!(journal_mode, "delete");
+    }
+
+    #[tokio::test]
+    async fn test_store_events_in_bulk_is_idempotent() {
+        let (cache, _temp_file) = create_test_cache().await;
+        let mut events = topup_events(5000);
+
+        // Spans many multi-row statements
+        cache.store_events(&events).await.unwrap();
+        assert_eq!(cache.count_events().await.unwrap(), 5000);
+
+        // Re-inserting replaces rather than duplicates, and the latest data wins
+        events[0].data = EventData::BatchTopUp {
+            topup_amount: "2000".to_string(),
+            normalised_balance: "0".to_string(),
+            payer: None,
+        };
+        cache.store_events(&events).await.unwrap();
+        assert_eq!(cache.count_events().await.unwrap(), 5000);
+
+        let stored = cache.get_events_in_block_range(1000, 1000).await.unwrap();
+        assert_eq!(stored.len(), 10);
+        assert!(matches!(
+            &stored[0].data,
+            EventData::BatchTopUp { topup_amount, .. } if topup_amount == "2000"
+        ));
+
+        // Duplicates within one call keep the last occurrence
+        let mut duplicated = topup_events(2);
+        duplicated.push(duplicated[0].clone());
+        duplicated[2].block_hash = Some("0xfeed".to_string());
+        cache.store_events(&duplicated).await.unwrap();
+        assert_eq!(cache.count_events().await.unwrap(), 5000);
+        let stored = cache.get_events_in_block_range(1000, 1000).await.unwrap();
+        assert_eq!(stored[0].block_hash.as_deref(), Some("0xfeed"));
+    }
+
+    /// Make the test database reject rows matching `condition` on insert into `table`
+    async fn reject_inserts(cache: &Cache, table: &str, condition: &str) {
+        let DatabasePool::Sqlite(pool) = &cache.pool else {
+            panic!("tests use SQLite");
+        };
+        sqlx::query(&format!(
+            "CREATE TRIGGER reject_{table} BEFORE INSERT ON {table} WHEN {condition} \
+             BEGIN SELECT RAISE(ABORT, 'rejected by test'); END"
+        ))
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_store_rolls_back_whole_batch_on_failure() {
+        let (cache, _temp_file) = create_test_cache().await;
+
+        // Fail an event well past the first multi-row statement
+        reject_inserts(&cache, "events", "NEW.block_number = 1400").await;
+        assert!(cache.store_events(&topup_events(5000)).await.is_err());
+        assert_eq!(cache.count_events().await.unwrap(), 0);
+        assert_eq!(cache.get_last_block().await.unwrap(), None);
+
+        // Fail the last batch after the others were written
+        reject_inserts(&cache, "batches", &format!("NEW.batch_id = '{}'", BatchId::test_id(3))).await;
+        let batch = |n: u64| BatchInfo::test_batch(n, 20, "1000");
+        assert!(cache.store_batches(&[batch(1), batch(2), batch(3)]).await.is_err());
+        assert_eq!(cache.count_batches().await.unwrap(), 0);
+
+        // Without the failing row everything commits
+        cache.store_batches(&[batch(1), batch(2)]).await.unwrap();
+        assert_eq!(cache.count_batches().await.unwrap(), 2);
+    }
 }
@@ -0,0 +1,261 @@
+use crate::cache::Cache;
+use crate::cli::OutputFormat;
+use crate::display::{status, Console};
+use crate::error::{Result, StampError};
+use crate::events::{EventData, StampEvent};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tabled::Tabled;
+
+/// One event in a batch's history, with the batch state after it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimelineEntry {
+    pub block_number: u64,
+    pub timestamp: DateTime<Utc>,
+    pub event_type: String,
+    pub contract_source: String,
+    pub transaction_hash: String,
+    /// What the event changed, e.g. the top-up amount
+    pub change: String,
+    /// Normalised balance after this event, once known
+    pub normalised_balance: Option<String>,
+    /// Depth after this event, once known
+    pub depth: Option<u8>,
+}
+
+/// Table row for the batch timeline
+#[derive(Tabled)]
+struct TimelineRow {
+    #[tabled(rename = "Block")]
+    block: u64,
+    #[tabled(rename = "Time")]
+    time: String,
+    #[tabled(rename = "Event")]
+    event_type: String,
+    #[tabled(rename = "Contract")]
+    contract: String,
+    #[tabled(rename = "Change")]
+    change: String,
+    #[tabled(rename = "Normalised Balance")]
+    normalised_balance: String,
+    #[tabled(rename = "Depth")]
+    depth: String,
+}
+
+/// Resolve a full or partial batch ID against the cached batch IDs
+///
+/// Returns `None` when nothing matches and an error when the fragment matches
+/// more than one batch. An exact match wins over longer IDs containing it.
+pub fn resolve_batch_id(fragment: &str, candidates: Vec<String>) -> Result<Option<String>> {
+    if candidates.iter().any(|id| id == fragment) {
+        return Ok(Some(fragment.to_string()));
+    }
+
+    match candidates.len() {
+        0 => Ok(None),
+        1 => Ok(candidates.into_iter().next()),
+        n => Err(StampError::Parse(format!(
+            "Batch ID '{fragment}' is ambiguous: matches {n} batches ({})",
+            candidates.join(", ")
+        ))),
+    }
+}
+
+/// Build a batch's timeline from its events
+///
+/// Events are ordered by block and log index. Every `BatchCreated`,
+/// `BatchTopUp` and `BatchDepthIncrease` event carries the batch's new
+/// normalised balance, so the running balance is the latest one seen; the
+/// depth starts at the creation depth and follows each depth increase.
+pub fn build_timeline(events: &[StampEvent]) -> Vec<TimelineEntry> {
+    let mut events: Vec<&StampEvent> = events.iter().collect();
+    events.sort_by(|a, b| {
+        a.block_number
+            .cmp(&b.block_number)
+            .then(a.log_index.cmp(&b.log_index))
+            .then_with(|| a.transaction_hash.cmp(&b.transaction_hash))
+    });
+
+    let mut normalised_balance: Option<String> = None;
+    let mut depth: Option<u8> = None;
+
+    events
+        .into_iter()
+        .map(|event| {
+            let change = match &event.data {
+                EventData::BatchCreated {
+                    total_amount,
+                    normalised_balance: balance,
+                    owner,
+                    depth: created_depth,
+                    ..
+                } => {
+                    normalised_balance = Some(balance.clone());
+                    depth = Some(*created_depth);
+                    format!("created by {owner}, paid {total_amount}")
+                }
+                EventData::BatchTopUp {
+                    topup_amount,
+                    normalised_balance: balance,
+                    ..
+                } => {
+                    normalised_balance = Some(balance.clone());
+                    format!("+{topup_amount}")
+                }
+                EventData::BatchDepthIncrease {
+                    new_depth,
+                    normalised_balance: balance,
+                    ..
+                } => {
+                    let previous = depth.map_or_else(|| "?".to_string(), |d| d.to_string());
+                    normalised_balance = Some(balance.clone());
+                    depth = Some(*new_depth);
+                    format!("depth {previous} -> {new_depth}")
+                }
+                EventData::PotWithdrawn { total_amount, .. } => format!("pot withdrawn {total_amount}"),
+                EventData::PriceUpdate { price } => format!("price {price}"),
+                EventData::CopyBatchFailed { index, .. } => format!("copy failed at index {index}"),
+            };
+
+            TimelineEntry {
+                block_number: event.block_number,
+                timestamp: event.block_timestamp,
+                event_type: event.event_type.to_string(),
+                contract_source: event.contract_source.clone(),
+                transaction_hash: event.transaction_hash.clone(),
+                change,
+                normalised_balance: normalised_balance.clone(),
+                depth,
+            }
+        })
+        .collect()
+}
+
+/// Execute the batch history command
+///
+/// `batch_id` may be a partial ID in `filter::normalize_hex` form.
+pub async fn execute(cache: Cache, batch_id: &str, output: OutputFormat, console: Console) -> Result<()> {
+    let Some(batch_id) = resolve_batch_id(batch_id, cache.find_batch_ids(batch_id).await?)? else {
+        status!(console, "No cached events for a batch matching '{batch_id}'. Run 'fetch' or 'sync' first.");
+        return Ok(());
+    };
+
+    let events = cache.get_events_for_batch(&batch_id).await?;
+    let timeline = build_timeline(&events);
+
+    match output {
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows: Vec<TimelineRow> = timeline
+                .iter()
+                .map(|entry| TimelineRow {
+                    block: entry.block_number,
+                    time: entry.timestamp.format("%Y-%m-%d %H:%M UTC").to_string(),
+                    event_type: entry.event_type.clone(),
+                    contract: entry.contract_source.clone(),
+                    change: entry.change.clone(),
+                    normalised_balance: entry.normalised_balance.clone().unwrap_or_else(|| "-".to_string()),
+                    depth: entry.depth.map_or_else(|| "-".to_string(), |d| d.to_string()),
+                })
+                .collect();
+
+            status!(console, "\n📜 History of batch {batch_id}\n");
+            let table = output.render_table(tabled::Table::new(rows));
+            println!("{table}\n");
+            println!("{} events", timeline.len());
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&timeline)?;
+            println!("{json}");
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            for entry in &timeline {
+                wtr.serialize(entry)?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    const BATCH: &str = "0xabcd010000000000000000000000000000000000000000000000000000000000";
+
+    fn event(batch_id: &str, block: u64, log_index: u64, data: serde_json::Value) -> StampEvent {
+        serde_json::from_value(json!({
+            "event_type": data["type"],
+            "batch_id": batch_id,
+            "block_number": block,
+            "block_timestamp": Utc::now(),
+            "transaction_hash": format!("0x{block:064x}"),
+            "log_index": log_index,
+            "contract_source": "PostageStamp",
+            "data": data,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_batch_timeline_tracks_balance_and_depth() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        let created = event(
+            BATCH,
+            100,
+            0,
+            json!({ "type": "BatchCreated", "total_amount": "1000", "normalised_balance": "500", "owner": "0x1111", "depth": 20, "bucket_depth": 16, "immutable_flag": false, "payer": null }),
+        );
+        let topup = event(
+            BATCH,
+            200,
+            3,
+            json!({ "type": "BatchTopUp", "topup_amount": "300", "normalised_balance": "800", "payer": null }),
+        );
+        let increase = event(
+            BATCH,
+            300,
+            1,
+            json!({ "type": "BatchDepthIncrease", "new_depth": 22, "normalised_balance": "200", "payer": null }),
+        );
+        let other_batch = event(
+            "0xabcd020000000000000000000000000000000000000000000000000000000000",
+            150,
+            0,
+            json!({ "type": "BatchTopUp", "topup_amount": "1", "normalised_balance": "1", "payer": null }),
+        );
+        cache.store_events(&[increase, created, topup, other_batch]).await.unwrap();
+
+        let events = cache.get_events_for_batch(BATCH).await.unwrap();
+        let timeline = build_timeline(&events);
+
+        let steps: Vec<(u64, &str, Option<&str>, Option<u8>)> = timeline
+            .iter()
+            .map(|e| (e.block_number, e.event_type.as_str(), e.normalised_balance.as_deref(), e.depth))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                (100, "BatchCreated", Some("500"), Some(20)),
+                (200, "BatchTopUp", Some("800"), Some(20)),
+                (300, "BatchDepthIncrease", Some("200"), Some(22)),
+            ]
+        );
+        assert_eq!(timeline[1].change, "+300");
+        assert_eq!(timeline[2].change, "depth 20 -> 22");
+
+        // Partial IDs resolve only when they name a single batch
+        let ids = cache.find_batch_ids("0xabcd").await.unwrap();
+        assert!(matches!(resolve_batch_id("0xabcd", ids), Err(StampError::Parse(_))));
+        let ids = cache.find_batch_ids("0xabcd01").await.unwrap();
+        assert_eq!(resolve_batch_id("0xabcd01", ids).unwrap().as_deref(), Some(BATCH));
+        let ids = cache.find_batch_ids("0xffff").await.unwrap();
+        assert_eq!(resolve_batch_id("0xffff", ids).unwrap(), None);
+    }
+}
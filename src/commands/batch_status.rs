@@ -1,11 +1,23 @@
 use crate::blockchain::BlockchainClient;
 use crate::cache::Cache;
-use crate::cli::{BatchStatusSortBy, OutputFormat};
-use crate::error::Result;
+use crate::cli::{BatchStatusFilter, BatchStatusGroupBy, BatchStatusSortBy, OutputFormat, SortOrder};
+use crate::display::{format_number, status, Console};
+use crate::error::{Result, StampError};
 use crate::events::BatchInfo;
-use crate::price::{blocks_to_days, PriceChange, PriceConfig};
+use crate::price::fiat::{fiat_cost, FiatFeed};
+use crate::price::{
+    blocks_to_days, blocks_to_duration, format_storage, to_bzz, PriceChange, PriceConfig, PriceSchedule,
+};
+use alloy::primitives::U256;
 use chrono::{DateTime, Utc};
+use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::str::FromStr;
+use tabled::settings::{location::ByColumnName, object::Cell, Color, Remove};
 use tabled::Tabled;
 
 /// Batch status entry with TTL and expiry information
@@ -29,6 +41,10 @@ pub struct BatchStatus {
     #[tabled(rename = "Balance (PLUR/chunk)")]
     pub normalised_balance: String,
 
+    /// Remaining balance across all chunks of the batch, in BZZ
+    #[tabled(rename = "Remaining Balance (BZZ)")]
+    pub remaining_balance_bzz: String,
+
     #[tabled(rename = "TTL (blocks)")]
     pub ttl_blocks: String,
 
@@ -38,8 +54,27 @@ pub struct BatchStatus {
     #[tabled(rename = "Expiry Date")]
     pub expiry_date: String,
 
+    /// Creation cost (`total_amount` of the BatchCreated event) at the current BZZ/USD rate, with `--fiat usd`
+    #[tabled(rename = "Cost (USD)", display_with = "display_cost")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<String>,
+
     #[tabled(skip)]
     pub expiry_timestamp: DateTime<Utc>,
+
+    /// Raw remaining balance (PLUR/chunk) used to compute the TTL
+    #[tabled(skip)]
+    pub remaining_balance: String,
+
+    /// TTL in blocks, unformatted
+    #[tabled(skip)]
+    #[serde(skip)]
+    pub ttl_blocks_raw: u128,
+
+    /// TTL in days, unrounded
+    #[tabled(skip)]
+    #[serde(skip)]
+    pub ttl_days_raw: f64,
 }
 
 impl BatchStatus {
@@ -59,7 +94,9 @@ impl BatchStatus {
 
         // Calculate TTL in blocks (normalised_balance / price)
         // Note: normalised_balance is already per-chunk, so we just divide by price (per-chunk per-block)
-        let ttl_blocks = if balance_value > 0 && price_config.base_price > 0 {
+        let ttl_blocks = if let Some(schedule) = &price_config.price_schedule {
+            schedule.ttl_blocks(balance_value, price_config.base_price, block_time_seconds) as u128
+        } else if balance_value > 0 && price_config.base_price > 0 {
             balance_value / price_config.base_price
         } else {
             0
@@ -69,38 +106,376 @@ impl BatchStatus {
         let ttl_days_value = blocks_to_days(ttl_blocks as u64, block_time_seconds);
 
         // Calculate expiry timestamp
-        let seconds_until_expiry = (ttl_blocks as f64 * block_time_seconds) as u128;
-        let expiry_timestamp = Utc::now() + chrono::Duration::seconds(seconds_until_expiry as i64);
+        let expiry_timestamp = Utc::now() + blocks_to_duration(ttl_blocks as u64, block_time_seconds);
 
         Ok(Self {
-            batch_id: batch.batch_id.clone(),
+            batch_id: batch.batch_id.to_string(),
             owner: batch.owner.clone(),
             payer: batch.payer.clone().unwrap_or_else(|| "-".to_string()),
             depth: batch.depth,
             size_chunks: format_number(size_chunks),
             normalised_balance: format_number(balance_value),
+            remaining_balance_bzz: to_bzz(&(U256::from(balance_value) * U256::from(size_chunks)).to_string())?,
             ttl_blocks: format_number(ttl_blocks),
             ttl_days: format!("{ttl_days_value:.2}"),
             expiry_date: expiry_timestamp.format("%Y-%m-%d %H:%M UTC").to_string(),
+            cost_usd: None,
             expiry_timestamp,
+            remaining_balance: batch.normalised_balance.clone(),
+            ttl_blocks_raw: ttl_blocks,
+            ttl_days_raw: ttl_days_value,
+        })
+    }
+
+    /// Remaining balance parsed for numeric comparison (zero if unparseable)
+    pub fn remaining_balance_value(&self) -> U256 {
+        U256::from_str(&self.remaining_balance).unwrap_or_default()
+    }
+
+    /// Whether the batch still has a balance and at least one block of TTL left
+    ///
+    /// Batches with a zero remaining balance, or too little to pay for another
+    /// block, are considered expired.
+    pub fn is_active(&self) -> bool {
+        !self.remaining_balance_value().is_zero() && self.ttl_blocks_raw > 0
+    }
+}
+
+/// Batch status rolled up across all batches of one owner
+#[derive(Debug, Clone, Serialize, Deserialize, Tabled)]
+pub struct OwnerRollup {
+    #[tabled(rename = "Owner")]
+    pub owner: String,
+
+    #[tabled(rename = "Batches")]
+    pub batch_count: usize,
+
+    #[tabled(rename = "Total Capacity")]
+    pub total_capacity: String,
+
+    #[tabled(rename = "Earliest Expiry")]
+    pub earliest_expiry: String,
+
+    /// Remaining balance across all batches of the owner, in BZZ
+    #[tabled(rename = "Remaining Balance (BZZ)")]
+    pub remaining_balance_bzz: String,
+
+    #[tabled(skip)]
+    pub total_chunks: u128,
+
+    #[tabled(skip)]
+    pub earliest_expiry_timestamp: DateTime<Utc>,
+}
+
+/// Roll batch statuses up per owner
+///
+/// Owners are compared case-insensitively. Rollups are ordered by earliest
+/// expiry so the owners needing a top-up soonest come first.
+pub fn rollup_by_owner(statuses: &[BatchStatus]) -> Vec<OwnerRollup> {
+    let mut by_owner: HashMap<String, (usize, u128, DateTime<Utc>, U256)> = HashMap::new();
+    for status in statuses {
+        let chunks = 1u128 << status.depth;
+        let balance = status.remaining_balance_value() * U256::from(chunks);
+        let entry = by_owner
+            .entry(status.owner.to_lowercase())
+            .or_insert((0, 0, status.expiry_timestamp, U256::ZERO));
+        entry.0 += 1;
+        entry.1 += chunks;
+        entry.2 = entry.2.min(status.expiry_timestamp);
+        entry.3 += balance;
+    }
+
+    let mut rollups: Vec<OwnerRollup> = by_owner
+        .into_iter()
+        .map(|(owner, (batch_count, total_chunks, earliest, balance))| OwnerRollup {
+            owner,
+            batch_count,
+            total_capacity: format_storage(total_chunks),
+            earliest_expiry: earliest.format("%Y-%m-%d %H:%M UTC").to_string(),
+            remaining_balance_bzz: to_bzz(&balance.to_string()).expect("U256 renders as decimal digits"),
+            total_chunks,
+            earliest_expiry_timestamp: earliest,
         })
+        .collect();
+
+    rollups.sort_by(|a, b| {
+        a.earliest_expiry_timestamp
+            .cmp(&b.earliest_expiry_timestamp)
+            .then_with(|| a.owner.cmp(&b.owner))
+    });
+    rollups
+}
+
+/// Resolve the storage price and current block used for TTL calculations
+///
+/// The price comes from `price_override`, the chain (with `refresh`) or the
+/// cached price, fetching it when nothing is cached. With `offline` no RPC call
+/// is made: the price must come from `price_override` or the cache, and the
+/// current block is the last cached block.
+pub async fn resolve_price_and_block(
+    cache: &Cache,
+    blockchain_client: &BlockchainClient,
+    registry: &crate::contracts::ContractRegistry,
+    price_override: Option<String>,
+    refresh: bool,
+    offline: bool,
+) -> Result<(u128, u64)> {
+    let base_price = if let Some(price_str) = price_override {
+        // User provided explicit price
+        price_str
+            .parse::<u128>()
+            .map_err(|_| StampError::Parse("Invalid price value".to_string()))?
+    } else if offline {
+        cache.get_cached_price().await?.ok_or_else(|| {
+            StampError::Config(
+                "No cached storage price for --offline. Pass --price or run once with network access."
+                    .to_string(),
+            )
+        })?
+    } else if refresh {
+        // Refresh mode: fetch current price from blockchain and cache it
+        let price = blockchain_client.get_current_price(registry).await?;
+        cache.cache_price(price).await?;
+        price
+    } else {
+        // Use cached price if available, otherwise fetch from blockchain
+        if let Some(cached_price) = cache.get_cached_price().await? {
+            cached_price
+        } else {
+            let price = blockchain_client.get_current_price(registry).await?;
+            cache.cache_price(price).await?;
+            price
+        }
+    };
+
+    let current_block = if offline {
+        cache.get_last_block().await?.unwrap_or(0)
+    } else {
+        blockchain_client.get_current_block_cached().await?
+    };
+
+    Ok((base_price, current_block))
+}
+
+/// Whether a remaining balance (PLUR/chunk) falls below the `--min-balance` threshold
+///
+/// Such dust batches are treated as effectively expired. Unparseable balances
+/// count as zero.
+pub fn below_min_balance(balance: &str, min_balance: Option<u128>) -> bool {
+    min_balance.is_some_and(|min| U256::from_str(balance).unwrap_or_default() < U256::from(min))
+}
+
+/// Batches expiring within this many days are shown in red
+const TTL_CRITICAL_DAYS: f64 = 7.0;
+
+/// Batches expiring within this many days are shown in yellow
+const TTL_WARNING_DAYS: f64 = 30.0;
+
+/// Whether table output should be colored
+///
+/// Only a terminal gets colors, and setting `NO_COLOR` to any non-empty value
+/// turns them off (<https://no-color.org>).
+pub fn colors_enabled(is_terminal: bool, no_color: Option<&std::ffi::OsStr>) -> bool {
+    is_terminal && no_color.is_none_or(|value| value.is_empty())
+}
+
+/// Color for a TTL cell: red under a week, yellow under a month, green otherwise
+fn ttl_color(ttl_days: f64) -> Color {
+    match ttl_days {
+        days if days < TTL_CRITICAL_DAYS => Color::FG_RED,
+        days if days < TTL_WARNING_DAYS => Color::FG_YELLOW,
+        _ => Color::FG_GREEN,
+    }
+}
+
+fn display_cost(cost: &Option<String>) -> String {
+    cost.clone().unwrap_or_else(|| "-".to_string())
+}
+
+/// Header of the fiat cost column, dropped from the table when no cost was computed
+const COST_COLUMN: &str = "Cost (USD)";
+
+/// Batch status table, without the cost column when no cost was computed
+fn status_table(statuses: &[BatchStatus]) -> tabled::Table {
+    let mut table = tabled::Table::new(statuses);
+    if statuses.iter().all(|status| status.cost_usd.is_none()) {
+        table.with(Remove::column(ByColumnName::new(COST_COLUMN)));
+    }
+    table
+}
+
+/// Render the batch status table, coloring the TTL cells by urgency when `color` is set
+pub fn render_status_table(statuses: &[BatchStatus], color: bool) -> String {
+    let mut table = status_table(statuses);
+    if color {
+        let headers = BatchStatus::headers();
+        let ttl_columns: Vec<usize> = headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| header.starts_with("TTL"))
+            .map(|(column, _)| column)
+            .collect();
+        for (row, status) in statuses.iter().enumerate() {
+            for &column in &ttl_columns {
+                // Row 0 is the header
+                table.modify(Cell::new(row + 1, column), ttl_color(status.ttl_days_raw));
+            }
+        }
+    }
+    table.to_string()
+}
+
+/// Default for how many blocks a cached balance stays valid when building statuses from the cache
+pub const BALANCE_VALIDITY_BLOCKS: u64 = 518_400;
+
+/// Balance resolved for `--refresh`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshedBalance {
+    /// Remaining balance in PLUR per chunk
+    pub balance: String,
+    /// Whether the balance was fetched from the chain rather than reused from the cache
+    pub fetched: bool,
+}
+
+/// Resolve a batch balance for `--refresh`
+///
+/// A cached balance fetched fewer than `validity_blocks` blocks before
+/// `current_block` is reused; otherwise the balance is fetched from the chain
+/// and cached. A batch that no longer exists is cached with a zero balance.
+pub async fn refresh_balance(
+    cache: &Cache,
+    blockchain_client: &BlockchainClient,
+    registry: &crate::contracts::ContractRegistry,
+    retry: &crate::retry::RetryConfig,
+    batch_id: &str,
+    current_block: u64,
+    validity_blocks: u64,
+) -> Result<RefreshedBalance> {
+    if let Some(balance) = cache.get_cached_balance(batch_id, current_block, validity_blocks).await? {
+        tracing::debug!("Reusing cached balance for batch {}", batch_id);
+        return Ok(RefreshedBalance { balance, fetched: false });
+    }
+
+    let balance = match blockchain_client.get_remaining_balance(batch_id, registry, retry).await {
+        Ok(balance) => balance,
+        // The batch is gone: treat it as expired
+        Err(StampError::BatchNotFound { .. }) => "0".to_string(),
+        // Don't cache failures - this allows retry with --only-missing later
+        Err(e) => return Err(e),
+    };
+
+    if let Err(e) = cache.cache_balance(batch_id, &balance, current_block).await {
+        tracing::warn!("Failed to cache balance: {}", e);
+    }
+
+    Ok(RefreshedBalance { balance, fetched: true })
+}
+
+/// Build a batch status from cached data only, without querying the chain
+///
+/// A cached balance fetched fewer than `validity_blocks` blocks ago is
+/// preferred over the creation-time balance.
+pub async fn status_from_cache(
+    cache: &Cache,
+    mut batch: BatchInfo,
+    price_config: &PriceConfig,
+    current_block: u64,
+    validity_blocks: u64,
+    block_time_seconds: f64,
+) -> Result<BatchStatus> {
+    if let Some(balance) = cache
+        .get_cached_balance(batch.batch_id.as_str(), current_block, validity_blocks)
+        .await?
+    {
+        batch.normalised_balance = balance;
     }
+
+    BatchStatus::from_batch(&batch, price_config, current_block, block_time_seconds)
 }
 
-/// Format large numbers with thousand separators
-fn format_number(n: u128) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    let len = s.len();
+/// Number of batches between progress log lines when stderr is not a terminal
+const PROGRESS_LOG_INTERVAL: usize = 100;
 
-    for (i, c) in s.chars().enumerate() {
-        if i > 0 && (len - i) % 3 == 0 {
-            result.push(',');
+/// Progress reporting while resolving batch balances
+///
+/// Renders a progress bar on stderr when it is a terminal and otherwise logs
+/// a `tracing` line every `PROGRESS_LOG_INTERVAL` batches, so stdout only
+/// carries the final output.
+pub(crate) enum BalanceProgress {
+    Bar(ProgressBar),
+    Log { total: usize },
+    Hidden,
+}
+
+impl BalanceProgress {
+    /// Pick the progress style for `total` batches
+    pub(crate) fn new(total: usize, no_progress: bool) -> Self {
+        if no_progress {
+            Self::Hidden
+        } else if std::io::stderr().is_terminal() {
+            let bar = ProgressBar::new(total as u64);
+            bar.set_style(
+                ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} batches | {msg} | ETA {eta}")
+                    .expect("valid progress template"),
+            );
+            Self::Bar(bar)
+        } else {
+            Self::Log { total }
         }
-        result.push(c);
     }
 
-    result
+    /// Report that `processed` batches are done
+    pub(crate) fn update(&self, processed: usize, cache_hits: usize, skipped: usize) {
+        let hit_rate = if processed > 0 {
+            cache_hits as f64 / processed as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        match self {
+            Self::Bar(bar) => {
+                bar.set_position(processed as u64);
+                bar.set_message(format!("cache hits {hit_rate:.1}%"));
+            }
+            Self::Log { total } => {
+                if processed > 0 && processed.is_multiple_of(PROGRESS_LOG_INTERVAL) && processed < *total {
+                    tracing::info!(
+                        processed,
+                        total = *total,
+                        cache_hit_rate = %format!("{hit_rate:.1}%"),
+                        skipped,
+                        "Resolving batch balances"
+                    );
+                }
+            }
+            Self::Hidden => {}
+        }
+    }
+
+    /// Remove the progress bar, if any
+    pub(crate) fn finish(&self) {
+        if let Self::Bar(bar) = self {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+/// Fill in the fiat cost of each batch from its creation amount
+///
+/// Batches without a cached BatchCreated event, or whose creation amount
+/// cannot be converted, show `-`.
+pub fn apply_fiat_costs(statuses: &mut [BatchStatus], amounts: &HashMap<String, String>, rate: f64) {
+    for status in statuses {
+        let cost = match amounts.get(&status.batch_id.to_lowercase()).map(|amount| fiat_cost(amount, rate)) {
+            Some(Ok(cost)) => format!("{cost:.2}"),
+            Some(Err(e)) => {
+                tracing::warn!("No fiat cost for batch {}: {}", status.batch_id, e);
+                "-".to_string()
+            }
+            None => "-".to_string(),
+        };
+        status.cost_usd = Some(cost);
+    }
 }
 
 /// Execute the batch status command
@@ -111,20 +486,31 @@ pub async fn execute(
     registry: &crate::contracts::ContractRegistry,
     config: &crate::config::AppConfig,
     sort_by: BatchStatusSortBy,
+    sort_order: Option<SortOrder>,
     output: OutputFormat,
     price_override: Option<String>,
     price_change_str: Option<String>,
+    price_schedule_str: Option<String>,
     refresh: bool,
+    offline: bool,
     only_missing: bool,
     hide_zero_balance: bool,
+    min_balance: Option<u128>,
     contract_filter: Option<String>,
     cache_validity_blocks: u64,
+    balance_cache_blocks: u64,
+    group_by: Option<BatchStatusGroupBy>,
+    status_filter: BatchStatusFilter,
+    no_progress: bool,
+    output_file: Option<&Path>,
+    fiat: Option<FiatFeed>,
+    console: Console,
 ) -> Result<()> {
-    // Get all batches from cache
-    let mut batches = cache.get_batches(0).await?;
+    // Get all batches from cache, sized by their current depth
+    let mut batches = cache.get_batches_with_current_depth(0).await?;
 
     if batches.is_empty() {
-        println!("No batches found in database. Run 'sync' or 'fetch' first.");
+        status!(console, "No batches found in database. Run 'sync' or 'fetch' first.");
         return Ok(());
     }
 
@@ -142,76 +528,51 @@ pub async fn execute(
         };
         let before = batches.len();
         batches.retain(|b| b.contract_source == contract_source);
-        println!("Filtered to {} batches from {} (was {})", batches.len(), contract_source, before);
+        status!(console, "Filtered to {} batches from {} (was {})", batches.len(), contract_source, before);
     }
 
     // Determine price configuration
-    let base_price = if let Some(price_str) = price_override {
-        // User provided explicit price
-        price_str
-            .parse::<u128>()
-            .map_err(|_| crate::error::StampError::Parse("Invalid price value".to_string()))?
-    } else if refresh {
-        // Refresh mode: fetch current price from blockchain and cache it
-        let price = blockchain_client.get_current_price(registry).await?;
-        cache.cache_price(price).await?;
-        price
-    } else {
-        // Use cached price if available, otherwise fetch from blockchain
-        if let Some(cached_price) = cache.get_cached_price().await? {
-            cached_price
-        } else {
-            let price = blockchain_client.get_current_price(registry).await?;
-            cache.cache_price(price).await?;
-            price
-        }
-    };
+    let (base_price, current_block) =
+        resolve_price_and_block(&cache, blockchain_client, registry, price_override, refresh, offline).await?;
 
-    let price_config = if let Some(change_str) = price_change_str {
+    let price_config = if let Some(schedule_str) = price_schedule_str {
+        let price_schedule = schedule_str.parse::<PriceSchedule>()?;
+        PriceConfig::with_price_schedule(base_price, price_schedule)
+    } else if let Some(change_str) = price_change_str {
         let price_change = change_str.parse::<PriceChange>()?;
         PriceConfig::with_price_change(base_price, price_change)
     } else {
         PriceConfig::new(base_price)
     };
 
-    // Get current block
-    let current_block = blockchain_client.get_current_block().await?;
-
     // Calculate status for each batch, fetching current balance from blockchain
     let mut statuses: Vec<BatchStatus> = Vec::new();
 
     if refresh && only_missing {
-        println!("📊 Fetching balances only for batches without cached data...");
-        println!("Using max_retries={} for rate-limited requests. Progress will be shown every 100 batches.\n", config.retry.max_retries);
+        status!(console, "📊 Fetching balances only for batches without cached data...");
+        status!(console, "Using max_retries={} for rate-limited requests.\n", config.retry.max_retries);
     } else if refresh {
-        println!("📊 Fetching current balances for {} batches from blockchain...", batches.len());
-        println!("Using max_retries={} for rate-limited requests. Progress will be shown every 100 batches.\n", config.retry.max_retries);
+        status!(console, "📊 Fetching current balances for {} batches from blockchain...", batches.len());
+        status!(console, "Using max_retries={} for rate-limited requests.\n", config.retry.max_retries);
     } else {
-        println!("📊 Using cached balances for {} batches...", batches.len());
-        println!("Note: Batches without cached balance will show creation-time balance (pass --refresh to fetch current balances)");
-        println!("Progress will be shown every 100 batches.\n");
+        status!(console, "📊 Using cached balances for {} batches...", batches.len());
+        status!(console, "Note: Batches without cached balance will show creation-time balance (pass --refresh to fetch current balances)\n");
     }
 
     let total = batches.len();
     let mut cache_hits = 0;
     let mut cache_misses = 0;
     let mut skipped = 0;
+    let mut dust = 0;
+    let progress = BalanceProgress::new(total, no_progress);
 
     for (idx, batch) in batches.iter().enumerate() {
-        // Show progress every 100 batches
-        if idx % 100 == 0 && idx > 0 {
-            println!(
-                "  ⏳ Progress: {}/{} batches ({:.1}%) - Cache: {} hits, {} misses, {} skipped",
-                idx, total, (idx as f64 / total as f64) * 100.0, cache_hits, cache_misses, skipped
-            );
-        }
-
-        // Check if we have a cached balance
-        let cached_balance = cache.get_cached_balance(&batch.batch_id, current_block, cache_validity_blocks).await.ok().flatten();
+        progress.update(idx, cache_hits, skipped);
 
         // Get balance based on refresh and only_missing flags
         let remaining_balance = if !refresh {
             // When refresh=false, use cache if available, otherwise use original balance
+            let cached_balance = cache.get_cached_balance(batch.batch_id.as_str(), current_block, cache_validity_blocks).await.ok().flatten();
             if let Some(cached) = cached_balance {
                 cache_hits += 1;
                 tracing::debug!("Cache hit for batch {}", batch.batch_id);
@@ -221,38 +582,38 @@ pub async fn execute(
                 tracing::debug!("No cached balance for batch {}, using original balance from creation", batch.batch_id);
                 batch.normalised_balance.clone() // Use last known balance (creation balance)
             }
-        } else if only_missing && cached_balance.is_some() {
-            // Skip batches that already have cached balance when only_missing=true
-            skipped += 1;
-            cache_hits += 1;
-            tracing::debug!("Skipping batch {} (already cached)", batch.batch_id);
-            cached_balance.unwrap()
         } else {
-            // Fetch from blockchain (either refresh=true without only_missing, or refresh=true with only_missing but no cache)
-            cache_misses += 1;
-            match blockchain_client.get_remaining_balance(&batch.batch_id, registry, &config.retry).await {
-                Ok(balance) => {
-                    // Only cache successful fetches
-                    if let Err(e) = cache.cache_balance(&batch.batch_id, &balance, current_block).await {
-                        tracing::warn!("Failed to cache balance: {}", e);
-                    }
+            // Reuse recently fetched balances (any cached balance with only_missing), fetch the rest
+            let validity_blocks = if only_missing { cache_validity_blocks } else { balance_cache_blocks };
+            match refresh_balance(&cache, blockchain_client, registry, &config.retry, batch.batch_id.as_str(), current_block, validity_blocks).await {
+                Ok(RefreshedBalance { balance, fetched: true }) => {
+                    cache_misses += 1;
 
                     // Small delay to avoid rate limiting (1ms between requests)
                     tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
 
                     balance
                 }
+                Ok(RefreshedBalance { balance, fetched: false }) => {
+                    skipped += 1;
+                    cache_hits += 1;
+                    tracing::debug!("Skipping batch {} (already cached)", batch.batch_id);
+                    balance
+                }
                 Err(e) => {
-                    // Don't cache failures - this allows retry with --only-missing later
-                    // Only log if it's not the common "batch doesn't exist" error
-                    if !e.to_string().contains("0x4ee9bc0f") {
-                        tracing::warn!("Failed to get balance for {}: {}", batch.batch_id, e);
-                    }
+                    cache_misses += 1;
+                    tracing::warn!("Failed to get balance for {}: {}", batch.batch_id, e);
                     "0".to_string()
                 }
             }
         };
 
+        // Treat dust balances as effectively expired
+        if below_min_balance(&remaining_balance, min_balance) {
+            dust += 1;
+            continue;
+        }
+
         // Create a modified batch with current balance
         let mut current_batch = batch.clone();
         current_batch.normalised_balance = remaining_balance;
@@ -262,72 +623,97 @@ pub async fn execute(
         }
     }
 
+    progress.finish();
+
     if skipped > 0 {
-        println!(
+        status!(
+            console,
             "  ✅ Completed: {}/{} batches - Cache: {} hits ({:.1}%), {} fetched, {} skipped\n",
             total, total, cache_hits, (cache_hits as f64 / total as f64) * 100.0, cache_misses, skipped
         );
     } else {
-        println!(
+        status!(
+            console,
             "  ✅ Completed: {}/{} batches - Cache: {} hits ({:.1}%), {} misses\n",
             total, total, cache_hits, (cache_hits as f64 / total as f64) * 100.0, cache_misses
         );
     }
 
+    if dust > 0 {
+        status!(console, "  🔍 Skipped {dust} batches below the minimum balance\n");
+    }
+
+    // Keep only active or expired batches as requested
+    let total_before_status = statuses.len();
+    statuses.retain(|s| status_filter.matches(s));
+    let status_filtered = total_before_status - statuses.len();
+    if status_filtered > 0 {
+        status!(console, "  🔍 Filtered out {status_filtered} batches not matching the --status filter\n");
+    }
+
     // Filter out zero balance batches if requested
     let total_before_filter = statuses.len();
     if hide_zero_balance {
         statuses.retain(|s| s.normalised_balance != "0");
         let filtered_count = total_before_filter - statuses.len();
         if filtered_count > 0 {
-            println!("  🔍 Filtered out {filtered_count} batches with zero balance\n");
+            status!(console, "  🔍 Filtered out {filtered_count} batches with zero balance\n");
         }
     }
 
-    // Sort results
-    match sort_by {
-        BatchStatusSortBy::BatchId => statuses.sort_by(|a, b| a.batch_id.cmp(&b.batch_id)),
-        BatchStatusSortBy::Depth => statuses.sort_by(|a, b| b.depth.cmp(&a.depth)), // Descending order (highest depth first)
-        BatchStatusSortBy::Ttl => {
-            statuses.sort_by(|a, b| {
-                // Parse ttl_blocks strings (removing commas) for numeric comparison
-                let a_ttl = a.ttl_blocks.replace(",", "").parse::<u128>().unwrap_or(0);
-                let b_ttl = b.ttl_blocks.replace(",", "").parse::<u128>().unwrap_or(0);
-                b_ttl.cmp(&a_ttl) // Descending order (highest TTL first)
-            })
-        }
-        BatchStatusSortBy::Expiry => {
-            statuses.sort_by(|a, b| a.expiry_timestamp.cmp(&b.expiry_timestamp))
-        }
-        BatchStatusSortBy::Size => {
-            statuses.sort_by(|a, b| {
-                let a_size = 1u128 << a.depth;
-                let b_size = 1u128 << b.depth;
-                b_size.cmp(&a_size)
-            })
+    // Add the fiat cost column; without a rate the output is the same as without --fiat
+    if let Some(feed) = fiat {
+        match feed.bzz_rate(&cache, offline).await {
+            Ok(rate) => {
+                let amounts = cache.get_creation_amounts().await?;
+                apply_fiat_costs(&mut statuses, &amounts, rate);
+            }
+            Err(e) => tracing::warn!("Skipping the fiat cost column: {}", e),
         }
     }
 
+    // Sort results
+    sort_statuses(&mut statuses, &sort_by, sort_order);
+
+    let mut out = crate::display::output_writer(output_file)?;
+
+    if let Some(BatchStatusGroupBy::Owner) = group_by {
+        write_owner_rollups(&mut out, &rollup_by_owner(&statuses), output, base_price)?;
+        out.flush()?;
+        return Ok(());
+    }
+
     // Output results
     match output {
         OutputFormat::Table => {
-            use tabled::Table;
-            let table = Table::new(&statuses).to_string();
-            println!("\n{table}\n");
+            let color = output_file.is_none()
+                && colors_enabled(std::io::stdout().is_terminal(), std::env::var_os("NO_COLOR").as_deref());
+            let table = render_status_table(&statuses, color);
+            writeln!(out, "\n{table}\n")?;
 
             let price_info = format!(
                 "Total batches: {} | Price: {} PLUR/chunk/block | TTL (blocks) = Balance / Price",
                 statuses.len(),
                 format_number(base_price)
             );
-            println!("{price_info}");
+            writeln!(out, "{price_info}")?;
+        }
+        OutputFormat::Markdown => {
+            let table = output.render_table(status_table(&statuses));
+            writeln!(out, "{table}\n")?;
+            writeln!(
+                out,
+                "Total batches: {} | Price: {} PLUR/chunk/block | TTL (blocks) = Balance / Price",
+                statuses.len(),
+                format_number(base_price)
+            )?;
         }
         OutputFormat::Json => {
             let json = serde_json::to_string_pretty(&statuses)?;
-            println!("{json}");
+            writeln!(out, "{json}")?;
         }
         OutputFormat::Csv => {
-            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            let mut wtr = csv::Writer::from_writer(&mut out);
             for status in &statuses {
                 wtr.serialize(status)?;
             }
@@ -335,44 +721,576 @@ pub async fn execute(
         }
     }
 
+    out.flush()?;
+    Ok(())
+}
+
+/// Sort batch statuses by a field
+///
+/// Each field has a natural direction (batch IDs and expiry ascending, depth,
+/// TTL, size and balance descending); `order` overrides it.
+pub fn sort_statuses(statuses: &mut [BatchStatus], sort_by: &BatchStatusSortBy, order: Option<SortOrder>) {
+    let (compare, natural): (fn(&BatchStatus, &BatchStatus) -> Ordering, SortOrder) = match sort_by {
+        BatchStatusSortBy::BatchId => (|a, b| a.batch_id.cmp(&b.batch_id), SortOrder::Asc),
+        BatchStatusSortBy::Depth => (|a, b| a.depth.cmp(&b.depth), SortOrder::Desc),
+        BatchStatusSortBy::Ttl => (|a, b| a.ttl_blocks_raw.cmp(&b.ttl_blocks_raw), SortOrder::Desc),
+        BatchStatusSortBy::Expiry => (|a, b| a.expiry_timestamp.cmp(&b.expiry_timestamp), SortOrder::Asc),
+        // Size is 2^depth
+        BatchStatusSortBy::Size => (|a, b| a.depth.cmp(&b.depth), SortOrder::Desc),
+        BatchStatusSortBy::Balance => (
+            |a, b| a.remaining_balance_value().cmp(&b.remaining_balance_value()),
+            SortOrder::Desc,
+        ),
+    };
+
+    match order.unwrap_or(natural) {
+        SortOrder::Asc => statuses.sort_by(compare),
+        SortOrder::Desc => statuses.sort_by(|a, b| compare(b, a)),
+    }
+}
+
+/// Write per-owner rollups in the requested output format
+fn write_owner_rollups<W: Write>(
+    out: &mut W,
+    rollups: &[OwnerRollup],
+    output: OutputFormat,
+    base_price: u128,
+) -> Result<()> {
+    match output {
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let table = output.render_table(tabled::Table::new(rollups));
+            if let OutputFormat::Markdown = output {
+                writeln!(out, "{table}\n")?;
+            } else {
+                writeln!(out, "\n{table}\n")?;
+            }
+            writeln!(
+                out,
+                "Total owners: {} | Price: {} PLUR/chunk/block",
+                rollups.len(),
+                format_number(base_price)
+            )?;
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(rollups)?;
+            writeln!(out, "{json}")?;
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *out);
+            for rollup in rollups {
+                wtr.serialize(rollup)?;
+            }
+            wtr.flush()?;
+        }
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::events::EventData;
     use chrono::Utc;
-
-    #[test]
-    fn test_format_number() {
-        assert_eq!(format_number(1000), "1,000");
-        assert_eq!(format_number(1000000), "1,000,000");
-        assert_eq!(format_number(1048576), "1,048,576");
-    }
+    use crate::types::BatchId;
 
     #[test]
     fn test_batch_status_creation() {
-        let batch = BatchInfo {
-            batch_id: "0x1234".to_string(),
-            owner: "0x5678".to_string(),
-            payer: None,
-            contract_source: "PostageStamp".to_string(),
-            depth: 20,
-            bucket_depth: 16,
-            immutable: false,
-            normalised_balance: "240000000".to_string(), // 240M PLUR - reasonable for testing
-            created_at: Utc::now(),
-            block_number: 1000,
-        };
+        let batch = BatchInfo::test_batch(0x1234, 20, "240000000"); // 240M PLUR - reasonable for testing
 
         let price_config = PriceConfig::new(24000);
         let status = BatchStatus::from_batch(&batch, &price_config, 38000000, 5.0).unwrap();
 
-        assert_eq!(status.batch_id, "0x1234");
+        assert_eq!(status.batch_id, BatchId::test_id(0x1234).to_string());
         assert_eq!(status.depth, 20);
         assert!(status.ttl_blocks != "0");
         assert!(!status.ttl_blocks.is_empty());
         // With balance=240M and price=24000, TTL should be 10,000 blocks
         assert_eq!(status.ttl_blocks, "10,000");
+        assert_eq!(status.remaining_balance, "240000000");
+    }
+
+    #[test]
+    fn test_ttl_colors_only_when_enabled() {
+        let batch = |id: u64, normalised_balance: &str| BatchInfo::test_batch(id, 20, normalised_balance);
+        let price_config = PriceConfig::new(24000);
+        let statuses: Vec<BatchStatus> = [
+            // 10,000 blocks: under a day
+            batch(1, "240000000"),
+            // 100 days
+            batch(2, "41472000000"),
+        ]
+        .iter()
+        .map(|b| BatchStatus::from_batch(b, &price_config, 38000000, 5.0).unwrap())
+        .collect();
+
+        let plain = render_status_table(&statuses, false);
+        assert!(!plain.contains('\u{1b}'));
+
+        let colored = render_status_table(&statuses, true);
+        assert!(colored.contains("\u{1b}[31m0.58\u{1b}[39m"));
+        assert!(colored.contains("\u{1b}[32m100.00\u{1b}[39m"));
+        assert!(!colored.contains("\u{1b}[33m"));
+
+        // Colors are for terminals only, and NO_COLOR wins
+        assert!(colors_enabled(true, None));
+        assert!(!colors_enabled(false, None));
+        assert!(!colors_enabled(true, Some(std::ffi::OsStr::new("1"))));
+        assert!(colors_enabled(true, Some(std::ffi::OsStr::new(""))));
+    }
+
+    #[test]
+    fn test_batch_status_with_price_schedule() {
+        let batch = BatchInfo::test_batch(0x1234, 20, "2728000");
+
+        // One day (17,280 blocks) at 100 PLUR, then 200 PLUR for the remaining 1,000,000
+        let schedule = "0:100,1:200".parse::<PriceSchedule>().unwrap();
+        let price_config = PriceConfig::with_price_schedule(100, schedule);
+        let status = BatchStatus::from_batch(&batch, &price_config, 38000000, 5.0).unwrap();
+
+        assert_eq!(status.ttl_blocks, "22,280");
+    }
+
+    #[test]
+    fn test_expiry_follows_block_time() {
+        let batch = BatchInfo::test_batch(0x1234, 20, "240000000");
+        let price_config = PriceConfig::new(24000);
+
+        // 10,000 blocks of TTL: ~13.9 hours at 5s, ~27.8 hours at 10s
+        let before = Utc::now();
+        let gnosis = BatchStatus::from_batch(&batch, &price_config, 0, 5.0).unwrap();
+        let slow = BatchStatus::from_batch(&batch, &price_config, 0, 10.0).unwrap();
+
+        let gnosis_offset = (gnosis.expiry_timestamp - before).num_seconds();
+        let slow_offset = (slow.expiry_timestamp - before).num_seconds();
+        assert!((gnosis_offset - 50_000).abs() <= 1);
+        assert!((slow_offset - 100_000).abs() <= 1);
+        assert_eq!(slow.ttl_days, "1.16");
+    }
+
+    #[test]
+    fn test_below_min_balance_boundary() {
+        // No threshold keeps everything
+        assert!(!below_min_balance("0", None));
+
+        // Exactly at the threshold is kept, one PLUR below is dust
+        assert!(!below_min_balance("1000", Some(1000)));
+        assert!(below_min_balance("999", Some(1000)));
+        assert!(!below_min_balance("1001", Some(1000)));
+
+        // Unparseable balances count as zero
+        assert!(below_min_balance("not-a-number", Some(1)));
+    }
+
+    #[tokio::test]
+    async fn test_offline_path_makes_no_rpc_calls() {
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+        cache
+            .store_batches(&[BatchInfo::test_batch(0x1234, 20, "240000000")])
+            .await
+            .unwrap();
+
+        // Nothing listens here, so any RPC call would fail
+        let client = BlockchainClient::new("http://127.0.0.1:1").await.unwrap();
+        let config = crate::config::AppConfig::default();
+        let registry = crate::contracts::ContractRegistry::from_config(&config).unwrap();
+
+        // Neither --price nor a cached price: offline cannot pick a price
+        let err = resolve_price_and_block(&cache, &client, &registry, None, false, true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StampError::Config(_)));
+
+        // --price with no cached events resolves to block 0
+        let resolved = resolve_price_and_block(&cache, &client, &registry, Some("24000".to_string()), false, true)
+            .await
+            .unwrap();
+        assert_eq!(resolved, (24000, 0));
+
+        // Cached price is the fallback without --price
+        cache.cache_price(26000).await.unwrap();
+        let resolved = resolve_price_and_block(&cache, &client, &registry, None, false, true)
+            .await
+            .unwrap();
+        assert_eq!(resolved, (26000, 0));
+
+        // The whole command runs without touching the network
+        execute(
+            cache,
+            &client,
+            &registry,
+            &config,
+            BatchStatusSortBy::Ttl,
+            None,
+            OutputFormat::Json,
+            Some("24000".to_string()),
+            None,
+            None,
+            false,
+            true,
+            false,
+            false,
+            None,
+            None,
+            BALANCE_VALIDITY_BLOCKS,
+            720,
+            None,
+            BatchStatusFilter::All,
+            true,
+            None,
+            None,
+            Console::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_fiat_costs_skip_unparseable_amounts() {
+        let price_config = PriceConfig::new(24000);
+        let mut statuses: Vec<BatchStatus> = [1, 2, 3]
+            .into_iter()
+            .map(|n| {
+                let batch = BatchInfo::test_batch(n, 20, "240000000");
+                BatchStatus::from_batch(&batch, &price_config, 0, 5.0).unwrap()
+            })
+            .collect();
+        let amounts = HashMap::from([
+            (statuses[0].batch_id.clone(), "40000000000000000".to_string()),
+            (statuses[1].batch_id.clone(), "not-a-number".to_string()),
+        ]);
+
+        apply_fiat_costs(&mut statuses, &amounts, 0.3125);
+        let costs: Vec<&str> = statuses.iter().map(|s| s.cost_usd.as_deref().unwrap()).collect();
+        assert_eq!(costs, ["1.25", "-", "-"]);
+    }
+
+    #[tokio::test]
+    async fn test_fiat_cost_column_from_mock_feed() {
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+        let batches: Vec<BatchInfo> = [0x1234, 0x5678]
+            .into_iter()
+            .map(|n| BatchInfo::test_batch(n, 20, "240000000"))
+            .collect();
+        cache.store_batches(&batches).await.unwrap();
+
+        // Only the first batch has a cached creation event: 4 BZZ
+        cache
+            .store_events(&[crate::events::StampEvent {
+                event_type: crate::events::EventType::BatchCreated,
+                batch_id: Some(batches[0].batch_id.to_string()),
+                block_number: 1000,
+                block_timestamp: Utc::now(),
+                transaction_hash: "0xabcd".to_string(),
+                log_index: 0,
+                contract_source: "PostageStamp".to_string(),
+                contract_address: None,
+                block_hash: None,
+                data: EventData::BatchCreated {
+                    total_amount: "40000000000000000".to_string(),
+                    normalised_balance: "240000000".to_string(),
+                    owner: "0x5678".to_string(),
+                    depth: 20,
+                    bucket_depth: 16,
+                    immutable_flag: false,
+                    payer: None,
+                },
+            }])
+            .await
+            .unwrap();
+
+        // One server plays both the RPC node and the price feed
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "method": "eth_blockNumber" })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x3e8"}"#)
+            .create_async()
+            .await;
+        let feed = server
+            .mock("GET", "/simple/price")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"swarm-bzz":{"usd":0.3125}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+        let config = crate::config::AppConfig::default();
+        let registry = crate::contracts::ContractRegistry::from_config(&config).unwrap();
+        let output_file = tempfile::NamedTempFile::new().unwrap();
+
+        execute(
+            cache,
+            &client,
+            &registry,
+            &config,
+            BatchStatusSortBy::BatchId,
+            None,
+            OutputFormat::Csv,
+            Some("24000".to_string()),
+            None,
+            None,
+            false,
+            false,
+            false,
+            false,
+            None,
+            None,
+            BALANCE_VALIDITY_BLOCKS,
+            720,
+            None,
+            BatchStatusFilter::All,
+            true,
+            Some(output_file.path()),
+            Some(FiatFeed::new("usd", Some(server.url()))),
+            Console::default(),
+        )
+        .await
+        .unwrap();
+        feed.assert_async().await;
+
+        let mut reader = csv::Reader::from_path(output_file.path()).unwrap();
+        let headers = reader.headers().unwrap().clone();
+        let cost = headers.iter().position(|h| h == "cost_usd").unwrap();
+        let costs: Vec<String> = reader.records().map(|r| r.unwrap()[cost].to_string()).collect();
+        // 4 BZZ × $0.3125 = $1.25; the batch without a creation event has no cost
+        assert_eq!(costs, ["1.25", "-"]);
+    }
+
+    #[test]
+    fn test_cost_column_hidden_without_rate() {
+        let batch = BatchInfo::test_batch(0x1234, 20, "240000000");
+        let mut statuses = vec![BatchStatus::from_batch(&batch, &PriceConfig::new(24000), 0, 5.0).unwrap()];
+        assert!(!render_status_table(&statuses, false).contains(COST_COLUMN));
+
+        statuses[0].cost_usd = Some("1.25".to_string());
+        let table = render_status_table(&statuses, false);
+        assert!(table.contains(COST_COLUMN));
+        assert!(table.contains("1.25"));
+    }
+
+    #[test]
+    fn test_remaining_balance_column_from_fetched_balance() {
+        let batch = BatchInfo::test_batch(0x1234, 20, "5000000000"); // balance fetched from chain
+
+        let price_config = PriceConfig::new(24000);
+        let status = BatchStatus::from_batch(&batch, &price_config, 38000000, 5.0).unwrap();
+
+        assert_eq!(status.remaining_balance, "5000000000");
+        // 5,000,000,000 PLUR/chunk * 2^20 chunks = 5,242,880,000,000,000 PLUR
+        assert_eq!(status.remaining_balance_bzz, "0.524288");
+    }
+
+    #[test]
+    fn test_sort_by_remaining_balance() {
+        let price_config = PriceConfig::new(24000);
+        let status = |id: u64, balance: &str| {
+            let batch = BatchInfo::test_batch(id, 20, balance);
+            BatchStatus::from_batch(&batch, &price_config, 38000000, 5.0).unwrap()
+        };
+
+        // String order would put "9..." above "10..."; numeric order must not
+        let mut statuses = [
+            status(1, "9000000"),
+            status(2, "1000000000000"),
+            status(3, "0"),
+            status(4, "10000000"),
+        ];
+        sort_statuses(&mut statuses, &BatchStatusSortBy::Balance, None);
+
+        let ids: Vec<_> = statuses.iter().map(|s| s.batch_id.clone()).collect();
+        assert_eq!(ids, [2, 4, 1, 3].map(|n| BatchId::test_id(n).to_string()));
+    }
+
+    #[test]
+    fn test_sort_order_overrides_field_direction() {
+        let price_config = PriceConfig::new(24000);
+        let status = |id: u64, balance: &str| {
+            let batch = BatchInfo::test_batch(id, 20, balance);
+            BatchStatus::from_batch(&batch, &price_config, 38000000, 5.0).unwrap()
+        };
+        // TTLs of 1,000, 100,000 and 10,000 blocks; "100,000" sorts first as a string
+        let mut statuses = [status(1, "24000000"), status(2, "2400000000"), status(3, "240000000")];
+        let ids = |statuses: &[BatchStatus]| -> Vec<String> { statuses.iter().map(|s| s.batch_id.clone()).collect() };
+
+        // TTL defaults to descending
+        sort_statuses(&mut statuses, &BatchStatusSortBy::Ttl, None);
+        assert_eq!(ids(&statuses), [2, 3, 1].map(|n| BatchId::test_id(n).to_string()));
+        sort_statuses(&mut statuses, &BatchStatusSortBy::Ttl, Some(SortOrder::Desc));
+        assert_eq!(ids(&statuses), [2, 3, 1].map(|n| BatchId::test_id(n).to_string()));
+        sort_statuses(&mut statuses, &BatchStatusSortBy::Ttl, Some(SortOrder::Asc));
+        assert_eq!(ids(&statuses), [1, 3, 2].map(|n| BatchId::test_id(n).to_string()));
+
+        // Batch IDs default to ascending
+        sort_statuses(&mut statuses, &BatchStatusSortBy::BatchId, None);
+        assert_eq!(ids(&statuses), [1, 2, 3].map(|n| BatchId::test_id(n).to_string()));
+        sort_statuses(&mut statuses, &BatchStatusSortBy::BatchId, Some(SortOrder::Desc));
+        assert_eq!(ids(&statuses), [3, 2, 1].map(|n| BatchId::test_id(n).to_string()));
+    }
+
+    #[test]
+    fn test_rollup_by_owner() {
+        let price_config = PriceConfig::new(24000);
+        let status = |id: u64, owner: &str, depth: u8, balance: &str| {
+            let batch = BatchInfo {
+                owner: owner.to_string(),
+                ..BatchInfo::test_batch(id, depth, balance)
+            };
+            BatchStatus::from_batch(&batch, &price_config, 38000000, 5.0).unwrap()
+        };
+
+        let statuses = [
+            status(1, "0xaaaa", 20, "2400000000"),
+            status(2, "0xbbbb", 22, "2400000000"),
+            status(3, "0xAAAA", 20, "240000000"),
+            status(4, "0xaaaa", 17, "24000000000"),
+        ];
+
+        let rollups = rollup_by_owner(&statuses);
+        assert_eq!(rollups.len(), 2);
+
+        // Owners are grouped case-insensitively and sorted by earliest expiry
+        let aaaa = &rollups[0];
+        assert_eq!(aaaa.owner, "0xaaaa");
+        assert_eq!(aaaa.batch_count, 3);
+        assert_eq!(aaaa.total_chunks, (1 << 21) + (1 << 17));
+        assert_eq!(aaaa.total_capacity, format_storage((1 << 21) + (1 << 17)));
+        assert_eq!(aaaa.earliest_expiry_timestamp, statuses[2].expiry_timestamp);
+        // (2.4e9 + 2.4e8) * 2^20 + 2.4e10 * 2^17 PLUR
+        assert_eq!(aaaa.remaining_balance_bzz, "0.591396864");
+
+        let bbbb = &rollups[1];
+        assert_eq!(bbbb.owner, "0xbbbb");
+        assert_eq!(bbbb.batch_count, 1);
+        assert_eq!(bbbb.total_chunks, 1 << 22);
+        assert_eq!(bbbb.earliest_expiry_timestamp, statuses[1].expiry_timestamp);
+
+        assert!(rollup_by_owner(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_status_filter_modes() {
+        let price_config = PriceConfig::new(24000);
+        let status = |id: u64, balance: &str| {
+            let batch = BatchInfo::test_batch(id, 20, balance);
+            BatchStatus::from_batch(&batch, &price_config, 38000000, 5.0).unwrap()
+        };
+
+        // Zero balance, live balance, and a balance too small for another block
+        let statuses = [
+            status(1, "0"),
+            status(2, "240000000"),
+            status(3, "1000"),
+            status(4, "2400000000"),
+        ];
+
+        let filtered = |filter: BatchStatusFilter| -> Vec<String> {
+            statuses
+                .iter()
+                .filter(|s| filter.matches(s))
+                .map(|s| s.batch_id.clone())
+                .collect()
+        };
+        let ids = |ns: &[u64]| -> Vec<String> { ns.iter().map(|&n| BatchId::test_id(n).to_string()).collect() };
+
+        assert_eq!(filtered(BatchStatusFilter::Active), ids(&[2, 4]));
+        assert_eq!(filtered(BatchStatusFilter::Expired), ids(&[1, 3]));
+        assert_eq!(filtered(BatchStatusFilter::All), ids(&[1, 2, 3, 4]));
+    }
+
+    /// Writer collecting formatted tracing output for assertions
+    #[derive(Clone, Default)]
+    struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn captured_progress(progress: BalanceProgress) -> String {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            for processed in 0..250 {
+                progress.update(processed, processed / 2, 0);
+            }
+            progress.finish();
+        });
+
+        String::from_utf8(logs.0.lock().unwrap().clone()).unwrap()
+    }
+
+    #[test]
+    fn test_progress_without_terminal_logs_tracing_events() {
+        let output = captured_progress(BalanceProgress::Log { total: 250 });
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("Resolving batch balances"));
+        assert!(lines[0].contains("processed=100"));
+        assert!(lines[0].contains("total=250"));
+        assert!(lines[0].contains("cache_hit_rate=50.0%"));
+        assert!(lines[1].contains("processed=200"));
+        // No bar rendering or cursor escape codes
+        assert!(!output.contains('\x1b'));
+        assert!(!output.contains('\r'));
+
+        // --no-progress reports nothing at all
+        assert!(captured_progress(BalanceProgress::new(250, true)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_balance_respects_cache_window() {
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "method": "eth_call" })))
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":"0x{:064x}"}}"#, 5_000_000u64))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+        let config = crate::config::AppConfig::default();
+        let registry = crate::contracts::ContractRegistry::from_config(&config).unwrap();
+        let batch_id = format!("0x{}", "12".repeat(32));
+
+        // Fetched 719 blocks ago: inside a 720-block window, reused without an RPC call
+        cache.cache_balance(&batch_id, "1000", 10_000).await.unwrap();
+        let reused = refresh_balance(&cache, &client, &registry, &config.retry, &batch_id, 10_719, 720)
+            .await
+            .unwrap();
+        assert_eq!(reused, RefreshedBalance { balance: "1000".to_string(), fetched: false });
+
+        // Fetched 720 blocks ago: just outside the window, fetched again and re-cached
+        let refetched = refresh_balance(&cache, &client, &registry, &config.retry, &batch_id, 10_720, 720)
+            .await
+            .unwrap();
+        assert_eq!(refetched, RefreshedBalance { balance: "5000000".to_string(), fetched: true });
+        assert_eq!(
+            cache.get_cached_balance(&batch_id, 10_720, 1).await.unwrap().as_deref(),
+            Some("5000000")
+        );
+
+        mock.assert_async().await;
     }
 }
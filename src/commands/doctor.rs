@@ -0,0 +1,359 @@
+use crate::blockchain::BlockchainClient;
+use crate::cache::{Cache, PoolSettings};
+use crate::config::{AppConfig, GNOSIS_CHAIN_ID};
+use crate::contracts::{ContractRegistry, StorageIncentivesContractRegistry};
+use crate::display::{status, Console};
+use crate::error::{Result, StampError};
+use std::path::PathBuf;
+
+/// Block times outside this range (seconds) are almost certainly a typo
+const PLAUSIBLE_BLOCK_TIME: std::ops::RangeInclusive<f64> = 1.0..=30.0;
+
+/// Outcome of a single preflight check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// Suspicious but not fatal; does not change the exit code
+    Warn,
+    /// Critical; `doctor` exits nonzero
+    Fail,
+}
+
+impl CheckStatus {
+    fn icon(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+        }
+    }
+}
+
+/// One line of the doctor checklist
+#[derive(Debug, Clone)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warning or failure
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Pass, detail: detail.into(), hint: None }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Warn, detail: detail.into(), hint: Some(hint.into()) }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Fail, detail: detail.into(), hint: Some(hint.into()) }
+    }
+}
+
+/// Run every preflight check against `config`
+///
+/// Checks never return early on a failure, except that the per-contract
+/// bytecode checks are skipped when the RPC endpoint cannot be reached.
+pub async fn run_checks(
+    config: &AppConfig,
+    rpc_headers: Vec<(String, String)>,
+    pool_settings: PoolSettings,
+) -> Vec<DoctorCheck> {
+    let mut checks = vec![check_config(config), check_block_time(config.blockchain.block_time_seconds)];
+
+    match BlockchainClient::new_with_headers(&config.rpc.url, rpc_headers).await {
+        Ok(client) => {
+            let (rpc_checks, reachable) = check_rpc(&client, config).await;
+            checks.extend(rpc_checks);
+            if reachable {
+                checks.extend(check_contracts(&client, config).await);
+            }
+        }
+        Err(e) => checks.push(DoctorCheck::fail(
+            "RPC endpoint",
+            format!("{}: {e}", config.rpc.url),
+            "Check rpc.url in the config file or pass --rpc-url",
+        )),
+    }
+
+    checks.push(check_cache(config, pool_settings).await);
+    checks
+}
+
+fn check_config(config: &AppConfig) -> DoctorCheck {
+    let result = config
+        .validate()
+        .map_err(StampError::Config)
+        .and_then(|_| ContractRegistry::from_config(config))
+        .and_then(|_| StorageIncentivesContractRegistry::from_config(config));
+
+    match result {
+        Ok(_) => DoctorCheck::pass("Config", format!("{} contracts configured", config.contracts.len())),
+        Err(e) => DoctorCheck::fail("Config", e.to_string(), "Run 'config validate' and fix the reported field"),
+    }
+}
+
+fn check_block_time(block_time_seconds: f64) -> DoctorCheck {
+    let detail = format!("block_time_seconds = {block_time_seconds}");
+    if PLAUSIBLE_BLOCK_TIME.contains(&block_time_seconds) {
+        DoctorCheck::pass("Block time", detail)
+    } else {
+        DoctorCheck::warn(
+            "Block time",
+            detail,
+            "Gnosis Chain produces a block every 5 seconds; TTL and expiry estimates scale with this value",
+        )
+    }
+}
+
+/// Check chain ID and head block; the flag reports whether the endpoint answered
+async fn check_rpc(client: &BlockchainClient, config: &AppConfig) -> (Vec<DoctorCheck>, bool) {
+    let mut checks = Vec::new();
+
+    match (client.get_chain_id().await, config.blockchain.chain_id) {
+        (Ok(actual), Some(expected)) if actual != expected => checks.push(DoctorCheck::fail(
+            "RPC chain ID",
+            StampError::ChainMismatch { expected, actual }.to_string(),
+            "Point --rpc-url at a node for the configured chain, or set blockchain.chain_id for a testnet",
+        )),
+        (Ok(GNOSIS_CHAIN_ID), _) => {
+            checks.push(DoctorCheck::pass("RPC chain ID", format!("{GNOSIS_CHAIN_ID} (Gnosis Chain)")))
+        }
+        (Ok(actual), Some(_)) => checks.push(DoctorCheck::pass("RPC chain ID", actual.to_string())),
+        (Ok(actual), None) => checks.push(DoctorCheck::warn(
+            "RPC chain ID",
+            format!("{actual}, not checked (blockchain.chain_id unset)"),
+            "The Swarm contracts live on Gnosis Chain; set blockchain.chain_id to fail fast on the wrong network",
+        )),
+        (Err(e), _) => {
+            checks.push(DoctorCheck::fail(
+                "RPC endpoint",
+                format!("{}: {e}", config.rpc.url),
+                "Check that the endpoint is up, reachable from here and accepts the --rpc-header values",
+            ));
+            return (checks, false);
+        }
+    }
+
+    let latest_deployment = config.contracts.iter().map(|c| c.deployment_block).max().unwrap_or(0);
+    match client.get_current_block().await {
+        Ok(0) => checks.push(DoctorCheck::fail(
+            "RPC block number",
+            "node reports block 0",
+            "The node has not synced yet; use another endpoint or wait for it to sync",
+        )),
+        Ok(block) if block < latest_deployment => checks.push(DoctorCheck::warn(
+            "RPC block number",
+            format!("{block}, before the latest contract deployment at {latest_deployment}"),
+            "The node is still syncing or serves a different chain",
+        )),
+        Ok(block) => checks.push(DoctorCheck::pass("RPC block number", block.to_string())),
+        Err(e) => {
+            checks.push(DoctorCheck::fail(
+                "RPC block number",
+                e.to_string(),
+                "Check that the endpoint supports eth_blockNumber",
+            ));
+            return (checks, false);
+        }
+    }
+
+    (checks, true)
+}
+
+async fn check_contracts(client: &BlockchainClient, config: &AppConfig) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+    for contract in &config.contracts {
+        let name = format!("Contract {}", contract.name);
+        match client.get_code(&contract.address).await {
+            Ok(code) if code.is_empty() => checks.push(DoctorCheck::fail(
+                name,
+                format!("no bytecode at {}", contract.address),
+                "Check the contract address in the config and that the RPC endpoint serves Gnosis Chain",
+            )),
+            Ok(code) => checks.push(DoctorCheck::pass(
+                name,
+                format!("{} bytes of bytecode at {}", code.len(), contract.address),
+            )),
+            Err(e) => checks.push(DoctorCheck::fail(
+                name,
+                e.to_string(),
+                "Check the contract address in the config",
+            )),
+        }
+    }
+    checks
+}
+
+async fn check_cache(config: &AppConfig, pool_settings: PoolSettings) -> DoctorCheck {
+    let path = PathBuf::from(&config.database.path);
+    let cache = match Cache::with_pool_settings(&path, pool_settings).await {
+        Ok(cache) => cache,
+        Err(e) => {
+            return DoctorCheck::fail(
+                "Cache database",
+                format!("{}: {e}", config.database.path),
+                "Check database.path (or --cache-db) and that its directory is writable",
+            );
+        }
+    };
+
+    match cache.healthcheck().await {
+        Ok(health) if health.migrations_current() => DoctorCheck::pass(
+            "Cache database",
+            format!("writable, {} migrations applied", health.applied_migrations),
+        ),
+        Ok(health) => DoctorCheck::fail(
+            "Cache database",
+            format!(
+                "{} of {} migrations applied",
+                health.applied_migrations, health.expected_migrations
+            ),
+            "A previous migration failed; inspect the _sqlx_migrations table or start from a fresh cache",
+        ),
+        Err(e) => DoctorCheck::fail(
+            "Cache database",
+            e.to_string(),
+            "Check that the database is not read-only and the user may write to it",
+        ),
+    }
+}
+
+/// Execute the doctor command
+///
+/// Prints the checklist and fails if any critical check failed.
+pub async fn execute(
+    config: &AppConfig,
+    rpc_headers: Vec<(String, String)>,
+    pool_settings: PoolSettings,
+    console: Console,
+) -> Result<()> {
+    status!(console, "\n🩺 Running preflight checks\n");
+
+    let checks = run_checks(config, rpc_headers, pool_settings).await;
+    for check in &checks {
+        println!("  {} {}: {}", check.status.icon(), check.name, check.detail);
+        if let Some(hint) = &check.hint {
+            println!("     → {hint}");
+        }
+    }
+
+    let failed = checks.iter().filter(|c| c.status == CheckStatus::Fail).count();
+    let warned = checks.iter().filter(|c| c.status == CheckStatus::Warn).count();
+    if failed > 0 {
+        println!("\n{failed} checks failed, {warned} warnings.");
+        return Err(StampError::Config(format!("{failed} doctor checks failed")));
+    }
+
+    println!("\nAll critical checks passed ({warned} warnings).");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ContractConfig;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    async fn mock_rpc(server: &mut mockito::ServerGuard, chain_id: &str, code: &str) {
+        for (method, result) in [("eth_chainId", chain_id), ("eth_blockNumber", "0x2625a00"), ("eth_getCode", code)] {
+            server
+                .mock("POST", "/")
+                .match_body(Matcher::PartialJson(json!({ "method": method })))
+                .with_header("content-type", "application/json")
+                .with_body(json!({ "jsonrpc": "2.0", "id": 0, "result": result }).to_string())
+                .create_async()
+                .await;
+        }
+    }
+
+    fn test_config(rpc_url: &str, db_path: &std::path::Path) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.rpc.url = rpc_url.to_string();
+        config.database.path = db_path.to_string_lossy().to_string();
+        config.contracts = vec![ContractConfig {
+            name: "PostageStamp".to_string(),
+            contract_type: "PostageStamp".to_string(),
+            address: "0x45a1502382541Cd610CC9068e88727426b696293".to_string(),
+            deployment_block: 31305656,
+            version: None,
+            active: true,
+            end_block: None,
+            paused_at: None,
+            round: None,
+        }];
+        config
+    }
+
+    fn status_of<'a>(checks: &'a [DoctorCheck], name: &str) -> &'a DoctorCheck {
+        checks.iter().find(|c| c.name == name).unwrap_or_else(|| panic!("no {name} check"))
+    }
+
+    #[tokio::test]
+    async fn test_all_checks_pass_against_healthy_node() {
+        let mut server = mockito::Server::new_async().await;
+        mock_rpc(&mut server, "0x64", "0x6080604052").await;
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let config = test_config(&server.url(), db_file.path());
+
+        let checks = run_checks(&config, Vec::new(), PoolSettings::default()).await;
+        for check in &checks {
+            assert_eq!(check.status, CheckStatus::Pass, "{check:?}");
+        }
+        assert_eq!(status_of(&checks, "RPC block number").detail, "40000000");
+        assert_eq!(status_of(&checks, "Contract PostageStamp").detail, format!("5 bytes of bytecode at {}", config.contracts[0].address));
+
+        execute(&config, Vec::new(), PoolSettings::default(), Console::default()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_missing_bytecode_fails() {
+        let mut server = mockito::Server::new_async().await;
+        mock_rpc(&mut server, "0x64", "0x").await;
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let config = test_config(&server.url(), db_file.path());
+
+        let checks = run_checks(&config, Vec::new(), PoolSettings::default()).await;
+        let contract = status_of(&checks, "Contract PostageStamp");
+        assert_eq!(contract.status, CheckStatus::Fail);
+        assert!(contract.hint.is_some());
+        assert_eq!(status_of(&checks, "Cache database").status, CheckStatus::Pass);
+
+        assert!(execute(&config, Vec::new(), PoolSettings::default(), Console::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_rpc_skips_contract_checks() {
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let config = test_config("http://127.0.0.1:1", db_file.path());
+
+        let checks = run_checks(&config, Vec::new(), PoolSettings::default()).await;
+        assert_eq!(status_of(&checks, "RPC endpoint").status, CheckStatus::Fail);
+        assert!(checks.iter().all(|c| !c.name.starts_with("Contract ")));
+        assert_eq!(status_of(&checks, "Config").status, CheckStatus::Pass);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_chain_fails() {
+        let mut server = mockito::Server::new_async().await;
+        mock_rpc(&mut server, "0x1", "0x6080604052").await;
+        let db_file = tempfile::NamedTempFile::new().unwrap();
+        let config = test_config(&server.url(), db_file.path());
+
+        let checks = run_checks(&config, Vec::new(), PoolSettings::default()).await;
+        let chain = status_of(&checks, "RPC chain ID");
+        assert_eq!(chain.status, CheckStatus::Fail);
+        assert!(chain.detail.contains("chain 1, expected chain 100"), "{}", chain.detail);
+    }
+
+    #[test]
+    fn test_block_time_plausibility() {
+        assert_eq!(check_block_time(5.0).status, CheckStatus::Pass);
+        assert_eq!(check_block_time(500.0).status, CheckStatus::Warn);
+        assert_eq!(check_block_time(0.05).status, CheckStatus::Warn);
+    }
+}
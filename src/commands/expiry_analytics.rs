@@ -1,12 +1,22 @@
 use crate::blockchain::BlockchainClient;
 use crate::cache::Cache;
-use crate::cli::{ExpiryAnalyticsSortBy, OutputFormat, TimePeriod};
+use crate::cli::{ExpiryAnalyticsSortBy, OutputFormat, SortOrder, TimePeriod};
+use crate::commands::batch_status::{
+    below_min_balance, refresh_balance, resolve_price_and_block, RefreshedBalance,
+};
+use crate::display::{format_number, status, Console};
 use crate::error::Result;
 use crate::events::BatchInfo;
-use crate::price::{blocks_to_days, calculate_ttl_blocks, PriceChange, PriceConfig};
-use chrono::{DateTime, Datelike, Utc};
+use crate::price::{
+    blocks_to_days, blocks_to_duration, calculate_ttl_blocks, format_storage, PriceChange, PriceConfig,
+    PriceSchedule,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
 use tabled::Tabled;
 
 /// Expiry analytics entry showing aggregated data for a time period
@@ -31,86 +41,74 @@ pub struct ExpiryPeriod {
     pub chunks_raw: u128,
 }
 
+/// Sums over all expiry periods
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExpiryTotals {
+    pub periods: usize,
+    pub batches: usize,
+    pub chunks: u128,
+    pub storage: String,
+}
+
+impl ExpiryTotals {
+    pub fn from_periods(periods: &[ExpiryPeriod]) -> Self {
+        let chunks = periods.iter().map(|p| p.chunks_raw).sum();
+        Self {
+            periods: periods.len(),
+            batches: periods.iter().map(|p| p.batch_count).sum(),
+            chunks,
+            storage: format_storage(chunks),
+        }
+    }
+}
+
 impl ExpiryPeriod {
     /// Format period based on time period type
+    ///
+    /// Periods follow the UTC calendar. Weeks are ISO weeks starting on Monday,
+    /// labelled with the ISO week-numbering year (e.g. 2021-01-03 is `2020-W53`);
+    /// quarters are calendar quarters such as `2024-Q2`.
     fn format_period(timestamp: DateTime<Utc>, period: &TimePeriod) -> (String, DateTime<Utc>) {
+        let date = timestamp.date_naive();
+        let start_of = |day: NaiveDate| day.and_hms_opt(0, 0, 0).unwrap().and_utc();
         match period {
-            TimePeriod::Day => {
-                let formatted = timestamp.format("%Y-%m-%d").to_string();
-                let period_start = timestamp
-                    .date_naive()
-                    .and_hms_opt(0, 0, 0)
-                    .unwrap()
-                    .and_utc();
-                (formatted, period_start)
-            }
+            TimePeriod::Day => (date.format("%Y-%m-%d").to_string(), start_of(date)),
             TimePeriod::Week => {
-                let iso_week = timestamp.iso_week();
+                let iso_week = date.iso_week();
                 let formatted = format!("{}-W{:02}", iso_week.year(), iso_week.week());
-                // Get the Monday of this week
-                let days_from_monday = timestamp.weekday().num_days_from_monday();
-                let period_start = (timestamp - chrono::Duration::days(days_from_monday as i64))
-                    .date_naive()
-                    .and_hms_opt(0, 0, 0)
-                    .unwrap()
-                    .and_utc();
-                (formatted, period_start)
+                // Monday of this ISO week, which may fall in the previous calendar year
+                let monday = date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+                (formatted, start_of(monday))
             }
-            TimePeriod::Month => {
-                let formatted = timestamp.format("%Y-%m").to_string();
-                let period_start = timestamp
-                    .date_naive()
-                    .with_day(1)
-                    .unwrap()
-                    .and_hms_opt(0, 0, 0)
-                    .unwrap()
-                    .and_utc();
-                (formatted, period_start)
+            TimePeriod::Month => (date.format("%Y-%m").to_string(), start_of(date.with_day(1).unwrap())),
+            TimePeriod::Quarter => {
+                let quarter = date.month0() / 3;
+                let first_day = NaiveDate::from_ymd_opt(date.year(), quarter * 3 + 1, 1).unwrap();
+                (format!("{}-Q{}", date.year(), quarter + 1), start_of(first_day))
             }
-        }
-    }
-
-    /// Format large numbers with thousand separators
-    fn format_number(n: u128) -> String {
-        let s = n.to_string();
-        let mut result = String::new();
-        let len = s.len();
-
-        for (i, c) in s.chars().enumerate() {
-            if i > 0 && (len - i) % 3 == 0 {
-                result.push(',');
+            TimePeriod::Year => {
+                let first_day = NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap();
+                (date.year().to_string(), start_of(first_day))
             }
-            result.push(c);
         }
-
-        result
     }
+}
 
-    /// Format storage size in human-readable format
-    fn format_storage(chunks: u128) -> String {
-        // Each chunk is 4KB
-        const CHUNK_SIZE: u128 = 4096;
-        let bytes = chunks * CHUNK_SIZE;
-
-        const KB: u128 = 1024;
-        const MB: u128 = KB * 1024;
-        const GB: u128 = MB * 1024;
-        const TB: u128 = GB * 1024;
-        const PB: u128 = TB * 1024;
-
-        if bytes >= PB {
-            format!("{:.2} PB", bytes as f64 / PB as f64)
-        } else if bytes >= TB {
-            format!("{:.2} TB", bytes as f64 / TB as f64)
-        } else if bytes >= GB {
-            format!("{:.2} GB", bytes as f64 / GB as f64)
-        } else if bytes >= MB {
-            format!("{:.2} MB", bytes as f64 / MB as f64)
-        } else if bytes >= KB {
-            format!("{:.2} KB", bytes as f64 / KB as f64)
-        } else {
-            format!("{bytes} B")
+/// Sort expiry periods by a field
+///
+/// Periods are chronological by default and chunks/storage largest first;
+/// `order` overrides that direction.
+pub fn sort_periods(periods: &mut [ExpiryPeriod], sort_by: &ExpiryAnalyticsSortBy, order: Option<SortOrder>) {
+    let (compare, natural): (fn(&ExpiryPeriod, &ExpiryPeriod) -> Ordering, SortOrder) = match sort_by {
+        ExpiryAnalyticsSortBy::Period => (|a, b| a.period_start.cmp(&b.period_start), SortOrder::Asc),
+        ExpiryAnalyticsSortBy::Chunks | ExpiryAnalyticsSortBy::Storage => {
+            (|a, b| a.chunks_raw.cmp(&b.chunks_raw), SortOrder::Desc)
         }
+    };
+
+    match order.unwrap_or(natural) {
+        SortOrder::Asc => periods.sort_by(compare),
+        SortOrder::Desc => periods.sort_by(|a, b| compare(b, a)),
     }
 }
 
@@ -124,60 +122,53 @@ pub async fn execute(
     period: TimePeriod,
     output: OutputFormat,
     sort_by: ExpiryAnalyticsSortBy,
+    sort_order: Option<SortOrder>,
     price_override: Option<String>,
     price_change_str: Option<String>,
+    price_schedule_str: Option<String>,
     refresh: bool,
+    offline: bool,
+    min_balance: Option<u128>,
     cache_validity_blocks: u64,
+    balance_cache_blocks: u64,
+    output_file: Option<&Path>,
+    totals: bool,
+    console: Console,
 ) -> Result<()> {
-    // Get all batches from cache
-    let batches = cache.get_batches(0).await?;
+    // Get all batches from cache, sized by their current depth
+    let batches = cache.get_batches_with_current_depth(0).await?;
 
     if batches.is_empty() {
-        println!("No batches found in database. Run 'sync' or 'fetch' first.");
+        status!(console, "No batches found in database. Run 'sync' or 'fetch' first.");
         return Ok(());
     }
 
     // Determine price configuration
-    let base_price = if let Some(price_str) = price_override {
-        // User provided explicit price
-        price_str
-            .parse::<u128>()
-            .map_err(|_| crate::error::StampError::Parse("Invalid price value".to_string()))?
-    } else if refresh {
-        // Refresh mode: fetch current price from blockchain and cache it
-        let price = blockchain_client.get_current_price(registry).await?;
-        cache.cache_price(price).await?;
-        price
-    } else {
-        // Use cached price if available, otherwise fetch from blockchain
-        if let Some(cached_price) = cache.get_cached_price().await? {
-            cached_price
-        } else {
-            let price = blockchain_client.get_current_price(registry).await?;
-            cache.cache_price(price).await?;
-            price
-        }
-    };
+    let (base_price, current_block) =
+        resolve_price_and_block(&cache, blockchain_client, registry, price_override, refresh, offline).await?;
 
-    let price_config = if let Some(change_str) = price_change_str {
+    let price_config = if let Some(schedule_str) = price_schedule_str {
+        let price_schedule = schedule_str.parse::<PriceSchedule>()?;
+        PriceConfig::with_price_schedule(base_price, price_schedule)
+    } else if let Some(change_str) = price_change_str {
         let price_change = change_str.parse::<PriceChange>()?;
         PriceConfig::with_price_change(base_price, price_change)
     } else {
         PriceConfig::new(base_price)
     };
 
-    // Get current block
-    let _current_block = blockchain_client.get_current_block().await?;
-
     // Calculate expiry for each batch and group by period
     let mut period_map: HashMap<String, (DateTime<Utc>, Vec<BatchInfo>)> = HashMap::new();
 
     if refresh {
-        println!("📊 Fetching current balances for {} batches from blockchain...", batches.len());
-        println!("Using cache for recent queries. Progress will be shown every 100 batches.\n");
+        status!(console, "📊 Fetching current balances for {} batches from blockchain...", batches.len());
+        status!(console, "Using cache for recent queries. Progress will be shown every 100 batches.\n");
+    } else if offline {
+        status!(console, "📊 Offline: using cached balances for {} batches, falling back to creation-time balances...", batches.len());
+        status!(console, "Progress will be shown every 100 batches.\n");
     } else {
-        println!("📊 Using cached balances for {} batches (pass --refresh to fetch from blockchain)...", batches.len());
-        println!("Progress will be shown every 100 batches.\n");
+        status!(console, "📊 Using cached balances for {} batches (pass --refresh to fetch from blockchain)...", batches.len());
+        status!(console, "Progress will be shown every 100 batches.\n");
     }
 
     let total = batches.len();
@@ -188,7 +179,8 @@ pub async fn execute(
     for (idx, batch) in batches.iter().enumerate() {
         // Show progress every 100 batches
         if idx % 100 == 0 && idx > 0 {
-            println!(
+            status!(
+                console,
                 "  ⏳ Progress: {}/{} batches ({:.1}%) - Cache: {} hits, {} misses, {} expired",
                 idx, total, (idx as f64 / total as f64) * 100.0, cache_hits, cache_misses, skipped
             );
@@ -197,42 +189,44 @@ pub async fn execute(
         // Get balance based on refresh flag
         let remaining_balance = if !refresh {
             // When refresh=false, use cache exclusively or return "0" if not cached
-            if let Ok(Some(cached)) = cache.get_cached_balance(&batch.batch_id, _current_block, cache_validity_blocks).await {
+            if let Ok(Some(cached)) = cache.get_cached_balance(batch.batch_id.as_str(), current_block, cache_validity_blocks).await {
                 cache_hits += 1;
                 tracing::debug!("Cache hit for batch {}", batch.batch_id);
                 cached
+            } else if offline {
+                cache_misses += 1;
+                tracing::debug!("No cached balance for batch {}, using creation balance", batch.batch_id);
+                batch.normalised_balance.clone()
             } else {
                 cache_misses += 1;
                 tracing::debug!("No cached balance for batch {}, using 0", batch.batch_id);
                 "0".to_string() // Don't fetch from blockchain
             }
         } else {
-            // When refresh=true, always fetch from blockchain
-            cache_misses += 1;
-            let balance = blockchain_client
-                .get_remaining_balance(&batch.batch_id, registry, &config.retry)
-                .await
-                .unwrap_or_else(|e| {
-                    // Only log if it's not the common "batch doesn't exist" error
-                    if !e.to_string().contains("0x4ee9bc0f") {
-                        tracing::warn!("Failed to get balance for {}: {}", batch.batch_id, e);
-                    }
+            // When refresh=true, reuse recently fetched balances and fetch the rest
+            match refresh_balance(&cache, blockchain_client, registry, &config.retry, batch.batch_id.as_str(), current_block, balance_cache_blocks).await {
+                Ok(RefreshedBalance { balance, fetched: true }) => {
+                    cache_misses += 1;
+
+                    // Small delay to avoid rate limiting (1ms between requests)
+                    tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+
+                    balance
+                }
+                Ok(RefreshedBalance { balance, fetched: false }) => {
+                    cache_hits += 1;
+                    balance
+                }
+                Err(e) => {
+                    cache_misses += 1;
+                    tracing::warn!("Failed to get balance for {}: {}", batch.batch_id, e);
                     "0".to_string()
-                });
-
-            // Cache the result
-            if let Err(e) = cache.cache_balance(&batch.batch_id, &balance, _current_block).await {
-                tracing::warn!("Failed to cache balance: {}", e);
+                }
             }
-
-            // Small delay to avoid rate limiting (1ms between requests)
-            tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
-
-            balance
         };
 
-        // Skip batches with zero balance (already expired)
-        if remaining_balance == "0" {
+        // Skip batches with zero or dust balance (already or effectively expired)
+        if remaining_balance == "0" || below_min_balance(&remaining_balance, min_balance) {
             skipped += 1;
             continue;
         }
@@ -249,8 +243,12 @@ pub async fn execute(
 
         let ttl_days_value = blocks_to_days(ttl_blocks, config.blockchain.block_time_seconds);
 
-        // If price change is configured, recalculate with effective price
-        let final_ttl_blocks = if let Some(ref price_change) = price_config.price_change {
+        // If a schedule or price change is configured, recalculate against it
+        let final_ttl_blocks = if let Some(ref schedule) = price_config.price_schedule {
+            let balance = current_batch.normalised_balance.parse::<u128>().unwrap_or(0);
+            let balance_per_chunk = balance >> current_batch.depth;
+            schedule.ttl_blocks(balance_per_chunk, price_config.base_price, config.blockchain.block_time_seconds)
+        } else if let Some(ref price_change) = price_config.price_change {
             let effective_price = price_change.average_price(price_config.base_price, ttl_days_value);
             calculate_ttl_blocks(&current_batch.normalised_balance, current_batch.depth, effective_price)?
         } else {
@@ -258,8 +256,8 @@ pub async fn execute(
         };
 
         // Calculate expiry timestamp
-        let seconds_until_expiry = final_ttl_blocks * 5;
-        let expiry_timestamp = Utc::now() + chrono::Duration::seconds(seconds_until_expiry as i64);
+        let expiry_timestamp =
+            Utc::now() + blocks_to_duration(final_ttl_blocks, config.blockchain.block_time_seconds);
 
         // Group by period
         let (period_key, period_start) = ExpiryPeriod::format_period(expiry_timestamp, &period);
@@ -271,7 +269,8 @@ pub async fn execute(
             .push(current_batch);
     }
 
-    println!(
+    status!(
+        console,
         "  ✅ Completed: {}/{} batches - Cache: {} hits ({:.1}%), {} misses, {} expired\n",
         total, total, cache_hits, (cache_hits as f64 / total as f64) * 100.0, cache_misses, skipped
     );
@@ -286,8 +285,8 @@ pub async fn execute(
             ExpiryPeriod {
                 period: period_key,
                 batch_count,
-                total_chunks: ExpiryPeriod::format_number(total_chunks),
-                total_storage: ExpiryPeriod::format_storage(total_chunks),
+                total_chunks: format_number(total_chunks),
+                total_storage: format_storage(total_chunks),
                 period_start,
                 chunks_raw: total_chunks,
             }
@@ -295,42 +294,64 @@ pub async fn execute(
         .collect();
 
     // Sort results
-    match sort_by {
-        ExpiryAnalyticsSortBy::Period => {
-            periods.sort_by(|a, b| a.period_start.cmp(&b.period_start))
-        }
-        ExpiryAnalyticsSortBy::Chunks => {
-            periods.sort_by(|a, b| b.chunks_raw.cmp(&a.chunks_raw))
-        }
-        ExpiryAnalyticsSortBy::Storage => {
-            periods.sort_by(|a, b| b.chunks_raw.cmp(&a.chunks_raw))
-        }
-    }
+    sort_periods(&mut periods, &sort_by, sort_order);
 
     // Output results
+    let mut out = crate::display::output_writer(output_file)?;
+    write_periods(&mut out, &periods, output, totals)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Write expiry periods in the requested output format
+///
+/// With `totals`, the table gets a footer, JSON is wrapped as
+/// `{"periods": [...], "totals": {...}}` and CSV ends with a `TOTAL` row.
+/// Without it, JSON is a bare array of periods.
+pub fn write_periods<W: Write>(out: &mut W, periods: &[ExpiryPeriod], output: OutputFormat, totals: bool) -> Result<()> {
+    let sum = ExpiryTotals::from_periods(periods);
+
     match output {
-        OutputFormat::Table => {
-            use tabled::Table;
-            let table = Table::new(&periods).to_string();
-            println!("\n{table}\n");
-            let total_batches: usize = periods.iter().map(|p| p.batch_count).sum();
-            let total_chunks: u128 = periods.iter().map(|p| p.chunks_raw).sum();
-            println!(
-                "Total periods: {} | Total batches: {} | Total storage: {}",
-                periods.len(),
-                total_batches,
-                ExpiryPeriod::format_storage(total_chunks)
-            );
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let table = output.render_table(tabled::Table::new(periods));
+            if let OutputFormat::Markdown = output {
+                writeln!(out, "{table}\n")?;
+            } else {
+                writeln!(out, "\n{table}\n")?;
+            }
+            if totals {
+                writeln!(
+                    out,
+                    "Total periods: {} | Total batches: {} | Total storage: {}",
+                    sum.periods, sum.batches, sum.storage
+                )?;
+            }
         }
         OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&periods)?;
-            println!("{json}");
+            let json = if totals {
+                serde_json::to_string_pretty(&serde_json::json!({ "periods": periods, "totals": sum }))?
+            } else {
+                serde_json::to_string_pretty(periods)?
+            };
+            writeln!(out, "{json}")?;
         }
         OutputFormat::Csv => {
-            let mut wtr = csv::Writer::from_writer(std::io::stdout());
-            for period in &periods {
+            let mut wtr = csv::Writer::from_writer(&mut *out);
+            for period in periods {
                 wtr.serialize(period)?;
             }
+            if totals {
+                // Same columns as the period rows; a total has no period start
+                wtr.write_record([
+                    "TOTAL".to_string(),
+                    sum.batches.to_string(),
+                    format_number(sum.chunks),
+                    sum.storage.clone(),
+                    String::new(),
+                    sum.chunks.to_string(),
+                ])?;
+            }
             wtr.flush()?;
         }
     }
@@ -352,6 +373,39 @@ mod tests {
         assert_eq!(period_start.minute(), 0);
     }
 
+    #[test]
+    fn test_format_period_week_boundaries() {
+        let week = |y, m, d, h, min, sec| {
+            let timestamp = Utc.with_ymd_and_hms(y, m, d, h, min, sec).unwrap();
+            let (formatted, start) = ExpiryPeriod::format_period(timestamp, &TimePeriod::Week);
+            (formatted, start.format("%Y-%m-%d %H:%M:%S").to_string())
+        };
+
+        // Sunday 23:59:59 -> Monday 00:00:00
+        assert_eq!(week(2025, 3, 16, 23, 59, 59), ("2025-W11".into(), "2025-03-10 00:00:00".into()));
+        assert_eq!(week(2025, 3, 17, 0, 0, 0), ("2025-W12".into(), "2025-03-17 00:00:00".into()));
+
+        // ISO week 53 spans the new year and starts in the previous year
+        assert_eq!(week(2021, 1, 2, 12, 0, 0), ("2020-W53".into(), "2020-12-28 00:00:00".into()));
+        assert_eq!(week(2021, 1, 4, 0, 0, 0), ("2021-W01".into(), "2021-01-04 00:00:00".into()));
+
+        // Week 1 can start in December
+        assert_eq!(week(2024, 12, 31, 23, 59, 59), ("2025-W01".into(), "2024-12-30 00:00:00".into()));
+    }
+
+    #[test]
+    fn test_format_period_day_boundary() {
+        let before = Utc.with_ymd_and_hms(2025, 1, 15, 23, 59, 59).unwrap();
+        let after = Utc.with_ymd_and_hms(2025, 1, 16, 0, 0, 0).unwrap();
+
+        let (before_key, before_start) = ExpiryPeriod::format_period(before, &TimePeriod::Day);
+        let (after_key, after_start) = ExpiryPeriod::format_period(after, &TimePeriod::Day);
+        assert_eq!(before_key, "2025-01-15");
+        assert_eq!(after_key, "2025-01-16");
+        assert_eq!(after_start, after);
+        assert_eq!(after_start - before_start, chrono::Duration::days(1));
+    }
+
     #[test]
     fn test_format_period_month() {
         let timestamp = Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap();
@@ -361,9 +415,114 @@ mod tests {
     }
 
     #[test]
-    fn test_format_storage() {
-        assert_eq!(ExpiryPeriod::format_storage(1), "4.00 KB");
-        assert_eq!(ExpiryPeriod::format_storage(256), "1.00 MB");
-        assert_eq!(ExpiryPeriod::format_storage(262144), "1.00 GB");
+    fn test_format_period_quarter_and_year() {
+        let period = |timestamp, time_period| {
+            let (formatted, start) = ExpiryPeriod::format_period(timestamp, time_period);
+            (formatted, start.format("%Y-%m-%d").to_string())
+        };
+
+        let march_end = Utc.with_ymd_and_hms(2024, 3, 31, 23, 59, 59).unwrap();
+        let april = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        let december = Utc.with_ymd_and_hms(2024, 12, 31, 12, 0, 0).unwrap();
+        assert_eq!(period(march_end, &TimePeriod::Quarter), ("2024-Q1".into(), "2024-01-01".into()));
+        assert_eq!(period(april, &TimePeriod::Quarter), ("2024-Q2".into(), "2024-04-01".into()));
+        assert_eq!(period(december, &TimePeriod::Quarter), ("2024-Q4".into(), "2024-10-01".into()));
+        assert_eq!(period(december, &TimePeriod::Year), ("2024".into(), "2024-01-01".into()));
+    }
+
+    #[test]
+    fn test_sort_periods_by_chunks_both_directions() {
+        let period = |day: u32, chunks: u128| ExpiryPeriod {
+            period: format!("2025-01-{day:02}"),
+            batch_count: 1,
+            total_chunks: format_number(chunks),
+            total_storage: format_storage(chunks),
+            period_start: Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap(),
+            chunks_raw: chunks,
+        };
+        let mut periods = [period(1, 1 << 20), period(2, 1 << 24), period(3, 1 << 17)];
+        let labels = |periods: &[ExpiryPeriod]| -> Vec<String> { periods.iter().map(|p| p.period.clone()).collect() };
+
+        // Chunks default to largest first
+        sort_periods(&mut periods, &ExpiryAnalyticsSortBy::Chunks, None);
+        assert_eq!(labels(&periods), ["2025-01-02", "2025-01-01", "2025-01-03"]);
+        sort_periods(&mut periods, &ExpiryAnalyticsSortBy::Chunks, Some(SortOrder::Asc));
+        assert_eq!(labels(&periods), ["2025-01-03", "2025-01-01", "2025-01-02"]);
+
+        // Periods default to chronological
+        sort_periods(&mut periods, &ExpiryAnalyticsSortBy::Period, None);
+        assert_eq!(labels(&periods), ["2025-01-01", "2025-01-02", "2025-01-03"]);
+        sort_periods(&mut periods, &ExpiryAnalyticsSortBy::Period, Some(SortOrder::Desc));
+        assert_eq!(labels(&periods), ["2025-01-03", "2025-01-02", "2025-01-01"]);
+    }
+
+    #[test]
+    fn test_totals_match_rows_in_json_and_csv() {
+        let period = |day: u32, batches: usize, chunks: u128| ExpiryPeriod {
+            period: format!("2025-01-{day:02}"),
+            batch_count: batches,
+            total_chunks: format_number(chunks),
+            total_storage: format_storage(chunks),
+            period_start: Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap(),
+            chunks_raw: chunks,
+        };
+        let periods = [period(1, 2, 1 << 20), period(2, 3, 1 << 22)];
+        let render = |output: OutputFormat, totals: bool| {
+            let mut buffer = Vec::new();
+            write_periods(&mut buffer, &periods, output, totals).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        let json: serde_json::Value = serde_json::from_str(&render(OutputFormat::Json, true)).unwrap();
+        let rows = json["periods"].as_array().unwrap();
+        let row_batches: u64 = rows.iter().map(|r| r["batch_count"].as_u64().unwrap()).sum();
+        let row_chunks: u64 = rows.iter().map(|r| r["chunks_raw"].as_u64().unwrap()).sum();
+        assert_eq!(json["totals"]["periods"], rows.len());
+        assert_eq!(json["totals"]["batches"], row_batches);
+        assert_eq!(json["totals"]["chunks"], row_chunks);
+        assert_eq!(json["totals"]["storage"], format_storage((1 << 20) + (1 << 22)));
+
+        let csv = render(OutputFormat::Csv, true);
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let records: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        let (total, rows) = records.split_last().unwrap();
+        assert_eq!(&total[0], "TOTAL");
+        let row_batches: usize = rows.iter().map(|r| r[1].parse::<usize>().unwrap()).sum();
+        let row_chunks: u128 = rows.iter().map(|r| r[5].parse::<u128>().unwrap()).sum();
+        assert_eq!(total[1].parse::<usize>().unwrap(), row_batches);
+        assert_eq!(total[5].parse::<u128>().unwrap(), row_chunks);
+
+        // --no-totals keeps the plain rows
+        let json: serde_json::Value = serde_json::from_str(&render(OutputFormat::Json, false)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert!(!render(OutputFormat::Csv, false).contains("TOTAL"));
+    }
+
+    #[test]
+    fn test_markdown_output() {
+        let periods = [ExpiryPeriod {
+            period: "2025-01-01".to_string(),
+            batch_count: 2,
+            total_chunks: format_number(1 << 20),
+            total_storage: format_storage(1 << 20),
+            period_start: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            chunks_raw: 1 << 20,
+        }];
+        let render = |output: OutputFormat| {
+            let mut buffer = Vec::new();
+            write_periods(&mut buffer, &periods, output, true).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        // Starts with a GitHub-flavored header row and separator line
+        let markdown = render(OutputFormat::Markdown);
+        let mut lines = markdown.lines();
+        let header = lines.next().unwrap();
+        assert!(header.starts_with("| Period") && header.ends_with('|'), "{header}");
+        let separator = lines.next().unwrap();
+        assert!(separator.starts_with('|') && separator.ends_with('|'), "{separator}");
+        assert!(separator.chars().all(|c| matches!(c, '|' | '-' | ':')), "{separator}");
+        assert!(lines.next().unwrap().contains("2025-01-01"));
+        assert!(!render(OutputFormat::Table).starts_with('|'));
     }
 }
@@ -1,2 +1,11 @@
+pub mod batch_history;
 pub mod batch_status;
+pub mod doctor;
 pub mod expiry_analytics;
+pub mod network_storage;
+pub mod nodes;
+pub mod price_history;
+pub mod rounds;
+pub mod top_owners;
+pub mod verify;
+pub mod winners;
@@ -0,0 +1,253 @@
+use crate::blockchain::BlockchainClient;
+use crate::cache::Cache;
+use crate::cli::OutputFormat;
+use crate::commands::batch_status::{
+    refresh_balance, resolve_price_and_block, status_from_cache, BatchStatus, BALANCE_VALIDITY_BLOCKS,
+};
+use crate::display::{format_number, status, Console};
+use crate::error::Result;
+use crate::price::{format_storage, NetworkStorage, PriceConfig, CHUNK_SIZE_BYTES};
+use chrono::Utc;
+
+/// Sum the capacity of all active batches
+///
+/// A batch contributes `2^depth` chunks while it still has TTL left (see
+/// [`BatchStatus::is_active`]); expired batches are only counted.
+pub fn network_storage(statuses: &[BatchStatus], block_number: u64) -> NetworkStorage {
+    let (active, expired): (Vec<&BatchStatus>, Vec<&BatchStatus>) =
+        statuses.iter().partition(|status| status.is_active());
+    let total_chunks: u128 = active.iter().map(|status| 1u128 << status.depth).sum();
+
+    NetworkStorage {
+        active_batches: active.len(),
+        expired_batches: expired.len(),
+        total_chunks,
+        total_bytes: total_chunks * CHUNK_SIZE_BYTES,
+        block_number,
+        computed_at: Utc::now(),
+    }
+}
+
+/// Print a network storage snapshot in the requested output format
+fn print_network_storage(storage: &NetworkStorage, output: OutputFormat, console: Console) -> Result<()> {
+    match output {
+        OutputFormat::Table | OutputFormat::Markdown => {
+            status!(console, "\n💾 Network Storage\n");
+            println!("Committed storage: {}", format_storage(storage.total_chunks));
+            println!("Total chunks: {}", format_number(storage.total_chunks));
+            println!("Total bytes: {}", format_number(storage.total_bytes));
+            println!(
+                "Active batches: {} ({} expired)",
+                format_number(storage.active_batches as u128),
+                format_number(storage.expired_batches as u128)
+            );
+            println!(
+                "Computed at block {} ({})",
+                format_number(storage.block_number as u128),
+                storage.computed_at.format("%Y-%m-%d %H:%M UTC")
+            );
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(storage)?;
+            println!("{json}");
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            wtr.serialize(storage)?;
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute the network storage command
+///
+/// With `cached`, prints the last stored snapshot without recomputing it.
+/// Otherwise current balances are fetched from the chain, reusing balances
+/// cached fewer than `balance_cache_blocks` blocks ago, and the new snapshot
+/// is cached. With `offline`, balances come from the balance cache (falling
+/// back to each batch's creation-time balance).
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    cache: Cache,
+    blockchain_client: &BlockchainClient,
+    registry: &crate::contracts::ContractRegistry,
+    config: &crate::config::AppConfig,
+    price_override: Option<String>,
+    offline: bool,
+    balance_cache_blocks: u64,
+    cached: bool,
+    output: OutputFormat,
+    console: Console,
+) -> Result<()> {
+    if cached {
+        return match cache.get_cached_network_storage().await? {
+            Some(storage) => print_network_storage(&storage, output, console),
+            None => {
+                status!(console, "No cached network storage. Run 'network-storage' without --cached first.");
+                Ok(())
+            }
+        };
+    }
+
+    let batches = cache.get_batches_with_current_depth(0).await?;
+    if batches.is_empty() {
+        status!(console, "No batches found in database. Run 'sync' or 'fetch' first.");
+        return Ok(());
+    }
+
+    let (base_price, current_block) =
+        resolve_price_and_block(&cache, blockchain_client, registry, price_override, false, offline).await?;
+    let price_config = PriceConfig::new(base_price);
+
+    let block_time_seconds = config.blockchain.block_time_seconds;
+    let mut statuses = Vec::with_capacity(batches.len());
+    for mut batch in batches {
+        if !offline {
+            match refresh_balance(
+                &cache,
+                blockchain_client,
+                registry,
+                &config.retry,
+                batch.batch_id.as_str(),
+                current_block,
+                balance_cache_blocks,
+            )
+            .await
+            {
+                Ok(refreshed) => {
+                    batch.normalised_balance = refreshed.balance;
+                    statuses.push(BatchStatus::from_batch(&batch, &price_config, current_block, block_time_seconds)?);
+                    continue;
+                }
+                Err(e) => tracing::warn!("Failed to get balance for {}: {}", batch.batch_id, e),
+            }
+        }
+
+        statuses.push(
+            status_from_cache(&cache, batch, &price_config, current_block, BALANCE_VALIDITY_BLOCKS, block_time_seconds)
+                .await?,
+        );
+    }
+
+    let storage = network_storage(&statuses, current_block);
+    cache.cache_network_storage(&storage).await?;
+
+    print_network_storage(&storage, output, console)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::BatchInfo;
+    use crate::types::BatchId;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_network_storage_counts_only_live_batches() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        // Two live batches, one drained and one with less than a block of balance left
+        cache
+            .store_batches(&[
+                BatchInfo::test_batch(1, 20, "240000000"),
+                BatchInfo::test_batch(2, 22, "2400000000"),
+                BatchInfo::test_batch(3, 24, "0"),
+                BatchInfo::test_batch(4, 23, "1000"),
+            ])
+            .await
+            .unwrap();
+
+        // A cached balance shows batch 1 has since been drained
+        cache.cache_balance(BatchId::test_id(1).as_str(), "0", 1000).await.unwrap();
+
+        let price_config = PriceConfig::new(24000);
+        let mut statuses = Vec::new();
+        for batch in cache.get_batches_with_current_depth(0).await.unwrap() {
+            statuses.push(status_from_cache(&cache, batch, &price_config, 1000, BALANCE_VALIDITY_BLOCKS, 5.0).await.unwrap());
+        }
+
+        let storage = network_storage(&statuses, 1000);
+        assert_eq!(storage.active_batches, 1);
+        assert_eq!(storage.expired_batches, 3);
+        assert_eq!(storage.total_chunks, 1 << 22);
+        assert_eq!(storage.total_bytes, (1 << 22) * 4096);
+        assert_eq!(format_storage(storage.total_chunks), "16.00 GB");
+
+        // The snapshot round-trips through the cache
+        assert!(cache.get_cached_network_storage().await.unwrap().is_none());
+        cache.cache_network_storage(&storage).await.unwrap();
+        assert_eq!(cache.get_cached_network_storage().await.unwrap(), Some(storage));
+    }
+
+    #[tokio::test]
+    async fn test_network_storage_excludes_batches_expired_on_chain() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        // Both batches were funded at creation, but the chain reports batch 0xaaaa drained
+        cache
+            .store_batches(&[
+                BatchInfo::test_batch(0xaaaa, 20, "2400000000"),
+                BatchInfo::test_batch(0xbbbb, 22, "2400000000"),
+            ])
+            .await
+            .unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let rpc_result = |result: String| format!(r#"{{"jsonrpc":"2.0","id":0,"result":"{result}"}}"#);
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "method": "eth_blockNumber" })))
+            .with_header("content-type", "application/json")
+            .with_body(rpc_result("0x3e8".to_string()))
+            .create_async()
+            .await;
+        let drained = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(format!("{:064x}", 0xaaaa)))
+            .with_header("content-type", "application/json")
+            .with_body(rpc_result(format!("0x{:064x}", 0)))
+            .expect(1)
+            .create_async()
+            .await;
+        let funded = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(format!("{:064x}", 0xbbbb)))
+            .with_header("content-type", "application/json")
+            .with_body(rpc_result(format!("0x{:064x}", 2_400_000_000u64)))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BlockchainClient::new(&server.url()).await.unwrap();
+        let config = crate::config::AppConfig::default();
+        let registry = crate::contracts::ContractRegistry::from_config(&config).unwrap();
+
+        execute(
+            cache.clone(),
+            &client,
+            &registry,
+            &config,
+            Some("24000".to_string()),
+            false,
+            720,
+            false,
+            OutputFormat::Json,
+            Console::default(),
+        )
+        .await
+        .unwrap();
+
+        let storage = cache.get_cached_network_storage().await.unwrap().unwrap();
+        assert_eq!(storage.block_number, 1000);
+        assert_eq!(storage.active_batches, 1);
+        assert_eq!(storage.expired_batches, 1);
+        assert_eq!(storage.total_chunks, 1 << 22);
+
+        drained.assert_async().await;
+        funded.assert_async().await;
+    }
+}
@@ -0,0 +1,248 @@
+use crate::cache::Cache;
+use crate::cli::OutputFormat;
+use crate::display::{format_number, status, Console};
+use crate::error::{Result, StampError};
+use crate::events::StorageIncentivesEvent;
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::Tabled;
+
+/// Staking state of one node operator, from StakeRegistry events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NodeStats {
+    pub owner: String,
+    /// Overlay from the owner's most recent event
+    pub overlay: String,
+    /// Number of distinct overlays the owner has staked with
+    pub overlays: usize,
+    /// Committed stake from the most recent `StakeUpdated` event, in PLUR
+    pub committed_stake: u128,
+    pub freezes: usize,
+    /// Sum of all `StakeSlashed` amounts, in PLUR
+    pub total_slashed: u128,
+    /// Block of the owner's most recent event
+    pub last_block: u64,
+}
+
+/// Table row for the node overview
+#[derive(Tabled)]
+struct NodeRow {
+    #[tabled(rename = "Owner")]
+    owner: String,
+    #[tabled(rename = "Overlay")]
+    overlay: String,
+    #[tabled(rename = "Committed Stake (PLUR)")]
+    committed_stake: String,
+    #[tabled(rename = "Freezes")]
+    freezes: usize,
+    #[tabled(rename = "Slashed (PLUR)")]
+    total_slashed: String,
+    #[tabled(rename = "Last Block")]
+    last_block: u64,
+}
+
+/// Parse an optional PLUR amount from a cached stake event
+fn amount(event: &StorageIncentivesEvent, value: Option<&String>) -> Result<u128> {
+    value.map_or(Ok(0), |v| {
+        v.parse::<u128>().map_err(|_| {
+            StampError::Parse(format!(
+                "Invalid {} amount '{}' at block {}",
+                event.event_type, v, event.block_number
+            ))
+        })
+    })
+}
+
+/// Aggregate StakeRegistry events per node operator
+///
+/// Nodes are grouped on the owner address rather than the overlay, so an
+/// `OverlayChanged` keeps the node's history together. `events` must be in
+/// chain order. Nodes are sorted by committed stake, then by owner.
+pub fn node_stats(events: &[StorageIncentivesEvent]) -> Result<Vec<NodeStats>> {
+    let mut nodes: HashMap<String, (NodeStats, Vec<String>)> = HashMap::new();
+
+    for event in events {
+        let Some(owner) = event.owner_address.as_ref() else {
+            continue;
+        };
+        let (node, overlays) = nodes.entry(owner.to_lowercase()).or_insert_with(|| {
+            (
+                NodeStats {
+                    owner: owner.to_lowercase(),
+                    overlay: String::new(),
+                    overlays: 0,
+                    committed_stake: 0,
+                    freezes: 0,
+                    total_slashed: 0,
+                    last_block: 0,
+                },
+                Vec::new(),
+            )
+        });
+
+        if let Some(overlay) = &event.overlay {
+            if !overlays.contains(overlay) {
+                overlays.push(overlay.clone());
+            }
+            node.overlay = overlay.clone();
+        }
+        node.last_block = event.block_number;
+
+        match event.event_type.as_str() {
+            "StakeUpdated" => node.committed_stake = amount(event, event.committed_stake.as_ref())?,
+            "StakeFrozen" => node.freezes += 1,
+            "StakeSlashed" => node.total_slashed += amount(event, event.slash_amount.as_ref())?,
+            // A full withdrawal leaves nothing committed
+            "StakeWithdrawn" => node.committed_stake = 0,
+            _ => {}
+        }
+    }
+
+    let mut stats: Vec<NodeStats> = nodes
+        .into_values()
+        .map(|(mut node, overlays)| {
+            node.overlays = overlays.len();
+            node
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.committed_stake
+            .cmp(&a.committed_stake)
+            .then_with(|| a.owner.cmp(&b.owner))
+    });
+    Ok(stats)
+}
+
+/// Execute the nodes command
+pub async fn execute(cache: Cache, output: OutputFormat, console: Console) -> Result<()> {
+    let events = cache.get_stake_events().await?;
+    let nodes = node_stats(&events)?;
+
+    if nodes.is_empty() {
+        status!(console, "No cached StakeRegistry events. Run 'fetch' to collect storage incentives events.");
+        return Ok(());
+    }
+
+    match output {
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows: Vec<NodeRow> = nodes
+                .iter()
+                .map(|node| NodeRow {
+                    owner: node.owner.clone(),
+                    overlay: if node.overlays > 1 {
+                        format!("{} (+{} earlier)", node.overlay, node.overlays - 1)
+                    } else {
+                        node.overlay.clone()
+                    },
+                    committed_stake: format_number(node.committed_stake),
+                    freezes: node.freezes,
+                    total_slashed: format_number(node.total_slashed),
+                    last_block: node.last_block,
+                })
+                .collect();
+
+            let table = output.render_table(tabled::Table::new(rows));
+            println!("\n{table}\n");
+            let total_stake: u128 = nodes.iter().map(|node| node.committed_stake).sum();
+            println!(
+                "{} nodes, {} PLUR committed in total",
+                nodes.len(),
+                format_number(total_stake)
+            );
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&nodes)?;
+            println!("{json}");
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            for node in &nodes {
+                wtr.serialize(node)?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::NamedTempFile;
+
+    fn stake_event(block_number: u64, event_type: &str, owner: &str, overlay: &str) -> StorageIncentivesEvent {
+        StorageIncentivesEvent {
+            block_number,
+            block_timestamp: Utc::now(),
+            transaction_hash: format!("0x{block_number:x}"),
+            contract_source: "StakeRegistry".to_string(),
+            event_type: event_type.to_string(),
+            owner_address: Some(owner.to_string()),
+            overlay: Some(overlay.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn updated(block_number: u64, owner: &str, overlay: &str, stake: &str) -> StorageIncentivesEvent {
+        StorageIncentivesEvent {
+            committed_stake: Some(stake.to_string()),
+            potential_stake: Some(stake.to_string()),
+            height: Some(0),
+            ..stake_event(block_number, "StakeUpdated", owner, overlay)
+        }
+    }
+
+    fn slashed(block_number: u64, owner: &str, overlay: &str, amount: &str) -> StorageIncentivesEvent {
+        StorageIncentivesEvent {
+            slash_amount: Some(amount.to_string()),
+            ..stake_event(block_number, "StakeSlashed", owner, overlay)
+        }
+    }
+
+    fn frozen(block_number: u64, owner: &str, overlay: &str) -> StorageIncentivesEvent {
+        StorageIncentivesEvent {
+            freeze_time: Some("152".to_string()),
+            ..stake_event(block_number, "StakeFrozen", owner, overlay)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_node_stats_from_cached_stake_events() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        cache
+            .store_storage_incentives_events(&[
+                updated(100, "0x01", "0xaa", "1000"),
+                updated(110, "0x02", "0xbb", "5000"),
+                frozen(120, "0x01", "0xaa"),
+                slashed(121, "0x01", "0xaa", "300"),
+                // Owner 0x01 moves to a new overlay and tops up
+                stake_event(130, "OverlayChanged", "0x01", "0xcc"),
+                updated(140, "0x01", "0xcc", "9000"),
+                frozen(150, "0x02", "0xbb"),
+                frozen(160, "0x02", "0xbb"),
+                slashed(161, "0x02", "0xbb", "50"),
+                slashed(162, "0x02", "0xbb", "25"),
+            ])
+            .await
+            .unwrap();
+
+        let nodes = node_stats(&cache.get_stake_events().await.unwrap()).unwrap();
+
+        let summary: Vec<(&str, &str, usize, u128, usize, u128)> = nodes
+            .iter()
+            .map(|n| (n.owner.as_str(), n.overlay.as_str(), n.overlays, n.committed_stake, n.freezes, n.total_slashed))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("0x01", "0xcc", 2, 9000, 1, 300),
+                ("0x02", "0xbb", 1, 5000, 2, 75),
+            ]
+        );
+        assert_eq!(nodes[0].last_block, 140);
+    }
+}
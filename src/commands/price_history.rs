@@ -0,0 +1,177 @@
+use crate::cache::Cache;
+use crate::cli::OutputFormat;
+use crate::display::{self, format_number, status, Console};
+use crate::error::Result;
+use crate::price::PricePoint;
+use tabled::Tabled;
+
+/// Maximum number of points drawn in the price history sparkline
+const SPARKLINE_WIDTH: usize = 72;
+
+/// Table row for the price history
+#[derive(Tabled)]
+struct PriceRow {
+    #[tabled(rename = "Block")]
+    block_number: String,
+    #[tabled(rename = "Timestamp")]
+    timestamp: String,
+    #[tabled(rename = "Price (PLUR/chunk/block)")]
+    price: String,
+    #[tabled(rename = "Change")]
+    change: String,
+}
+
+/// Reduce a block-ordered price series to the points where the price changed
+///
+/// Consecutive `PriceUpdate` events that repeat the same price are dropped,
+/// keeping the first block at which each price took effect.
+pub fn price_changes(mut history: Vec<PricePoint>) -> Vec<PricePoint> {
+    history.dedup_by_key(|point| point.price);
+    history
+}
+
+/// Prices to draw in the sparkline, sampled down to fit a terminal line
+pub fn chart_prices(history: &[PricePoint]) -> Vec<u128> {
+    if history.len() > SPARKLINE_WIDTH {
+        (0..SPARKLINE_WIDTH)
+            .map(|i| history[i * history.len() / SPARKLINE_WIDTH].price)
+            .collect()
+    } else {
+        history.iter().map(|p| p.price).collect()
+    }
+}
+
+/// Print the price series as a sparkline with its range
+fn print_chart(history: &[PricePoint]) {
+    let prices = chart_prices(history);
+    let min = prices.iter().min().copied().unwrap_or_default();
+    let max = prices.iter().max().copied().unwrap_or_default();
+    let latest = history.last().map(|p| p.price).unwrap_or_default();
+
+    println!("{}", display::sparkline(&prices));
+    println!(
+        "\nMin: {} PLUR  Max: {} PLUR  Latest: {} PLUR\n",
+        format_number(min),
+        format_number(max),
+        format_number(latest)
+    );
+}
+
+/// Execute the price history command over price updates within `window`
+///
+/// With `chart`, table and markdown output also draw the series as a sparkline.
+pub async fn execute(
+    cache: Cache,
+    window: (i64, i64),
+    output: OutputFormat,
+    chart: bool,
+    console: Console,
+) -> Result<()> {
+    let history = price_changes(cache.get_price_history(window.0, window.1).await?);
+
+    if history.is_empty() {
+        status!(console, "No cached price history. Run 'fetch' to collect PriceOracle PriceUpdate events.");
+        return Ok(());
+    }
+
+    match output {
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows: Vec<PriceRow> = history
+                .iter()
+                .enumerate()
+                .map(|(i, point)| PriceRow {
+                    block_number: format_number(point.block_number as u128),
+                    timestamp: point.block_timestamp.format("%Y-%m-%d %H:%M UTC").to_string(),
+                    price: format_number(point.price),
+                    change: match i.checked_sub(1).map(|prev| history[prev].price) {
+                        Some(previous) if previous > 0 => format!(
+                            "{:+.2}%",
+                            (point.price as f64 - previous as f64) / previous as f64 * 100.0
+                        ),
+                        _ => "-".to_string(),
+                    },
+                })
+                .collect();
+
+            let table = output.render_table(tabled::Table::new(rows));
+            println!("\n{table}\n");
+            if chart {
+                print_chart(&history);
+            }
+            println!("Total price changes: {}", history.len());
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&history)?;
+            println!("{json}");
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            for point in &history {
+                wtr.serialize(point)?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::StorageIncentivesEvent;
+    use chrono::{Duration, Utc};
+    use tempfile::NamedTempFile;
+
+    fn price_update(block_number: u64, price: &str) -> StorageIncentivesEvent {
+        serde_json::from_value(serde_json::json!({
+            "block_number": block_number,
+            "block_timestamp": Utc::now() - Duration::hours(1),
+            "transaction_hash": format!("0x{block_number:x}"),
+            "log_index": 0,
+            "contract_source": "PriceOracle",
+            "event_type": "PriceUpdate",
+            "price": price,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_price_history_ordered_and_deduped() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        // Stored out of order; the middle update repeats the first price
+        cache
+            .store_storage_incentives_events(&[
+                price_update(3000, "26000"),
+                price_update(1000, "24000"),
+                price_update(2000, "24000"),
+            ])
+            .await
+            .unwrap();
+
+        let history = price_changes(cache.get_price_history(0, i64::MAX).await.unwrap());
+
+        let series: Vec<(u64, u128)> = history.iter().map(|p| (p.block_number, p.price)).collect();
+        assert_eq!(series, [(1000, 24000), (3000, 26000)]);
+    }
+
+    #[test]
+    fn test_chart_has_one_point_per_price_up_to_the_width() {
+        let point = |i: usize| PricePoint {
+            block_number: i as u64,
+            block_timestamp: Utc::now(),
+            price: 24000 + i as u128,
+        };
+
+        let short: Vec<PricePoint> = (0..10).map(point).collect();
+        assert_eq!(display::sparkline(&chart_prices(&short)).chars().count(), 10);
+
+        // Long series are sampled down, keeping the first point
+        let long: Vec<PricePoint> = (0..500).map(point).collect();
+        let prices = chart_prices(&long);
+        assert_eq!(prices.len(), SPARKLINE_WIDTH);
+        assert_eq!(prices[0], 24000);
+    }
+}
@@ -0,0 +1,297 @@
+use crate::cache::Cache;
+use crate::cli::OutputFormat;
+use crate::display::{status, Console};
+use crate::error::Result;
+use crate::events::StorageIncentivesEvent;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tabled::Tabled;
+
+/// Outcome of one redistribution round, rebuilt from its Redistribution events
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoundSummary {
+    pub round: u64,
+    /// Commits in the round; the contract's `CountCommits` value when emitted
+    pub commits: u64,
+    /// Reveals in the round; the contract's `CountReveals` value when emitted
+    pub reveals: u64,
+    /// `reveals / commits`; low values mean nodes committed but failed to reveal.
+    /// `None` when the round has no commits
+    pub reveal_rate: Option<f64>,
+    /// `None` when no winner was selected (skipped or failed claim)
+    pub winner_overlay: Option<String>,
+    pub winner_owner: Option<String>,
+    pub truth_hash: Option<String>,
+}
+
+/// Table row for the round overview
+#[derive(Tabled)]
+struct RoundRow {
+    #[tabled(rename = "Round")]
+    round: u64,
+    #[tabled(rename = "Commits")]
+    commits: u64,
+    #[tabled(rename = "Reveals")]
+    reveals: u64,
+    #[tabled(rename = "Reveal Rate")]
+    reveal_rate: String,
+    #[tabled(rename = "Winner Overlay")]
+    winner_overlay: String,
+    #[tabled(rename = "Winner Owner")]
+    winner_owner: String,
+    #[tabled(rename = "Truth Hash")]
+    truth_hash: String,
+}
+
+/// Group Redistribution events into one summary per round, in round order
+///
+/// Events without a round number are ignored. The `CountCommits`/`CountReveals`
+/// values emitted at claim time are used when present since they also cover
+/// events outside the cached range; otherwise the individual `Committed` and
+/// `Revealed` events are counted.
+pub fn round_summaries(events: &[StorageIncentivesEvent]) -> Vec<RoundSummary> {
+    // round -> (summary, emitted commit count, emitted reveal count)
+    let mut rounds: BTreeMap<u64, (RoundSummary, Option<u64>, Option<u64>)> = BTreeMap::new();
+
+    for event in events {
+        let Some(round) = event.round_number else {
+            continue;
+        };
+        let (summary, commit_count, reveal_count) = rounds.entry(round).or_insert_with(|| {
+            (
+                RoundSummary {
+                    round,
+                    commits: 0,
+                    reveals: 0,
+                    reveal_rate: None,
+                    winner_overlay: None,
+                    winner_owner: None,
+                    truth_hash: None,
+                },
+                None,
+                None,
+            )
+        });
+
+        match event.event_type.as_str() {
+            "Committed" => summary.commits += 1,
+            "Revealed" => summary.reveals += 1,
+            "CountCommits" => *commit_count = event.commit_count,
+            "CountReveals" => *reveal_count = event.reveal_count,
+            "WinnerSelected" => {
+                summary.winner_overlay = event.winner_overlay.clone();
+                summary.winner_owner = event.winner_owner.clone();
+            }
+            "TruthSelected" => summary.truth_hash = event.truth_hash.clone(),
+            _ => {}
+        }
+    }
+
+    rounds
+        .into_values()
+        .map(|(mut summary, commit_count, reveal_count)| {
+            summary.commits = commit_count.unwrap_or(summary.commits);
+            summary.reveals = reveal_count.unwrap_or(summary.reveals);
+            summary.reveal_rate =
+                (summary.commits > 0).then(|| summary.reveals as f64 / summary.commits as f64);
+            summary
+        })
+        .collect()
+}
+
+/// Execute the rounds command
+///
+/// Rounds with fewer than `min_commits` commits are left out, since their
+/// reveal rate says little about node health.
+pub async fn execute(
+    cache: Cache,
+    from_round: Option<u64>,
+    to_round: Option<u64>,
+    min_commits: Option<u64>,
+    output: OutputFormat,
+    console: Console,
+) -> Result<()> {
+    let events = cache
+        .get_round_events(from_round.unwrap_or(0), to_round.unwrap_or(u64::MAX))
+        .await?;
+    let mut rounds = round_summaries(&events);
+    if let Some(min_commits) = min_commits {
+        rounds.retain(|summary| summary.commits >= min_commits);
+    }
+
+    if rounds.is_empty() {
+        status!(console, "No cached redistribution rounds. Run 'fetch' to collect Redistribution events.");
+        return Ok(());
+    }
+
+    match output {
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let dash = || "-".to_string();
+            let rows: Vec<RoundRow> = rounds
+                .iter()
+                .map(|summary| RoundRow {
+                    round: summary.round,
+                    commits: summary.commits,
+                    reveals: summary.reveals,
+                    reveal_rate: summary
+                        .reveal_rate
+                        .map_or_else(dash, |rate| format!("{:.0}%", rate * 100.0)),
+                    winner_overlay: summary.winner_overlay.clone().unwrap_or_else(dash),
+                    winner_owner: summary.winner_owner.clone().unwrap_or_else(dash),
+                    truth_hash: summary.truth_hash.clone().unwrap_or_else(dash),
+                })
+                .collect();
+
+            let table = output.render_table(tabled::Table::new(rows));
+            println!("\n{table}\n");
+            let without_winner = rounds.iter().filter(|r| r.winner_overlay.is_none()).count();
+            println!(
+                "{} rounds, {} without a winner",
+                rounds.len(),
+                without_winner
+            );
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&rounds)?;
+            println!("{json}");
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            for summary in &rounds {
+                wtr.serialize(summary)?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::NamedTempFile;
+
+    fn redistribution_event(block_number: u64, log_index: u64, round: u64, event_type: &str) -> StorageIncentivesEvent {
+        StorageIncentivesEvent {
+            block_number,
+            block_timestamp: Utc::now(),
+            transaction_hash: format!("0x{block_number:x}{log_index:x}"),
+            log_index,
+            contract_source: "Redistribution".to_string(),
+            event_type: event_type.to_string(),
+            round_number: Some(round),
+            ..Default::default()
+        }
+    }
+
+    fn with_overlay(event: StorageIncentivesEvent, overlay: &str) -> StorageIncentivesEvent {
+        StorageIncentivesEvent {
+            overlay: Some(overlay.to_string()),
+            ..event
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_summary_from_cached_events() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        let round = 1000;
+        let block = round * 152;
+        cache
+            .store_storage_incentives_events(&[
+                with_overlay(redistribution_event(block + 1, 0, round, "Committed"), "0xaa"),
+                with_overlay(redistribution_event(block + 2, 0, round, "Committed"), "0xbb"),
+                with_overlay(redistribution_event(block + 3, 0, round, "Committed"), "0xcc"),
+                with_overlay(redistribution_event(block + 40, 0, round, "Revealed"), "0xaa"),
+                with_overlay(redistribution_event(block + 41, 0, round, "Revealed"), "0xbb"),
+                StorageIncentivesEvent {
+                    truth_hash: Some("0x7777".to_string()),
+                    ..redistribution_event(block + 80, 0, round, "TruthSelected")
+                },
+                StorageIncentivesEvent {
+                    winner_overlay: Some("0xbb".to_string()),
+                    winner_owner: Some("0x02".to_string()),
+                    winner_stake: Some("1000".to_string()),
+                    ..redistribution_event(block + 80, 1, round, "WinnerSelected")
+                },
+                StorageIncentivesEvent {
+                    commit_count: Some(3),
+                    ..redistribution_event(block + 80, 2, round, "CountCommits")
+                },
+                StorageIncentivesEvent {
+                    reveal_count: Some(2),
+                    ..redistribution_event(block + 80, 3, round, "CountReveals")
+                },
+                // The next round only got a commit in, so nobody won
+                with_overlay(redistribution_event(block + 153, 0, round + 1, "Committed"), "0xaa"),
+            ])
+            .await
+            .unwrap();
+
+        let rounds = round_summaries(&cache.get_round_events(round, round + 1).await.unwrap());
+
+        assert_eq!(
+            rounds,
+            [
+                RoundSummary {
+                    round,
+                    commits: 3,
+                    reveals: 2,
+                    reveal_rate: Some(2.0 / 3.0),
+                    winner_overlay: Some("0xbb".to_string()),
+                    winner_owner: Some("0x02".to_string()),
+                    truth_hash: Some("0x7777".to_string()),
+                },
+                RoundSummary {
+                    round: round + 1,
+                    commits: 1,
+                    reveals: 0,
+                    reveal_rate: Some(0.0),
+                    winner_overlay: None,
+                    winner_owner: None,
+                    truth_hash: None,
+                },
+            ]
+        );
+
+        // The round range bounds the query
+        let only_first = cache.get_round_events(round, round).await.unwrap();
+        assert_eq!(round_summaries(&only_first).len(), 1);
+    }
+
+    #[test]
+    fn test_reveal_rate_prefers_emitted_counts() {
+        let round = 2000;
+        let block = round * 152;
+        let mut events: Vec<StorageIncentivesEvent> = (0..5)
+            .map(|i| redistribution_event(block + 1 + i, 0, round, "Committed"))
+            .chain((0..2).map(|i| redistribution_event(block + 40 + i, 0, round, "Revealed")))
+            .collect();
+
+        // Counted from the individual events
+        let rounds = round_summaries(&events);
+        assert_eq!((rounds[0].commits, rounds[0].reveals), (5, 2));
+        assert_eq!(rounds[0].reveal_rate, Some(0.4));
+
+        // The claim-time counts win, even if some events were not cached
+        events.truncate(3);
+        events.push(StorageIncentivesEvent {
+            commit_count: Some(5),
+            ..redistribution_event(block + 80, 0, round, "CountCommits")
+        });
+        events.push(StorageIncentivesEvent {
+            reveal_count: Some(2),
+            ..redistribution_event(block + 80, 1, round, "CountReveals")
+        });
+        let rounds = round_summaries(&events);
+        assert_eq!((rounds[0].commits, rounds[0].reveals), (5, 2));
+        assert_eq!(rounds[0].reveal_rate, Some(0.4));
+
+        // A round without commits has no rate
+        let rounds = round_summaries(&[redistribution_event(block + 200, 0, round + 1, "TruthSelected")]);
+        assert_eq!(rounds[0].reveal_rate, None);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::cache::Cache;
+use crate::cli::OutputFormat;
+use crate::display::{format_number, status, Console};
+use crate::error::Result;
+use crate::events::BatchInfo;
+use crate::price::format_storage;
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::Tabled;
+
+/// Storage purchased by one owner address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OwnerStats {
+    pub owner: String,
+    pub batch_count: usize,
+    pub total_chunks: u128,
+    pub total_storage: String,
+}
+
+/// Table row for the owner ranking
+#[derive(Tabled)]
+struct OwnerRow {
+    #[tabled(rename = "Rank")]
+    rank: usize,
+    #[tabled(rename = "Owner")]
+    owner: String,
+    #[tabled(rename = "Batches")]
+    batch_count: usize,
+    #[tabled(rename = "Total Chunks")]
+    total_chunks: String,
+    #[tabled(rename = "Total Storage")]
+    total_storage: String,
+}
+
+/// Rank owners by the number of chunks across all their batches
+///
+/// Each batch contributes `2^depth` chunks. Owners are compared
+/// case-insensitively and ties are broken by address so the order is stable.
+pub fn rank_owners(batches: &[BatchInfo], limit: usize) -> Vec<OwnerStats> {
+    let mut totals: HashMap<String, (usize, u128)> = HashMap::new();
+    for batch in batches {
+        let entry = totals.entry(batch.owner.to_lowercase()).or_default();
+        entry.0 += 1;
+        entry.1 += 1u128 << batch.depth;
+    }
+
+    let mut ranking: Vec<OwnerStats> = totals
+        .into_iter()
+        .map(|(owner, (batch_count, total_chunks))| OwnerStats {
+            owner,
+            batch_count,
+            total_chunks,
+            total_storage: format_storage(total_chunks),
+        })
+        .collect();
+
+    ranking.sort_by(|a, b| b.total_chunks.cmp(&a.total_chunks).then_with(|| a.owner.cmp(&b.owner)));
+    ranking.truncate(limit);
+    ranking
+}
+
+/// Execute the top owners command over batches created within `window`
+pub async fn execute(
+    cache: Cache,
+    limit: usize,
+    window: (i64, i64),
+    output: OutputFormat,
+    console: Console,
+) -> Result<()> {
+    let batches = cache.get_batches_between(window.0, window.1).await?;
+    let ranking = rank_owners(&batches, limit);
+
+    if ranking.is_empty() {
+        status!(console, "No cached batches. Run 'fetch' to collect batch events.");
+        return Ok(());
+    }
+
+    match output {
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows: Vec<OwnerRow> = ranking
+                .iter()
+                .enumerate()
+                .map(|(i, stats)| OwnerRow {
+                    rank: i + 1,
+                    owner: stats.owner.clone(),
+                    batch_count: stats.batch_count,
+                    total_chunks: format_number(stats.total_chunks),
+                    total_storage: stats.total_storage.clone(),
+                })
+                .collect();
+
+            let table = output.render_table(tabled::Table::new(rows));
+            println!("\n{table}\n");
+            println!("Showing top {} owners of {} batches", ranking.len(), batches.len());
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&ranking)?;
+            println!("{json}");
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            for stats in &ranking {
+                wtr.serialize(stats)?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch(id: u64, owner: &str, depth: u8) -> BatchInfo {
+        BatchInfo {
+            owner: owner.to_string(),
+            block_number: 1,
+            ..BatchInfo::test_batch(id, depth, "1000")
+        }
+    }
+
+    #[test]
+    fn test_rank_owners_by_total_chunks() {
+        let batches = [
+            batch(1, "0xaaaa", 20),
+            batch(2, "0xbbbb", 22),
+            batch(3, "0xAAAA", 20),
+            batch(4, "0xcccc", 17),
+            batch(5, "0xaaaa", 17),
+        ];
+
+        let ranking = rank_owners(&batches, 10);
+
+        let summary: Vec<(&str, usize, u128)> = ranking
+            .iter()
+            .map(|s| (s.owner.as_str(), s.batch_count, s.total_chunks))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("0xbbbb", 1, 1 << 22),
+                ("0xaaaa", 3, (1 << 21) + (1 << 17)),
+                ("0xcccc", 1, 1 << 17),
+            ]
+        );
+        assert_eq!(ranking[0].total_storage, "16.00 GB");
+        assert_eq!(ranking[2].total_storage, "512.00 MB");
+
+        // The limit keeps only the largest owners
+        let top = rank_owners(&batches, 2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[1].owner, "0xaaaa");
+    }
+}
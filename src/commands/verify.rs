@@ -0,0 +1,170 @@
+use crate::cache::Cache;
+use crate::contracts::{ContractRegistry, StorageIncentivesContractRegistry};
+use crate::display::{status, Console};
+use crate::error::Result;
+
+/// A block range with no cached chunk for a contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageGap {
+    pub contract: String,
+    pub from_block: u64,
+    pub to_block: u64,
+}
+
+/// Merge overlapping or adjacent inclusive block ranges
+///
+/// Input must be sorted by start block, as returned by `Cache::get_cached_ranges`.
+pub fn merge_ranges(ranges: &[(u64, u64)]) -> Vec<(u64, u64)> {
+    let mut merged: Vec<(u64, u64)> = Vec::new();
+
+    for &(from, to) in ranges {
+        match merged.last_mut() {
+            Some(last) if from <= last.1.saturating_add(1) => last.1 = last.1.max(to),
+            _ => merged.push((from, to)),
+        }
+    }
+
+    merged
+}
+
+/// Find uncovered ranges between `start_block` and the highest cached block
+///
+/// Blocks cached before `start_block` are ignored.
+pub fn find_gaps(ranges: &[(u64, u64)], start_block: u64) -> Vec<(u64, u64)> {
+    let mut gaps = Vec::new();
+    let mut next_expected = start_block;
+
+    for (from, to) in merge_ranges(ranges) {
+        if to < next_expected {
+            continue;
+        }
+        if from > next_expected {
+            gaps.push((next_expected, from - 1));
+        }
+        next_expected = to.saturating_add(1);
+    }
+
+    gaps
+}
+
+/// Find coverage gaps in one contract's cached ranges
+///
+/// `ranges` are the contract's chunks as returned by `Cache::get_cached_ranges`.
+pub fn contract_gaps(name: &str, ranges: &[(u64, u64)], deployment_block: u64) -> Vec<CoverageGap> {
+    find_gaps(ranges, deployment_block)
+        .into_iter()
+        .map(|(from_block, to_block)| CoverageGap {
+            contract: name.to_string(),
+            from_block,
+            to_block,
+        })
+        .collect()
+}
+
+/// Execute the verify command
+pub async fn execute(
+    cache: Cache,
+    registry: &ContractRegistry,
+    si_registry: &StorageIncentivesContractRegistry,
+    console: Console,
+) -> Result<()> {
+    let contracts: Vec<(&str, &str, u64)> = registry
+        .all()
+        .iter()
+        .map(|c| (c.name(), c.address(), c.deployment_block()))
+        .chain(
+            si_registry
+                .all()
+                .iter()
+                .map(|c| (c.name(), c.address(), c.deployment_block())),
+        )
+        .collect();
+
+    status!(console, "\n🔍 Verifying cached block coverage for {} contracts\n", contracts.len());
+
+    let mut all_gaps = Vec::new();
+    for (name, address, deployment_block) in contracts {
+        let ranges = cache.get_cached_ranges(address).await?;
+        let Some(max_block) = ranges.iter().map(|&(_, to)| to).max() else {
+            println!("  ⚠️  {name}: no cached chunks (run 'fetch' or 'sync')");
+            continue;
+        };
+
+        let gaps = contract_gaps(name, &ranges, deployment_block);
+        if gaps.is_empty() {
+            println!("  ✅ {name}: blocks {deployment_block}–{max_block} fully cached");
+        }
+        for gap in &gaps {
+            println!("  ❌ {}: blocks {}–{} missing", gap.contract, gap.from_block, gap.to_block);
+        }
+        all_gaps.extend(gaps);
+    }
+
+    if all_gaps.is_empty() {
+        println!("\nNo gaps found.");
+    } else {
+        let missing: u64 = all_gaps.iter().map(|g| g.to_block - g.from_block + 1).sum();
+        println!(
+            "\nFound {} gaps covering {} blocks. Re-run 'fetch --from-block <X> --to-block <Y>' for each gap.",
+            all_gaps.len(),
+            missing
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_merge_overlapping_and_adjacent_ranges() {
+        let ranges = [(100, 199), (150, 250), (251, 300), (400, 500)];
+        assert_eq!(merge_ranges(&ranges), vec![(100, 300), (400, 500)]);
+    }
+
+    #[test]
+    fn test_find_gaps_from_deployment_block() {
+        let ranges = [(100, 199), (300, 399)];
+
+        // Gap before the first chunk and between chunks
+        assert_eq!(find_gaps(&ranges, 50), vec![(50, 99), (200, 299)]);
+        // Chunks before the start block are ignored
+        assert_eq!(find_gaps(&ranges, 250), vec![(250, 299)]);
+        // Full coverage
+        assert!(find_gaps(&[(100, 199), (200, 299)], 100).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_contract_gaps_reports_missing_chunk() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+        let address = "0x45a1502382541Cd610CC9068e88727426b696293";
+
+        // Chunks 1000-1999 and 3000-3999 cached, 2000-2999 missing
+        cache.cache_chunk("chunk-a", address, 1000, 1999, 3).await.unwrap();
+        cache.cache_chunk("chunk-c", address, 3000, 3999, 1).await.unwrap();
+        // Another contract's chunk does not fill the gap
+        cache
+            .cache_chunk("other", "0x0000000000000000000000000000000000000001", 2000, 2999, 0)
+            .await
+            .unwrap();
+
+        let ranges = cache.get_cached_ranges(address).await.unwrap();
+        let gaps = contract_gaps("PostageStamp", &ranges, 1000);
+        assert_eq!(
+            gaps,
+            vec![CoverageGap {
+                contract: "PostageStamp".to_string(),
+                from_block: 2000,
+                to_block: 2999,
+            }]
+        );
+
+        // Address lookup is case-insensitive
+        let ranges = cache.get_cached_ranges(&address.to_lowercase()).await.unwrap();
+        assert_eq!(contract_gaps("PostageStamp", &ranges, 1000).len(), 1);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::cache::Cache;
+use crate::cli::OutputFormat;
+use crate::display::{format_number, status, Console};
+use crate::error::Result;
+use crate::events::RoundWinner;
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::Tabled;
+
+/// Redistribution wins of one overlay
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WinnerStats {
+    pub overlay: String,
+    pub owner: String,
+    pub wins: usize,
+    /// Share of all rounds won in the period, in percent
+    pub win_share_pct: f64,
+    /// Sum of the winning stake over all wins in PLUR
+    pub total_stake: u128,
+    /// Average winning stake in PLUR
+    pub average_stake: u128,
+}
+
+/// Table row for the winner leaderboard
+#[derive(Tabled)]
+struct WinnerRow {
+    #[tabled(rename = "Rank")]
+    rank: usize,
+    #[tabled(rename = "Overlay")]
+    overlay: String,
+    #[tabled(rename = "Owner")]
+    owner: String,
+    #[tabled(rename = "Wins")]
+    wins: usize,
+    #[tabled(rename = "Share")]
+    share: String,
+    #[tabled(rename = "Total Stake (PLUR)")]
+    total_stake: String,
+    #[tabled(rename = "Avg Stake (PLUR)")]
+    average_stake: String,
+}
+
+/// Build a leaderboard of overlays by number of rounds won
+///
+/// Ties are broken by total stake, then by overlay so the order is stable.
+/// The owner shown is the one from the overlay's most recent win.
+pub fn winner_leaderboard(winners: &[RoundWinner]) -> Vec<WinnerStats> {
+    let mut by_overlay: HashMap<&str, (&str, usize, u128)> = HashMap::new();
+    for winner in winners {
+        let entry = by_overlay.entry(&winner.overlay).or_insert((&winner.owner, 0, 0));
+        entry.0 = &winner.owner;
+        entry.1 += 1;
+        entry.2 += winner.stake;
+    }
+
+    let total_wins = winners.len();
+    let mut leaderboard: Vec<WinnerStats> = by_overlay
+        .into_iter()
+        .map(|(overlay, (owner, wins, total_stake))| WinnerStats {
+            overlay: overlay.to_string(),
+            owner: owner.to_string(),
+            wins,
+            win_share_pct: wins as f64 / total_wins as f64 * 100.0,
+            total_stake,
+            average_stake: total_stake / wins as u128,
+        })
+        .collect();
+
+    leaderboard.sort_by(|a, b| {
+        b.wins
+            .cmp(&a.wins)
+            .then_with(|| b.total_stake.cmp(&a.total_stake))
+            .then_with(|| a.overlay.cmp(&b.overlay))
+    });
+    leaderboard
+}
+
+/// Execute the winners command over rounds won within `window`
+pub async fn execute(cache: Cache, window: (i64, i64), output: OutputFormat, console: Console) -> Result<()> {
+    let winners = cache.get_winners(window.0, window.1).await?;
+    let leaderboard = winner_leaderboard(&winners);
+
+    if leaderboard.is_empty() {
+        status!(console, "No cached redistribution winners. Run 'fetch' to collect Redistribution WinnerSelected events.");
+        return Ok(());
+    }
+
+    match output {
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows: Vec<WinnerRow> = leaderboard
+                .iter()
+                .enumerate()
+                .map(|(i, stats)| WinnerRow {
+                    rank: i + 1,
+                    overlay: stats.overlay.clone(),
+                    owner: stats.owner.clone(),
+                    wins: stats.wins,
+                    share: format!("{:.2}%", stats.win_share_pct),
+                    total_stake: format_number(stats.total_stake),
+                    average_stake: format_number(stats.average_stake),
+                })
+                .collect();
+
+            let table = output.render_table(tabled::Table::new(rows));
+            println!("\n{table}\n");
+            println!(
+                "{} rounds won by {} overlays",
+                winners.len(),
+                leaderboard.len()
+            );
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&leaderboard)?;
+            println!("{json}");
+        }
+        OutputFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+            for stats in &leaderboard {
+                wtr.serialize(stats)?;
+            }
+            wtr.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::StorageIncentivesEvent;
+    use chrono::{Duration, Utc};
+    use tempfile::NamedTempFile;
+
+    fn winner_selected(block_number: u64, overlay: &str, owner: &str, stake: &str) -> StorageIncentivesEvent {
+        serde_json::from_value(serde_json::json!({
+            "block_number": block_number,
+            "block_timestamp": Utc::now() - Duration::hours(1),
+            "transaction_hash": format!("0x{block_number:x}"),
+            "log_index": 0,
+            "contract_source": "Redistribution",
+            "event_type": "WinnerSelected",
+            "round_number": block_number / 152,
+            "winner_overlay": overlay,
+            "winner_owner": owner,
+            "winner_stake": stake,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_winner_leaderboard_from_cached_events() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        cache
+            .store_storage_incentives_events(&[
+                winner_selected(152, "0xaa", "0x01", "1000"),
+                winner_selected(304, "0xbb", "0x02", "5000"),
+                winner_selected(456, "0xaa", "0x01", "3000"),
+                winner_selected(608, "0xcc", "0x03", "2000"),
+                winner_selected(760, "0xaa", "0x01", "2000"),
+                winner_selected(912, "0xbb", "0x02", "7000"),
+            ])
+            .await
+            .unwrap();
+
+        let winners = cache.get_winners(0, i64::MAX).await.unwrap();
+        assert_eq!(winners.len(), 6);
+        assert_eq!(winners[0].round_number, Some(1));
+
+        let leaderboard = winner_leaderboard(&winners);
+
+        let summary: Vec<(&str, usize, u128, u128)> = leaderboard
+            .iter()
+            .map(|s| (s.overlay.as_str(), s.wins, s.total_stake, s.average_stake))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("0xaa", 3, 6000, 2000),
+                ("0xbb", 2, 12000, 6000),
+                ("0xcc", 1, 2000, 2000),
+            ]
+        );
+        assert_eq!(leaderboard[0].owner, "0x01");
+        assert!((leaderboard[0].win_share_pct - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_winner_leaderboard_ties_ranked_by_stake() {
+        let winner = |overlay: &str, stake| RoundWinner {
+            block_number: 1,
+            block_timestamp: Utc::now(),
+            round_number: None,
+            overlay: overlay.to_string(),
+            owner: "0x01".to_string(),
+            stake,
+        };
+
+        let leaderboard = winner_leaderboard(&[winner("0xaa", 100), winner("0xbb", 300)]);
+        assert_eq!(leaderboard[0].overlay, "0xbb");
+        assert_eq!(leaderboard[1].overlay, "0xaa");
+        assert!(winner_leaderboard(&[]).is_empty());
+    }
+}
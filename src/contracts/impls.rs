@@ -10,9 +10,10 @@
 /// - Redistribution: Schelling coordination game
 use super::parser::{
     parse_postage_stamp_event, parse_price_oracle_event, parse_redistribution_event,
-    parse_stake_registry_event, parse_stamps_registry_event,
+    parse_stake_registry_event, parse_stamps_registry_event, to_hex,
 };
 use super::{Contract, StorageIncentivesContract};
+use crate::config::RoundConfig;
 use crate::error::Result;
 use crate::events::{StampEvent, StorageIncentivesEvent};
 use alloy::primitives::TxHash;
@@ -37,6 +38,7 @@ use chrono::{DateTime, Utc};
 pub struct PostageStampContract {
     address: String,
     deployment_block: u64,
+    end_block: Option<u64>,
 }
 
 impl PostageStampContract {
@@ -50,8 +52,15 @@ impl PostageStampContract {
         Self {
             address,
             deployment_block,
+            end_block: None,
         }
     }
+
+    /// Set the block at which this version was retired (exclusive)
+    pub fn with_end_block(mut self, end_block: Option<u64>) -> Self {
+        self.end_block = end_block;
+        self
+    }
 }
 
 impl Contract for PostageStampContract {
@@ -67,6 +76,10 @@ impl Contract for PostageStampContract {
         self.deployment_block
     }
 
+    fn end_block(&self) -> Option<u64> {
+        self.end_block
+    }
+
     fn parse_log(
         &self,
         log: Log,
@@ -77,7 +90,7 @@ impl Contract for PostageStampContract {
     ) -> Result<Option<StampEvent>> {
         // Use dedicated PostageStamp parser
         // Extract contract address from log for event attribution
-        let contract_address = crate::types::ContractAddress::new(format!("{:?}", log.address()))?;
+        let contract_address = crate::types::ContractAddress::new(to_hex(log.address()))?;
 
         parse_postage_stamp_event(
             log,
@@ -118,6 +131,7 @@ impl Contract for PostageStampContract {
 pub struct StampsRegistryContract {
     address: String,
     deployment_block: u64,
+    end_block: Option<u64>,
 }
 
 impl StampsRegistryContract {
@@ -131,8 +145,15 @@ impl StampsRegistryContract {
         Self {
             address,
             deployment_block,
+            end_block: None,
         }
     }
+
+    /// Set the block at which this version was retired (exclusive)
+    pub fn with_end_block(mut self, end_block: Option<u64>) -> Self {
+        self.end_block = end_block;
+        self
+    }
 }
 
 impl Contract for StampsRegistryContract {
@@ -148,6 +169,10 @@ impl Contract for StampsRegistryContract {
         self.deployment_block
     }
 
+    fn end_block(&self) -> Option<u64> {
+        self.end_block
+    }
+
     fn parse_log(
         &self,
         log: Log,
@@ -158,7 +183,7 @@ impl Contract for StampsRegistryContract {
     ) -> Result<Option<StampEvent>> {
         // Use dedicated StampsRegistry parser
         // Extract contract address from log for event attribution
-        let contract_address = crate::types::ContractAddress::new(format!("{:?}", log.address()))?;
+        let contract_address = crate::types::ContractAddress::new(to_hex(log.address()))?;
 
         parse_stamps_registry_event(
             log,
@@ -180,45 +205,6 @@ impl Contract for StampsRegistryContract {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_postage_stamp_contract_metadata() {
-        let contract = PostageStampContract::new(
-            "0x1234567890123456789012345678901234567890".to_string(),
-            1000,
-        );
-
-        assert_eq!(contract.name(), "PostageStamp");
-        assert_eq!(
-            contract.address(),
-            "0x1234567890123456789012345678901234567890"
-        );
-        assert_eq!(contract.deployment_block(), 1000);
-        assert!(contract.supports_price_query());
-        assert!(contract.supports_balance_query());
-    }
-
-    #[test]
-    fn test_stamps_registry_contract_metadata() {
-        let contract = StampsRegistryContract::new(
-            "0x1234567890123456789012345678901234567890".to_string(),
-            2000,
-        );
-
-        assert_eq!(contract.name(), "StampsRegistry");
-        assert_eq!(
-            contract.address(),
-            "0x1234567890123456789012345678901234567890"
-        );
-        assert_eq!(contract.deployment_block(), 2000);
-        assert!(!contract.supports_price_query());
-        assert!(!contract.supports_balance_query());
-    }
-}
-
 // ============================================================================
 // Storage Incentives Contract Implementations
 // ============================================================================
@@ -235,6 +221,7 @@ mod tests {
 pub struct PriceOracleContract {
     address: String,
     deployment_block: u64,
+    round: RoundConfig,
 }
 
 impl PriceOracleContract {
@@ -243,8 +230,15 @@ impl PriceOracleContract {
         Self {
             address,
             deployment_block,
+            round: RoundConfig::default(),
         }
     }
+
+    /// Set the round timing of this version (defaults to 152-block rounds)
+    pub fn with_round(mut self, round: Option<RoundConfig>) -> Self {
+        self.round = round.unwrap_or_default();
+        self
+    }
 }
 
 impl StorageIncentivesContract for PriceOracleContract {
@@ -269,7 +263,7 @@ impl StorageIncentivesContract for PriceOracleContract {
         log_index: u64,
     ) -> Result<Option<StorageIncentivesEvent>> {
         // Extract contract address from log for event attribution
-        let contract_address = crate::types::ContractAddress::new(format!("{:?}", log.address()))?;
+        let contract_address = crate::types::ContractAddress::new(to_hex(log.address()))?;
 
         parse_price_oracle_event(
             log,
@@ -279,6 +273,7 @@ impl StorageIncentivesContract for PriceOracleContract {
             log_index,
             self.name(),
             contract_address,
+            &self.round,
         )
     }
 }
@@ -332,7 +327,7 @@ impl StorageIncentivesContract for StakeRegistryContract {
         log_index: u64,
     ) -> Result<Option<StorageIncentivesEvent>> {
         // Extract contract address from log for event attribution
-        let contract_address = crate::types::ContractAddress::new(format!("{:?}", log.address()))?;
+        let contract_address = crate::types::ContractAddress::new(to_hex(log.address()))?;
 
         parse_stake_registry_event(
             log,
@@ -357,6 +352,9 @@ impl StorageIncentivesContract for StakeRegistryContract {
 /// - Reveal (blocks 38-75): Nodes reveal their data
 /// - Claim (blocks 76-151): Winner selected and rewards distributed
 ///
+/// Offsets shown are for the default 152-block round; versions with
+/// different timing set it with `with_round`.
+///
 /// # Events
 ///
 /// - Committed, Revealed, WinnerSelected, TruthSelected
@@ -366,6 +364,7 @@ impl StorageIncentivesContract for StakeRegistryContract {
 pub struct RedistributionContract {
     address: String,
     deployment_block: u64,
+    round: RoundConfig,
 }
 
 impl RedistributionContract {
@@ -374,8 +373,15 @@ impl RedistributionContract {
         Self {
             address,
             deployment_block,
+            round: RoundConfig::default(),
         }
     }
+
+    /// Set the round timing of this version (defaults to 152-block rounds)
+    pub fn with_round(mut self, round: Option<RoundConfig>) -> Self {
+        self.round = round.unwrap_or_default();
+        self
+    }
 }
 
 impl StorageIncentivesContract for RedistributionContract {
@@ -400,7 +406,7 @@ impl StorageIncentivesContract for RedistributionContract {
         log_index: u64,
     ) -> Result<Option<StorageIncentivesEvent>> {
         // Extract contract address from log for event attribution
-        let contract_address = crate::types::ContractAddress::new(format!("{:?}", log.address()))?;
+        let contract_address = crate::types::ContractAddress::new(to_hex(log.address()))?;
 
         parse_redistribution_event(
             log,
@@ -410,6 +416,46 @@ impl StorageIncentivesContract for RedistributionContract {
             log_index,
             self.name(),
             contract_address,
+            &self.round,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postage_stamp_contract_metadata() {
+        let contract = PostageStampContract::new(
+            "0x1234567890123456789012345678901234567890".to_string(),
+            1000,
+        );
+
+        assert_eq!(contract.name(), "PostageStamp");
+        assert_eq!(
+            contract.address(),
+            "0x1234567890123456789012345678901234567890"
+        );
+        assert_eq!(contract.deployment_block(), 1000);
+        assert!(contract.supports_price_query());
+        assert!(contract.supports_balance_query());
+    }
+
+    #[test]
+    fn test_stamps_registry_contract_metadata() {
+        let contract = StampsRegistryContract::new(
+            "0x1234567890123456789012345678901234567890".to_string(),
+            2000,
+        );
+
+        assert_eq!(contract.name(), "StampsRegistry");
+        assert_eq!(
+            contract.address(),
+            "0x1234567890123456789012345678901234567890"
+        );
+        assert_eq!(contract.deployment_block(), 2000);
+        assert!(!contract.supports_price_query());
+        assert!(!contract.supports_balance_query());
+    }
+}
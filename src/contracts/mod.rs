@@ -66,6 +66,28 @@ pub trait Contract: Send + Sync {
     /// Events before this block are not fetched.
     fn deployment_block(&self) -> u64;
 
+    /// Block at which this contract version was retired, if any
+    ///
+    /// Exclusive, matching `ContractMetadata::active_at_block`: events at or
+    /// after this block belong to a later version and are not fetched.
+    ///
+    /// Default: None (still active)
+    fn end_block(&self) -> Option<u64> {
+        None
+    }
+
+    /// Clamp a requested block range to the blocks this version was live for
+    ///
+    /// Returns `None` if the range does not overlap `[deployment_block, end_block)`.
+    fn fetch_window(&self, from_block: u64, to_block: u64) -> Option<(u64, u64)> {
+        let from = from_block.max(self.deployment_block());
+        let to = match self.end_block() {
+            Some(end) => to_block.min(end.checked_sub(1)?),
+            None => to_block,
+        };
+        (from <= to).then_some((from, to))
+    }
+
     /// Parse a raw log into a StampEvent
     ///
     /// # Arguments
@@ -240,7 +262,7 @@ impl ContractRegistry {
     pub fn find_price_query_contract(&self) -> Option<&dyn Contract> {
         self.contracts
             .iter()
-            .find(|c| c.supports_price_query())
+            .find(|c| c.supports_price_query() && self.is_active(c.as_ref()))
             .map(|b| b.as_ref())
     }
 
@@ -253,10 +275,20 @@ impl ContractRegistry {
     pub fn find_balance_query_contract(&self) -> Option<&dyn Contract> {
         self.contracts
             .iter()
-            .find(|c| c.supports_balance_query())
+            .find(|c| c.supports_balance_query() && self.is_active(c.as_ref()))
             .map(|b| b.as_ref())
     }
 
+    /// Whether a registered contract is the active version of its type
+    ///
+    /// Contracts registered without configuration metadata are treated as active.
+    fn is_active(&self, contract: &dyn Contract) -> bool {
+        crate::types::ContractAddress::new(contract.address())
+            .ok()
+            .and_then(|addr| self.find_by_address(&addr))
+            .is_none_or(|meta| meta.active)
+    }
+
     /// Find contract metadata by address
     ///
     /// # Arguments
@@ -410,27 +442,35 @@ impl ContractRegistry {
             indexes.sort_by_key(|&idx| registry.metadata[idx].deployment_block);
         }
 
+        for indexes in type_map.values() {
+            let versions: Vec<&metadata::ContractMetadata> =
+                indexes.iter().map(|&idx| &registry.metadata[idx]).collect();
+            check_version_succession(&versions)?;
+        }
+
         // Store indexes
         registry.address_map = address_map;
         registry.type_map = type_map;
 
-        // Second pass: Build Contract trait objects for ACTIVE contracts only
-        // (only PostageStamp and StampsRegistry, not storage incentives)
+        // Second pass: Build Contract trait objects for every PostageStamp/StampsRegistry
+        // version (not storage incentives). Historical versions are fetched only
+        // within their [deployment_block, end_block) window.
         for contract_config in &config.contracts {
-            // Only create Contract trait objects for active PostageStamp/StampsRegistry
-            if !contract_config.active {
-                continue;
-            }
-
             let contract: Option<Box<dyn Contract>> = match contract_config.contract_type.as_str() {
-                "PostageStamp" => Some(Box::new(impls::PostageStampContract::new(
-                    contract_config.address.clone(),
-                    contract_config.deployment_block,
-                ))),
-                "StampsRegistry" => Some(Box::new(impls::StampsRegistryContract::new(
-                    contract_config.address.clone(),
-                    contract_config.deployment_block,
-                ))),
+                "PostageStamp" => Some(Box::new(
+                    impls::PostageStampContract::new(
+                        contract_config.address.clone(),
+                        contract_config.deployment_block,
+                    )
+                    .with_end_block(contract_config.end_block),
+                )),
+                "StampsRegistry" => Some(Box::new(
+                    impls::StampsRegistryContract::new(
+                        contract_config.address.clone(),
+                        contract_config.deployment_block,
+                    )
+                    .with_end_block(contract_config.end_block),
+                )),
                 // Skip storage incentives contracts (handled by StorageIncentivesContractRegistry)
                 "PriceOracle" | "StakeRegistry" | "Redistribution" => None,
                 _ => {
@@ -450,6 +490,56 @@ impl ContractRegistry {
     }
 }
 
+/// Check that the versions of one contract type form a clean succession
+///
+/// At most one version may be active, and version lifetimes
+/// (`deployment_block..end_block`, open-ended without an end block) must not
+/// overlap; otherwise `find_active_by_type` picks an arbitrary version and
+/// events are fetched twice. `versions` must be sorted by deployment block.
+fn check_version_succession(versions: &[&metadata::ContractMetadata]) -> Result<()> {
+    let describe = |meta: &metadata::ContractMetadata| {
+        format!("'{}' {} ({})", meta.name, meta.version.as_str(), meta.address.as_str())
+    };
+
+    let active: Vec<String> = versions
+        .iter()
+        .filter(|meta| meta.active)
+        .map(|meta| describe(meta))
+        .collect();
+    if active.len() > 1 {
+        return Err(crate::error::StampError::Config(format!(
+            "Contract type '{}' has {} active versions: {}. Mark all but the current one as active: false",
+            versions[0].contract_type,
+            active.len(),
+            active.join(", ")
+        )));
+    }
+
+    for pair in versions.windows(2) {
+        let (earlier, later) = (pair[0], pair[1]);
+        if earlier
+            .end_block
+            .is_none_or(|end| end > later.deployment_block)
+        {
+            let end = earlier
+                .end_block
+                .map_or_else(|| "no end_block".to_string(), |end| format!("end_block {end}"));
+            return Err(crate::error::StampError::Config(format!(
+                "Contract type '{}': {} (deployment_block {}, {}) overlaps {} deployed at block {}. \
+                 Set end_block to at most the later deployment block",
+                earlier.contract_type,
+                describe(earlier),
+                earlier.deployment_block,
+                end,
+                describe(later),
+                later.deployment_block
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 impl Default for ContractRegistry {
     fn default() -> Self {
         Self::new()
@@ -508,18 +598,24 @@ impl StorageIncentivesContractRegistry {
         for contract_config in &config.contracts {
             let contract: Option<Box<dyn StorageIncentivesContract>> =
                 match contract_config.contract_type.as_str() {
-                    "PriceOracle" => Some(Box::new(impls::PriceOracleContract::new(
-                        contract_config.address.clone(),
-                        contract_config.deployment_block,
-                    ))),
+                    "PriceOracle" => Some(Box::new(
+                        impls::PriceOracleContract::new(
+                            contract_config.address.clone(),
+                            contract_config.deployment_block,
+                        )
+                        .with_round(contract_config.round),
+                    )),
                     "StakeRegistry" => Some(Box::new(impls::StakeRegistryContract::new(
                         contract_config.address.clone(),
                         contract_config.deployment_block,
                     ))),
-                    "Redistribution" => Some(Box::new(impls::RedistributionContract::new(
-                        contract_config.address.clone(),
-                        contract_config.deployment_block,
-                    ))),
+                    "Redistribution" => Some(Box::new(
+                        impls::RedistributionContract::new(
+                            contract_config.address.clone(),
+                            contract_config.deployment_block,
+                        )
+                        .with_round(contract_config.round),
+                    )),
                     // Skip non-storage-incentives contracts
                     "PostageStamp" | "StampsRegistry" => None,
                     _ => {
@@ -589,6 +685,145 @@ mod tests {
         assert_eq!(redistribution_versions.len(), 1);
     }
 
+    /// Default config with an older PostageStamp version retired when the
+    /// current version was deployed at 31,305,656
+    fn config_with_historical_postage_stamp() -> AppConfig {
+        let mut config = AppConfig::default();
+        config.contracts.push(crate::config::ContractConfig {
+            name: "PostageStamp".to_string(),
+            contract_type: "PostageStamp".to_string(),
+            address: "0x30d155478eF27Ab32A1D578BE7b84BC5988aF381".to_string(),
+            deployment_block: 25_527_076,
+            version: Some("v0.8.0".to_string()),
+            active: false,
+            end_block: Some(31_305_656),
+            paused_at: None,
+            round: None,
+        });
+        config
+    }
+
+    #[test]
+    fn test_registry_includes_historical_versions() {
+        let config = config_with_historical_postage_stamp();
+        let registry = ContractRegistry::from_config(&config).unwrap();
+
+        // Both PostageStamp versions are fetched
+        assert_eq!(registry.all().len(), 3);
+        assert_eq!(registry.get_versions("PostageStamp").len(), 2);
+
+        // Queries still go to the active version
+        let active = registry.find_balance_query_contract().unwrap();
+        assert_eq!(active.address(), "0x45a1502382541Cd610CC9068e88727426b696293");
+        let active = registry.find_price_query_contract().unwrap();
+        assert_eq!(active.address(), "0x45a1502382541Cd610CC9068e88727426b696293");
+    }
+
+    #[test]
+    fn test_fetch_windows_for_successive_versions() {
+        let config = config_with_historical_postage_stamp();
+        let registry = ContractRegistry::from_config(&config).unwrap();
+        let windows = |from: u64, to: u64| -> Vec<(String, Option<(u64, u64)>)> {
+            registry
+                .all()
+                .iter()
+                .filter(|c| c.name() == "PostageStamp")
+                .map(|c| (c.address().to_string(), c.fetch_window(from, to)))
+                .collect()
+        };
+
+        let old = "0x30d155478eF27Ab32A1D578BE7b84BC5988aF381".to_string();
+        let current = "0x45a1502382541Cd610CC9068e88727426b696293".to_string();
+
+        // A range spanning both lifetimes is split at each version's boundaries
+        assert_eq!(
+            windows(20_000_000, 40_000_000),
+            vec![
+                (current.clone(), Some((31_305_656, 40_000_000))),
+                (old.clone(), Some((25_527_076, 31_305_655))),
+            ]
+        );
+
+        // After the old version was retired, only the current one is fetched
+        assert_eq!(
+            windows(32_000_000, 40_000_000),
+            vec![(current.clone(), Some((32_000_000, 40_000_000))), (old.clone(), None)]
+        );
+
+        // Before the current version was deployed, only the old one is fetched
+        assert_eq!(
+            windows(26_000_000, 30_000_000),
+            vec![(current, None), (old, Some((26_000_000, 30_000_000)))]
+        );
+    }
+
+    fn historical_redistribution(address: &str, deployment_block: u64, end_block: Option<u64>) -> crate::config::ContractConfig {
+        crate::config::ContractConfig {
+            name: "Redistribution".to_string(),
+            contract_type: "Redistribution".to_string(),
+            address: address.to_string(),
+            deployment_block,
+            version: Some("v0.9.1".to_string()),
+            active: false,
+            end_block,
+            paused_at: None,
+            round: None,
+        }
+    }
+
+    #[test]
+    fn test_registry_accepts_version_succession() {
+        // Each version ends where the next one starts, as in config.yaml
+        let mut config = AppConfig::default();
+        config.contracts.extend([
+            historical_redistribution("0x1F9a1FDe5c6350E949C5E4aa163B4c97011199B4", 37_339_181, Some(40_430_261)),
+            historical_redistribution("0x69C62De6D2dE2FE8B2E6d8e7B0ad4de2fC67e5C3", 40_430_261, Some(41_105_199)),
+        ]);
+
+        let registry = ContractRegistry::from_config(&config).unwrap();
+        assert_eq!(registry.get_versions("Redistribution").len(), 3);
+    }
+
+    #[test]
+    fn test_registry_rejects_overlapping_versions() {
+        // Retired after the current Redistribution (41,105,199) was deployed
+        let mut config = AppConfig::default();
+        config.contracts.push(historical_redistribution(
+            "0x1F9a1FDe5c6350E949C5E4aa163B4c97011199B4",
+            40_430_261,
+            Some(41_200_000),
+        ));
+        let err = ContractRegistry::from_config(&config).unwrap_err();
+        assert!(
+            matches!(&err, crate::error::StampError::Config(msg) if msg.contains("overlaps")),
+            "{err}"
+        );
+
+        // A historical version without an end block never retires
+        let mut config = AppConfig::default();
+        config.contracts.push(historical_redistribution(
+            "0x1F9a1FDe5c6350E949C5E4aa163B4c97011199B4",
+            40_430_261,
+            None,
+        ));
+        assert!(ContractRegistry::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_registry_rejects_two_active_versions() {
+        let mut config = AppConfig::default();
+        config.contracts.push(crate::config::ContractConfig {
+            active: true,
+            ..historical_redistribution("0x1F9a1FDe5c6350E949C5E4aa163B4c97011199B4", 40_430_261, Some(41_105_199))
+        });
+
+        let err = ContractRegistry::from_config(&config).unwrap_err();
+        assert!(
+            matches!(&err, crate::error::StampError::Config(msg) if msg.contains("2 active versions")),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_registry_unknown_contract_type() {
         let mut config = AppConfig::default();
@@ -601,6 +836,7 @@ mod tests {
             active: true,
             end_block: None,
             paused_at: None,
+            round: None,
         });
 
         let result = ContractRegistry::from_config(&config);
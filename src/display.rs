@@ -1,12 +1,61 @@
-use crate::batch::aggregate_events;
+use crate::batch::{aggregate_events, SummaryDiff};
 use crate::cli::GroupBy;
 use crate::error::Result;
 use crate::events::{BatchInfo, EventData, EventType, StampEvent};
+use crate::price::{format_storage, to_bzz};
+use crate::types::checksum_address;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use tabled::{
     Table, Tabled,
     settings::{Alignment, Modify, Style, object::Rows},
 };
 
+/// Writer for rendered command output: the file at `path`, or stdout
+///
+/// Only the rendered table/JSON/CSV goes here; progress messages and logs stay
+/// on the terminal.
+pub fn output_writer(path: Option<&Path>) -> Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(std::io::stdout()),
+    })
+}
+
+/// Console for human status messages, silenced by `--quiet`
+///
+/// Built from the CLI flags and passed to the commands that print progress
+/// notes; the command payload does not go through it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Console {
+    quiet: bool,
+}
+
+impl Console {
+    pub fn new(quiet: bool) -> Self {
+        Self { quiet }
+    }
+
+    /// Whether status messages are hidden
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+}
+
+/// Print a human status message to stdout unless the [`Console`] is quiet
+///
+/// For progress notes and confirmations only; the command payload (tables,
+/// JSON, CSV) keeps using `println!` so it is never suppressed.
+macro_rules! status {
+    ($console:expr, $($arg:tt)*) => {
+        if !$console.is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use status;
+
 /// Display events in a markdown table
 pub fn display_events(events: &[StampEvent]) -> Result<()> {
     if events.is_empty() {
@@ -56,20 +105,24 @@ pub fn display_events(events: &[StampEvent]) -> Result<()> {
 }
 
 /// Display summary statistics
-pub fn display_summary(
+///
+/// The recent batches table lists at most `recent` batches, newest first.
+pub fn display_summary<W: Write>(
+    out: &mut W,
     events: &[StampEvent],
     batches: &[BatchInfo],
     group_by: GroupBy,
+    recent: usize,
 ) -> Result<()> {
     if events.is_empty() {
-        println!("\nNo events found in cache.\n");
+        writeln!(out, "\nNo events found in cache.\n")?;
         return Ok(());
     }
 
-    println!("\n## Postage Stamp Statistics Summary\n");
+    writeln!(out, "\n## Postage Stamp Statistics Summary\n")?;
 
     // Overall statistics
-    println!("### Overall Statistics\n");
+    writeln!(out, "### Overall Statistics\n")?;
     let batch_created = events
         .iter()
         .filter(|e| matches!(e.event_type, EventType::BatchCreated))
@@ -93,35 +146,38 @@ pub fn display_summary(
         .filter(|e| e.contract_source == "StampsRegistry")
         .count();
 
-    println!("- **Total Events:** {}", events.len());
-    println!("  - PostageStamp: {postage_stamp_count}");
-    println!("  - StampsRegistry: {stamps_registry_count}");
-    println!("- **Batch Created:** {batch_created}");
-    println!("- **Batch Top-ups:** {batch_topup}");
-    println!("- **Batch Depth Increases:** {batch_depth_increase}");
-    println!("- **Unique Batches:** {}\n", batches.len());
+    writeln!(out, "- **Total Events:** {}", events.len())?;
+    writeln!(out, "  - PostageStamp: {postage_stamp_count}")?;
+    writeln!(out, "  - StampsRegistry: {stamps_registry_count}")?;
+    writeln!(out, "- **Batch Created:** {batch_created}")?;
+    writeln!(out, "- **Batch Top-ups:** {batch_topup}")?;
+    writeln!(out, "- **Batch Depth Increases:** {batch_depth_increase}")?;
+    writeln!(out, "- **Unique Batches:** {}\n", batches.len())?;
 
     // Time range
     if let (Some(first), Some(last)) = (events.first(), events.last()) {
-        println!("### Time Range\n");
-        println!(
+        writeln!(out, "### Time Range\n")?;
+        writeln!(
+            out,
             "- **From:** {}",
             first.block_timestamp.format("%Y-%m-%d %H:%M")
-        );
-        println!(
+        )?;
+        writeln!(
+            out,
             "- **To:** {}",
             last.block_timestamp.format("%Y-%m-%d %H:%M")
-        );
-        println!(
+        )?;
+        writeln!(
+            out,
             "- **Duration:** {} days\n",
             (last.block_timestamp - first.block_timestamp).num_days()
-        );
+        )?;
     }
 
     // Aggregate by period
     let period_stats = aggregate_events(events, &group_by);
 
-    println!("### Activity by {group_by:?}\n");
+    writeln!(out, "### Activity by {group_by:?}\n")?;
 
     #[derive(Tabled)]
     struct PeriodRow {
@@ -137,6 +193,16 @@ pub fn display_summary(
         total: usize,
         #[tabled(rename = "Unique Batches")]
         unique: usize,
+        #[tabled(rename = "Chunks Created")]
+        chunks_created: u128,
+        #[tabled(rename = "Chunks from Depth Inc.")]
+        chunks_from_depth: u128,
+        #[tabled(rename = "Net Storage Added")]
+        net_storage: String,
+        #[tabled(rename = "PostageStamp")]
+        postage_stamp: usize,
+        #[tabled(rename = "StampsRegistry")]
+        stamps_registry: usize,
     }
 
     let rows: Vec<PeriodRow> = period_stats
@@ -148,6 +214,11 @@ pub fn display_summary(
             depth_inc: stats.batch_depth_increase_count,
             total: stats.total_events,
             unique: stats.unique_batches,
+            chunks_created: stats.chunks_created,
+            chunks_from_depth: stats.chunks_from_depth_increase,
+            net_storage: format_storage(stats.net_chunks_added()),
+            postage_stamp: stats.postage_stamp_events,
+            stamps_registry: stats.stamps_registry_events,
         })
         .collect();
 
@@ -156,20 +227,21 @@ pub fn display_summary(
         .with(Style::markdown())
         .with(Modify::new(Rows::new(1..)).with(Alignment::right()));
 
-    println!("{table}\n");
+    writeln!(out, "{table}\n")?;
 
     // Most active period
     if let Some(most_active) = period_stats.iter().max_by_key(|s| s.total_events) {
-        println!("### Most Active Period\n");
-        println!(
+        writeln!(out, "### Most Active Period\n")?;
+        writeln!(
+            out,
             "**{}** with {} events\n",
             most_active.period_label, most_active.total_events
-        );
+        )?;
     }
 
     // Batch details
-    if !batches.is_empty() {
-        println!("### Recent Batches\n");
+    if !batches.is_empty() && recent > 0 {
+        writeln!(out, "### Recent Batches\n")?;
 
         #[derive(Tabled)]
         struct BatchRow {
@@ -187,13 +259,11 @@ pub fn display_summary(
             created: String,
         }
 
-        let recent_batches: Vec<BatchRow> = batches
-            .iter()
-            .rev()
-            .take(10)
+        let recent_batches: Vec<BatchRow> = recent_batches(batches, recent)
+            .into_iter()
             .map(|batch| BatchRow {
-                batch_id: truncate_hash(&batch.batch_id),
-                owner: truncate_hash(&batch.owner),
+                batch_id: truncate_hash(batch.batch_id.as_str()),
+                owner: truncate_address(&batch.owner),
                 depth: batch.depth,
                 bucket_depth: batch.bucket_depth,
                 immutable: if batch.immutable { "Yes" } else { "No" }.to_string(),
@@ -206,12 +276,73 @@ pub fn display_summary(
             .with(Style::markdown())
             .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
 
-        println!("{table}\n");
+        writeln!(out, "{table}\n")?;
     }
 
     Ok(())
 }
 
+/// Display the change in summary statistics since a snapshot
+pub fn display_summary_diff<W: Write>(out: &mut W, diff: &SummaryDiff) -> Result<()> {
+    writeln!(out, "## Changes Since Snapshot\n")?;
+    writeln!(out, "- **Total Events:** {:+}", diff.total_events)?;
+    writeln!(out, "- **Unique Batches:** {:+}\n", diff.total_batches)?;
+
+    #[derive(Tabled)]
+    struct DeltaRow {
+        #[tabled(rename = "Period")]
+        period: String,
+        #[tabled(rename = "Created")]
+        created: String,
+        #[tabled(rename = "Top-ups")]
+        topups: String,
+        #[tabled(rename = "Depth Inc.")]
+        depth_inc: String,
+        #[tabled(rename = "Total Events")]
+        total: String,
+        #[tabled(rename = "Unique Batches")]
+        unique: String,
+        #[tabled(rename = "Net Chunks")]
+        net_chunks: String,
+    }
+
+    let rows: Vec<DeltaRow> = diff
+        .periods
+        .iter()
+        .filter(|delta| delta.is_changed())
+        .map(|delta| DeltaRow {
+            period: delta.period_label.clone(),
+            created: format!("{:+}", delta.batch_created_count),
+            topups: format!("{:+}", delta.batch_topup_count),
+            depth_inc: format!("{:+}", delta.batch_depth_increase_count),
+            total: format!("{:+}", delta.total_events),
+            unique: format!("{:+}", delta.unique_batches),
+            net_chunks: format!("{:+}", delta.net_chunks_added()),
+        })
+        .collect();
+
+    if rows.is_empty() {
+        writeln!(out, "No period changed.\n")?;
+        return Ok(());
+    }
+
+    let mut table = Table::new(rows);
+    table
+        .with(Style::markdown())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::right()));
+
+    writeln!(out, "{table}\n")?;
+
+    Ok(())
+}
+
+/// Select the `limit` most recently created batches, newest first
+///
+/// Batches are expected in ascending creation order, as returned by the cache.
+fn recent_batches(batches: &[BatchInfo], limit: usize) -> Vec<&BatchInfo> {
+    batches.iter().rev().take(limit).collect()
+}
+
 /// Format event details for display
 fn format_event_details(data: &EventData) -> String {
     match data {
@@ -224,23 +355,23 @@ fn format_event_details(data: &EventData) -> String {
         } => {
             format!(
                 "Owner: {}, Depth: {}, Bucket: {}, Immutable: {}",
-                truncate_hash(owner),
+                truncate_address(owner),
                 depth,
                 bucket_depth,
                 if *immutable_flag { "Yes" } else { "No" }
             )
         }
         EventData::BatchTopUp { topup_amount, .. } => {
-            format!("Top-up: {} BZZ", format_amount(topup_amount))
+            format!("Top-up: {} BZZ", bzz_or_raw(topup_amount))
         }
         EventData::BatchDepthIncrease { new_depth, .. } => {
             format!("New Depth: {new_depth}")
         }
         EventData::PotWithdrawn { recipient, total_amount } => {
-            format!("Recipient: {}, Amount: {} BZZ", truncate_hash(recipient), format_amount(total_amount))
+            format!("Recipient: {}, Amount: {} BZZ", truncate_address(recipient), bzz_or_raw(total_amount))
         }
         EventData::PriceUpdate { price } => {
-            format!("Price: {} PLUR", format_amount(price))
+            format!("Price: {price} PLUR")
         }
         EventData::CopyBatchFailed { index, batch_id } => {
             format!("Index: {}, Batch: {}", index, truncate_hash(batch_id))
@@ -257,6 +388,11 @@ fn truncate_hash(hash: &str) -> String {
     }
 }
 
+/// Truncate an address after applying its EIP-55 checksum casing
+fn truncate_address(address: &str) -> String {
+    truncate_hash(&checksum_address(address))
+}
+
 /// Truncate contract name for display
 fn truncate_contract_name(contract: &str) -> String {
     match contract {
@@ -266,20 +402,64 @@ fn truncate_contract_name(contract: &str) -> String {
     }
 }
 
-/// Format amount from wei to a more readable format
-fn format_amount(amount: &str) -> String {
-    if let Ok(value) = amount.parse::<u128>() {
-        let eth_value = value as f64 / 1e16;
-        format!("{eth_value:.4}")
-    } else {
-        amount.to_string()
+/// A PLUR amount in BZZ, or as given if it is not a plain integer
+fn bzz_or_raw(amount: &str) -> String {
+    to_bzz(amount).unwrap_or_else(|_| amount.to_string())
+}
+
+/// Render a series of values as a Unicode sparkline, one character per value
+///
+/// Values are scaled between the series minimum and maximum. A flat series
+/// renders as a flat line at the lowest level.
+pub fn sparkline(values: &[u128]) -> String {
+    const TICKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|&value| {
+            if range == 0 {
+                TICKS[0]
+            } else {
+                let level = (value - min) as f64 / range as f64 * (TICKS.len() - 1) as f64;
+                TICKS[level.round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Format large numbers with thousand separators
+pub fn format_number(n: u128) -> String {
+    let s = n.to_string();
+    let mut result = String::new();
+    let len = s.len();
+
+    for (i, c) in s.chars().enumerate() {
+        if i > 0 && (len - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(c);
     }
+
+    result
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::types::BatchId;
     use super::*;
 
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(1000), "1,000");
+        assert_eq!(format_number(1000000), "1,000,000");
+        assert_eq!(format_number(1048576), "1,048,576");
+    }
+
     #[test]
     fn test_truncate_hash() {
         let hash = "0x1234567890abcdef1234567890abcdef";
@@ -295,10 +475,27 @@ mod tests {
     }
 
     #[test]
-    fn test_format_amount() {
-        let amount = "1000000000000000000"; // 1e18 = 100 PLUR
-        let formatted = format_amount(amount);
-        assert_eq!(formatted, "100.0000");
+    fn test_bzz_or_raw() {
+        assert_eq!(bzz_or_raw("1000000000000000000"), "100"); // 1e18 PLUR = 100 BZZ
+        assert_eq!(bzz_or_raw("0x10"), "0x10");
+    }
+
+    #[test]
+    fn test_addresses_displayed_checksummed() {
+        let data = EventData::BatchCreated {
+            total_amount: "1".to_string(),
+            normalised_balance: "1".to_string(),
+            owner: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+            depth: 20,
+            bucket_depth: 16,
+            immutable_flag: false,
+            payer: None,
+        };
+        assert!(format_event_details(&data).contains("Owner: 0x5aAe...eAed"));
+
+        // Batch IDs are not addresses and keep their casing
+        let batch_id = "0xc4d6a0f53b7fa2bb0f1a3e52d15e6bcb1d5b53a3c6e3e8b83b5e2f4fe3b6b2a1";
+        assert_eq!(truncate_address(batch_id), truncate_hash(batch_id));
     }
 
     #[test]
@@ -318,4 +515,84 @@ mod tests {
         assert!(formatted.contains("Bucket: 16"));
         assert!(formatted.contains("Immutable: No"));
     }
+
+    #[test]
+    fn test_sparkline_length_matches_points() {
+        let values = [24000, 24500, 25000, 24800, 26000, 30000, 28000];
+        let line = sparkline(&values);
+        assert_eq!(line.chars().count(), values.len());
+        assert!(line.starts_with('▁'));
+        assert_eq!(line.chars().nth(5), Some('█'));
+    }
+
+    #[test]
+    fn test_sparkline_flat_and_empty() {
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(sparkline(&[5, 5, 5]), "▁▁▁");
+    }
+
+    #[test]
+    fn test_recent_batches_limit() {
+        use chrono::{Duration, Utc};
+
+        let start = Utc::now();
+        let batches: Vec<BatchInfo> = (0..15)
+            .map(|i| BatchInfo {
+                created_at: start + Duration::hours(i),
+                block_number: 1000 + i as u64,
+                ..BatchInfo::test_batch(i as u64, 20, "500000000000000000")
+            })
+            .collect();
+
+        let recent = recent_batches(&batches, 3);
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[0].batch_id, BatchId::test_id(0xe));
+        assert_eq!(recent[2].batch_id, BatchId::test_id(0xc));
+
+        assert_eq!(recent_batches(&batches, 50).len(), 15);
+        assert!(recent_batches(&batches, 0).is_empty());
+    }
+
+    #[test]
+    fn test_display_summary_writes_to_buffer() {
+        use chrono::Utc;
+
+        let batch_id = BatchId::test_id(1);
+        let event = StampEvent {
+            event_type: EventType::BatchCreated,
+            batch_id: Some(batch_id.to_string()),
+            block_number: 1000,
+            block_timestamp: Utc::now(),
+            transaction_hash: "0xfa".to_string(),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchCreated {
+                total_amount: "1000".to_string(),
+                normalised_balance: "500".to_string(),
+                owner: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+                depth: 20,
+                bucket_depth: 16,
+                immutable_flag: false,
+                payer: None,
+            },
+        };
+        let batch = BatchInfo {
+            batch_id,
+            owner: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+            created_at: event.block_timestamp,
+            ..BatchInfo::test_batch(0, 20, "500")
+        };
+
+        let mut buffer = Vec::new();
+        display_summary(&mut buffer, &[event], &[batch], GroupBy::Day, 10).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        assert!(output.contains("## Postage Stamp Statistics Summary"));
+        assert!(output.contains("- **Batch Created:** 1"));
+        assert!(output.contains("| Period"));
+        assert!(output.contains("### Recent Batches"));
+        assert!(output.contains("0x5aAe...eAed"));
+    }
 }
@@ -5,6 +5,20 @@ pub enum StampError {
     #[error("RPC error: {0}")]
     Rpc(String),
 
+    /// The RPC provider is throttling (HTTP 429) or temporarily unavailable (HTTP 502)
+    #[error("RPC error: {0}")]
+    RateLimited(String),
+
+    /// `remainingBalance` reverted with `BatchDoesNotExist` (selector `0x4ee9bc0f`)
+    ///
+    /// `message` is the provider's revert message.
+    #[error("RPC error: {message}")]
+    BatchNotFound { batch_id: String, message: String },
+
+    /// The RPC endpoint could not be reached or did not answer
+    #[error("RPC error: {0}")]
+    RpcTransport(String),
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -28,10 +42,101 @@ pub enum StampError {
 
     #[error("CSV error: {0}")]
     Csv(#[from] csv::Error),
+
+    /// The RPC endpoint serves a different chain than the configured one
+    #[error("RPC endpoint is on chain {actual}, expected chain {expected}; check --rpc-url or blockchain.chain_id")]
+    ChainMismatch { expected: u64, actual: u64 },
+
+    /// A query spans more blocks or rows than `limit` allows
+    #[error("{}: {message}", .limit.prefix())]
+    QueryTooLarge { limit: QueryLimit, message: String },
+
+    /// The fiat price feed could not be reached or returned no usable rate
+    #[error("Price feed error: {0}")]
+    PriceFeed(String),
+
+    /// A calculation on on-chain amounts does not fit its integer type
+    #[error("Arithmetic overflow: {0}")]
+    Overflow(String),
+
+    /// One or more event hooks failed to handle an event
+    #[error("Event hook error: {0}")]
+    Hook(String),
+}
+
+impl StampError {
+    /// Whether retrying the same request later may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, StampError::RateLimited(_))
+    }
+}
+
+/// Which limit rejected a [`StampError::QueryTooLarge`] query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryLimit {
+    /// The RPC provider refused an oversized log query
+    Provider,
+    /// The cache's `max_query_rows` guard
+    MaxQueryRows,
+}
+
+impl QueryLimit {
+    /// Display prefix, kept from before provider errors were classified
+    fn prefix(self) -> &'static str {
+        match self {
+            QueryLimit::Provider => "RPC error",
+            QueryLimit::MaxQueryRows => "Query too large",
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, StampError>;
 
+/// Whether `message` mentions an HTTP status code as a standalone number
+pub(crate) fn mentions_status(message: &str, code: &str) -> bool {
+    message.match_indices(code).any(|(start, _)| {
+        let before = message[..start].chars().next_back();
+        let after = message[start + code.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_alphanumeric()) && !after.is_some_and(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+/// Map a provider error message to a structured error
+///
+/// Provider errors only arrive as text, so they are classified here, once,
+/// instead of by substring checks wherever they are handled. Messages that
+/// match no known pattern stay `StampError::Rpc`.
+pub fn classify_rpc_error(message: impl Into<String>) -> StampError {
+    let message = message.into();
+    let lower = message.to_lowercase();
+
+    if mentions_status(&message, "429")
+        || mentions_status(&message, "502")
+        || lower.contains("too many requests")
+        || lower.contains("rate limit")
+        || lower.contains("bad gateway")
+    {
+        StampError::RateLimited(message)
+    } else if lower.contains("query returned more than")
+        || lower.contains("response size exceeded")
+        || (lower.contains("block range") && (lower.contains("too large") || lower.contains("exceed")))
+    {
+        StampError::QueryTooLarge {
+            limit: QueryLimit::Provider,
+            message,
+        }
+    } else if lower.contains("error sending request")
+        || lower.contains("connection refused")
+        || lower.contains("connection reset")
+        || lower.contains("timed out")
+        || lower.contains("dns error")
+    {
+        StampError::RpcTransport(message)
+    } else {
+        StampError::Rpc(message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +151,25 @@ mod tests {
 
         let err = StampError::Parse("invalid number".to_string());
         assert_eq!(err.to_string(), "Parse error: invalid number");
+
+        // Structured variants render as they did before they were split out
+        let err = StampError::QueryTooLarge {
+            limit: QueryLimit::Provider,
+            message: "Operation failed: block range is too large".to_string(),
+        };
+        assert_eq!(err.to_string(), "RPC error: Operation failed: block range is too large");
+
+        let err = StampError::QueryTooLarge {
+            limit: QueryLimit::MaxQueryRows,
+            message: "query would load 5 events rows".to_string(),
+        };
+        assert_eq!(err.to_string(), "Query too large: query would load 5 events rows");
+
+        let err = StampError::BatchNotFound {
+            batch_id: "0x12".to_string(),
+            message: "Operation failed: execution reverted, data: \"0x4ee9bc0f\"".to_string(),
+        };
+        assert_eq!(err.to_string(), "RPC error: Operation failed: execution reverted, data: \"0x4ee9bc0f\"");
     }
 
     #[test]
@@ -60,4 +184,51 @@ mod tests {
         let stamp_err: StampError = json_err.into();
         assert!(matches!(stamp_err, StampError::Serialization(_)));
     }
+
+    #[test]
+    fn test_classify_rpc_error() {
+        let classify = |message: &str| classify_rpc_error(message);
+
+        assert!(matches!(
+            classify("Operation failed: HTTP error 429 with body: {\"error\":\"Too Many Requests\"}"),
+            StampError::RateLimited(_)
+        ));
+        assert!(matches!(
+            classify("Operation failed: HTTP error 502 with body: <html>Bad Gateway</html>"),
+            StampError::RateLimited(_)
+        ));
+        assert!(matches!(
+            classify("Failed to get latest block: server returned an error response: error code -32005: daily request count exceeded, request rate limited"),
+            StampError::RateLimited(_)
+        ));
+        assert!(matches!(
+            classify("Operation failed: server returned an error response: error code -32005: query returned more than 10000 results"),
+            StampError::QueryTooLarge { .. }
+        ));
+        assert!(matches!(
+            classify("Operation failed: server returned an error response: error code -32000: block range is too large"),
+            StampError::QueryTooLarge { .. }
+        ));
+        assert!(matches!(
+            classify("Failed to get current block: error sending request for url (http://127.0.0.1:1/): client error (Connect): tcp connect error: Connection refused (os error 111)"),
+            StampError::RpcTransport(_)
+        ));
+
+        // Status codes embedded in larger numbers are not rate limits
+        assert!(matches!(
+            classify("Failed to get block 14290001: execution reverted"),
+            StampError::Rpc(_)
+        ));
+
+        // Display output is unchanged by the classification
+        for message in [
+            "Operation failed: HTTP error 429",
+            "Operation failed: server returned an error response: error code -32000: block range is too large",
+            "Failed to get current block: error sending request for url (http://127.0.0.1:1/)",
+        ] {
+            assert_eq!(classify(message).to_string(), StampError::Rpc(message.to_string()).to_string());
+        }
+        assert!(classify("Operation failed: HTTP error 429").is_retryable());
+        assert!(!classify("Operation failed: execution reverted, data: \"0x4ee9bc0f\"").is_retryable());
+    }
 }
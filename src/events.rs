@@ -1,4 +1,4 @@
-use crate::types::ContractAddress;
+use crate::types::{BatchId, ContractAddress};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +15,8 @@ pub struct StampEvent {
     pub contract_source: String, // Which contract emitted this event (e.g., "PostageStamp")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contract_address: Option<ContractAddress>, // Actual on-chain address of the contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<String>, // Hash of the including block, used to detect reorgs
     pub data: EventData,
 }
 
@@ -81,7 +83,7 @@ pub enum EventData {
 /// Information about a batch retrieved from the blockchain
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchInfo {
-    pub batch_id: String,
+    pub batch_id: BatchId,
     pub owner: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payer: Option<String>, // Only present in StampsRegistry events
@@ -94,13 +96,55 @@ pub struct BatchInfo {
     pub block_number: u64,
 }
 
+#[cfg(test)]
+impl BatchInfo {
+    /// PostageStamp batch created at block 1000, for test fixtures
+    pub fn test_batch(id: u64, depth: u8, normalised_balance: &str) -> Self {
+        Self {
+            batch_id: BatchId::test_id(id),
+            owner: "0x5678".to_string(),
+            payer: None,
+            contract_source: "PostageStamp".to_string(),
+            depth,
+            bucket_depth: 16,
+            immutable: false,
+            normalised_balance: normalised_balance.to_string(),
+            created_at: Utc::now(),
+            block_number: 1000,
+        }
+    }
+}
+
+#[cfg(test)]
+impl StampEvent {
+    /// PostageStamp top-up of batch `0x1234`, for test fixtures
+    pub fn test_topup(transaction_hash: &str, log_index: u64, block_number: u64) -> Self {
+        Self {
+            event_type: EventType::BatchTopUp,
+            batch_id: Some("0x1234".to_string()),
+            block_number,
+            block_timestamp: Utc::now(),
+            transaction_hash: transaction_hash.to_string(),
+            log_index,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchTopUp {
+                topup_amount: "100000000000000000".to_string(),
+                normalised_balance: "600000000000000000".to_string(),
+                payer: None,
+            },
+        }
+    }
+}
+
 // ============================================================================
 // Storage Incentives Events (PriceOracle, StakeRegistry, Redistribution)
 // ============================================================================
 
 /// Unified event type for storage incentives contracts
 /// Covers PriceOracle, StakeRegistry, and Redistribution events
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StorageIncentivesEvent {
     // Core event metadata (always present)
     pub block_number: u64,
@@ -113,7 +157,7 @@ pub struct StorageIncentivesEvent {
     pub event_type: String,
 
     // Calculated/derived fields
-    pub round_number: Option<u64>,   // block_number / 152
+    pub round_number: Option<u64>,   // block_number / round length
     pub phase: Option<String>,       // 'commit', 'reveal', 'claim' (for redistribution)
 
     // Common identity fields
@@ -161,6 +205,43 @@ pub struct StorageIncentivesEvent {
     pub chunk_address: Option<String>,
 }
 
+impl StorageIncentivesEvent {
+    /// Event with only the core metadata set and every optional field `None`
+    ///
+    /// Parsers add the fields an event type carries with struct update syntax,
+    /// e.g. `StorageIncentivesEvent { price, ..StorageIncentivesEvent::base(...) }`.
+    pub fn base(
+        block_number: u64,
+        block_timestamp: DateTime<Utc>,
+        transaction_hash: String,
+        log_index: u64,
+        contract_source: &str,
+        event_type: &str,
+    ) -> Self {
+        Self {
+            block_number,
+            block_timestamp,
+            transaction_hash,
+            log_index,
+            contract_source: contract_source.to_string(),
+            event_type: event_type.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A redistribution round winner, taken from a Redistribution `WinnerSelected` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoundWinner {
+    pub block_number: u64,
+    pub block_timestamp: DateTime<Utc>,
+    pub round_number: Option<u64>,
+    pub overlay: String,
+    pub owner: String,
+    /// Stake of the winning node in PLUR
+    pub stake: u128,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +267,7 @@ mod tests {
             log_index: 0,
             contract_source: "PostageStamp".to_string(),
             contract_address: None,
+            block_hash: None,
             data: EventData::BatchCreated {
                 total_amount: "1000000000000000000".to_string(),
                 normalised_balance: "500000000000000000".to_string(),
@@ -1,38 +1,240 @@
-use crate::batch::PeriodStats;
-use crate::error::Result;
-use crate::events::{BatchInfo, StampEvent};
+use crate::batch::{self, PeriodStats};
+use crate::cache::Cache;
+use crate::commands::batch_status::BatchStatus;
+use crate::error::{Result, StampError};
+use crate::events::{BatchInfo, EventData, StampEvent};
+use crate::price;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::Write;
-use std::path::Path;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Header row for event CSV exports
+const EVENTS_CSV_HEADER: &str =
+    "block_number,timestamp,event_type,batch_id,transaction_hash,log_index,details";
+
+/// `EventData` fields written as their own columns by wide CSV exports, replacing `details`
+const WIDE_EVENT_FIELDS: [&str; 12] = [
+    "owner",
+    "payer",
+    "depth",
+    "bucket_depth",
+    "immutable",
+    "total_amount",
+    "topup_amount",
+    "new_depth",
+    "normalised_balance",
+    "price",
+    "recipient",
+    "index",
+];
+
+/// PLUR amount fields that `--normalise-amounts` also exports in BZZ as `<field>_bzz`
+const AMOUNT_FIELDS: [&str; 3] = ["total_amount", "normalised_balance", "topup_amount"];
 
 /// Export format types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ExportFormat {
     Csv,
     Json,
+    /// Newline-delimited JSON: one compact record per line
+    Ndjson,
 }
 
 /// Export events to a file
+///
+/// With `normalise_amounts`, every PLUR amount in the event data is also
+/// written in BZZ (`total_amount_bzz`, `normalised_balance_bzz`,
+/// `topup_amount_bzz`) next to the raw value. With `csv_wide`, CSV output has
+/// one column per event data field instead of a JSON `details` column; it has
+/// no effect on other formats.
 pub fn export_events<P: AsRef<Path>>(
     events: &[StampEvent],
     path: P,
     format: ExportFormat,
+    normalise_amounts: bool,
+    csv_wide: bool,
 ) -> Result<()> {
+    if normalise_amounts && format != ExportFormat::Csv {
+        let records = events
+            .iter()
+            .map(|event| {
+                Ok(WithBzzAmounts {
+                    record: event,
+                    bzz: event_bzz_amounts(event)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return match format {
+            ExportFormat::Json => export_json(&records, path),
+            _ => export_ndjson(&records, path),
+        };
+    }
+
     match format {
-        ExportFormat::Csv => export_events_csv(events, path),
+        ExportFormat::Csv => export_events_csv(events, path, normalise_amounts, csv_wide),
         ExportFormat::Json => export_events_json(events, path),
+        ExportFormat::Ndjson => export_ndjson(events, path),
+    }
+}
+
+/// Export events straight from the cache to a file without buffering them
+///
+/// Events from `since_ts` on are read through `Cache::stream_events` and
+/// written one at a time, so memory usage stays flat regardless of the dataset
+/// size. Only events for which `filter` returns true are written. Returns the
+/// number of events written.
+///
+/// With `dedupe`, events are collapsed with `batch::dedupe_events` one block at
+/// a time: the cache streams in block order and duplicates share a block number.
+#[allow(clippy::too_many_arguments)]
+pub async fn export_events_streaming<P, F>(
+    cache: &Cache,
+    since_ts: i64,
+    path: P,
+    format: ExportFormat,
+    normalise_amounts: bool,
+    csv_wide: bool,
+    dedupe: bool,
+    filter: F,
+) -> Result<usize>
+where
+    P: AsRef<Path>,
+    F: Fn(&StampEvent) -> bool,
+{
+    let mut writer = EventStreamWriter::create(path, format, normalise_amounts, csv_wide)?;
+    let mut block: Vec<StampEvent> = Vec::new();
+    cache
+        .stream_events(since_ts, |event| {
+            if !filter(&event) {
+                return Ok(());
+            }
+            if !dedupe {
+                return writer.write_event(&event);
+            }
+            if block.first().is_some_and(|first| first.block_number != event.block_number) {
+                write_deduped(&mut writer, std::mem::take(&mut block))?;
+            }
+            block.push(event);
+            Ok(())
+        })
+        .await?;
+    write_deduped(&mut writer, block)?;
+    writer.finish()
+}
+
+/// Write one block's events after collapsing cross-contract duplicates
+fn write_deduped(writer: &mut EventStreamWriter, events: Vec<StampEvent>) -> Result<()> {
+    for event in batch::dedupe_events(events) {
+        writer.write_event(&event)?;
+    }
+    Ok(())
+}
+
+/// Incremental event writer used by streaming exports
+///
+/// JSON output is a single array written element by element; CSV and NDJSON
+/// output use the same layout as `export_events`.
+pub struct EventStreamWriter {
+    writer: BufWriter<File>,
+    format: ExportFormat,
+    normalise_amounts: bool,
+    csv_wide: bool,
+    count: usize,
+}
+
+impl EventStreamWriter {
+    /// Create the output file and write any leading header
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        format: ExportFormat,
+        normalise_amounts: bool,
+        csv_wide: bool,
+    ) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            ExportFormat::Csv => writeln!(writer, "{}", events_csv_header(normalise_amounts, csv_wide))?,
+            ExportFormat::Json => write!(writer, "[")?,
+            ExportFormat::Ndjson => {}
+        }
+        Ok(Self {
+            writer,
+            format,
+            normalise_amounts,
+            csv_wide,
+            count: 0,
+        })
+    }
+
+    /// Append a single event to the output
+    pub fn write_event(&mut self, event: &StampEvent) -> Result<()> {
+        let bzz = if self.normalise_amounts {
+            event_bzz_amounts(event)?
+        } else {
+            BTreeMap::new()
+        };
+        let record = WithBzzAmounts { record: event, bzz };
+        match self.format {
+            ExportFormat::Csv => {
+                write_event_csv_row(&mut self.writer, event, self.normalise_amounts, self.csv_wide)?
+            }
+            ExportFormat::Json => {
+                if self.count > 0 {
+                    write!(self.writer, ",")?;
+                }
+                writeln!(self.writer)?;
+                serde_json::to_writer(&mut self.writer, &record)?;
+            }
+            ExportFormat::Ndjson => write_ndjson_line(&mut self.writer, &record)?,
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Write any trailing output, flush the file and return the number of events written
+    pub fn finish(mut self) -> Result<usize> {
+        if let ExportFormat::Json = self.format {
+            writeln!(self.writer, "\n]")?;
+        }
+        self.writer.flush()?;
+        Ok(self.count)
     }
 }
 
 /// Export batches to a file
+///
+/// With `normalise_amounts`, each batch also gets a `normalised_balance_bzz`
+/// field next to the raw PLUR balance.
 pub fn export_batches<P: AsRef<Path>>(
     batches: &[BatchInfo],
     path: P,
     format: ExportFormat,
+    normalise_amounts: bool,
 ) -> Result<()> {
+    if normalise_amounts && format != ExportFormat::Csv {
+        let records = batches
+            .iter()
+            .map(|batch| {
+                Ok(WithBzzAmounts {
+                    record: batch,
+                    bzz: batch_bzz_amounts(batch)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return match format {
+            ExportFormat::Json => export_json(&records, path),
+            _ => export_ndjson(&records, path),
+        };
+    }
+
     match format {
-        ExportFormat::Csv => export_batches_csv(batches, path),
+        ExportFormat::Csv => export_batches_csv(batches, path, normalise_amounts),
         ExportFormat::Json => export_batches_json(batches, path),
+        ExportFormat::Ndjson => export_ndjson(batches, path),
     }
 }
 
@@ -45,51 +247,298 @@ pub fn export_stats<P: AsRef<Path>>(
     match format {
         ExportFormat::Csv => export_stats_csv(stats, path),
         ExportFormat::Json => export_stats_json(stats, path),
+        ExportFormat::Ndjson => export_ndjson(stats, path),
     }
 }
 
+/// Export batch expiry dates as an iCalendar file
+///
+/// Writes one all-day VEVENT per batch on its expiry date, titled with the
+/// batch ID and storage capacity, so operators can import expiry reminders
+/// into a calendar.
+pub fn export_expiry_ics<P: AsRef<Path>>(statuses: &[BatchStatus], path: P) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_expiry_ics(&mut writer, statuses, Utc::now())?;
+    writer.flush()?;
+    Ok(())
+}
+
+// iCalendar export implementation
+
+fn write_expiry_ics<W: Write>(
+    writer: &mut W,
+    statuses: &[BatchStatus],
+    generated_at: DateTime<Utc>,
+) -> Result<()> {
+    let dtstamp = generated_at.format("%Y%m%dT%H%M%SZ");
+
+    write_ics_line(writer, "BEGIN:VCALENDAR")?;
+    write_ics_line(writer, "VERSION:2.0")?;
+    write_ics_line(writer, "PRODID:-//beeport-stamp-stats//Batch Expiry//EN")?;
+    write_ics_line(writer, "CALSCALE:GREGORIAN")?;
+
+    for status in statuses {
+        let expiry_day = status.expiry_timestamp.date_naive();
+        let capacity = price::format_capacity(status.depth);
+        let description = format!(
+            "Batch ID: {}\nOwner: {}\nDepth: {} ({} chunks)\nTTL: {} days\nExpires: {}",
+            status.batch_id,
+            status.owner,
+            status.depth,
+            status.size_chunks,
+            status.ttl_days,
+            status.expiry_date
+        );
+
+        write_ics_line(writer, "BEGIN:VEVENT")?;
+        write_ics_line(writer, &format!("UID:{}@beeport-stamp-stats", status.batch_id))?;
+        write_ics_line(writer, &format!("DTSTAMP:{dtstamp}"))?;
+        write_ics_line(writer, &format!("DTSTART;VALUE=DATE:{}", expiry_day.format("%Y%m%d")))?;
+        write_ics_line(
+            writer,
+            &format!("DTEND;VALUE=DATE:{}", (expiry_day + chrono::Days::new(1)).format("%Y%m%d")),
+        )?;
+        write_ics_line(
+            writer,
+            &format!("SUMMARY:{}", escape_ics_text(&format!("Batch {} expires ({capacity})", status.batch_id))),
+        )?;
+        write_ics_line(writer, &format!("DESCRIPTION:{}", escape_ics_text(&description)))?;
+        write_ics_line(writer, "END:VEVENT")?;
+    }
+
+    write_ics_line(writer, "END:VCALENDAR")?;
+    Ok(())
+}
+
+/// Write a CRLF-terminated content line, folded at 75 octets as RFC 5545 requires
+fn write_ics_line<W: Write>(writer: &mut W, line: &str) -> Result<()> {
+    let mut limit = 75;
+    let mut rest = line;
+
+    while rest.len() > limit {
+        let mut split = limit;
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        write!(writer, "{}\r\n ", &rest[..split])?;
+        rest = &rest[split..];
+        // Continuation lines start with a space, which counts towards the limit
+        limit = 74;
+    }
+
+    write!(writer, "{rest}\r\n")?;
+    Ok(())
+}
+
+/// Escape an iCalendar TEXT value
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// BZZ amount normalisation
+
+/// A record serialized with its BZZ amounts appended as extra fields
+#[derive(Serialize)]
+struct WithBzzAmounts<'a, T> {
+    #[serde(flatten)]
+    record: &'a T,
+    #[serde(flatten)]
+    bzz: BTreeMap<String, String>,
+}
+
+/// BZZ values of the PLUR amounts carried by an event, keyed `<field>_bzz`
+fn event_bzz_amounts(event: &StampEvent) -> Result<BTreeMap<String, String>> {
+    let amounts: &[(&str, &str)] = match &event.data {
+        EventData::BatchCreated {
+            total_amount,
+            normalised_balance,
+            ..
+        } => &[("total_amount", total_amount), ("normalised_balance", normalised_balance)],
+        EventData::BatchTopUp {
+            topup_amount,
+            normalised_balance,
+            ..
+        } => &[("topup_amount", topup_amount), ("normalised_balance", normalised_balance)],
+        EventData::BatchDepthIncrease {
+            normalised_balance, ..
+        } => &[("normalised_balance", normalised_balance)],
+        EventData::PotWithdrawn { total_amount, .. } => &[("total_amount", total_amount)],
+        EventData::PriceUpdate { .. } | EventData::CopyBatchFailed { .. } => &[],
+    };
+
+    amounts
+        .iter()
+        .map(|(field, plur)| Ok((format!("{field}_bzz"), price::to_bzz(plur)?)))
+        .collect()
+}
+
+fn batch_bzz_amounts(batch: &BatchInfo) -> Result<BTreeMap<String, String>> {
+    Ok(BTreeMap::from([(
+        "normalised_balance_bzz".to_string(),
+        price::to_bzz(&batch.normalised_balance)?,
+    )]))
+}
+
+/// Event data as `(column, value)` pairs for wide CSV exports
+///
+/// A payer is only included when the event carries one.
+fn event_data_fields(data: &EventData) -> BTreeMap<&'static str, String> {
+    let mut fields = BTreeMap::new();
+
+    match data {
+        EventData::BatchCreated {
+            total_amount,
+            normalised_balance,
+            owner,
+            depth,
+            bucket_depth,
+            immutable_flag,
+            payer,
+        } => {
+            fields.insert("owner", owner.clone());
+            fields.insert("depth", depth.to_string());
+            fields.insert("bucket_depth", bucket_depth.to_string());
+            fields.insert("immutable", immutable_flag.to_string());
+            fields.insert("total_amount", total_amount.clone());
+            fields.insert("normalised_balance", normalised_balance.clone());
+            fields.extend(payer.clone().map(|payer| ("payer", payer)));
+        }
+        EventData::BatchTopUp {
+            topup_amount,
+            normalised_balance,
+            payer,
+        } => {
+            fields.insert("topup_amount", topup_amount.clone());
+            fields.insert("normalised_balance", normalised_balance.clone());
+            fields.extend(payer.clone().map(|payer| ("payer", payer)));
+        }
+        EventData::BatchDepthIncrease {
+            new_depth,
+            normalised_balance,
+            payer,
+        } => {
+            fields.insert("new_depth", new_depth.to_string());
+            fields.insert("normalised_balance", normalised_balance.clone());
+            fields.extend(payer.clone().map(|payer| ("payer", payer)));
+        }
+        EventData::PotWithdrawn {
+            recipient,
+            total_amount,
+        } => {
+            fields.insert("recipient", recipient.clone());
+            fields.insert("total_amount", total_amount.clone());
+        }
+        EventData::PriceUpdate { price } => {
+            fields.insert("price", price.clone());
+        }
+        EventData::CopyBatchFailed { index, .. } => {
+            fields.insert("index", index.clone());
+        }
+    }
+
+    fields
+}
+
 // CSV export implementations
 
-fn export_events_csv<P: AsRef<Path>>(events: &[StampEvent], path: P) -> Result<()> {
+fn events_csv_header(normalise_amounts: bool, csv_wide: bool) -> String {
+    let mut header = EVENTS_CSV_HEADER.to_string();
+    if csv_wide {
+        header = header.replace(",details", &format!(",{}", WIDE_EVENT_FIELDS.join(",")));
+    }
+    if normalise_amounts {
+        for field in AMOUNT_FIELDS {
+            header.push_str(&format!(",{field}_bzz"));
+        }
+    }
+    header
+}
+
+fn export_events_csv<P: AsRef<Path>>(
+    events: &[StampEvent],
+    path: P,
+    normalise_amounts: bool,
+    csv_wide: bool,
+) -> Result<()> {
     let mut file = File::create(path)?;
 
     // Write header
-    writeln!(
-        file,
-        "block_number,timestamp,event_type,batch_id,transaction_hash,log_index,details"
-    )?;
+    writeln!(file, "{}", events_csv_header(normalise_amounts, csv_wide))?;
 
     // Write data
     for event in events {
+        write_event_csv_row(&mut file, event, normalise_amounts, csv_wide)?;
+    }
+
+    Ok(())
+}
+
+fn write_event_csv_row<W: Write>(
+    writer: &mut W,
+    event: &StampEvent,
+    normalise_amounts: bool,
+    csv_wide: bool,
+) -> Result<()> {
+    write!(
+        writer,
+        "{},{},{},{},{},{},",
+        event.block_number,
+        event.block_timestamp.to_rfc3339(),
+        event.event_type,
+        event.batch_id.as_deref().unwrap_or("N/A"),
+        event.transaction_hash,
+        event.log_index,
+    )?;
+
+    if csv_wide {
+        // Fields an event type does not have are left empty
+        let fields = event_data_fields(&event.data);
+        let values: Vec<&str> = WIDE_EVENT_FIELDS
+            .iter()
+            .map(|column| fields.get(column).map_or("", String::as_str))
+            .collect();
+        write!(writer, "{}", values.join(","))?;
+    } else {
         let details = serde_json::to_string(&event.data)?;
-        writeln!(
-            file,
-            "{},{},{},{},{},{},\"{}\"",
-            event.block_number,
-            event.block_timestamp.to_rfc3339(),
-            event.event_type,
-            event.batch_id.as_deref().unwrap_or("N/A"),
-            event.transaction_hash,
-            event.log_index,
-            details.replace("\"", "\"\"")
-        )?;
+        write!(writer, "\"{}\"", details.replace("\"", "\"\""))?;
+    }
+
+    if normalise_amounts {
+        // Amounts an event does not carry are left empty
+        let bzz = event_bzz_amounts(event)?;
+        for field in AMOUNT_FIELDS {
+            let value = bzz.get(&format!("{field}_bzz")).map_or("", String::as_str);
+            write!(writer, ",{value}")?;
+        }
     }
 
+    writeln!(writer)?;
     Ok(())
 }
 
-fn export_batches_csv<P: AsRef<Path>>(batches: &[BatchInfo], path: P) -> Result<()> {
+fn export_batches_csv<P: AsRef<Path>>(
+    batches: &[BatchInfo],
+    path: P,
+    normalise_amounts: bool,
+) -> Result<()> {
     let mut file = File::create(path)?;
 
     // Write header
-    writeln!(
+    write!(
         file,
         "batch_id,owner,payer,depth,bucket_depth,immutable,normalised_balance,created_at"
     )?;
+    if normalise_amounts {
+        write!(file, ",normalised_balance_bzz")?;
+    }
+    writeln!(file)?;
 
     // Write data
     for batch in batches {
-        writeln!(
+        write!(
             file,
             "{},{},{},{},{},{},{},{}",
             batch.batch_id,
@@ -101,6 +550,10 @@ fn export_batches_csv<P: AsRef<Path>>(batches: &[BatchInfo], path: P) -> Result<
             batch.normalised_balance,
             batch.created_at.to_rfc3339()
         )?;
+        if normalise_amounts {
+            write!(file, ",{}", price::to_bzz(&batch.normalised_balance)?)?;
+        }
+        writeln!(file)?;
     }
 
     Ok(())
@@ -112,21 +565,25 @@ fn export_stats_csv<P: AsRef<Path>>(stats: &[PeriodStats], path: P) -> Result<()
     // Write header
     writeln!(
         file,
-        "period_key,period_label,batch_created,batch_topup,batch_depth_increase,total_events,unique_batches"
+        "period_key,period_label,batch_created,batch_topup,batch_depth_increase,total_events,unique_batches,chunks_created,chunks_from_depth_increase,postage_stamp_events,stamps_registry_events"
     )?;
 
     // Write data
     for stat in stats {
         writeln!(
             file,
-            "{},{},{},{},{},{},{}",
+            "{},{},{},{},{},{},{},{},{},{},{}",
             stat.period_key,
             stat.period_label,
             stat.batch_created_count,
             stat.batch_topup_count,
             stat.batch_depth_increase_count,
             stat.total_events,
-            stat.unique_batches
+            stat.unique_batches,
+            stat.chunks_created,
+            stat.chunks_from_depth_increase,
+            stat.postage_stamp_events,
+            stat.stamps_registry_events
         )?;
     }
 
@@ -153,10 +610,154 @@ fn export_stats_json<P: AsRef<Path>>(stats: &[PeriodStats], path: P) -> Result<(
     Ok(())
 }
 
+fn export_json<T: Serialize, P: AsRef<Path>>(records: &[T], path: P) -> Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(file, records)?;
+    Ok(())
+}
+
+// NDJSON export implementation
+
+fn export_ndjson<T: Serialize, P: AsRef<Path>>(records: &[T], path: P) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for record in records {
+        write_ndjson_line(&mut writer, record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_ndjson_line<T: Serialize, W: Write>(writer: &mut W, record: &T) -> Result<()> {
+    let line = serde_json::to_string(record)?;
+    writeln!(writer, "{line}")?;
+    Ok(())
+}
+
+// Export manifests
+
+/// Integrity manifest written next to an export by `export --manifest`
+///
+/// Records are CSV rows after the header, NDJSON lines, or JSON array
+/// elements, hashed in file order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// File name of the export, relative to the manifest
+    pub file: String,
+    pub format: ExportFormat,
+    /// SHA-256 of the whole export file
+    pub sha256: String,
+    pub record_count: usize,
+    /// SHA-256 of each record, in file order
+    pub record_sha256: Vec<String>,
+}
+
+/// Path of the manifest for an export file (`<export>.manifest.json`)
+pub fn manifest_path<P: AsRef<Path>>(export_path: P) -> PathBuf {
+    let mut path = export_path.as_ref().as_os_str().to_owned();
+    path.push(".manifest.json");
+    PathBuf::from(path)
+}
+
+/// Hash an export file and write its manifest next to it
+pub fn write_export_manifest<P: AsRef<Path>>(export_path: P, format: ExportFormat) -> Result<ExportManifest> {
+    let export_path = export_path.as_ref();
+    let content = std::fs::read(export_path)?;
+
+    let record_sha256 = record_hashes(&content, &format)?;
+    let manifest = ExportManifest {
+        file: export_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        format,
+        sha256: sha256_hex(&content),
+        record_count: record_sha256.len(),
+        record_sha256,
+    };
+
+    let file = File::create(manifest_path(export_path))?;
+    serde_json::to_writer_pretty(file, &manifest)?;
+    Ok(manifest)
+}
+
+/// Check an export file against its manifest
+///
+/// Returns a description of every mismatch; an empty list means the file is
+/// intact.
+pub fn verify_export_manifest<P: AsRef<Path>>(export_path: P) -> Result<Vec<String>> {
+    let export_path = export_path.as_ref();
+    let manifest_file = manifest_path(export_path);
+    let manifest: ExportManifest = serde_json::from_reader(File::open(&manifest_file).map_err(|e| {
+        StampError::Config(format!("Cannot read manifest {}: {e}", manifest_file.display()))
+    })?)?;
+    let content = std::fs::read(export_path)?;
+
+    let mut problems = Vec::new();
+    if sha256_hex(&content) != manifest.sha256 {
+        problems.push("file SHA-256 does not match the manifest".to_string());
+    }
+
+    let hashes = record_hashes(&content, &manifest.format)?;
+    if hashes.len() != manifest.record_count || manifest.record_sha256.len() != manifest.record_count {
+        problems.push(format!(
+            "record count is {}, manifest lists {}",
+            hashes.len(),
+            manifest.record_count
+        ));
+    }
+    for (index, (actual, expected)) in hashes.iter().zip(&manifest.record_sha256).enumerate() {
+        if actual != expected {
+            problems.push(format!("record {} does not match its checksum", index + 1));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// SHA-256 of each record in an export, in file order
+fn record_hashes(content: &[u8], format: &ExportFormat) -> Result<Vec<String>> {
+    match format {
+        ExportFormat::Csv => {
+            // Hash each row's raw bytes so quoting and whitespace changes are detected
+            let mut reader = csv::ReaderBuilder::new()
+                .flexible(true)
+                .from_reader(content);
+            reader.byte_headers()?;
+
+            let mut hashes = Vec::new();
+            let mut record = csv::ByteRecord::new();
+            let mut start = reader.position().byte() as usize;
+            while reader.read_byte_record(&mut record)? {
+                let end = reader.position().byte() as usize;
+                hashes.push(sha256_hex(&content[start..end]));
+                start = end;
+            }
+            Ok(hashes)
+        }
+        ExportFormat::Ndjson => Ok(content
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(sha256_hex)
+            .collect()),
+        ExportFormat::Json => {
+            let records: Vec<serde_json::Value> = serde_json::from_slice(content)?;
+            records
+                .iter()
+                .map(|record| Ok(sha256_hex(&serde_json::to_vec(record)?)))
+                .collect()
+        }
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::events::{EventData, EventType};
+    use crate::types::BatchId;
     use chrono::{TimeZone, Utc};
     use tempfile::NamedTempFile;
 
@@ -171,6 +772,7 @@ mod tests {
             log_index: 0,
             contract_source: "PostageStamp".to_string(),
             contract_address: None,
+            block_hash: None,
             data: EventData::BatchCreated {
                 total_amount: "1000000000000000000".to_string(),
                 normalised_balance: "500000000000000000".to_string(),
@@ -183,7 +785,7 @@ mod tests {
         }];
 
         let temp_file = NamedTempFile::new().unwrap();
-        export_events(&events, temp_file.path(), ExportFormat::Json).unwrap();
+        export_events(&events, temp_file.path(), ExportFormat::Json, false, false).unwrap();
 
         let content = std::fs::read_to_string(temp_file.path()).unwrap();
         assert!(content.contains("BatchCreated"));
@@ -201,6 +803,7 @@ mod tests {
             log_index: 0,
             contract_source: "PostageStamp".to_string(),
             contract_address: None,
+            block_hash: None,
             data: EventData::BatchCreated {
                 total_amount: "1000000000000000000".to_string(),
                 normalised_balance: "500000000000000000".to_string(),
@@ -213,7 +816,7 @@ mod tests {
         }];
 
         let temp_file = NamedTempFile::new().unwrap();
-        export_events(&events, temp_file.path(), ExportFormat::Csv).unwrap();
+        export_events(&events, temp_file.path(), ExportFormat::Csv, false, false).unwrap();
 
         let content = std::fs::read_to_string(temp_file.path()).unwrap();
         assert!(content.contains("block_number"));
@@ -221,50 +824,133 @@ mod tests {
         assert!(content.contains("0x1234"));
     }
 
+    #[test]
+    fn test_export_events_csv_wide() {
+        let topup = StampEvent {
+            event_type: EventType::BatchTopUp,
+            batch_id: Some("0x1234".to_string()),
+            block_number: 1000,
+            block_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            transaction_hash: "0xabcd".to_string(),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchTopUp {
+                topup_amount: "12345678901234567890".to_string(),
+                normalised_balance: "500000000000000000".to_string(),
+                payer: None,
+            },
+        };
+        let created = StampEvent {
+            event_type: EventType::BatchCreated,
+            log_index: 1,
+            data: EventData::BatchCreated {
+                total_amount: "1000".to_string(),
+                normalised_balance: "10".to_string(),
+                owner: "0xowner".to_string(),
+                depth: 20,
+                bucket_depth: 16,
+                immutable_flag: true,
+                payer: Some("0xpayer".to_string()),
+            },
+            ..topup.clone()
+        };
+
+        let temp_file = NamedTempFile::new().unwrap();
+        export_events(&[topup, created], temp_file.path(), ExportFormat::Csv, true, true).unwrap();
+        let mut reader = csv::Reader::from_path(temp_file.path()).unwrap();
+        let headers = reader.headers().unwrap().clone();
+        assert!(!headers.iter().any(|h| h == "details"));
+        assert_eq!(&headers[6], "owner");
+        let records: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        let column = |row: usize, name: &str| records[row][headers.iter().position(|h| h == name).unwrap()].to_string();
+
+        assert_eq!(column(0, "topup_amount"), "12345678901234567890");
+        assert_eq!(column(0, "normalised_balance"), "500000000000000000");
+        assert_eq!(column(0, "owner"), "");
+        assert_eq!(column(0, "depth"), "");
+        assert_eq!(column(0, "payer"), "");
+        assert_eq!(column(0, "topup_amount_bzz"), "1234.567890123456789");
+
+        assert_eq!(column(1, "owner"), "0xowner");
+        assert_eq!(column(1, "payer"), "0xpayer");
+        assert_eq!(column(1, "depth"), "20");
+        assert_eq!(column(1, "immutable"), "true");
+        assert_eq!(column(1, "topup_amount"), "");
+    }
+
+    #[test]
+    fn test_export_events_normalise_amounts() {
+        let events = vec![StampEvent {
+            event_type: EventType::BatchTopUp,
+            batch_id: Some("0x1234".to_string()),
+            block_number: 1000,
+            block_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            transaction_hash: "0xabcd".to_string(),
+            log_index: 0,
+            contract_source: "PostageStamp".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchTopUp {
+                topup_amount: "12345678901234567890".to_string(),
+                normalised_balance: "500000000000000000".to_string(),
+                payer: None,
+            },
+        }];
+
+        // JSON: raw amounts stay in `data`, BZZ amounts are added to the record
+        let temp_file = NamedTempFile::new().unwrap();
+        export_events(&events, temp_file.path(), ExportFormat::Json, true, false).unwrap();
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let records: Vec<serde_json::Value> = serde_json::from_str(&content).unwrap();
+        assert_eq!(records[0]["data"]["topup_amount"], "12345678901234567890");
+        assert_eq!(records[0]["topup_amount_bzz"], "1234.567890123456789");
+        assert_eq!(records[0]["normalised_balance_bzz"], "50");
+        assert!(records[0].get("total_amount_bzz").is_none());
+
+        // CSV: extra columns after the raw ones, empty where the event has no such amount
+        let temp_file = NamedTempFile::new().unwrap();
+        export_events(&events, temp_file.path(), ExportFormat::Csv, true, false).unwrap();
+        let mut reader = csv::Reader::from_path(temp_file.path()).unwrap();
+        let headers = reader.headers().unwrap().clone();
+        assert_eq!(&headers[6], "details");
+        let record = reader.records().next().unwrap().unwrap();
+        let column = |name: &str| record[headers.iter().position(|h| h == name).unwrap()].to_string();
+        assert_eq!(column("topup_amount_bzz"), "1234.567890123456789");
+        assert_eq!(column("normalised_balance_bzz"), "50");
+        assert_eq!(column("total_amount_bzz"), "");
+        assert!(column("details").contains("12345678901234567890"));
+    }
+
     #[test]
     fn test_export_batches_json() {
         let batches = vec![BatchInfo {
-            batch_id: "0x1234".to_string(),
-            owner: "0x5678".to_string(),
-            payer: None,
-            contract_source: "PostageStamp".to_string(),
-            depth: 20,
-            bucket_depth: 16,
-            immutable: false,
-            normalised_balance: "500000000000000000".to_string(),
             created_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
-            block_number: 1000,
+            ..BatchInfo::test_batch(0x1234, 20, "500000000000000000")
         }];
 
         let temp_file = NamedTempFile::new().unwrap();
-        export_batches(&batches, temp_file.path(), ExportFormat::Json).unwrap();
+        export_batches(&batches, temp_file.path(), ExportFormat::Json, false).unwrap();
 
         let content = std::fs::read_to_string(temp_file.path()).unwrap();
-        assert!(content.contains("0x1234"));
+        assert!(content.contains(BatchId::test_id(0x1234).as_str()));
         assert!(content.contains("0x5678"));
     }
 
     #[test]
     fn test_export_batches_csv() {
         let batches = vec![BatchInfo {
-            batch_id: "0x1234".to_string(),
-            owner: "0x5678".to_string(),
-            payer: None,
-            contract_source: "PostageStamp".to_string(),
-            depth: 20,
-            bucket_depth: 16,
-            immutable: false,
-            normalised_balance: "500000000000000000".to_string(),
             created_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
-            block_number: 1000,
+            ..BatchInfo::test_batch(0x1234, 20, "500000000000000000")
         }];
 
         let temp_file = NamedTempFile::new().unwrap();
-        export_batches(&batches, temp_file.path(), ExportFormat::Csv).unwrap();
+        export_batches(&batches, temp_file.path(), ExportFormat::Csv, false).unwrap();
 
         let content = std::fs::read_to_string(temp_file.path()).unwrap();
         assert!(content.contains("batch_id"));
-        assert!(content.contains("0x1234"));
+        assert!(content.contains(BatchId::test_id(0x1234).as_str()));
     }
 
     #[test]
@@ -277,6 +963,10 @@ mod tests {
             batch_depth_increase_count: 2,
             total_events: 17,
             unique_batches: 5,
+            chunks_created: 0,
+            chunks_from_depth_increase: 0,
+            postage_stamp_events: 0,
+            stamps_registry_events: 0,
         }];
 
         let temp_file = NamedTempFile::new().unwrap();
@@ -297,6 +987,10 @@ mod tests {
             batch_depth_increase_count: 2,
             total_events: 17,
             unique_batches: 5,
+            chunks_created: 1 << 20,
+            chunks_from_depth_increase: 3 << 20,
+            postage_stamp_events: 12,
+            stamps_registry_events: 5,
         }];
 
         let temp_file = NamedTempFile::new().unwrap();
@@ -305,5 +999,268 @@ mod tests {
         let content = std::fs::read_to_string(temp_file.path()).unwrap();
         assert!(content.contains("period_key"));
         assert!(content.contains("2025-01"));
+        assert!(content
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with(",chunks_created,chunks_from_depth_increase,postage_stamp_events,stamps_registry_events"));
+        assert!(content.contains(",1048576,3145728,12,5"));
+    }
+
+    fn synthetic_topup(i: u64) -> StampEvent {
+        StampEvent {
+            batch_id: Some(format!("0x{i:064x}")),
+            block_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            ..StampEvent::test_topup(&format!("0x{i:064x}"), 0, 1000 + i)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_events_streaming_json() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        let events: Vec<StampEvent> = (0..10_000).map(synthetic_topup).collect();
+        cache.store_events(&events).await.unwrap();
+        drop(events);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let written =
+            export_events_streaming(&cache, 0, temp_file.path(), ExportFormat::Json, false, false, true, |_| true)
+                .await
+                .unwrap();
+        assert_eq!(written, 10_000);
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let parsed: Vec<StampEvent> = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed.len(), 10_000);
+        assert_eq!(parsed[0].block_number, 1000);
+        assert_eq!(parsed[9_999].block_number, 10_999);
+    }
+
+    #[tokio::test]
+    async fn test_export_events_streaming_csv_with_filter() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        let events: Vec<StampEvent> = (0..100).map(synthetic_topup).collect();
+        cache.store_events(&events).await.unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let written = export_events_streaming(
+            &cache,
+            0,
+            temp_file.path(),
+            ExportFormat::Csv,
+            false,
+            false,
+            true,
+            |e| e.block_number % 2 == 0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(written, 50);
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        // Header plus one line per exported event
+        assert_eq!(content.lines().count(), 51);
+        assert!(content.starts_with(EVENTS_CSV_HEADER));
+    }
+
+    #[tokio::test]
+    async fn test_export_events_streaming_dedupes_per_block() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        // Every top-up is also reported by StampsRegistry in the same block
+        let events: Vec<StampEvent> = (0..10)
+            .flat_map(|i| {
+                let registry_copy = StampEvent {
+                    contract_source: "StampsRegistry".to_string(),
+                    log_index: 1,
+                    ..synthetic_topup(i)
+                };
+                [synthetic_topup(i), registry_copy]
+            })
+            .collect();
+        cache.store_events(&events).await.unwrap();
+
+        let export = |dedupe: bool| {
+            let cache = &cache;
+            async move {
+                let temp_file = NamedTempFile::new().unwrap();
+                let written = export_events_streaming(
+                    cache,
+                    0,
+                    temp_file.path(),
+                    ExportFormat::Ndjson,
+                    false,
+                    false,
+                    dedupe,
+                    |_| true,
+                )
+                .await
+                .unwrap();
+                let content = std::fs::read_to_string(temp_file.path()).unwrap();
+                (written, content)
+            }
+        };
+
+        let (written, content) = export(true).await;
+        assert_eq!(written, 10);
+        assert!(content.lines().all(|line| line.contains("StampsRegistry")));
+
+        let (written, _) = export(false).await;
+        assert_eq!(written, 20);
+    }
+
+    #[test]
+    fn test_event_stream_writer_empty_json() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let writer = EventStreamWriter::create(temp_file.path(), ExportFormat::Json, false, false).unwrap();
+        assert_eq!(writer.finish().unwrap(), 0);
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let parsed: Vec<StampEvent> = serde_json::from_str(&content).unwrap();
+        assert!(parsed.is_empty());
+    }
+
+    #[test]
+    fn test_export_events_ndjson() {
+        let events: Vec<StampEvent> = (0..25).map(synthetic_topup).collect();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        export_events(&events, temp_file.path(), ExportFormat::Ndjson, false, false).unwrap();
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 25);
+        for (i, line) in lines.iter().enumerate() {
+            let event: StampEvent = serde_json::from_str(line).unwrap();
+            assert_eq!(event.block_number, 1000 + i as u64);
+        }
+    }
+
+    #[test]
+    fn test_export_batches_ndjson() {
+        let batches: Vec<BatchInfo> = (0..3)
+            .map(|i| BatchInfo {
+                created_at: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+                block_number: 1000 + i,
+                ..BatchInfo::test_batch(i, 20, "500000000000000000")
+            })
+            .collect();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        export_batches(&batches, temp_file.path(), ExportFormat::Ndjson, false).unwrap();
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for line in lines {
+            let batch: BatchInfo = serde_json::from_str(line).unwrap();
+            assert_eq!(batch.owner, "0x5678");
+        }
+    }
+
+    #[test]
+    fn test_export_stats_ndjson() {
+        let stats = vec![
+            PeriodStats {
+                period_key: "2025-01".to_string(),
+                period_label: "January 2025".to_string(),
+                batch_created_count: 5,
+                batch_topup_count: 10,
+                batch_depth_increase_count: 2,
+                total_events: 17,
+                unique_batches: 5,
+                chunks_created: 0,
+                chunks_from_depth_increase: 0,
+                postage_stamp_events: 0,
+                stamps_registry_events: 0,
+            },
+            PeriodStats {
+                period_key: "2025-02".to_string(),
+                period_label: "February 2025".to_string(),
+                batch_created_count: 1,
+                batch_topup_count: 0,
+                batch_depth_increase_count: 0,
+                total_events: 1,
+                unique_batches: 1,
+                chunks_created: 0,
+                chunks_from_depth_increase: 0,
+                postage_stamp_events: 0,
+                stamps_registry_events: 0,
+            },
+        ];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        export_stats(&stats, temp_file.path(), ExportFormat::Ndjson).unwrap();
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(value.get("period_key").is_some());
+        }
+    }
+
+    #[test]
+    fn test_export_expiry_ics() {
+        let batch = BatchInfo {
+            batch_id: BatchId::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef").unwrap(),
+            ..BatchInfo::test_batch(0, 20, "240000000")
+        };
+        let mut status =
+            BatchStatus::from_batch(&batch, &crate::price::PriceConfig::new(24000), 0, 5.0).unwrap();
+        status.expiry_timestamp = Utc.with_ymd_and_hms(2026, 11, 20, 15, 30, 0).unwrap();
+
+        let mut output = Vec::new();
+        let generated_at = Utc.with_ymd_and_hms(2026, 10, 16, 9, 0, 0).unwrap();
+        write_expiry_ics(&mut output, &[status], generated_at).unwrap();
+        let content = String::from_utf8(output).unwrap();
+
+        assert!(content.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(content.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(content.matches("BEGIN:VEVENT").count(), 1);
+        assert!(content.contains("DTSTAMP:20261016T090000Z\r\n"));
+        assert!(content.contains("DTSTART;VALUE=DATE:20261120\r\n"));
+        assert!(content.contains("DTEND;VALUE=DATE:20261121\r\n"));
+
+        // No physical line exceeds 75 octets
+        assert!(content.split("\r\n").all(|line| line.len() <= 75));
+
+        // Unfolded, the summary carries the batch ID and capacity
+        let unfolded = content.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("SUMMARY:Batch {} expires (4 GiB)\r\n", batch.batch_id)));
+        assert!(unfolded.contains("Depth: 20 (1\\,048\\,576 chunks)\\nTTL:"));
+    }
+
+    #[test]
+    fn test_export_manifest_round_trip() {
+        let events: Vec<StampEvent> = (0..3).map(synthetic_topup).collect();
+
+        for format in [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Ndjson] {
+            let temp_file = NamedTempFile::new().unwrap();
+            export_events(&events, temp_file.path(), format.clone(), false, false).unwrap();
+
+            let manifest = write_export_manifest(temp_file.path(), format.clone()).unwrap();
+            assert_eq!(manifest.record_count, 3, "{format:?}");
+            assert_eq!(manifest.record_sha256.len(), 3);
+            assert!(verify_export_manifest(temp_file.path()).unwrap().is_empty());
+
+            // Tampering with one record is reported against that record
+            let content = std::fs::read_to_string(temp_file.path()).unwrap();
+            let tampered = content.replacen("1001", "1009", 1);
+            assert_ne!(tampered, content);
+            std::fs::write(temp_file.path(), tampered).unwrap();
+
+            let problems = verify_export_manifest(temp_file.path()).unwrap();
+            assert!(problems.iter().any(|p| p.contains("file SHA-256")), "{format:?}: {problems:?}");
+            assert!(problems.iter().any(|p| p == "record 2 does not match its checksum"), "{format:?}: {problems:?}");
+
+            std::fs::remove_file(manifest_path(temp_file.path())).unwrap();
+        }
     }
 }
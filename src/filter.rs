@@ -0,0 +1,476 @@
+//! Event and batch filtering shared by the CLI commands and the query server
+
+use crate::cache::Cache;
+use crate::cli::{FilterContract, FilterEventType};
+use crate::error::{Result, StampError};
+use crate::events::{BatchInfo, EventData, StampEvent};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// Normalize a user-supplied hex filter value to the form stored in the cache
+///
+/// Trims surrounding whitespace, lowercases, and ensures a `0x` prefix, so
+/// `" 0XABCD "`, `"ABCD"` and `"0xabcd"` all become `"0xabcd"`.
+///
+/// # Errors
+///
+/// Returns error if the value is empty or contains non-hex characters
+pub fn normalize_hex(input: &str) -> Result<String> {
+    let (_, digits) = split_hex(input)?;
+    Ok(format!("0x{digits}"))
+}
+
+/// Normalize a user-supplied partial hex value for substring matching
+///
+/// Like [`normalize_hex`], but only keeps a `0x` prefix when the user typed
+/// one, so `"ABCD"` becomes `"abcd"` and matches anywhere in a stored ID,
+/// while `"0xABCD"` becomes `"0xabcd"` and only matches at the start.
+///
+/// # Errors
+///
+/// Returns error if the value is empty or contains non-hex characters
+pub fn normalize_hex_fragment(input: &str) -> Result<String> {
+    let (prefixed, digits) = split_hex(input)?;
+    Ok(if prefixed { format!("0x{digits}") } else { digits })
+}
+
+/// Lowercase and validate a hex value, returning whether it had a `0x` prefix and its digits
+fn split_hex(input: &str) -> Result<(bool, String)> {
+    let lower = input.trim().to_lowercase();
+    let (prefixed, digits) = match lower.strip_prefix("0x") {
+        Some(digits) => (true, digits),
+        None => (false, lower.as_str()),
+    };
+
+    if digits.is_empty() {
+        return Err(StampError::Parse(format!(
+            "Invalid hex value '{input}': no hex digits"
+        )));
+    }
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(StampError::Parse(format!(
+            "Invalid hex value '{input}': contains non-hex characters"
+        )));
+    }
+
+    Ok((prefixed, digits.to_string()))
+}
+
+/// Clap value parser for hex filter arguments
+pub fn parse_hex_arg(input: &str) -> std::result::Result<String, String> {
+    normalize_hex(input).map_err(|e| e.to_string())
+}
+
+/// Clap value parser for partial hex filter arguments such as `--batch-id`
+pub fn parse_hex_fragment_arg(input: &str) -> std::result::Result<String, String> {
+    normalize_hex_fragment(input).map_err(|e| e.to_string())
+}
+
+/// Parse a user-supplied date filter value
+///
+/// Accepts an RFC 3339 timestamp such as `2024-03-31T23:59:59Z` or a plain
+/// `YYYY-MM-DD` date, which means midnight UTC at the start of that day.
+///
+/// # Errors
+///
+/// Returns error if the value is neither form
+pub fn parse_date(input: &str) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(input) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+        .map_err(|_| {
+            StampError::Parse(format!(
+                "Invalid date '{input}': expected YYYY-MM-DD or an RFC 3339 timestamp"
+            ))
+        })
+}
+
+/// Clap value parser for date filter arguments
+pub fn parse_date_arg(input: &str) -> std::result::Result<DateTime<Utc>, String> {
+    parse_date(input).map_err(|e| e.to_string())
+}
+
+/// Filters applied to cached events and batches
+///
+/// Every field is optional; an empty filter matches everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventFilter {
+    /// Only keep events of this type
+    pub event_type: Option<FilterEventType>,
+    /// Only keep events and batches whose batch ID contains this string
+    ///
+    /// Expected in [`normalize_hex_fragment`] form.
+    #[serde(rename = "batch_id")]
+    pub batch_id_contains: Option<String>,
+    /// Only keep events emitted by this contract
+    pub contract: Option<FilterContract>,
+    /// Only keep events and batches owned by this address
+    ///
+    /// Only `BatchCreated` events carry an owner; call [`EventFilter::resolve_owner`]
+    /// to match the owner's other batch events too. Expected in [`normalize_hex`] form.
+    pub owner: Option<String>,
+    /// Only keep events and batches paid for by this address
+    ///
+    /// Only StampsRegistry events carry a payer. Expected in [`normalize_hex`] form.
+    pub payer: Option<String>,
+    /// IDs of the batches owned by `owner`, filled in by [`EventFilter::resolve_owner`]
+    #[serde(skip)]
+    pub owner_batch_ids: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+    /// Look up the batches owned by `owner` in the cache
+    ///
+    /// Afterwards the owner filter matches every event of those batches, such as
+    /// top-ups and depth increases, instead of only their `BatchCreated` events.
+    pub async fn resolve_owner(&mut self, cache: &Cache) -> Result<()> {
+        if let Some(owner) = &self.owner {
+            let ids = cache.get_batch_ids_by_owner(owner).await?;
+            tracing::debug!("Owner {} has {} cached batches", owner, ids.len());
+            self.owner_batch_ids = Some(ids.into_iter().collect());
+        }
+        Ok(())
+    }
+
+    /// Check whether an event passes all configured filters
+    pub fn matches(&self, event: &StampEvent) -> bool {
+        self.event_type.as_ref().is_none_or(|f| f.matches(&event.event_type))
+            && self.batch_id_contains.as_ref().is_none_or(|f| {
+                event.batch_id.as_ref().is_some_and(|id| id.contains(f))
+            })
+            && self.contract.as_ref().is_none_or(|f| f.matches(&event.contract_source))
+            && self.owner.as_ref().is_none_or(|f| match &self.owner_batch_ids {
+                Some(ids) => event.batch_id.as_ref().is_some_and(|id| ids.contains(id)),
+                None => address_matches(event_owner(event), f),
+            })
+            && self.payer.as_ref().is_none_or(|f| address_matches(event_payer(event), f))
+    }
+
+    /// Check whether a batch passes the batch ID, owner and payer filters
+    ///
+    /// Event type and contract filters only apply to events.
+    pub fn matches_batch(&self, batch: &BatchInfo) -> bool {
+        self.batch_id_contains.as_ref().is_none_or(|f| batch.batch_id.as_str().contains(f.as_str()))
+            && self.owner.as_ref().is_none_or(|f| address_matches(Some(&batch.owner), f))
+            && self.payer.as_ref().is_none_or(|f| address_matches(batch.payer.as_deref(), f))
+    }
+
+    /// Retain only the events that pass the filter
+    pub fn apply(&self, events: &mut Vec<StampEvent>) {
+        let before = events.len();
+        events.retain(|e| self.matches(e));
+        if events.len() != before {
+            tracing::info!("Event filters: {} -> {} events", before, events.len());
+        }
+    }
+
+    /// Retain only the batches that pass the filter
+    pub fn apply_to_batches(&self, batches: &mut Vec<BatchInfo>) {
+        batches.retain(|b| self.matches_batch(b));
+    }
+}
+
+/// Owner address embedded in the event data, if the event carries one
+fn event_owner(event: &StampEvent) -> Option<&str> {
+    match &event.data {
+        EventData::BatchCreated { owner, .. } => Some(owner),
+        _ => None,
+    }
+}
+
+/// Payer address embedded in the event data, if the event carries one
+fn event_payer(event: &StampEvent) -> Option<&str> {
+    match &event.data {
+        EventData::BatchCreated { payer, .. }
+        | EventData::BatchTopUp { payer, .. }
+        | EventData::BatchDepthIncrease { payer, .. } => payer.as_deref(),
+        _ => None,
+    }
+}
+
+/// Compare a stored address against a normalized filter value
+///
+/// Case-insensitive so values cached before addresses were stored in
+/// lowercase still match.
+fn address_matches(stored: Option<&str>, filter: &str) -> bool {
+    stored.is_some_and(|addr| addr.trim().eq_ignore_ascii_case(filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(event_type: &str, batch_id: &str, contract: &str) -> StampEvent {
+        serde_json::from_value(json!({
+            "event_type": event_type,
+            "batch_id": batch_id,
+            "block_number": 1,
+            "block_timestamp": "2025-01-01T00:00:00Z",
+            "transaction_hash": "0xabc",
+            "log_index": 0,
+            "contract_source": contract,
+            "data": { "type": "BatchTopUp", "topup_amount": "1", "normalised_balance": "1" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_normalize_hex_mixed_case_and_whitespace() {
+        assert_eq!(normalize_hex("0X12AB").unwrap(), "0x12ab");
+        assert_eq!(normalize_hex("  0xAbCd\n").unwrap(), "0xabcd");
+        assert_eq!(normalize_hex("ABCD").unwrap(), "0xabcd");
+        assert_eq!(normalize_hex("0xabcd").unwrap(), "0xabcd");
+    }
+
+    #[test]
+    fn test_normalize_hex_rejects_invalid() {
+        assert!(normalize_hex("").is_err());
+        assert!(normalize_hex("  0x ").is_err());
+        assert!(normalize_hex("0xzz12").is_err());
+        assert!(normalize_hex("12 34").is_err());
+    }
+
+    #[test]
+    fn test_normalize_hex_fragment_keeps_typed_prefix() {
+        assert_eq!(normalize_hex_fragment(" ABCD ").unwrap(), "abcd");
+        assert_eq!(normalize_hex_fragment("0XABcd").unwrap(), "0xabcd");
+        assert!(normalize_hex_fragment("0x").is_err());
+        assert!(normalize_hex_fragment("wxyz").is_err());
+    }
+
+    #[test]
+    fn test_parse_date_accepts_dates_and_timestamps() {
+        assert_eq!(parse_date("2024-01-01").unwrap().to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert_eq!(
+            parse_date("2024-03-31T23:59:59+02:00").unwrap().to_rfc3339(),
+            "2024-03-31T21:59:59+00:00"
+        );
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("last tuesday").is_err());
+    }
+
+    #[test]
+    fn test_normalized_batch_id_matches_stored_value() {
+        let filter = EventFilter {
+            batch_id_contains: Some(normalize_hex_fragment(" 0X1234 ").unwrap()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&event("BatchTopUp", "0x1234abcd", "PostageStamp")));
+    }
+
+    #[test]
+    fn test_partial_batch_id_from_middle_of_stored_id() {
+        let stored = event("BatchTopUp", "0x12abcd34", "PostageStamp");
+
+        let filter = EventFilter {
+            batch_id_contains: Some(normalize_hex_fragment("ABCD").unwrap()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&stored));
+
+        // A typed prefix still anchors the match to the start of the ID
+        let filter = EventFilter {
+            batch_id_contains: Some(normalize_hex_fragment("0xabcd").unwrap()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&stored));
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = EventFilter::default();
+        assert!(filter.matches(&event("BatchTopUp", "0x1234", "PostageStamp")));
+    }
+
+    #[test]
+    fn test_event_type_filter() {
+        let filter = EventFilter {
+            event_type: Some(FilterEventType::BatchCreated),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&event("BatchCreated", "0x1234", "PostageStamp")));
+        assert!(!filter.matches(&event("BatchTopUp", "0x1234", "PostageStamp")));
+        assert!(!filter.matches(&event("BatchDepthIncrease", "0x1234", "PostageStamp")));
+    }
+
+    #[test]
+    fn test_batch_id_filter() {
+        let filter = EventFilter {
+            batch_id_contains: Some("0xab".to_string()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&event("BatchTopUp", "0xabcd", "PostageStamp")));
+        assert!(!filter.matches(&event("BatchTopUp", "0x1234", "PostageStamp")));
+
+        // Events without a batch ID never match a batch ID filter
+        let mut no_batch = event("BatchTopUp", "0xabcd", "PostageStamp");
+        no_batch.batch_id = None;
+        assert!(!filter.matches(&no_batch));
+    }
+
+    #[test]
+    fn test_contract_filter() {
+        let filter = EventFilter {
+            contract: Some(FilterContract::PostageStamp),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&event("BatchTopUp", "0x1234", "PostageStamp")));
+        assert!(!filter.matches(&event("BatchTopUp", "0x1234", "StampsRegistry")));
+    }
+
+    fn created(batch_id: &str, owner: &str, payer: Option<&str>) -> StampEvent {
+        let contract = if payer.is_some() { "StampsRegistry" } else { "PostageStamp" };
+        let mut e = event("BatchCreated", batch_id, contract);
+        e.data = EventData::BatchCreated {
+            total_amount: "1000".to_string(),
+            normalised_balance: "500".to_string(),
+            owner: owner.to_string(),
+            depth: 20,
+            bucket_depth: 16,
+            immutable_flag: false,
+            payer: payer.map(str::to_string),
+        };
+        e
+    }
+
+    const OWNER: &str = "0x1111111111111111111111111111111111111111";
+    const PAYER: &str = "0x2222222222222222222222222222222222222222";
+
+    #[test]
+    fn test_owner_filter() {
+        let filter = EventFilter {
+            owner: Some(normalize_hex("0X1111111111111111111111111111111111111111").unwrap()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&created("0x01", OWNER, None)));
+        assert!(filter.matches(&created("0x02", OWNER, Some(PAYER))));
+        assert!(!filter.matches(&created("0x03", PAYER, None)));
+        // Top-ups carry no owner
+        assert!(!filter.matches(&event("BatchTopUp", "0x01", "PostageStamp")));
+    }
+
+    #[test]
+    fn test_resolved_owner_matches_all_batch_events() {
+        let filter = EventFilter {
+            owner: Some(OWNER.to_string()),
+            owner_batch_ids: Some(HashSet::from(["0x01".to_string()])),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&created("0x01", OWNER, None)));
+        assert!(filter.matches(&event("BatchTopUp", "0x01", "PostageStamp")));
+        assert!(filter.matches(&event("BatchDepthIncrease", "0x01", "PostageStamp")));
+        assert!(!filter.matches(&event("BatchTopUp", "0x02", "PostageStamp")));
+    }
+
+    #[test]
+    fn test_payer_filter() {
+        let filter = EventFilter {
+            payer: Some(PAYER.to_string()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&created("0x01", OWNER, Some(PAYER))));
+        // PostageStamp events have no payer and never match a payer filter
+        assert!(!filter.matches(&created("0x02", OWNER, None)));
+        assert!(!filter.matches(&created("0x03", OWNER, Some(OWNER))));
+    }
+
+    #[test]
+    fn test_owner_and_payer_filter() {
+        let filter = EventFilter {
+            owner: Some(OWNER.to_string()),
+            payer: Some(PAYER.to_string()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&created("0x01", OWNER, Some(PAYER))));
+        assert!(!filter.matches(&created("0x02", OWNER, None)));
+        assert!(!filter.matches(&created("0x03", PAYER, Some(PAYER))));
+    }
+
+    #[test]
+    fn test_owner_filter_ignores_stored_case() {
+        let filter = EventFilter {
+            owner: Some("0xabcdef0000000000000000000000000000000000".to_string()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&created("0x01", "0xABCDEF0000000000000000000000000000000000", None)));
+    }
+
+    #[test]
+    fn test_owner_and_payer_filter_batches() {
+        let batch = |owner: &str, payer: Option<&str>| BatchInfo {
+            owner: owner.to_string(),
+            payer: payer.map(str::to_string),
+            contract_source: "StampsRegistry".to_string(),
+            block_number: 1,
+            ..BatchInfo::test_batch(1, 20, "500")
+        };
+        let filter = EventFilter {
+            payer: Some(PAYER.to_string()),
+            ..Default::default()
+        };
+
+        assert!(filter.matches_batch(&batch(OWNER, Some(PAYER))));
+        assert!(!filter.matches_batch(&batch(OWNER, None)));
+    }
+
+    #[test]
+    fn test_apply_retains_matching_events() {
+        let filter = EventFilter {
+            event_type: Some(FilterEventType::BatchTopUp),
+            contract: Some(FilterContract::PostageStamp),
+            ..Default::default()
+        };
+        let mut events = vec![
+            event("BatchTopUp", "0x01", "PostageStamp"),
+            event("BatchCreated", "0x02", "PostageStamp"),
+            event("BatchTopUp", "0x03", "StampsRegistry"),
+            event("BatchTopUp", "0x04", "PostageStamp"),
+        ];
+
+        filter.apply(&mut events);
+
+        let ids: Vec<_> = events.iter().filter_map(|e| e.batch_id.as_deref()).collect();
+        assert_eq!(ids, vec!["0x01", "0x04"]);
+    }
+
+    #[test]
+    fn test_combined_filters() {
+        let filter = EventFilter {
+            event_type: Some(FilterEventType::BatchTopUp),
+            batch_id_contains: Some("12".to_string()),
+            contract: Some(FilterContract::StampsRegistry),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&event("BatchTopUp", "0x1234", "StampsRegistry")));
+        assert!(!filter.matches(&event("BatchCreated", "0x1234", "StampsRegistry")));
+        assert!(!filter.matches(&event("BatchTopUp", "0xabcd", "StampsRegistry")));
+        assert!(!filter.matches(&event("BatchTopUp", "0x1234", "PostageStamp")));
+    }
+
+    #[test]
+    fn test_deserialize_from_query_values() {
+        let filter: EventFilter = serde_json::from_value(json!({
+            "event_type": "batch-depth-increase",
+            "contract": "postage-stamp"
+        }))
+        .unwrap();
+
+        assert!(matches!(filter.event_type, Some(FilterEventType::BatchDepthIncrease)));
+        assert!(matches!(filter.contract, Some(FilterContract::PostageStamp)));
+        assert!(filter.batch_id_contains.is_none());
+    }
+}
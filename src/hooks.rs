@@ -1,12 +1,49 @@
+use crate::error::{Result, StampError};
 use crate::events::StampEvent;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Maximum number of events queued for webhook or broker delivery before new ones are dropped
+const WEBHOOK_QUEUE_CAPACITY: usize = 1024;
+
+/// Limits applied to webhook delivery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookConfig {
+    /// Timeout for each POST request, including reading the response
+    pub timeout: Duration,
+    /// Maximum number of events waiting for delivery before new ones are dropped
+    pub queue_capacity: usize,
+    /// Maximum JSON body size in bytes; larger events are dropped
+    pub max_body_bytes: usize,
+    /// Number of concurrent delivery tasks
+    pub workers: usize,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            queue_capacity: WEBHOOK_QUEUE_CAPACITY,
+            max_body_bytes: 64 * 1024,
+            workers: 4,
+        }
+    }
+}
 
 /// Event hook trait for handling new events
 ///
 /// Implementations can filter events by contract source and provide
 /// contract-specific behavior
+#[async_trait::async_trait]
 pub trait EventHook: Send + Sync {
     /// Called when a new event is detected from any contract
-    fn on_event(&self, event: &StampEvent);
+    async fn on_event(&self, event: &StampEvent) -> Result<()>;
 
     /// Called when a new event is detected from PostageStamp contract
     fn on_postage_stamp_event(&self, event: &StampEvent) {
@@ -25,13 +62,19 @@ pub trait EventHook: Send + Sync {
             event.block_number
         );
     }
+
+    /// Called once when the follow loop stops
+    ///
+    /// Hooks that deliver in the background wait here for queued events to finish.
+    async fn shutdown(self: Box<Self>) {}
 }
 
 /// Default stub hook implementation that routes events to contract-specific handlers
 pub struct StubHook;
 
+#[async_trait::async_trait]
 impl EventHook for StubHook {
-    fn on_event(&self, event: &StampEvent) {
+    async fn on_event(&self, event: &StampEvent) -> Result<()> {
         tracing::debug!(
             "Hook invoked for {} event: {} at block {}",
             event.contract_source,
@@ -45,6 +88,7 @@ impl EventHook for StubHook {
             "StampsRegistry" => self.on_stamps_registry_event(event),
             _ => tracing::warn!("Unknown contract source: {}", event.contract_source),
         }
+        Ok(())
     }
 
     fn on_postage_stamp_event(&self, event: &StampEvent) {
@@ -70,14 +114,374 @@ impl EventHook for StubHook {
     }
 }
 
+/// Hook that forwards every event as JSON to an HTTP endpoint
+///
+/// `on_event` only enqueues the event; a small pool of background tasks shares the
+/// HTTP client and performs the POST requests, so a slow endpoint never blocks the
+/// poll loop. Each request is bounded by `WebhookConfig::timeout`. When the bounded
+/// queue is full, or an event exceeds the body size limit, it is dropped with a warning.
+/// Other JSON payloads, such as price alerts, can be queued with `enqueue`.
+pub struct WebhookHook {
+    url: String,
+    sender: mpsc::Sender<WebhookPayload>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+/// A serialized webhook body, described for log messages
+struct WebhookPayload {
+    description: String,
+    body: Vec<u8>,
+}
+
+impl WebhookHook {
+    /// Create a webhook hook with the given limits and spawn its delivery tasks on the current tokio runtime
+    ///
+    /// Fails if the HTTP client cannot be built, e.g. when no TLS backend is available.
+    pub fn with_config(url: impl Into<String>, config: WebhookConfig) -> Result<Self> {
+        let url = url.into();
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| StampError::Config(format!("Failed to build webhook client for {url}: {e}")))?;
+        let (sender, receiver) = mpsc::channel::<WebhookPayload>(config.queue_capacity.max(1));
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+
+        let workers = (0..config.workers.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                let client = client.clone();
+                let target = url.clone();
+                let max_body_bytes = config.max_body_bytes;
+
+                tokio::spawn(async move {
+                    loop {
+                        // Hold the lock only while waiting, so other workers can deliver concurrently
+                        let Some(payload) = receiver.lock().await.recv().await else {
+                            break;
+                        };
+                        deliver_webhook(&client, &target, payload, max_body_bytes).await;
+                    }
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            url,
+            sender,
+            workers,
+        })
+    }
+
+    /// Target URL events are posted to
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Queue any JSON payload for delivery; `description` identifies it in log messages
+    ///
+    /// Like `on_event`, this never waits: the payload is dropped with a warning when
+    /// the queue is full.
+    pub fn enqueue<T: serde::Serialize>(&self, payload: &T, description: String) {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!("Failed to serialize webhook {}: {}", description, e);
+                return;
+            }
+        };
+
+        if let Err(e) = self.sender.try_send(WebhookPayload { description, body }) {
+            let reason = e.to_string();
+            tracing::warn!("Dropping webhook {}: {}", e.into_inner().description, reason);
+        }
+    }
+
+    /// Stop accepting events and wait for all queued deliveries to finish
+    pub async fn close(self) {
+        drop(self.sender);
+        for worker in self.workers {
+            if let Err(e) = worker.await {
+                tracing::warn!("Webhook worker terminated abnormally: {}", e);
+            }
+        }
+    }
+}
+
+/// POST one payload to the webhook, logging failures and oversized bodies
+async fn deliver_webhook(client: &reqwest::Client, url: &str, payload: WebhookPayload, max_body_bytes: usize) {
+    let WebhookPayload { description, body } = payload;
+
+    if body.len() > max_body_bytes {
+        tracing::warn!(
+            "Dropping webhook {}: body of {} bytes exceeds the {} byte limit",
+            description,
+            body.len(),
+            max_body_bytes
+        );
+        return;
+    }
+
+    let result = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+
+    if let Err(e) = result {
+        tracing::warn!("Webhook delivery failed for {}: {}", description, e);
+    }
+}
+
+#[async_trait::async_trait]
+impl EventHook for WebhookHook {
+    async fn on_event(&self, event: &StampEvent) -> Result<()> {
+        // A full queue drops the event rather than failing the other hooks
+        self.enqueue(
+            event,
+            format!("event {} at block {}", event.event_type, event.block_number),
+        );
+        Ok(())
+    }
+
+    async fn shutdown(self: Box<Self>) {
+        self.close().await;
+    }
+}
+
+/// Hook that appends every event as a JSON line to a daily log file
+///
+/// Events are written to `<dir>/events-YYYY-MM-DD.jsonl`, rotating to a new file
+/// when the UTC date changes. Each line is flushed immediately so the log survives
+/// a crash independently of the cache database. Writes run on tokio's blocking
+/// thread pool so disk I/O never stalls the poll loop.
+#[derive(Clone)]
+pub struct FileHook {
+    dir: PathBuf,
+    current: Arc<Mutex<Option<(NaiveDate, File)>>>,
+    /// Source of the current time, which picks the log file
+    clock: fn() -> DateTime<Utc>,
+}
+
+impl FileHook {
+    /// Create a file hook writing into `dir`, creating the directory if needed
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            current: Arc::new(Mutex::new(None)),
+            clock: Utc::now,
+        })
+    }
+
+    /// Use `clock` instead of the system time to pick the log file
+    #[cfg(test)]
+    fn with_clock(mut self, clock: fn() -> DateTime<Utc>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Directory the event logs are written to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the log file for a given UTC date
+    pub fn log_path(&self, date: NaiveDate) -> PathBuf {
+        self.dir.join(format!("events-{}.jsonl", date.format("%Y-%m-%d")))
+    }
+
+    /// Append an event to the log file for the date of `now`
+    fn write_event_at(&self, event: &StampEvent, now: DateTime<Utc>) -> Result<()> {
+        let date = now.date_naive();
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Rotate when the date changes (or on first write)
+        if current.as_ref().is_none_or(|(open_date, _)| *open_date != date) {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.log_path(date))?;
+            *current = Some((date, file));
+        }
+
+        if let Some((_, file)) = current.as_mut() {
+            let line = serde_json::to_string(event)?;
+            writeln!(file, "{line}")?;
+            file.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl EventHook for FileHook {
+    async fn on_event(&self, event: &StampEvent) -> Result<()> {
+        let hook = self.clone();
+        let owned = event.clone();
+        let now = (self.clock)();
+        let written = tokio::task::spawn_blocking(move || hook.write_event_at(&owned, now))
+            .await
+            .map_err(|e| StampError::Hook(format!("event log writer panicked: {e}")))
+            .and_then(|result| result);
+
+        written.map_err(|e| {
+            StampError::Hook(format!(
+                "failed to append {} event at block {} to event log: {}",
+                event.event_type, event.block_number, e
+            ))
+        })
+    }
+}
+
+/// Hook that publishes every event as JSON to a NATS subject
+///
+/// Like `WebhookHook`, publishing happens on a background task fed by a bounded
+/// queue; failures are logged and never stall the poll loop. Only available when
+/// built with the `broker` feature.
+#[cfg(feature = "broker")]
+pub struct BrokerHook {
+    subject: String,
+    sender: mpsc::Sender<StampEvent>,
+    publisher: JoinHandle<()>,
+}
+
+#[cfg(feature = "broker")]
+impl BrokerHook {
+    /// Connect to the NATS server at `url` and spawn the publishing task
+    pub async fn connect(url: &str, subject: impl Into<String>) -> Result<Self> {
+        let subject = subject.into();
+        let client = async_nats::connect(url).await.map_err(|e| {
+            StampError::Config(format!("Failed to connect to broker {url}: {e}"))
+        })?;
+        let (sender, mut receiver) = mpsc::channel::<StampEvent>(WEBHOOK_QUEUE_CAPACITY);
+        let target = subject.clone();
+
+        let publisher = tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                let payload = match serde_json::to_vec(&event) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        tracing::warn!("Failed to serialize event for broker: {}", e);
+                        continue;
+                    }
+                };
+
+                if let Err(e) = client.publish(target.clone(), payload.into()).await {
+                    tracing::warn!(
+                        "Broker publish failed for {} at block {}: {}",
+                        event.event_type,
+                        event.block_number,
+                        e
+                    );
+                }
+            }
+
+            if let Err(e) = client.flush().await {
+                tracing::warn!("Failed to flush broker connection: {}", e);
+            }
+        });
+
+        Ok(Self {
+            subject,
+            sender,
+            publisher,
+        })
+    }
+
+    /// Subject events are published to
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Stop accepting events and wait for queued events to be published and flushed
+    pub async fn close(self) {
+        drop(self.sender);
+        if let Err(e) = self.publisher.await {
+            tracing::warn!("Broker publisher terminated abnormally: {}", e);
+        }
+    }
+}
+
+#[cfg(feature = "broker")]
+#[async_trait::async_trait]
+impl EventHook for BrokerHook {
+    async fn on_event(&self, event: &StampEvent) -> Result<()> {
+        // A full queue drops the event rather than failing the other hooks
+        if let Err(e) = self.sender.try_send(event.clone()) {
+            tracing::warn!(
+                "Dropping broker event {} at block {}: {}",
+                event.event_type,
+                event.block_number,
+                e
+            );
+        }
+        Ok(())
+    }
+    async fn shutdown(self: Box<Self>) {
+        self.close().await;
+    }
+}
+
+/// Set of hooks that every new event is fanned out to
+///
+/// Hooks run in registration order. A failing hook does not stop the
+/// remaining ones; their errors are collected and reported together.
+#[derive(Default)]
+pub struct HookRegistry {
+    hooks: Vec<Box<dyn EventHook>>,
+}
+
+impl HookRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a hook; it runs after the hooks registered before it
+    pub fn register(&mut self, hook: Box<dyn EventHook>) {
+        self.hooks.push(hook);
+    }
+
+    /// Shut down every hook in registration order, waiting for queued deliveries
+    pub async fn close(self) {
+        for hook in self.hooks {
+            hook.shutdown().await;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventHook for HookRegistry {
+    async fn on_event(&self, event: &StampEvent) -> Result<()> {
+        let mut errors = Vec::new();
+        for hook in &self.hooks {
+            match hook.on_event(event).await {
+                Ok(()) => {}
+                Err(StampError::Hook(msg)) => errors.push(msg),
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(StampError::Hook(errors.join("; ")))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::events::{EventData, EventType};
-    use chrono::Utc;
+    use chrono::{TimeZone, Utc};
 
-    #[test]
-    fn test_stub_hook_postage_stamp() {
+    #[tokio::test]
+    async fn test_stub_hook_postage_stamp() {
         let hook = StubHook;
         let event = StampEvent {
             event_type: EventType::BatchCreated,
@@ -88,6 +492,7 @@ mod tests {
             log_index: 0,
             contract_source: "PostageStamp".to_string(),
             contract_address: None,
+            block_hash: None,
             data: EventData::BatchCreated {
                 total_amount: "1000000000000000000".to_string(),
                 normalised_balance: "500000000000000000".to_string(),
@@ -99,12 +504,11 @@ mod tests {
             },
         };
 
-        // Should not panic
-        hook.on_event(&event);
+        hook.on_event(&event).await.unwrap();
     }
 
-    #[test]
-    fn test_stub_hook_stamps_registry() {
+    #[tokio::test]
+    async fn test_stub_hook_stamps_registry() {
         let hook = StubHook;
         let event = StampEvent {
             event_type: EventType::BatchCreated,
@@ -115,6 +519,7 @@ mod tests {
             log_index: 0,
             contract_source: "StampsRegistry".to_string(),
             contract_address: None,
+            block_hash: None,
             data: EventData::BatchCreated {
                 total_amount: "2000000000000000000".to_string(),
                 normalised_balance: "1000000000000000000".to_string(),
@@ -126,7 +531,232 @@ mod tests {
             },
         };
 
-        // Should not panic
-        hook.on_event(&event);
+        hook.on_event(&event).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_webhook_hook_posts_event() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/events")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "event_type": "BatchTopUp",
+                "batch_id": "0x1234",
+                "block_number": 3000,
+                "data": {
+                    "type": "BatchTopUp",
+                    "topup_amount": "100000000000000000"
+                }
+            })))
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let hook = WebhookHook::with_config(format!("{}/events", server.url()), WebhookConfig::default()).unwrap();
+        hook.on_event(&StampEvent::test_topup("0xbeef", 1, 3000))
+        .await
+        .unwrap();
+        hook.close().await;
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_file_hook_rotates_on_date_change() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log_dir = temp_dir.path().join("nested").join("logs");
+        let hook = FileHook::new(&log_dir).unwrap();
+        assert!(log_dir.is_dir());
+
+        let event = StampEvent::test_topup("0xbeef", 1, 3000);
+
+        let before_midnight = Utc.with_ymd_and_hms(2025, 3, 1, 23, 59, 0).unwrap();
+        let after_midnight = Utc.with_ymd_and_hms(2025, 3, 2, 0, 1, 0).unwrap();
+
+        hook.write_event_at(&event, before_midnight).unwrap();
+        hook.write_event_at(&event, before_midnight).unwrap();
+        hook.write_event_at(&event, after_midnight).unwrap();
+
+        let day1 = std::fs::read_to_string(hook.log_path(before_midnight.date_naive())).unwrap();
+        let day2 = std::fs::read_to_string(hook.log_path(after_midnight.date_naive())).unwrap();
+        assert_eq!(day1.lines().count(), 2);
+        assert_eq!(day2.lines().count(), 1);
+        assert!(log_dir.join("events-2025-03-01.jsonl").exists());
+
+        for line in day1.lines().chain(day2.lines()) {
+            let parsed: StampEvent = serde_json::from_str(line).unwrap();
+            assert_eq!(parsed.block_number, 3000);
+        }
+        assert_eq!(std::fs::read_dir(&log_dir).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_file_hook_on_event_appends_line() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let hook = FileHook::new(temp_dir.path())
+            .unwrap()
+            .with_clock(|| Utc.with_ymd_and_hms(2025, 3, 1, 23, 59, 59).unwrap());
+
+        hook.on_event(&StampEvent::test_topup("0xbeef", 1, 4000)).await.unwrap();
+        hook.on_event(&StampEvent::test_topup("0xbeef", 1, 4001)).await.unwrap();
+
+        let log = std::fs::read_to_string(temp_dir.path().join("events-2025-03-01.jsonl")).unwrap();
+        assert_eq!(log.lines().count(), 2);
+    }
+
+    /// Records the block number of every event it sees, optionally failing afterwards
+    struct RecordingHook {
+        seen: Arc<Mutex<Vec<u64>>>,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl EventHook for RecordingHook {
+        async fn on_event(&self, event: &StampEvent) -> Result<()> {
+            self.seen.lock().unwrap().push(event.block_number);
+            if self.fail {
+                return Err(StampError::Hook(format!("rejected block {}", event.block_number)));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_slow_endpoint_does_not_block() {
+        // Accept connections but never answer until released, like a hung endpoint
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (release, mut released) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            loop {
+                tokio::select! {
+                    _ = &mut released => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => connections.push(stream),
+                        Err(_) => break,
+                    },
+                }
+            }
+        });
+
+        let hook = WebhookHook::with_config(
+            format!("http://{addr}/events"),
+            WebhookConfig {
+                timeout: Duration::from_secs(60),
+                queue_capacity: 4,
+                max_body_bytes: 64 * 1024,
+                workers: 2,
+            },
+        )
+        .unwrap();
+
+        // A burst far larger than the queue is absorbed while the endpoint is still hung;
+        // if enqueueing waited on delivery this would never finish
+        tokio::time::timeout(Duration::from_secs(5), async {
+            for block in 0..500 {
+                hook.on_event(&StampEvent::test_topup("0xbeef", 1, block)).await.unwrap();
+            }
+        })
+        .await
+        .expect("events should be queued without waiting for the endpoint");
+
+        // Once the endpoint goes away, only the queued events are attempted
+        release.send(()).unwrap();
+        server.await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), hook.close())
+            .await
+            .expect("webhook workers should finish once the endpoint is gone");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_drops_oversized_body() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/events")
+            .with_status(200)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let hook = WebhookHook::with_config(
+            format!("{}/events", server.url()),
+            WebhookConfig {
+                max_body_bytes: 16,
+                ..WebhookConfig::default()
+            },
+        )
+        .unwrap();
+        hook.on_event(&StampEvent::test_topup("0xbeef", 1, 3000)).await.unwrap();
+        hook.close().await;
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_hook_registry_fans_out_past_failures() {
+        let first = Arc::new(Mutex::new(Vec::new()));
+        let second = Arc::new(Mutex::new(Vec::new()));
+
+        let mut registry = HookRegistry::new();
+        registry.register(Box::new(RecordingHook {
+            seen: Arc::clone(&first),
+            fail: true,
+        }));
+        registry.register(Box::new(RecordingHook {
+            seen: Arc::clone(&second),
+            fail: false,
+        }));
+
+        for block in [100, 101, 102] {
+            let err = registry.on_event(&StampEvent::test_topup("0xbeef", 1, block)).await.unwrap_err();
+            assert!(matches!(&err, StampError::Hook(msg) if msg == &format!("rejected block {block}")));
+        }
+
+        // The failing hook ran first, yet the second still saw every event
+        assert_eq!(*first.lock().unwrap(), vec![100, 101, 102]);
+        assert_eq!(*second.lock().unwrap(), vec![100, 101, 102]);
+    }
+
+    #[tokio::test]
+    async fn test_hook_registry_close_drains_webhook_queue() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/events")
+            .with_status(200)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let mut registry = HookRegistry::new();
+        let webhook = WebhookHook::with_config(format!("{}/events", server.url()), WebhookConfig::default()).unwrap();
+        registry.register(Box::new(webhook));
+        for block in [100, 101, 102] {
+            registry.on_event(&StampEvent::test_topup("0xbeef", 1, block)).await.unwrap();
+        }
+        registry.close().await;
+
+        // Every queued event was delivered before close returned
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_webhook_enqueue_posts_other_payloads() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/alerts")
+            .match_body(mockito::Matcher::Json(serde_json::json!({"change_pct": 12.5})))
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let hook = WebhookHook::with_config(format!("{}/alerts", server.url()), WebhookConfig::default()).unwrap();
+        hook.enqueue(&serde_json::json!({"change_pct": 12.5}), "price alert".to_string());
+        hook.close().await;
+
+        mock.assert_async().await;
     }
 }
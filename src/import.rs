@@ -0,0 +1,344 @@
+use crate::blockchain::batch_info_from_events;
+use crate::cache::Cache;
+use crate::error::{Result, StampError};
+use crate::events::{EventData, EventType, StampEvent};
+use crate::export::ExportFormat;
+use crate::types::BatchId;
+use chrono::{DateTime, Utc};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Events read back from an export, plus the rows that could not be parsed
+#[derive(Debug)]
+pub struct ImportedEvents {
+    pub events: Vec<StampEvent>,
+    /// Malformed rows that were skipped
+    pub skipped: usize,
+}
+
+/// Counts reported by [`import_events`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub events: usize,
+    pub batches: usize,
+    pub skipped: usize,
+}
+
+/// Read events from a file written by `export --data-type events`
+///
+/// Rows that do not deserialize into a `StampEvent`, or whose batch ID is not
+/// 32 bytes of hex, are skipped with a warning and counted; a file that cannot be read or is not a JSON array at all is an
+/// error. Extra columns such as the `*_bzz` amounts from `--normalise-amounts`
+/// are ignored.
+pub fn read_events<P: AsRef<Path>>(path: P, format: ExportFormat) -> Result<ImportedEvents> {
+    match format {
+        ExportFormat::Csv => read_events_csv(path),
+        ExportFormat::Json => read_events_json(path),
+        ExportFormat::Ndjson => read_events_ndjson(path),
+    }
+}
+
+/// Import exported events into the cache
+///
+/// Stores the events and rebuilds the batch records from their BatchCreated
+/// events. Events already cached are replaced, so importing the same file
+/// twice is harmless.
+pub async fn import_events<P: AsRef<Path>>(
+    cache: &Cache,
+    path: P,
+    format: ExportFormat,
+) -> Result<ImportSummary> {
+    let imported = read_events(path, format)?;
+    let batches = batch_info_from_events(&imported.events);
+
+    cache.store_events(&imported.events).await?;
+    cache.store_batches(&batches).await?;
+
+    Ok(ImportSummary {
+        events: imported.events.len(),
+        batches: batches.len(),
+        skipped: imported.skipped,
+    })
+}
+
+fn read_events_json<P: AsRef<Path>>(path: P) -> Result<ImportedEvents> {
+    let file = File::open(path)?;
+    let rows: Vec<serde_json::Value> = serde_json::from_reader(BufReader::new(file))?;
+    Ok(collect_rows(rows.into_iter().enumerate().map(|(i, row)| {
+        let parsed = serde_json::from_value(row).map_err(StampError::from);
+        (i + 1, parsed.and_then(normalize_batch_id))
+    })))
+}
+
+fn read_events_ndjson<P: AsRef<Path>>(path: P) -> Result<ImportedEvents> {
+    let file = File::open(path)?;
+    let mut rows = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = serde_json::from_str(&line).map_err(StampError::from);
+        rows.push((i + 1, parsed.and_then(normalize_batch_id)));
+    }
+    Ok(collect_rows(rows))
+}
+
+fn read_events_csv<P: AsRef<Path>>(path: P) -> Result<ImportedEvents> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| StampError::Parse(format!("CSV import: missing column '{name}'")))
+    };
+    let columns = CsvColumns {
+        block_number: column("block_number")?,
+        timestamp: column("timestamp")?,
+        event_type: column("event_type")?,
+        batch_id: column("batch_id")?,
+        transaction_hash: column("transaction_hash")?,
+        log_index: column("log_index")?,
+        details: column("details")?,
+    };
+
+    let rows: Vec<_> = reader
+        .records()
+        .enumerate()
+        .map(|(i, record)| {
+            // Line 1 is the header
+            let parsed = record
+                .map_err(StampError::from)
+                .and_then(|record| columns.parse(&record));
+            (i + 2, parsed)
+        })
+        .collect();
+    Ok(collect_rows(rows))
+}
+
+/// Positions of the event CSV columns, located by header name
+struct CsvColumns {
+    block_number: usize,
+    timestamp: usize,
+    event_type: usize,
+    batch_id: usize,
+    transaction_hash: usize,
+    log_index: usize,
+    details: usize,
+}
+
+impl CsvColumns {
+    /// Rebuild an event from one CSV row
+    ///
+    /// The CSV layout has no contract columns: `contract_source` is inferred
+    /// from the `payer` field, which only StampsRegistry events carry, and
+    /// `contract_address` and `block_hash` are left unset.
+    fn parse(&self, record: &csv::StringRecord) -> Result<StampEvent> {
+        let field = |index: usize| record.get(index).unwrap_or_default();
+
+        let block_number = field(self.block_number)
+            .parse()
+            .map_err(|e| StampError::Parse(format!("block_number: {e}")))?;
+        let block_timestamp = DateTime::parse_from_rfc3339(field(self.timestamp))
+            .map_err(|e| StampError::Parse(format!("timestamp: {e}")))?
+            .with_timezone(&Utc);
+        let event_type: EventType =
+            serde_json::from_value(serde_json::Value::String(field(self.event_type).to_string()))?;
+        let log_index = field(self.log_index)
+            .parse()
+            .map_err(|e| StampError::Parse(format!("log_index: {e}")))?;
+        let data: EventData = serde_json::from_str(field(self.details))?;
+
+        let batch_id = match field(self.batch_id) {
+            "" | "N/A" => None,
+            id => Some(BatchId::new(id)?.as_str().to_string()),
+        };
+        let contract_source = match &data {
+            EventData::BatchCreated { payer: Some(_), .. }
+            | EventData::BatchTopUp { payer: Some(_), .. }
+            | EventData::BatchDepthIncrease { payer: Some(_), .. } => "StampsRegistry",
+            _ => "PostageStamp",
+        };
+
+        Ok(StampEvent {
+            event_type,
+            batch_id,
+            block_number,
+            block_timestamp,
+            transaction_hash: field(self.transaction_hash).to_string(),
+            log_index,
+            contract_source: contract_source.to_string(),
+            contract_address: None,
+            block_hash: None,
+            data,
+        })
+    }
+}
+
+/// Validate an event's batch ID, storing it in its normalized lowercase form
+fn normalize_batch_id(mut event: StampEvent) -> Result<StampEvent> {
+    if let Some(batch_id) = &event.batch_id {
+        event.batch_id = Some(BatchId::new(batch_id)?.as_str().to_string());
+    }
+    Ok(event)
+}
+
+/// Keep the rows that parsed into a valid event, logging and counting the rest
+fn collect_rows(rows: impl IntoIterator<Item = (usize, Result<StampEvent>)>) -> ImportedEvents {
+    let mut events = Vec::new();
+    let mut skipped = 0;
+    for (row, parsed) in rows {
+        match parsed {
+            Ok(event) => events.push(event),
+            Err(e) => {
+                tracing::warn!("Skipping malformed row {}: {}", row, e);
+                skipped += 1;
+            }
+        }
+    }
+    ImportedEvents { events, skipped }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::export_events;
+    use chrono::TimeZone;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const BATCH_ID: &str = "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef";
+
+    fn sample_events() -> Vec<StampEvent> {
+        let created = StampEvent {
+            event_type: EventType::BatchCreated,
+            batch_id: Some(BATCH_ID.to_string()),
+            block_number: 1000,
+            block_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            transaction_hash: "0xaaaa".to_string(),
+            log_index: 0,
+            contract_source: "StampsRegistry".to_string(),
+            contract_address: None,
+            block_hash: None,
+            data: EventData::BatchCreated {
+                total_amount: "12345678901234567890".to_string(),
+                normalised_balance: "500000000000000000".to_string(),
+                owner: "0xowner".to_string(),
+                depth: 20,
+                bucket_depth: 16,
+                immutable_flag: false,
+                payer: Some("0xpayer".to_string()),
+            },
+        };
+        let topup = StampEvent {
+            event_type: EventType::BatchTopUp,
+            block_number: 1010,
+            transaction_hash: "0xbbbb".to_string(),
+            log_index: 3,
+            contract_source: "PostageStamp".to_string(),
+            data: EventData::BatchTopUp {
+                topup_amount: "1000".to_string(),
+                normalised_balance: "600000000000000000".to_string(),
+                payer: None,
+            },
+            ..created.clone()
+        };
+        vec![created, topup]
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let events = sample_events();
+
+        for format in [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Ndjson] {
+            let export_file = NamedTempFile::new().unwrap();
+            export_events(&events, export_file.path(), format.clone(), true, false).unwrap();
+
+            let db_file = NamedTempFile::new().unwrap();
+            let cache = Cache::new(db_file.path()).await.unwrap();
+            let summary = import_events(&cache, export_file.path(), format.clone()).await.unwrap();
+            assert_eq!(summary, ImportSummary { events: 2, batches: 1, skipped: 0 }, "{format:?}");
+
+            let stored = cache.get_events(0).await.unwrap();
+            assert_eq!(stored.len(), 2, "{format:?}");
+            let created = stored.iter().find(|e| e.transaction_hash == "0xaaaa").unwrap();
+            assert_eq!(created.block_number, 1000);
+            assert_eq!(created.block_timestamp, events[0].block_timestamp);
+            assert_eq!(created.contract_source, "StampsRegistry");
+            assert!(matches!(
+                &created.data,
+                EventData::BatchCreated { total_amount, payer: Some(payer), depth: 20, .. }
+                    if total_amount == "12345678901234567890" && payer == "0xpayer"
+            ));
+
+            let batches = cache.get_batches(0).await.unwrap();
+            assert_eq!(batches.len(), 1);
+            assert_eq!(batches[0].owner, "0xowner");
+        }
+    }
+
+    #[test]
+    fn test_malformed_rows_are_skipped() {
+        let events = sample_events();
+        let export_file = NamedTempFile::new().unwrap();
+        export_events(&events, export_file.path(), ExportFormat::Csv, false, false).unwrap();
+        let mut file = std::fs::OpenOptions::new().append(true).open(export_file.path()).unwrap();
+        writeln!(file, "not-a-block,2025-01-01T00:00:00+00:00,BatchTopUp,0x1234,0xcccc,0,\"{{}}\"").unwrap();
+        writeln!(file, "1020,2025-01-01T00:00:00+00:00,NoSuchEvent,0x1234,0xdddd,0,\"{{}}\"").unwrap();
+        // Parses, but the batch ID is too short to be real
+        let topup = r#"{""type"":""BatchTopUp"",""topup_amount"":""1"",""normalised_balance"":""1"",""payer"":null}"#;
+        writeln!(file, "1030,2025-01-01T00:00:00+00:00,BatchTopUp,0x1234,0xeeee,0,\"{topup}\"").unwrap();
+
+        let imported = read_events(export_file.path(), ExportFormat::Csv).unwrap();
+        assert_eq!(imported.events.len(), 2);
+        assert_eq!(imported.skipped, 3);
+        // Without a payer the source cannot be told apart from PostageStamp
+        assert_eq!(imported.events[1].contract_source, "PostageStamp");
+
+        let json_file = NamedTempFile::new().unwrap();
+        std::fs::write(json_file.path(), r#"[{"event_type":"BatchTopUp"}]"#).unwrap();
+        let imported = read_events(json_file.path(), ExportFormat::Json).unwrap();
+        assert!(imported.events.is_empty());
+        assert_eq!(imported.skipped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rows_with_malformed_batch_ids_are_not_stored() {
+        let mut events = sample_events();
+        events[1].batch_id = Some(BATCH_ID.to_uppercase().replace("0X", ""));
+        let mut bad = events[1].clone();
+        bad.batch_id = Some("0x1234".to_string());
+        bad.transaction_hash = "0xffff".to_string();
+        events.push(bad);
+
+        for format in [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Ndjson] {
+            let export_file = NamedTempFile::new().unwrap();
+            export_events(&events, export_file.path(), format.clone(), false, false).unwrap();
+
+            let db_file = NamedTempFile::new().unwrap();
+            let cache = Cache::new(db_file.path()).await.unwrap();
+            let summary = import_events(&cache, export_file.path(), format.clone()).await.unwrap();
+            assert_eq!(summary, ImportSummary { events: 2, batches: 1, skipped: 1 }, "{format:?}");
+
+            // The bad row is never stored and the good IDs are stored normalized
+            let stored = cache.get_events(0).await.unwrap();
+            assert_eq!(stored.len(), 2, "{format:?}");
+            assert!(stored.iter().all(|e| e.batch_id.as_deref() == Some(BATCH_ID)), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_malformed_batch_id_does_not_fail_batch_rebuild() {
+        let mut events = sample_events();
+        let mut bad = events[0].clone();
+        bad.batch_id = Some("0x1234".to_string());
+        events.push(bad);
+
+        // The valid BatchCreated event still yields its batch
+        let batches = batch_info_from_events(&events);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].batch_id.as_str(), BATCH_ID);
+    }
+}
@@ -14,9 +14,13 @@ pub mod display;
 pub mod error;
 pub mod events;
 pub mod export;
+pub mod filter;
 pub mod hooks;
+pub mod import;
+pub mod metrics;
 pub mod price;
 pub mod retry;
+pub mod server;
 pub mod types;
 
 // Re-export commonly used types
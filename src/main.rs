@@ -9,23 +9,30 @@ mod display;
 mod error;
 mod events;
 mod export;
+mod filter;
 mod hooks;
+mod import;
+mod metrics;
 mod price;
 mod retry;
+mod server;
 mod types;
 
-use anyhow::Result;
 use clap::Parser;
+use std::process::ExitCode;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     // Parse CLI arguments first to get verbose flag
     let cli = cli::Cli::parse();
 
     // Initialize tracing with appropriate log level
     let default_level = if cli.verbose {
         "beeport_stamp_stats=debug"
+    } else if cli.explain_sql {
+        // Query logs are debug-level so parameter values never reach info output
+        "beeport_stamp_stats=info,beeport_stamp_stats::cache=debug"
     } else {
         "beeport_stamp_stats=info"
     };
@@ -35,9 +42,13 @@ async fn main() -> Result<()> {
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| default_level.into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(cli::log_layer(cli.log_format, std::io::stderr))
         .init();
 
-    // Execute the command
-    cli.execute().await
+    // Execute the command, reporting any error before exiting
+    let result = cli.execute().await;
+    if let Err(e) = &result {
+        eprintln!("Error: {e}");
+    }
+    ExitCode::from(cli::exit_code(&result))
 }
@@ -0,0 +1,165 @@
+//! Prometheus metrics for long-running follow mode
+//!
+//! Follow mode creates one shared [`Metrics`] handle and passes it to the
+//! blockchain client and retry policy, which record into it from the fetch,
+//! parse and retry paths. The registry is only exposed over HTTP when follow
+//! mode is started with `--metrics-port`.
+
+use crate::error::Result;
+use axum::{Router, extract::State, http::header, response::IntoResponse, routing::get};
+use prometheus::{Encoder, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Collection of all exported metrics
+pub struct Metrics {
+    registry: Registry,
+    events_total: IntCounterVec,
+    rpc_requests_total: IntCounter,
+    rpc_retries_total: IntCounter,
+    last_synced_block: IntGauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metrics").finish_non_exhaustive()
+    }
+}
+
+impl Metrics {
+    /// Create a fresh set of metrics in its own registry
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let events_total = IntCounterVec::new(
+            Opts::new("beeport_events_total", "Number of events fetched from the chain"),
+            &["event_type", "contract"],
+        )
+        .expect("valid metric definition");
+        let rpc_requests_total =
+            IntCounter::new("beeport_rpc_requests_total", "Number of RPC requests sent")
+                .expect("valid metric definition");
+        let rpc_retries_total = IntCounter::new(
+            "beeport_rpc_retries_total",
+            "Number of RPC requests retried after a transient error",
+        )
+        .expect("valid metric definition");
+        let last_synced_block = IntGauge::new(
+            "beeport_last_synced_block",
+            "Highest block processed by follow mode",
+        )
+        .expect("valid metric definition");
+
+        registry
+            .register(Box::new(events_total.clone()))
+            .expect("metric registered once");
+        registry
+            .register(Box::new(rpc_requests_total.clone()))
+            .expect("metric registered once");
+        registry
+            .register(Box::new(rpc_retries_total.clone()))
+            .expect("metric registered once");
+        registry
+            .register(Box::new(last_synced_block.clone()))
+            .expect("metric registered once");
+
+        Self {
+            registry,
+            events_total,
+            rpc_requests_total,
+            rpc_retries_total,
+            last_synced_block,
+        }
+    }
+
+    /// Count a fetched event, labelled by event type and contract
+    pub fn record_event(&self, event_type: &str, contract: &str) {
+        self.events_total.with_label_values(&[event_type, contract]).inc();
+    }
+
+    /// Count an RPC request attempt
+    pub fn record_rpc_request(&self) {
+        self.rpc_requests_total.inc();
+    }
+
+    /// Count an RPC retry
+    pub fn record_rpc_retry(&self) {
+        self.rpc_retries_total.inc();
+    }
+
+    /// Update the last synced block gauge
+    pub fn set_last_synced_block(&self, block: u64) {
+        self.last_synced_block.set(block as i64);
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::warn!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+async fn metrics_handler(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        metrics.render(),
+    )
+}
+
+/// Serve `metrics` at `/metrics` on `listener` until `shutdown` resolves
+pub async fn serve<F>(listener: TcpListener, metrics: Arc<Metrics>, shutdown: F) -> Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(metrics);
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let metrics = Arc::new(Metrics::new());
+        metrics.set_last_synced_block(12345);
+        metrics.record_rpc_request();
+        metrics.record_event("BatchTopUp", "PostageStamp");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, Arc::clone(&metrics), async {
+            let _ = shutdown_rx.await;
+        }));
+
+        let body = reqwest::get(format!("http://{addr}/metrics"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(body.contains("beeport_last_synced_block 12345"));
+        assert!(body.contains("beeport_rpc_requests_total 1"));
+        assert!(body.contains(r#"beeport_events_total{contract="PostageStamp",event_type="BatchTopUp"} 1"#));
+
+        shutdown_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
@@ -1,6 +1,69 @@
 use crate::error::{Result, StampError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
+pub mod fiat;
+
+/// A single observed storage price, taken from a PriceOracle `PriceUpdate` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PricePoint {
+    pub block_number: u64,
+    pub block_timestamp: DateTime<Utc>,
+    /// Price per chunk per block in PLUR
+    pub price: u128,
+}
+
+/// Storage currently committed by all active (non-expired) batches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkStorage {
+    pub active_batches: usize,
+    pub expired_batches: usize,
+    pub total_chunks: u128,
+    pub total_bytes: u128,
+    /// Block the balances and TTLs were evaluated at
+    pub block_number: u64,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// A storage price move beyond the alert threshold, reported by `watch-price`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceAlert {
+    pub block_number: u64,
+    pub previous_price: u128,
+    pub current_price: u128,
+    /// Relative change in percent (negative when the price dropped)
+    pub change_pct: f64,
+}
+
+impl PriceAlert {
+    /// Compare two price observations
+    ///
+    /// Returns an alert when the price moved by more than `threshold_pct` percent
+    /// in either direction. A zero previous price has no meaningful baseline and
+    /// never alerts.
+    pub fn check(
+        previous_price: u128,
+        current_price: u128,
+        threshold_pct: f64,
+        block_number: u64,
+    ) -> Option<Self> {
+        if previous_price == 0 {
+            return None;
+        }
+
+        let change_pct =
+            (current_price as f64 - previous_price as f64) / previous_price as f64 * 100.0;
+
+        (change_pct.abs() > threshold_pct).then_some(Self {
+            block_number,
+            previous_price,
+            current_price,
+            change_pct,
+        })
+    }
+}
+
 /// Price configuration for batch calculations
 #[derive(Debug, Clone)]
 pub struct PriceConfig {
@@ -8,6 +71,8 @@ pub struct PriceConfig {
     pub base_price: u128,
     /// Optional price change configuration
     pub price_change: Option<PriceChange>,
+    /// Optional piecewise-constant price schedule
+    pub price_schedule: Option<PriceSchedule>,
 }
 
 /// Price change configuration
@@ -93,12 +158,93 @@ impl PriceChange {
     }
 }
 
+/// Piecewise-constant price schedule
+///
+/// Each step sets the price per chunk per block from `day_offset` days from now
+/// until the next step. Before the first step the base price applies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceSchedule {
+    /// `(day_offset, price)` steps, sorted by day offset
+    pub steps: Vec<(f64, u128)>,
+}
+
+impl FromStr for PriceSchedule {
+    type Err = StampError;
+
+    /// Parse a schedule from comma-separated "day:price" pairs
+    /// Example: "0:24000,30:48000" means 24000 PLUR now, 48000 PLUR from day 30
+    fn from_str(s: &str) -> Result<Self> {
+        let mut steps = Vec::new();
+
+        for pair in s.split(',') {
+            let (day, price) = pair.trim().split_once(':').ok_or_else(|| {
+                StampError::Parse(
+                    "Price schedule must be comma-separated 'day:price' pairs (e.g., '0:24000,30:48000')"
+                        .to_string(),
+                )
+            })?;
+
+            let day = day
+                .parse::<f64>()
+                .map_err(|_| StampError::Parse(format!("Invalid schedule day '{day}'")))?;
+            let price = price
+                .parse::<u128>()
+                .map_err(|_| StampError::Parse(format!("Invalid schedule price '{price}'")))?;
+
+            if day.is_nan() || day < 0.0 {
+                return Err(StampError::Parse("Schedule days cannot be negative".to_string()));
+            }
+            if price == 0 {
+                return Err(StampError::Parse("Schedule prices cannot be zero".to_string()));
+            }
+
+            steps.push((day, price));
+        }
+
+        steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if steps.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(StampError::Parse("Schedule days must be unique".to_string()));
+        }
+
+        Ok(Self { steps })
+    }
+}
+
+impl PriceSchedule {
+    /// Calculate TTL in blocks by spending a per-chunk balance against the schedule
+    ///
+    /// The balance pays for each segment at that segment's price until it runs
+    /// out; the last step's price applies indefinitely.
+    pub fn ttl_blocks(&self, balance_per_chunk: u128, base_price: u128, block_time_seconds: f64) -> u64 {
+        let mut remaining = balance_per_chunk;
+        let mut block = 0u64;
+        let mut price = base_price;
+
+        for &(day_offset, next_price) in &self.steps {
+            let segment_end = days_to_blocks(day_offset, block_time_seconds);
+            if segment_end > block {
+                let cost = u128::from(segment_end - block).saturating_mul(price);
+                if remaining < cost {
+                    break;
+                }
+                remaining -= cost;
+                block = segment_end;
+            }
+            price = next_price;
+        }
+
+        let blocks = remaining.checked_div(price).unwrap_or(0);
+        block.saturating_add(u64::try_from(blocks).unwrap_or(u64::MAX))
+    }
+}
+
 impl PriceConfig {
     /// Create a new price configuration with just a base price
     pub fn new(base_price: u128) -> Self {
         Self {
             base_price,
             price_change: None,
+            price_schedule: None,
         }
     }
 
@@ -107,6 +253,16 @@ impl PriceConfig {
         Self {
             base_price,
             price_change: Some(price_change),
+            price_schedule: None,
+        }
+    }
+
+    /// Create a price configuration with a piecewise price schedule
+    pub fn with_price_schedule(base_price: u128, price_schedule: PriceSchedule) -> Self {
+        Self {
+            base_price,
+            price_change: None,
+            price_schedule: Some(price_schedule),
         }
     }
 
@@ -131,30 +287,42 @@ impl PriceConfig {
 
 /// Calculate Time To Live (TTL) in blocks for a batch
 ///
-/// Formula: TTL = normalised_balance / (price_per_chunk_per_block × chunks)
+/// Formula: TTL = floor(normalised_balance / (price_per_chunk_per_block × 2^depth))
 ///
 /// Where:
-/// - normalised_balance: The balance in PLUR (smallest unit)
+/// - normalised_balance: The balance in PLUR (smallest unit), as a decimal `u128`
 /// - price_per_chunk_per_block: Price per chunk per block in PLUR
-/// - chunks: Number of chunks (2^depth)
+/// - 2^depth: Number of chunks in the batch
+///
+/// Returns `StampError::Parse` for an unparseable balance or a zero price, and
+/// `StampError::Overflow` when the per-block cost does not fit in `u128` or
+/// the TTL does not fit in `u64`.
 pub fn calculate_ttl_blocks(
     normalised_balance: &str,
     depth: u8,
     price_per_chunk_per_block: u128,
 ) -> Result<u64> {
-    let balance = u128::from_str(normalised_balance)
-        .map_err(|_| StampError::Parse("Invalid normalised balance".to_string()))?;
+    let balance = u128::from_str(normalised_balance).map_err(|e| {
+        StampError::Parse(format!("Invalid normalised balance '{normalised_balance}': {e}"))
+    })?;
 
     if price_per_chunk_per_block == 0 {
         return Err(StampError::Parse("Price cannot be zero".to_string()));
     }
 
-    let chunks: u128 = 1u128 << depth; // 2^depth
-    let total_price_per_block = price_per_chunk_per_block * chunks;
+    let total_price_per_block = 1u128
+        .checked_shl(u32::from(depth)) // 2^depth chunks
+        .and_then(|chunks| price_per_chunk_per_block.checked_mul(chunks))
+        .ok_or_else(|| {
+            StampError::Overflow(format!(
+                "price {price_per_chunk_per_block} × 2^{depth} chunks exceeds u128"
+            ))
+        })?;
 
     let ttl = balance / total_price_per_block;
 
-    Ok(ttl as u64)
+    u64::try_from(ttl)
+        .map_err(|_| StampError::Overflow(format!("TTL of {ttl} blocks exceeds u64")))
 }
 
 /// Calculate Time To Live in days from blocks
@@ -168,22 +336,135 @@ pub fn blocks_to_days(blocks: u64, block_time_seconds: f64) -> f64 {
     (blocks as f64) * block_time_seconds / SECONDS_PER_DAY
 }
 
+/// Convert a number of blocks into wall-clock time
+///
+/// # Arguments
+/// * `blocks` - Number of blocks
+/// * `block_time_seconds` - Time per block in seconds (e.g., 5.0 for Gnosis Chain)
+pub fn blocks_to_duration(blocks: u64, block_time_seconds: f64) -> chrono::Duration {
+    chrono::Duration::seconds(((blocks as f64) * block_time_seconds) as i64)
+}
+
 /// Calculate days to blocks
 ///
 /// # Arguments
 /// * `days` - Number of days
 /// * `block_time_seconds` - Time per block in seconds (e.g., 5.0 for Gnosis Chain)
-#[allow(dead_code)]
 pub fn days_to_blocks(days: f64, block_time_seconds: f64) -> u64 {
     const SECONDS_PER_DAY: f64 = 86400.0;
 
     ((days * SECONDS_PER_DAY) / block_time_seconds).round() as u64
 }
 
+/// Bytes of storage per chunk
+pub const CHUNK_SIZE_BYTES: u128 = 4096;
+
+/// Format a chunk count as a human-readable storage size
+pub fn format_storage(chunks: u128) -> String {
+    let bytes = chunks * CHUNK_SIZE_BYTES;
+
+    const KB: u128 = 1024;
+    const MB: u128 = KB * 1024;
+    const GB: u128 = MB * 1024;
+    const TB: u128 = GB * 1024;
+    const PB: u128 = TB * 1024;
+
+    if bytes >= PB {
+        format!("{:.2} PB", bytes as f64 / PB as f64)
+    } else if bytes >= TB {
+        format!("{:.2} TB", bytes as f64 / TB as f64)
+    } else if bytes >= GB {
+        format!("{:.2} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.2} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.2} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+/// Format the storage capacity of a batch of the given depth (4 KiB chunks)
+pub fn format_capacity(depth: u8) -> String {
+    const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    let mut value = CHUNK_SIZE_BYTES << depth;
+    let mut unit = 0;
+    while value >= 1024 && value.is_multiple_of(1024) && unit < UNITS.len() - 1 {
+        value /= 1024;
+        unit += 1;
+    }
+
+    format!("{value} {}", UNITS[unit])
+}
+
+/// Number of decimal places between PLUR and BZZ (1 BZZ = 10^16 PLUR)
+const BZZ_DECIMALS: usize = 16;
+
+/// Convert a PLUR amount to BZZ without losing precision
+///
+/// Works on the decimal digits directly, so amounts beyond `u128` convert
+/// exactly. Trailing zeros are trimmed: `"12345000000000000000"` becomes
+/// `"1234.5"`.
+pub fn to_bzz(plur: &str) -> Result<String> {
+    if plur.is_empty() || !plur.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(StampError::Parse(format!("Invalid PLUR amount: {plur}")));
+    }
+
+    let digits = plur.trim_start_matches('0');
+    let padded = format!("{digits:0>width$}", width = BZZ_DECIMALS + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - BZZ_DECIMALS);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        Ok(whole.to_string())
+    } else {
+        Ok(format!("{whole}.{fraction}"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_to_bzz() {
+        assert_eq!(to_bzz("12345678901234567890").unwrap(), "1234.567890123456789");
+        assert_eq!(to_bzz("10000000000000000").unwrap(), "1");
+        assert_eq!(to_bzz("240000000").unwrap(), "0.000000024");
+        assert_eq!(to_bzz("0").unwrap(), "0");
+        // Beyond u128
+        assert_eq!(
+            to_bzz("1000000000000000000000000000000000000000000").unwrap(),
+            "100000000000000000000000000"
+        );
+
+        assert!(to_bzz("").is_err());
+        assert!(to_bzz("-5").is_err());
+        assert!(to_bzz("1.5").is_err());
+    }
+
+    #[test]
+    fn test_price_alert_threshold() {
+        // 10% rise crosses a 5% threshold
+        let alert = PriceAlert::check(1000, 1100, 5.0, 42).unwrap();
+        assert_eq!(alert.block_number, 42);
+        assert_eq!(alert.previous_price, 1000);
+        assert_eq!(alert.current_price, 1100);
+        assert!((alert.change_pct - 10.0).abs() < 1e-10);
+
+        // Drops alert too, with a negative change
+        let alert = PriceAlert::check(1000, 800, 5.0, 42).unwrap();
+        assert!((alert.change_pct + 20.0).abs() < 1e-10);
+
+        // Moves within the threshold do not alert
+        assert!(PriceAlert::check(1000, 1050, 5.0, 42).is_none());
+        assert!(PriceAlert::check(1000, 1000, 0.0, 42).is_none());
+
+        // No baseline to compare against
+        assert!(PriceAlert::check(0, 1000, 5.0, 42).is_none());
+    }
+
     #[test]
     fn test_price_change_parsing() {
         let change = PriceChange::from_str("200:10").unwrap();
@@ -264,6 +545,33 @@ mod tests {
         assert_eq!(ttl, 9);
     }
 
+    #[test]
+    fn test_ttl_calculation_guards() {
+        // Zero price would divide by zero
+        let err = calculate_ttl_blocks("1000000000", 20, 0).unwrap_err();
+        assert!(matches!(err, StampError::Parse(_)));
+
+        // Unparseable and out-of-range balances
+        for balance in ["", "abc", "-1", "1.5", "340282366920938463463374607431768211456"] {
+            let err = calculate_ttl_blocks(balance, 20, 100).unwrap_err();
+            assert!(matches!(err, StampError::Parse(_)), "{balance}: {err}");
+        }
+
+        // Near-u128::MAX balance: the TTL no longer fits in u64
+        let balance = (u128::MAX - 1).to_string();
+        let err = calculate_ttl_blocks(&balance, 20, 100).unwrap_err();
+        assert!(matches!(err, StampError::Overflow(_)));
+
+        // ...but a large enough per-block cost brings it back in range
+        assert_eq!(calculate_ttl_blocks(&balance, 64, 1).unwrap(), u64::MAX);
+
+        // Per-block cost overflowing u128
+        let err = calculate_ttl_blocks("1000", 100, u128::from(u64::MAX)).unwrap_err();
+        assert!(matches!(err, StampError::Overflow(_)));
+        let err = calculate_ttl_blocks("1000", 200, 1).unwrap_err();
+        assert!(matches!(err, StampError::Overflow(_)));
+    }
+
     #[test]
     fn test_blocks_to_days() {
         // 17,280 blocks = 1 day (at 5 seconds per block)
@@ -279,6 +587,18 @@ mod tests {
         assert!((days - 1.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_blocks_to_duration_scales_with_block_time() {
+        let gnosis = blocks_to_duration(17280, 5.0);
+        assert_eq!(gnosis, chrono::Duration::days(1));
+
+        // Doubling the block time doubles the expiry offset
+        assert_eq!(blocks_to_duration(17280, 10.0), gnosis * 2);
+
+        // Sub-second block times are honoured
+        assert_eq!(blocks_to_duration(1000, 0.25), chrono::Duration::seconds(250));
+    }
+
     #[test]
     fn test_days_to_blocks() {
         // 1 day = 17,280 blocks (at 5 seconds per block)
@@ -294,6 +614,34 @@ mod tests {
         assert_eq!(blocks, 7200);
     }
 
+    #[test]
+    fn test_price_schedule_parsing() {
+        let schedule = PriceSchedule::from_str("30:48000, 0:24000").unwrap();
+        assert_eq!(schedule.steps, vec![(0.0, 24000), (30.0, 48000)]);
+
+        assert!(PriceSchedule::from_str("24000").is_err());
+        assert!(PriceSchedule::from_str("-1:24000").is_err());
+        assert!(PriceSchedule::from_str("0:0").is_err());
+        assert!(PriceSchedule::from_str("0:24000,0:48000").is_err());
+        assert!(PriceSchedule::from_str("abc:24000").is_err());
+    }
+
+    #[test]
+    fn test_two_segment_schedule_ttl() {
+        // 1 day = 17,280 blocks at 5s/block
+        // Day 0-1 at 100 PLUR costs 1,728,000; the remaining 1,000,000 at
+        // 200 PLUR lasts 5,000 more blocks
+        let schedule = PriceSchedule::from_str("0:100,1:200").unwrap();
+        assert_eq!(schedule.ttl_blocks(2_728_000, 999, 5.0), 17_280 + 5_000);
+
+        // Balance runs out inside the first segment
+        assert_eq!(schedule.ttl_blocks(1_000_000, 999, 5.0), 10_000);
+
+        // Base price applies before the first step
+        let schedule = PriceSchedule::from_str("1:200").unwrap();
+        assert_eq!(schedule.ttl_blocks(2_728_000, 100, 5.0), 17_280 + 5_000);
+    }
+
     #[test]
     fn test_price_config() {
         let config = PriceConfig::new(1000);
@@ -309,4 +657,19 @@ mod tests {
         // Should return average price, not base price
         assert!(effective > 1000);
     }
+
+    #[test]
+    fn test_format_storage() {
+        assert_eq!(format_storage(1), "4.00 KB");
+        assert_eq!(format_storage(256), "1.00 MB");
+        assert_eq!(format_storage(262144), "1.00 GB");
+    }
+
+    #[test]
+    fn test_format_capacity() {
+        assert_eq!(format_capacity(0), "4 KiB");
+        assert_eq!(format_capacity(17), "512 MiB");
+        assert_eq!(format_capacity(20), "4 GiB");
+        assert_eq!(format_capacity(24), "64 GiB");
+    }
 }
@@ -0,0 +1,163 @@
+//! Fiat value of BZZ amounts from an HTTP price feed
+//!
+//! Rates come from a CoinGecko-compatible `simple/price` endpoint and are
+//! cached in `cache_metadata` for [`FIAT_RATE_TTL_SECS`], so repeated runs do
+//! not hit the API.
+
+use crate::cache::Cache;
+use crate::error::{Result, StampError};
+use std::time::Duration;
+
+/// CoinGecko API used when no `--price-feed-url` is given
+pub const DEFAULT_PRICE_FEED_URL: &str = "https://api.coingecko.com/api/v3";
+
+/// CoinGecko coin ID of the BZZ token
+const BZZ_COIN_ID: &str = "swarm-bzz";
+
+/// How long a fetched rate is reused before the feed is asked again
+pub const FIAT_RATE_TTL_SECS: i64 = 600;
+
+/// Timeout for a single price feed request
+const FEED_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Price feed quoting BZZ in a fiat currency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiatFeed {
+    /// Base URL of the CoinGecko-compatible API
+    pub url: String,
+    /// Lowercase currency code, e.g. `usd`
+    pub currency: String,
+}
+
+impl FiatFeed {
+    /// Feed for `currency`, using the CoinGecko API unless `url` overrides it
+    pub fn new(currency: impl Into<String>, url: Option<String>) -> Self {
+        Self {
+            url: url.unwrap_or_else(|| DEFAULT_PRICE_FEED_URL.to_string()),
+            currency: currency.into().to_lowercase(),
+        }
+    }
+
+    /// Current price of one BZZ, from the cache when fresh, otherwise from the feed
+    ///
+    /// With `offline`, only a fresh cached rate is used.
+    pub async fn bzz_rate(&self, cache: &Cache, offline: bool) -> Result<f64> {
+        if let Some(rate) = cache.get_cached_fiat_rate(&self.currency, FIAT_RATE_TTL_SECS).await? {
+            tracing::debug!("Using cached BZZ/{} rate {}", self.currency, rate);
+            return Ok(rate);
+        }
+
+        if offline {
+            return Err(StampError::PriceFeed(format!(
+                "no cached BZZ/{} rate and --offline is set",
+                self.currency
+            )));
+        }
+
+        let rate = self.fetch_bzz_rate().await?;
+        cache.cache_fiat_rate(&self.currency, rate).await?;
+        Ok(rate)
+    }
+
+    /// Ask the feed for the current BZZ price
+    async fn fetch_bzz_rate(&self) -> Result<f64> {
+        let client = reqwest::Client::builder()
+            .timeout(FEED_TIMEOUT)
+            .build()
+            .map_err(|e| StampError::PriceFeed(e.to_string()))?;
+
+        let response = client
+            .get(format!("{}/simple/price", self.url.trim_end_matches('/')))
+            .query(&[("ids", BZZ_COIN_ID), ("vs_currencies", self.currency.as_str())])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| StampError::PriceFeed(e.to_string()))?;
+
+        // Response shape: {"swarm-bzz": {"usd": 0.25}}
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| StampError::PriceFeed(e.to_string()))?;
+
+        body[BZZ_COIN_ID][self.currency.as_str()]
+            .as_f64()
+            .filter(|rate| rate.is_finite() && *rate >= 0.0)
+            .ok_or_else(|| {
+                StampError::PriceFeed(format!("response has no BZZ/{} rate: {body}", self.currency))
+            })
+    }
+}
+
+/// Fiat value of a PLUR amount at `rate` per BZZ
+pub fn fiat_cost(plur: &str, rate: f64) -> Result<f64> {
+    let bzz: f64 = super::to_bzz(plur)?
+        .parse()
+        .map_err(|e| StampError::Parse(format!("Invalid BZZ amount for {plur}: {e}")))?;
+    Ok(bzz * rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_fiat_cost() {
+        // 2 BZZ at $0.25
+        assert!((fiat_cost("20000000000000000", 0.25).unwrap() - 0.5).abs() < 1e-12);
+        assert_eq!(fiat_cost("0", 0.25).unwrap(), 0.0);
+        assert!(fiat_cost("not-a-number", 0.25).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bzz_rate_is_cached() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/simple/price")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("ids".into(), "swarm-bzz".into()),
+                mockito::Matcher::UrlEncoded("vs_currencies".into(), "usd".into()),
+            ]))
+            .with_body(r#"{"swarm-bzz":{"usd":0.25}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let feed = FiatFeed::new("USD", Some(server.url()));
+        assert_eq!(feed.bzz_rate(&cache, false).await.unwrap(), 0.25);
+        // Second lookup within the TTL is served from the cache
+        assert_eq!(feed.bzz_rate(&cache, false).await.unwrap(), 0.25);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_bzz_rate_feed_errors() {
+        let db_file = NamedTempFile::new().unwrap();
+        let cache = Cache::new(db_file.path()).await.unwrap();
+
+        // Offline with nothing cached
+        let feed = FiatFeed::new("usd", Some("http://127.0.0.1:1".to_string()));
+        let err = feed.bzz_rate(&cache, true).await.unwrap_err();
+        assert!(matches!(err, StampError::PriceFeed(_)));
+
+        // Unreachable feed
+        let err = feed.bzz_rate(&cache, false).await.unwrap_err();
+        assert!(matches!(err, StampError::PriceFeed(_)));
+
+        // Feed without a rate for the currency
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/simple/price")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"swarm-bzz":{}}"#)
+            .create_async()
+            .await;
+        let feed = FiatFeed::new("usd", Some(server.url()));
+        let err = feed.bzz_rate(&cache, false).await.unwrap_err();
+        assert!(matches!(err, StampError::PriceFeed(_)));
+    }
+}
@@ -3,9 +3,14 @@
 /// This module provides a generic retry mechanism with configurable exponential backoff
 /// and extended retry phases. It's designed to be reusable across different RPC providers
 /// and operation types.
+use crate::error::{classify_rpc_error, mentions_status, StampError};
+use crate::metrics::Metrics;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::future::Future;
-use tokio::time::{sleep, Duration};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration, Instant};
 
 /// Configuration for retry behavior
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +28,53 @@ pub struct RetryConfig {
     /// Wait time in seconds before entering extended retry mode
     /// After max_retries is exhausted, wait this long before resetting the counter
     pub extended_retry_wait_seconds: u64,
+
+    /// Randomize each backoff delay within ±this fraction of its computed value
+    ///
+    /// Spreads out retries of concurrent requests that hit a rate limit at the
+    /// same time. `0.0` (the default) keeps delays deterministic.
+    #[serde(default)]
+    pub jitter_fraction: f64,
+
+    /// Apply `jitter_fraction` to the extended retry wait as well
+    #[serde(default)]
+    pub jitter_extended_wait: bool,
+
+    /// Seed for the jitter random number generator, for reproducible delays
+    ///
+    /// Every `execute` call starts from the same seed, so leave this unset
+    /// outside of tests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter_seed: Option<u64>,
+
+    /// Give up once this many seconds have passed since the first attempt
+    ///
+    /// Bounds the otherwise endless extended retry mode during an outage.
+    /// Default: unset (retry until success)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_elapsed_seconds: Option<u64>,
+
+    /// Extra error messages `execute` treats as transient, on top of rate limits
+    ///
+    /// Matched case-insensitively as substrings; purely numeric patterns such
+    /// as `"503"` only match as a standalone status code.
+    /// Default: [`default_retryable_patterns`]
+    #[serde(default = "default_retryable_patterns")]
+    pub retryable_patterns: Vec<String>,
+
+    /// Metrics that count each retry, when set
+    ///
+    /// Not part of the configuration file; attach with [`RetryConfig::with_metrics`].
+    #[serde(skip)]
+    pub metrics: Option<Arc<Metrics>>,
+}
+
+/// Transient server and connection errors retried by default
+pub fn default_retryable_patterns() -> Vec<String> {
+    ["503", "service unavailable", "bad gateway", "connection reset"]
+        .into_iter()
+        .map(String::from)
+        .collect()
 }
 
 impl Default for RetryConfig {
@@ -32,6 +84,12 @@ impl Default for RetryConfig {
             initial_delay_ms: 100,
             backoff_multiplier: 4,
             extended_retry_wait_seconds: 300, // 5 minutes
+            jitter_fraction: 0.0,
+            jitter_extended_wait: false,
+            jitter_seed: None,
+            max_total_elapsed_seconds: None,
+            retryable_patterns: default_retryable_patterns(),
+            metrics: None,
         }
     }
 }
@@ -50,6 +108,109 @@ impl RetryConfig {
             initial_delay_ms,
             backoff_multiplier,
             extended_retry_wait_seconds,
+            ..Self::default()
+        }
+    }
+
+    /// Count retries in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Record one retry in the attached metrics, if any
+    fn record_retry(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_rpc_retry();
+        }
+    }
+
+    /// Random number generator for jitter, seeded from `jitter_seed` if set
+    pub fn jitter_rng(&self) -> StdRng {
+        match self.jitter_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    /// Spread `delay` uniformly over ±`jitter_fraction` of its value
+    fn jitter(&self, delay: Duration, rng: &mut impl Rng) -> Duration {
+        if self.jitter_fraction <= 0.0 {
+            return delay;
+        }
+        let factor = 1.0 + rng.gen_range(-self.jitter_fraction..=self.jitter_fraction);
+        delay.mul_f64(factor.max(0.0))
+    }
+
+    /// Delay before fast retry number `retries` (starting at 0)
+    ///
+    /// `initial_delay_ms * backoff_multiplier^retries`, with jitter applied
+    /// when `jitter_fraction` is set.
+    pub fn backoff_delay(&self, retries: u32, rng: &mut impl Rng) -> Duration {
+        let delay_ms = self
+            .initial_delay_ms
+            .saturating_mul(self.backoff_multiplier.saturating_pow(retries));
+        self.jitter(Duration::from_millis(delay_ms), rng)
+    }
+
+    /// Wait before the next extended retry round
+    ///
+    /// Jittered only when `jitter_extended_wait` is set.
+    pub fn extended_wait(&self, rng: &mut impl Rng) -> Duration {
+        let wait = Duration::from_secs(self.extended_retry_wait_seconds);
+        if self.jitter_extended_wait {
+            self.jitter(wait, rng)
+        } else {
+            wait
+        }
+    }
+
+    /// Whether `message` matches one of `retryable_patterns`
+    pub fn matches_retryable_pattern(&self, message: &str) -> bool {
+        let lower = message.to_lowercase();
+        self.retryable_patterns.iter().any(|pattern| {
+            if pattern.chars().all(|c| c.is_ascii_digit()) {
+                mentions_status(message, pattern)
+            } else {
+                lower.contains(&pattern.to_lowercase())
+            }
+        })
+    }
+
+    /// How long to sleep before the next attempt without overrunning the deadline
+    ///
+    /// Returns `None` once `max_total_elapsed_seconds` has passed since `started`.
+    fn delay_within_deadline(&self, started: Instant, delay: Duration) -> Option<Duration> {
+        match self.max_total_elapsed_seconds {
+            None => Some(delay),
+            Some(limit) => Duration::from_secs(limit)
+                .checked_sub(started.elapsed())
+                .filter(|remaining| !remaining.is_zero())
+                .map(|remaining| remaining.min(delay)),
+        }
+    }
+
+    /// Message for an operation abandoned at the retry deadline
+    fn deadline_message(started: Instant, total_retries: u32, error: impl std::fmt::Display) -> String {
+        format!(
+            "Retry deadline exceeded after {} retries in {:.1}s: {}",
+            total_retries,
+            started.elapsed().as_secs_f64(),
+            error
+        )
+    }
+
+    /// Error for an operation abandoned at the retry deadline
+    ///
+    /// Keeps the variant `error` was classified as, so callers can still match
+    /// on it, with the deadline noted in its message.
+    fn deadline_error(started: Instant, total_retries: u32, error: StampError) -> StampError {
+        let message = Self::deadline_message(started, total_retries, &error);
+        match error {
+            StampError::RateLimited(_) => StampError::RateLimited(message),
+            StampError::RpcTransport(_) => StampError::RpcTransport(message),
+            StampError::QueryTooLarge { limit, .. } => StampError::QueryTooLarge { limit, message },
+            _ => StampError::Rpc(message),
         }
     }
 
@@ -65,7 +226,8 @@ impl RetryConfig {
     /// **Phase 2: Extended retry**
     /// - When Phase 1 is exhausted, wait `extended_retry_wait_seconds`
     /// - Resets retry counter and returns to Phase 1
-    /// - Continues indefinitely until success
+    /// - Continues until success, or until `max_total_elapsed_seconds` have
+    ///   passed since the first attempt
     ///
     /// # Arguments
     ///
@@ -73,7 +235,12 @@ impl RetryConfig {
     ///
     /// # Returns
     ///
-    /// Returns the successful result or propagates non-retryable errors immediately
+    /// Returns the successful result or propagates non-retryable errors immediately.
+    /// Past the retry deadline the last error is returned with its classified
+    /// variant and the deadline noted in its message.
+    /// Errors are classified with [`classify_rpc_error`]; only
+    /// [`StampError::is_retryable`] errors and errors matching
+    /// `retryable_patterns` are retried.
     ///
     /// # Examples
     ///
@@ -83,13 +250,16 @@ impl RetryConfig {
     ///     make_rpc_call().await
     /// }).await?;
     /// ```
-    pub async fn execute<F, Fut, T, E>(&self, mut operation: F) -> Result<T, String>
+    pub async fn execute<F, Fut, T, E>(&self, mut operation: F) -> Result<T, StampError>
     where
         F: FnMut() -> Fut,
         Fut: Future<Output = std::result::Result<T, E>>,
         E: std::error::Error,
     {
         let mut extended_retry_count = 0;
+        let mut rng = self.jitter_rng();
+        let started = Instant::now();
+        let mut total_retries = 0;
 
         loop {
             let mut retries = 0;
@@ -99,49 +269,59 @@ impl RetryConfig {
                 match operation().await {
                     Ok(result) => return Ok(result),
                     Err(e) => {
-                        let error_msg = e.to_string();
+                        let message = format!("Operation failed: {e}");
+                        let transient = self.matches_retryable_pattern(&message);
+                        let error = classify_rpc_error(message);
 
-                        // Check if this is a rate limit or gateway error
-                        if error_msg.contains("429") || error_msg.contains("Too Many Requests")
-                            || error_msg.contains("502") || error_msg.contains("Bad Gateway") {
+                        // Check if this is a rate limit or other transient error
+                        if error.is_retryable() || transient {
                             if retries < self.max_retries {
                                 // Calculate exponential backoff delay
-                                let delay_ms = self
-                                    .initial_delay_ms
-                                    .saturating_mul(self.backoff_multiplier.pow(retries));
+                                let Some(delay) =
+                                    self.delay_within_deadline(started, self.backoff_delay(retries, &mut rng))
+                                else {
+                                    return Err(Self::deadline_error(started, total_retries, error));
+                                };
 
                                 let now = chrono::Local::now().format("%H:%M:%S");
                                 tracing::debug!(
-                                    "[{}] Retryable error (429/502), retrying after {}ms (attempt {}/{})",
+                                    "[{}] Retryable error, retrying after {}ms (attempt {}/{})",
                                     now,
-                                    delay_ms,
+                                    delay.as_millis(),
                                     retries + 1,
                                     self.max_retries
                                 );
 
-                                sleep(Duration::from_millis(delay_ms)).await;
+                                sleep(delay).await;
+                                self.record_retry();
                                 retries += 1;
+                                total_retries += 1;
                                 continue;
                             } else {
                                 // Phase 2: Extended retry
                                 extended_retry_count += 1;
+                                let Some(wait) = self.delay_within_deadline(started, self.extended_wait(&mut rng)) else {
+                                    return Err(Self::deadline_error(started, total_retries, error));
+                                };
                                 let now = chrono::Local::now().format("%H:%M:%S");
                                 tracing::warn!(
                                     "[{}] Max retries ({}) exhausted. Waiting {} seconds before retry #{} (extended mode)",
                                     now,
                                     self.max_retries,
-                                    self.extended_retry_wait_seconds,
+                                    wait.as_secs(),
                                     extended_retry_count
                                 );
 
-                                sleep(Duration::from_secs(self.extended_retry_wait_seconds)).await;
+                                sleep(wait).await;
+                                self.record_retry();
+                                total_retries += 1;
 
                                 // Break inner loop to reset retry counter
                                 break;
                             }
                         } else {
                             // Non-rate-limit error - fail immediately
-                            return Err(format!("Operation failed: {e}"));
+                            return Err(error);
                         }
                     }
                 }
@@ -181,6 +361,9 @@ impl RetryConfig {
         P: Fn(&E) -> bool,
     {
         let mut extended_retry_count = 0;
+        let mut rng = self.jitter_rng();
+        let started = Instant::now();
+        let mut total_retries = 0;
 
         loop {
             let mut retries = 0;
@@ -193,36 +376,45 @@ impl RetryConfig {
                         if is_retryable(&e) {
                             if retries < self.max_retries {
                                 // Calculate exponential backoff delay
-                                let delay_ms = self
-                                    .initial_delay_ms
-                                    .saturating_mul(self.backoff_multiplier.pow(retries));
+                                let Some(delay) =
+                                    self.delay_within_deadline(started, self.backoff_delay(retries, &mut rng))
+                                else {
+                                    return Err(Self::deadline_message(started, total_retries, format!("Operation failed: {e}")));
+                                };
 
                                 let now = chrono::Local::now().format("%H:%M:%S");
                                 tracing::debug!(
                                     "[{}] Retryable error, retrying after {}ms (attempt {}/{}): {}",
                                     now,
-                                    delay_ms,
+                                    delay.as_millis(),
                                     retries + 1,
                                     self.max_retries,
                                     e
                                 );
 
-                                sleep(Duration::from_millis(delay_ms)).await;
+                                sleep(delay).await;
+                                self.record_retry();
                                 retries += 1;
+                                total_retries += 1;
                                 continue;
                             } else {
                                 // Phase 2: Extended retry
                                 extended_retry_count += 1;
+                                let Some(wait) = self.delay_within_deadline(started, self.extended_wait(&mut rng)) else {
+                                    return Err(Self::deadline_message(started, total_retries, format!("Operation failed: {e}")));
+                                };
                                 let now = chrono::Local::now().format("%H:%M:%S");
                                 tracing::warn!(
                                     "[{}] Max retries ({}) exhausted. Waiting {} seconds before retry #{} (extended mode)",
                                     now,
                                     self.max_retries,
-                                    self.extended_retry_wait_seconds,
+                                    wait.as_secs(),
                                     extended_retry_count
                                 );
 
-                                sleep(Duration::from_secs(self.extended_retry_wait_seconds)).await;
+                                sleep(wait).await;
+                                self.record_retry();
+                                total_retries += 1;
 
                                 // Break inner loop to reset retry counter
                                 break;
@@ -300,7 +492,7 @@ mod tests {
             })
             .await;
 
-        assert!(result.is_err());
+        assert!(matches!(result, Err(StampError::Rpc(_))));
         assert_eq!(*attempt.lock().unwrap(), 1); // Should only try once
     }
 
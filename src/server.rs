@@ -0,0 +1,246 @@
+//! Read-only HTTP query server over the event cache
+//!
+//! Exposes cached events, batches, period summaries and batch status as JSON so
+//! dashboards can query the cache without re-running CLI commands.
+
+use crate::batch::SummaryStats;
+use crate::cache::{Cache, EventColumnFilter};
+use crate::cli::{FilterContract, FilterEventType, GroupBy};
+use crate::commands::batch_status::{self, BatchStatus};
+use crate::error::{Result, StampError};
+use crate::events::BatchInfo;
+use crate::filter::{normalize_hex, normalize_hex_fragment, EventFilter};
+use crate::price::PriceConfig;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::future::Future;
+use tokio::net::TcpListener;
+
+/// Months of history returned when a request does not specify `months`
+const DEFAULT_MONTHS: u32 = 12;
+
+/// Shared state handed to every request handler
+#[derive(Clone)]
+pub struct ServerState {
+    cache: Cache,
+    block_time_seconds: f64,
+}
+
+impl ServerState {
+    /// Create server state backed by the given cache
+    pub fn new(cache: Cache, block_time_seconds: f64) -> Self {
+        Self {
+            cache,
+            block_time_seconds,
+        }
+    }
+}
+
+/// Query parameters for `/events`
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    months: Option<u32>,
+    event_type: Option<FilterEventType>,
+    batch_id: Option<String>,
+    contract: Option<FilterContract>,
+    /// Page size; unset returns every matching event
+    limit: Option<usize>,
+    /// Matching events to skip before the page starts
+    offset: Option<usize>,
+}
+
+/// Response header carrying the number of matching events before paging
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// Query parameters for `/batches`
+#[derive(Debug, Deserialize)]
+struct BatchesQuery {
+    months: Option<u32>,
+    batch_id: Option<String>,
+}
+
+/// Query parameters for `/summary`
+#[derive(Debug, Deserialize)]
+struct SummaryQuery {
+    months: Option<u32>,
+    group_by: Option<GroupBy>,
+    event_type: Option<FilterEventType>,
+    batch_id: Option<String>,
+    contract: Option<FilterContract>,
+}
+
+/// Response body for `/summary`, in the same shape as `summary --snapshot`
+pub type SummaryResponse = SummaryStats;
+
+/// Error returned to HTTP clients as `{"error": "..."}`
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<StampError> for ApiError {
+    /// Requests the cache refuses or cannot parse are client errors; anything
+    /// else is a server failure and logged as such
+    fn from(err: StampError) -> Self {
+        let status = match &err {
+            StampError::QueryTooLarge { .. } | StampError::Parse(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        if status.is_server_error() {
+            tracing::error!("Query server error: {}", err);
+        } else {
+            tracing::debug!("Rejected query: {}", err);
+        }
+        Self::new(status, err.to_string())
+    }
+}
+
+/// Normalize an optional partial hex query parameter, rejecting malformed values
+fn normalize_hex_param(value: Option<String>) -> std::result::Result<Option<String>, ApiError> {
+    value
+        .map(|v| {
+            normalize_hex_fragment(&v).map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))
+        })
+        .transpose()
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(serde_json::json!({ "error": self.message }))).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+/// Build the query server router
+pub fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/events", get(events_handler))
+        .route("/batches", get(batches_handler))
+        .route("/summary", get(summary_handler))
+        .route("/batch/:id/status", get(batch_status_handler))
+        .with_state(state)
+}
+
+/// Serve the query API on `listener` until `shutdown` resolves
+pub async fn serve<F>(listener: TcpListener, state: ServerState, shutdown: F) -> Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+/// List events, one page at a time with `limit`/`offset`
+///
+/// Filters and paging both run in the database, so a request only reads the
+/// rows on its page. The `x-total-count` header gives the number of matching
+/// events before paging.
+async fn events_handler(
+    State(state): State<ServerState>,
+    Query(query): Query<EventsQuery>,
+) -> std::result::Result<Response, ApiError> {
+    let months = query.months.unwrap_or(DEFAULT_MONTHS);
+    let filter = EventColumnFilter {
+        event_type: query.event_type.map(|t| t.as_str().to_string()),
+        batch_id_contains: normalize_hex_param(query.batch_id)?,
+        contract_source: query.contract.map(|c| c.as_str().to_string()),
+    };
+    let limit = query.limit.unwrap_or(usize::MAX);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = state.cache.count_events_filtered(months, &filter).await?;
+    let events = state.cache.get_events_paged(months, &filter, limit, offset).await?;
+
+    Ok(([(TOTAL_COUNT_HEADER, total.to_string())], Json(events)).into_response())
+}
+
+async fn batches_handler(
+    State(state): State<ServerState>,
+    Query(query): Query<BatchesQuery>,
+) -> ApiResult<Vec<BatchInfo>> {
+    let mut batches = state
+        .cache
+        .get_batches(query.months.unwrap_or(DEFAULT_MONTHS))
+        .await?;
+
+    let filter = EventFilter {
+        batch_id_contains: normalize_hex_param(query.batch_id)?,
+        ..Default::default()
+    };
+    filter.apply_to_batches(&mut batches);
+
+    Ok(Json(batches))
+}
+
+async fn summary_handler(
+    State(state): State<ServerState>,
+    Query(query): Query<SummaryQuery>,
+) -> ApiResult<SummaryResponse> {
+    let months = query.months.unwrap_or(DEFAULT_MONTHS);
+    let mut events = state.cache.get_events(months).await?;
+    let mut batches = state.cache.get_batches(months).await?;
+
+    let filter = EventFilter {
+        event_type: query.event_type,
+        batch_id_contains: normalize_hex_param(query.batch_id)?,
+        contract: query.contract,
+        ..Default::default()
+    };
+    filter.apply(&mut events);
+    filter.apply_to_batches(&mut batches);
+
+    let group_by = query.group_by.unwrap_or(GroupBy::Week);
+
+    Ok(Json(SummaryStats::new(&events, &batches, &group_by)))
+}
+
+async fn batch_status_handler(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> ApiResult<BatchStatus> {
+    let id = normalize_hex(&id).map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+    let batch = state
+        .cache
+        .get_batch_with_current_depth(&id)
+        .await?
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("Batch {id} not found")))?;
+
+    let base_price = state.cache.get_cached_price().await?.ok_or_else(|| {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No cached storage price. Run 'batch-status' or 'price' first.",
+        )
+    })?;
+
+    let current_block = state.cache.get_last_block().await?.unwrap_or(0);
+
+    let status = batch_status::status_from_cache(
+        &state.cache,
+        batch,
+        &PriceConfig::new(base_price),
+        current_block,
+        batch_status::BALANCE_VALIDITY_BLOCKS,
+        state.block_time_seconds,
+    )
+    .await?;
+
+    Ok(Json(status))
+}
@@ -4,6 +4,7 @@
 //! mixing up different kinds of data and enable compile-time type checking.
 
 use crate::error::{Result, StampError};
+use alloy::primitives::{Address, FixedBytes};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -79,6 +80,99 @@ impl FromStr for ContractAddress {
     }
 }
 
+/// Postage batch ID (32-byte hex string with 0x prefix)
+///
+/// # Example
+///
+/// ```ignore
+/// let id = BatchId::new("0xABCD...")?; // 64 hex digits
+/// assert!(id.as_str().starts_with("0xabcd"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BatchId(String);
+
+impl BatchId {
+    /// Create from string, validating format and normalizing to lowercase
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - 64 hex digits, with or without a 0x prefix
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the ID is not 32 bytes of hex
+    pub fn new(id: impl Into<String>) -> Result<Self> {
+        let id = id.into();
+        let lower = id.trim().to_lowercase();
+        let digits = lower.strip_prefix("0x").unwrap_or(&lower);
+
+        if digits.len() != 64 {
+            return Err(StampError::Parse(format!(
+                "Invalid batch ID '{id}': must be 32 bytes (64 hex chars), got {}",
+                digits.len()
+            )));
+        }
+
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(StampError::Parse(format!(
+                "Invalid batch ID '{id}': contains non-hex characters"
+            )));
+        }
+
+        Ok(Self(format!("0x{digits}")))
+    }
+
+    /// Get as string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Get as the `bytes32` used in contract calls
+    pub fn to_bytes(&self) -> FixedBytes<32> {
+        FixedBytes::from_str(&self.0).expect("BatchId holds 32 bytes of hex")
+    }
+}
+
+#[cfg(test)]
+impl BatchId {
+    /// Batch ID holding `n` in its low bytes, for test fixtures
+    pub fn test_id(n: u64) -> Self {
+        Self(format!("0x{n:064x}"))
+    }
+}
+
+impl fmt::Display for BatchId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for BatchId {
+    type Err = StampError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl From<FixedBytes<32>> for BatchId {
+    fn from(bytes: FixedBytes<32>) -> Self {
+        Self(format!("{bytes:#x}"))
+    }
+}
+
+/// Format a 20-byte address with its EIP-55 checksum casing
+///
+/// The checksum comes from the keccak256 hash of the lowercase address.
+/// Anything that is not an address, such as a 32-byte batch ID, is returned
+/// unchanged.
+pub fn checksum_address(address: &str) -> String {
+    match Address::from_str(address) {
+        Ok(parsed) if address.starts_with("0x") => parsed.to_checksum(None),
+        _ => address.to_string(),
+    }
+}
+
 /// Contract version identifier (e.g., "v0.9.4", "Phase 4")
 ///
 /// # Example
@@ -205,6 +299,65 @@ mod tests {
         assert_eq!(addr1, addr2);
     }
 
+    const BATCH_ID: &str = "0xc4d6a0f53b7fa2bb0f1a3e52d15e6bcb1d5b53a3c6e3e8b83b5e2f4fe3b6b2a1";
+
+    #[test]
+    fn test_batch_id_valid() {
+        let id = BatchId::new(BATCH_ID).unwrap();
+        assert_eq!(id.as_str(), BATCH_ID);
+        assert_eq!(id.to_string(), BATCH_ID);
+        assert_eq!(BatchId::from(id.to_bytes()), id);
+
+        // The 0x prefix is optional
+        assert_eq!(BatchId::new(&BATCH_ID[2..]).unwrap(), id);
+    }
+
+    #[test]
+    fn test_batch_id_normalizes_case() {
+        let upper = BatchId::new(BATCH_ID.to_uppercase().replace("0X", "0x")).unwrap();
+        let mixed: BatchId = format!(" 0X{} ", &BATCH_ID[2..]).parse().unwrap();
+        assert_eq!(upper, mixed);
+        assert_eq!(upper.as_str(), BATCH_ID);
+    }
+
+    #[test]
+    fn test_batch_id_invalid() {
+        let err = BatchId::new("0x1234").unwrap_err();
+        assert!(err.to_string().contains("must be 32 bytes"));
+
+        // An address is too short to be a batch ID
+        assert!(BatchId::new("0x45a1502382541Cd610CC9068e88727426b696293").is_err());
+
+        let err = BatchId::new(BATCH_ID.replace('c', "z")).unwrap_err();
+        assert!(err.to_string().contains("non-hex"));
+    }
+
+    #[test]
+    fn test_batch_id_serializes_as_string() {
+        let id = BatchId::new(BATCH_ID).unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), format!("\"{BATCH_ID}\""));
+    }
+
+    #[test]
+    fn test_checksum_address_eip55_vectors() {
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            assert_eq!(checksum_address(&expected.to_lowercase()), expected);
+            assert_eq!(checksum_address(&expected.to_uppercase().replace("0X", "0x")), expected);
+        }
+    }
+
+    #[test]
+    fn test_checksum_address_leaves_other_values() {
+        assert_eq!(checksum_address(BATCH_ID), BATCH_ID);
+        assert_eq!(checksum_address("0x1234"), "0x1234");
+        assert_eq!(checksum_address("N/A"), "N/A");
+    }
+
     #[test]
     fn test_contract_version() {
         let version = ContractVersion::new("v0.9.4");
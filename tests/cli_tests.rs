@@ -0,0 +1,62 @@
+//! End-to-end tests running the compiled binary
+//!
+//! Tests cover:
+//! - `--quiet` leaving only the command output on stdout
+
+use beeport_stamp_stats::cache::{Cache, PoolSettings};
+use beeport_stamp_stats::events::BatchInfo;
+use chrono::Utc;
+use std::process::Command;
+use tempfile::NamedTempFile;
+
+/// Cache with two batches, so batch-status has something to report offline
+async fn seeded_cache() -> NamedTempFile {
+    let db_file = NamedTempFile::new().unwrap();
+    let cache = Cache::with_pool_settings(db_file.path(), PoolSettings::default()).await.unwrap();
+
+    let batches: Vec<BatchInfo> = (1..=2u64)
+        .map(|n| BatchInfo {
+            batch_id: format!("0x{n:064x}").parse().unwrap(),
+            owner: "0x5678".to_string(),
+            payer: None,
+            contract_source: "PostageStamp".to_string(),
+            depth: 20,
+            bucket_depth: 16,
+            immutable: false,
+            normalised_balance: "240000000".to_string(),
+            created_at: Utc::now(),
+            block_number: 1000 + n,
+        })
+        .collect();
+    cache.store_batches(&batches).await.unwrap();
+
+    db_file
+}
+
+fn batch_status_csv(db_path: &std::path::Path, quiet: bool) -> String {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_beeport-stamp-stats"));
+    cmd.arg("--cache-db").arg(db_path);
+    cmd.args(["batch-status", "--offline", "--price", "24000", "--no-progress", "--output", "csv"]);
+    if quiet {
+        cmd.arg("--quiet");
+    }
+
+    let output = cmd.env_remove("BEEPORT_CONFIG").output().unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[tokio::test]
+async fn test_quiet_leaves_only_data_on_stdout() {
+    let db_file = seeded_cache().await;
+
+    // Without --quiet the status messages share stdout with the CSV
+    let stdout = batch_status_csv(db_file.path(), false);
+    assert!(stdout.contains("📊"));
+
+    let stdout = batch_status_csv(db_file.path(), true);
+    let mut reader = csv::Reader::from_reader(stdout.as_bytes());
+    assert!(reader.headers().unwrap().iter().any(|h| h == "batch_id"));
+    assert_eq!(reader.records().count(), 2);
+    assert!(stdout.is_ascii(), "unexpected status output: {stdout}");
+}
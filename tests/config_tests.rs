@@ -6,7 +6,7 @@
 //! - Environment variable overrides
 //! - Invalid configurations
 
-use beeport_stamp_stats::config::{AppConfig, BlockchainConfig, ContractConfig, RpcConfig};
+use beeport_stamp_stats::config::{AppConfig, BlockchainConfig, ContractConfig, RoundConfig, RpcConfig};
 
 #[test]
 fn test_default_config() {
@@ -127,6 +127,27 @@ fn test_config_validation_zero_deployment_block() {
         .contains("Deployment block for contract"));
 }
 
+#[test]
+fn test_config_validation_round_phases_out_of_order() {
+    let mut config = AppConfig::default();
+    config.contracts[4].round = Some(RoundConfig {
+        length: 100,
+        reveal_start: 60,
+        claim_start: 50,
+    });
+
+    let result = config.validate();
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("Contract 'Redistribution'"));
+
+    config.contracts[4].round = Some(RoundConfig {
+        length: 100,
+        reveal_start: 25,
+        claim_start: 50,
+    });
+    assert!(config.validate().is_ok());
+}
+
 #[test]
 fn test_config_validation_zero_retry_delay() {
     let mut config = AppConfig::default();
@@ -151,6 +172,26 @@ fn test_config_validation_zero_backoff_multiplier() {
         .contains("backoff_multiplier must be greater than 0"));
 }
 
+#[test]
+fn test_config_validation_jitter_fraction_out_of_range() {
+    let mut config = AppConfig::default();
+    config.retry.jitter_fraction = 1.5;
+
+    let result = config.validate();
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("jitter_fraction"));
+}
+
+#[test]
+fn test_config_validation_empty_retryable_pattern() {
+    let mut config = AppConfig::default();
+    config.retry.retryable_patterns.push(" ".to_string());
+
+    let result = config.validate();
+    assert!(result.is_err());
+    assert!(result.unwrap_err().contains("retryable_patterns"));
+}
+
 #[test]
 fn test_config_load_uses_defaults_when_no_file() {
     // Loading without a file should use defaults
@@ -171,6 +212,7 @@ fn test_blockchain_config_defaults() {
     let config = BlockchainConfig {
         chunk_size: 10000,
         block_time_seconds: 5.0,
+        chain_id: None,
     };
 
     assert_eq!(config.chunk_size, 10000);
@@ -188,6 +230,7 @@ fn test_contract_config_creation() {
         active: true,
         end_block: None,
         paused_at: None,
+        round: None,
     };
 
     assert_eq!(contract.name, "TestContract");
@@ -7,9 +7,14 @@
 //! - Max retries exhaustion
 //! - Non-retryable errors
 //! - Custom predicates
+//! - Backoff jitter
+//! - Retry metrics
 
+use beeport_stamp_stats::error::StampError;
+use beeport_stamp_stats::metrics::Metrics;
 use beeport_stamp_stats::retry::RetryConfig;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[tokio::test]
 async fn test_retry_success_first_attempt() {
@@ -28,6 +33,7 @@ async fn test_retry_on_rate_limit() {
         initial_delay_ms: 10,
         backoff_multiplier: 2,
         extended_retry_wait_seconds: 30,
+        ..RetryConfig::default()
     };
     let attempt = Arc::new(Mutex::new(0));
     let attempt_clone = attempt.clone();
@@ -52,6 +58,36 @@ async fn test_retry_on_rate_limit() {
     assert_eq!(*attempt.lock().unwrap(), 3);
 }
 
+#[tokio::test]
+async fn test_retries_are_counted_in_attached_metrics() {
+    let metrics = Arc::new(Metrics::new());
+    let config = RetryConfig {
+        max_retries: 3,
+        initial_delay_ms: 1,
+        ..RetryConfig::default()
+    }
+    .with_metrics(Arc::clone(&metrics));
+    let attempt = Arc::new(Mutex::new(0));
+
+    let result = config
+        .execute(|| {
+            let attempt = attempt.clone();
+            async move {
+                let mut count = attempt.lock().unwrap();
+                *count += 1;
+                if *count < 3 {
+                    Err(std::io::Error::other("429 Too Many Requests"))
+                } else {
+                    Ok(42)
+                }
+            }
+        })
+        .await;
+
+    assert_eq!(result.unwrap(), 42);
+    assert!(metrics.render().contains("beeport_rpc_retries_total 2"));
+}
+
 #[tokio::test]
 async fn test_non_rate_limit_error_fails_immediately() {
     let config = RetryConfig::default();
@@ -80,6 +116,7 @@ async fn test_custom_predicate() {
         initial_delay_ms: 10,
         backoff_multiplier: 2,
         extended_retry_wait_seconds: 30,
+        ..RetryConfig::default()
     };
     let attempt = Arc::new(Mutex::new(0));
     let attempt_clone = attempt.clone();
@@ -117,6 +154,7 @@ async fn test_custom_predicate_non_retryable() {
         initial_delay_ms: 10,
         backoff_multiplier: 2,
         extended_retry_wait_seconds: 30,
+        ..RetryConfig::default()
     };
     let attempt = Arc::new(Mutex::new(0));
     let attempt_clone = attempt.clone();
@@ -149,6 +187,7 @@ async fn test_exponential_backoff_delays() {
         initial_delay_ms: 100,
         backoff_multiplier: 4,
         extended_retry_wait_seconds: 30,
+        ..RetryConfig::default()
     };
 
     let attempt = Arc::new(Mutex::new(0));
@@ -189,6 +228,7 @@ fn test_retry_config_creation() {
         initial_delay_ms: 200,
         backoff_multiplier: 3,
         extended_retry_wait_seconds: 600,
+        ..RetryConfig::default()
     };
 
     assert_eq!(config.max_retries, 10);
@@ -214,6 +254,7 @@ async fn test_multiple_rate_limit_errors() {
         initial_delay_ms: 10,
         backoff_multiplier: 2,
         extended_retry_wait_seconds: 30,
+        ..RetryConfig::default()
     };
     let attempt = Arc::new(Mutex::new(0));
     let attempt_clone = attempt.clone();
@@ -245,6 +286,7 @@ async fn test_error_message_contains_too_many_requests() {
         initial_delay_ms: 10,
         backoff_multiplier: 2,
         extended_retry_wait_seconds: 30,
+        ..RetryConfig::default()
     };
     let attempt = Arc::new(Mutex::new(0));
     let attempt_clone = attempt.clone();
@@ -270,3 +312,159 @@ async fn test_error_message_contains_too_many_requests() {
     assert_eq!(result.unwrap(), 42);
     assert_eq!(*attempt.lock().unwrap(), 3);
 }
+
+#[test]
+fn test_backoff_delays_without_jitter_are_exact() {
+    let config = RetryConfig {
+        max_retries: 5,
+        initial_delay_ms: 100,
+        backoff_multiplier: 4,
+        extended_retry_wait_seconds: 300,
+        ..RetryConfig::default()
+    };
+    let mut rng = config.jitter_rng();
+
+    let delays: Vec<u128> = (0..5).map(|retry| config.backoff_delay(retry, &mut rng).as_millis()).collect();
+    assert_eq!(delays, [100, 400, 1600, 6400, 25600]);
+    assert_eq!(config.extended_wait(&mut rng), Duration::from_secs(300));
+}
+
+#[test]
+fn test_backoff_jitter_stays_in_range_and_is_seedable() {
+    let config = RetryConfig {
+        max_retries: 5,
+        initial_delay_ms: 1000,
+        backoff_multiplier: 2,
+        extended_retry_wait_seconds: 100,
+        jitter_fraction: 0.25,
+        jitter_extended_wait: true,
+        jitter_seed: Some(42),
+        max_total_elapsed_seconds: None,
+        retryable_patterns: Vec::new(),
+        metrics: None,
+    };
+
+    let mut rng = config.jitter_rng();
+    let delays: Vec<Duration> = (0..5).map(|retry| config.backoff_delay(retry, &mut rng)).collect();
+    for (retry, delay) in delays.iter().enumerate() {
+        let base = 1000.0 * 2f64.powi(retry as i32);
+        let millis = delay.as_secs_f64() * 1000.0;
+        assert!((base * 0.75..=base * 1.25).contains(&millis), "retry {retry}: {millis}ms");
+    }
+    // Jitter actually varies the delays away from the exact backoff
+    assert!(delays.iter().enumerate().any(|(retry, delay)| delay.as_millis() != 1000 << retry));
+
+    let wait = config.extended_wait(&mut rng);
+    assert!((75.0..=125.0).contains(&wait.as_secs_f64()));
+
+    // The same seed reproduces the same delays
+    let mut replay = config.jitter_rng();
+    let replayed: Vec<Duration> = (0..5).map(|retry| config.backoff_delay(retry, &mut replay)).collect();
+    assert_eq!(replayed, delays);
+}
+
+#[tokio::test]
+async fn test_retry_deadline_gives_up_on_persistent_rate_limit() {
+    let config = RetryConfig {
+        max_retries: 100,
+        initial_delay_ms: 200,
+        backoff_multiplier: 1,
+        extended_retry_wait_seconds: 300,
+        max_total_elapsed_seconds: Some(1),
+        ..RetryConfig::default()
+    };
+    let attempt = Arc::new(Mutex::new(0));
+
+    let started = std::time::Instant::now();
+    let result = config
+        .execute(|| {
+            let attempt = attempt.clone();
+            async move {
+                *attempt.lock().unwrap() += 1;
+                Err::<(), _>(std::io::Error::other("HTTP 429: Too Many Requests"))
+            }
+        })
+        .await;
+
+    // Gives up at the deadline instead of falling through to the extended wait,
+    // still reporting the rate limit
+    assert!(started.elapsed() < Duration::from_secs(3));
+    let error = result.unwrap_err();
+    assert!(matches!(error, StampError::RateLimited(_)), "{error}");
+    let error = error.to_string();
+    let attempts = *attempt.lock().unwrap();
+    assert!(attempts > 1);
+    assert!(
+        error.contains(&format!("Retry deadline exceeded after {} retries", attempts - 1)),
+        "{error}"
+    );
+    assert!(error.contains("429"), "{error}");
+}
+
+/// Run an operation failing once with `message`, returning the result and attempt count
+async fn fail_once_with(config: &RetryConfig, message: &'static str) -> (Result<u32, String>, u32) {
+    let attempt = Arc::new(Mutex::new(0));
+    let result = config
+        .execute(|| {
+            let attempt = attempt.clone();
+            async move {
+                let mut count = attempt.lock().unwrap();
+                *count += 1;
+                if *count < 2 {
+                    Err(std::io::Error::other(message))
+                } else {
+                    Ok(*count)
+                }
+            }
+        })
+        .await
+        .map_err(|e| e.to_string());
+    let attempts = *attempt.lock().unwrap();
+    (result, attempts)
+}
+
+#[tokio::test]
+async fn test_transient_server_errors_are_retried() {
+    let config = RetryConfig {
+        initial_delay_ms: 1,
+        ..RetryConfig::default()
+    };
+
+    for message in [
+        "HTTP error 503 with body: Service Unavailable",
+        "HTTP error 502 with body: <html>Bad Gateway</html>",
+        "error sending request: connection reset by peer",
+    ] {
+        let (result, attempts) = fail_once_with(&config, message).await;
+        assert_eq!(result, Ok(2), "{message}");
+        assert_eq!(attempts, 2, "{message}");
+    }
+
+    // Application errors still fail fast
+    let (result, attempts) = fail_once_with(&config, "invalid address").await;
+    assert!(result.unwrap_err().contains("invalid address"));
+    assert_eq!(attempts, 1);
+
+    // Status codes inside larger numbers do not match
+    let (result, attempts) = fail_once_with(&config, "Failed to get block 15030000: execution reverted").await;
+    assert!(result.is_err());
+    assert_eq!(attempts, 1);
+}
+
+#[tokio::test]
+async fn test_custom_retryable_patterns() {
+    let config = RetryConfig {
+        initial_delay_ms: 1,
+        retryable_patterns: vec!["Upstream Busy".to_string()],
+        ..RetryConfig::default()
+    };
+
+    let (result, attempts) = fail_once_with(&config, "provider says: upstream busy, try later").await;
+    assert_eq!(result, Ok(2));
+    assert_eq!(attempts, 2);
+
+    // Replacing the defaults drops them
+    let (result, attempts) = fail_once_with(&config, "HTTP error 503").await;
+    assert!(result.is_err());
+    assert_eq!(attempts, 1);
+}
@@ -0,0 +1,232 @@
+//! Integration tests for the HTTP query server
+//!
+//! Tests cover:
+//! - Event listing with filters and paging
+//! - Oversized queries rejected as client errors
+//! - Batch listing
+//! - Period summaries
+//! - Batch status lookup
+
+use beeport_stamp_stats::cache::{Cache, PoolSettings};
+use beeport_stamp_stats::events::{BatchInfo, StampEvent};
+use beeport_stamp_stats::server::{self, ServerState, SummaryResponse};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+const BATCH_A: &str = "0xaaaa000000000000000000000000000000000000000000000000000000000001";
+const BATCH_B: &str = "0xbbbb000000000000000000000000000000000000000000000000000000000002";
+
+/// Noon yesterday, so every seeded event lands in the same day
+fn seeded_at() -> DateTime<Utc> {
+    (Utc::now() - Duration::days(1))
+        .date_naive()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+        .and_utc()
+}
+
+fn event(event_type: &str, batch_id: &str, contract: &str, block: u64, data: Value) -> StampEvent {
+    serde_json::from_value(json!({
+        "event_type": event_type,
+        "batch_id": batch_id,
+        "block_number": block,
+        "block_timestamp": seeded_at(),
+        "transaction_hash": format!("0x{block:064x}"),
+        "log_index": 0,
+        "contract_source": contract,
+        "data": data,
+    }))
+    .unwrap()
+}
+
+fn batch(batch_id: &str, contract: &str, block: u64) -> BatchInfo {
+    BatchInfo {
+        batch_id: batch_id.parse().unwrap(),
+        owner: "0x1111111111111111111111111111111111111111".to_string(),
+        payer: None,
+        contract_source: contract.to_string(),
+        depth: 20,
+        bucket_depth: 16,
+        immutable: false,
+        normalised_balance: "1000000".to_string(),
+        created_at: seeded_at(),
+        block_number: block,
+    }
+}
+
+/// Seed a temporary cache and start the server on an ephemeral port
+async fn start_server() -> (SocketAddr, oneshot::Sender<()>, NamedTempFile) {
+    start_server_with_max_rows(None).await
+}
+
+/// Like `start_server`, with the cache refusing queries over `max_query_rows`
+async fn start_server_with_max_rows(max_query_rows: Option<u64>) -> (SocketAddr, oneshot::Sender<()>, NamedTempFile) {
+    let db_file = NamedTempFile::new().unwrap();
+    let cache = Cache::with_pool_settings(db_file.path(), PoolSettings::default())
+        .await
+        .unwrap()
+        .with_max_query_rows(max_query_rows);
+
+    let created = json!({
+        "type": "BatchCreated",
+        "total_amount": "1000",
+        "normalised_balance": "1000000",
+        "owner": "0x1111111111111111111111111111111111111111",
+        "depth": 20,
+        "bucket_depth": 16,
+        "immutable_flag": false,
+        "payer": null
+    });
+
+    let events = vec![
+        event("BatchCreated", BATCH_A, "PostageStamp", 100, created.clone()),
+        event("BatchCreated", BATCH_B, "StampsRegistry", 101, created),
+        event(
+            "BatchTopUp",
+            BATCH_A,
+            "PostageStamp",
+            102,
+            json!({ "type": "BatchTopUp", "topup_amount": "500", "normalised_balance": "2000000" }),
+        ),
+    ];
+    cache.store_events(&events).await.unwrap();
+    cache
+        .store_batches(&[batch(BATCH_A, "PostageStamp", 100), batch(BATCH_B, "StampsRegistry", 101)])
+        .await
+        .unwrap();
+    cache.cache_price(1000).await.unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    tokio::spawn(server::serve(listener, ServerState::new(cache, 5.0), async {
+        let _ = shutdown_rx.await;
+    }));
+
+    (addr, shutdown_tx, db_file)
+}
+
+async fn get(addr: SocketAddr, path: &str) -> reqwest::Response {
+    reqwest::get(format!("http://{addr}{path}")).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_events_endpoint_with_filters() {
+    let (addr, _shutdown, _db) = start_server().await;
+
+    let all: Vec<Value> = get(addr, "/events").await.json().await.unwrap();
+    assert_eq!(all.len(), 3);
+
+    let created: Vec<Value> = get(addr, "/events?event_type=batch-created")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(created.len(), 2);
+
+    let registry: Vec<Value> = get(addr, "/events?months=1&contract=stamps-registry")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(registry.len(), 1);
+    assert_eq!(registry[0]["batch_id"], BATCH_B);
+}
+
+#[tokio::test]
+async fn test_events_endpoint_paging() {
+    let (addr, _shutdown, _db) = start_server().await;
+
+    let response = get(addr, "/events?limit=2&offset=1").await;
+    assert_eq!(response.headers()["x-total-count"], "3");
+    let page: Vec<Value> = response.json().await.unwrap();
+    assert_eq!(page.len(), 2);
+    assert_eq!(page[0]["block_number"], 101);
+    assert_eq!(page[1]["block_number"], 102);
+
+    // Filters apply before the page is cut
+    let response = get(addr, "/events?event_type=batch-created&limit=1&offset=1").await;
+    assert_eq!(response.headers()["x-total-count"], "2");
+    let page: Vec<Value> = response.json().await.unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0]["batch_id"], BATCH_B);
+}
+
+#[tokio::test]
+async fn test_events_endpoint_rejects_unknown_filter_value() {
+    let (addr, _shutdown, _db) = start_server().await;
+
+    let response = get(addr, "/events?event_type=not-an-event").await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_events_endpoint_too_large_is_client_error() {
+    let (addr, _shutdown, _db) = start_server_with_max_rows(Some(2)).await;
+
+    let response = get(addr, "/events").await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("max_query_rows (2)"));
+
+    // A page within the limit still loads
+    let response = get(addr, "/events?limit=2").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_batches_endpoint() {
+    let (addr, _shutdown, _db) = start_server().await;
+
+    let batches: Vec<Value> = get(addr, "/batches").await.json().await.unwrap();
+    assert_eq!(batches.len(), 2);
+
+    let filtered: Vec<Value> = get(addr, "/batches?batch_id=0xbbbb").await.json().await.unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0]["batch_id"], BATCH_B);
+}
+
+#[tokio::test]
+async fn test_summary_endpoint() {
+    let (addr, _shutdown, _db) = start_server().await;
+
+    let summary: SummaryResponse = get(addr, "/summary?group_by=day").await.json().await.unwrap();
+    assert_eq!(summary.total_events, 3);
+    assert_eq!(summary.total_batches, 2);
+    assert_eq!(summary.periods.len(), 1);
+    assert_eq!(summary.periods[0].batch_created_count, 2);
+    assert_eq!(summary.periods[0].batch_topup_count, 1);
+}
+
+#[tokio::test]
+async fn test_batch_status_endpoint() {
+    let (addr, _shutdown, _db) = start_server().await;
+
+    let status: Value = get(addr, &format!("/batch/{BATCH_A}/status"))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(status["batch_id"], BATCH_A);
+    assert_eq!(status["depth"], 20);
+    // 1,000,000 PLUR/chunk at 1,000 PLUR/chunk/block
+    assert_eq!(status["ttl_blocks"], "1,000");
+
+    // Mixed-case and unprefixed IDs resolve to the same batch
+    let upper: Value = get(addr, &format!("/batch/{}/status", BATCH_A[2..].to_uppercase()))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(upper["batch_id"], BATCH_A);
+
+    let invalid = get(addr, "/batch/not-hex/status").await;
+    assert_eq!(invalid.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let missing = get(addr, "/batch/0xdeadbeef/status").await;
+    assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+}